@@ -285,8 +285,7 @@ impl HcAppBundle {
                     app_pack_recursive(&path).await?;
                 }
 
-                let (bundle_path, _) =
-                    crate::packing::pack::<AppManifest>(&path, output, name).await?;
+                let (bundle_path, _) = crate::packing::pack_app(&path, output, name).await?;
                 println!("Wrote bundle {}", bundle_path.to_string_lossy());
             }
             Self::Unpack {
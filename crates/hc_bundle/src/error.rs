@@ -17,6 +17,10 @@ pub enum HcBundleError {
     #[error("DNA error: {0}")]
     DnaError(#[from] holochain_types::dna::DnaError),
 
+    /// AppBundleError
+    #[error(transparent)]
+    AppBundleError(#[from] holochain_types::prelude::AppBundleError),
+
     /// MrBundleError
     #[error(transparent)]
     MrBundleError(#[from] mr_bundle::error::MrBundleError),
@@ -3,6 +3,7 @@
 //! Defines the CLI commands for packing/unpacking both DNA and hApp bundles
 
 use crate::error::{HcBundleError, HcBundleResult};
+use holochain_types::prelude::{AppBundle, AppManifest};
 use holochain_util::ffs;
 use mr_bundle::RawBundle;
 use mr_bundle::{Bundle, Manifest};
@@ -80,18 +81,53 @@ pub async fn pack<M: Manifest>(
     let dir_path = ffs::canonicalize(dir_path).await?;
     let manifest_path = dir_path.join(M::path());
     let bundle: Bundle<M> = Bundle::pack_yaml(&manifest_path).await?;
-    let target_path = match target_path {
+    let target_path = bundle_target_path(&dir_path, target_path, name, M::bundle_extension())?;
+    bundle.write_to_file(&target_path).await?;
+    Ok((target_path, bundle))
+}
+
+/// Pack a directory containing an app manifest into an AppBundle, returning
+/// the path to which the bundle file was written.
+///
+/// Unlike [`pack`], DNAs with a URL location are downloaded and embedded in
+/// the bundle, after checking them against the DNA version in the manifest.
+pub async fn pack_app(
+    dir_path: &std::path::Path,
+    target_path: Option<PathBuf>,
+    name: String,
+) -> HcBundleResult<(PathBuf, AppBundle)> {
+    let dir_path = ffs::canonicalize(dir_path).await?;
+    let manifest_path = dir_path.join(AppManifest::path());
+    let bundle: Bundle<AppManifest> = Bundle::pack_yaml(&manifest_path).await?;
+    let (bundle, _) = AppBundle::from(bundle)
+        .embed_remote_dnas(|_| None, |_| None)
+        .await?;
+    let target_path = bundle_target_path(
+        &dir_path,
+        target_path,
+        name,
+        AppManifest::bundle_extension(),
+    )?;
+    bundle.write_to_file(&target_path).await?;
+    Ok((target_path, bundle))
+}
+
+fn bundle_target_path(
+    dir_path: &Path,
+    target_path: Option<PathBuf>,
+    name: String,
+    extension: &str,
+) -> HcBundleResult<PathBuf> {
+    match target_path {
         Some(target_path) => {
             if target_path.is_dir() {
-                dir_to_bundle_path(&target_path, name, M::bundle_extension())?
+                dir_to_bundle_path(&target_path, name, extension)
             } else {
-                target_path
+                Ok(target_path)
             }
         }
-        None => dir_to_bundle_path(&dir_path, name, M::bundle_extension())?,
-    };
-    bundle.write_to_file(&target_path).await?;
-    Ok((target_path, bundle))
+        None => dir_to_bundle_path(dir_path, name, extension),
+    }
 }
 
 fn dir_to_bundle_path(dir_path: &Path, name: String, extension: &str) -> HcBundleResult<PathBuf> {
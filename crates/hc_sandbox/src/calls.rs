@@ -1,4 +1,5 @@
-//! Helpers for making [`AdminRequest`]s to the admin api.
+//! Helpers for making [`AdminRequest`]s to the admin api,
+//! and zome calls with [`AppRequest::CallZome`].
 //!
 //! This module is designed for use in a CLI so it is more simplified
 //! then calling the [`CmdRunner`] directly.
@@ -12,12 +13,20 @@ use anyhow::bail;
 use anyhow::ensure;
 use holochain_conductor_api::AdminRequest;
 use holochain_conductor_api::AdminResponse;
+use holochain_conductor_api::AllowedOrigins;
+use holochain_conductor_api::AppAuthenticationTokenIssued;
+use holochain_conductor_api::AppRequest;
+use holochain_conductor_api::AppResponse;
 use holochain_conductor_api::AppStatusFilter;
+use holochain_conductor_api::CellInfo;
 use holochain_conductor_api::InterfaceDriver;
+use holochain_conductor_api::IssueAppAuthenticationTokenPayload;
 use holochain_conductor_api::{AdminInterfaceConfig, AppInfo};
 use holochain_p2p::kitsune_p2p::agent_store::AgentInfoSigned;
 use holochain_types::prelude::DnaHash;
 use holochain_types::prelude::DnaModifiersOpt;
+use holochain_types::prelude::ExternIO;
+use holochain_types::prelude::GrantZomeCallCapabilityPayload;
 use holochain_types::prelude::RegisterDnaPayload;
 use holochain_types::prelude::Timestamp;
 use holochain_types::prelude::YamlProperties;
@@ -29,7 +38,9 @@ use std::convert::TryFrom;
 use crate::cmds::Existing;
 use crate::expect_match;
 use crate::ports::get_admin_ports;
+use crate::ports::get_app_api;
 use crate::run::run_async;
+use crate::zome_call::SigningCredentials;
 use crate::CmdRunner;
 use structopt::StructOpt;
 
@@ -41,6 +52,10 @@ pub struct Call {
     /// If this is empty existing sandboxes will be used.
     /// Cannot be combined with existing sandboxes.
     pub running: Vec<u16>,
+    #[structopt(long, conflicts_with_all = &["existing_paths", "indices", "running"], value_delimiter = ",")]
+    /// Paths to the unix domain sockets of running conductor admin interfaces.
+    /// Cannot be combined with existing sandboxes or running ports.
+    pub running_socket: Vec<PathBuf>,
     #[structopt(flatten)]
     pub existing: Existing,
     #[structopt(subcommand)]
@@ -55,6 +70,7 @@ pub struct Call {
 pub enum AdminRequestCli {
     AddAdminWs(AddAdminWs),
     AddAppWs(AddAppWs),
+    IssueAppToken(IssueAppToken),
     RegisterDna(RegisterDna),
     InstallApp(InstallApp),
     /// Calls AdminRequest::UninstallApp.
@@ -76,6 +92,7 @@ pub enum AdminRequestCli {
     /// _Unimplemented_.
     AddAgents,
     ListAgents(ListAgents),
+    Zome(CallZome),
 }
 #[derive(Debug, StructOpt, Clone)]
 /// Calls AdminRequest::AddAdminInterfaces
@@ -93,6 +110,46 @@ pub struct AddAppWs {
     /// Optional port number.
     /// Defaults to assigned by OS.
     pub port: Option<u16>,
+
+    #[structopt(long, conflicts_with = "port")]
+    /// Path at which to create a unix domain socket
+    /// for the interface instead of using a port.
+    pub unix_socket: Option<PathBuf>,
+
+    #[structopt(long, value_delimiter = ",")]
+    /// Origins which may connect to the interface,
+    /// e.g. `http://localhost:8888`.
+    /// Defaults to allowing any origin.
+    pub allowed_origins: Vec<String>,
+}
+
+impl AddAppWs {
+    /// The origins allowed by the arguments.
+    pub fn allowed_origins(&self) -> AllowedOrigins {
+        if self.allowed_origins.is_empty() {
+            AllowedOrigins::Any
+        } else {
+            AllowedOrigins::Origins(self.allowed_origins.iter().cloned().collect())
+        }
+    }
+}
+
+#[derive(Debug, StructOpt, Clone)]
+/// Calls AdminRequest::IssueAppAuthenticationToken
+/// and issues a token which a client can use to connect
+/// to an app interface for this app.
+pub struct IssueAppToken {
+    /// The InstalledAppId the token is for.
+    pub app_id: String,
+
+    #[structopt(long, default_value = "30")]
+    /// How many seconds the token can be used for.
+    /// 0 means it never expires.
+    pub expiry_seconds: u64,
+
+    #[structopt(long)]
+    /// Allow the token to authenticate more than one connection.
+    pub multi_use: bool,
 }
 
 #[derive(Debug, StructOpt, Clone)]
@@ -203,14 +260,49 @@ pub struct ListApps {
     pub status: Option<AppStatusFilter>,
 }
 
+#[derive(Debug, StructOpt, Clone)]
+/// Calls AppRequest::CallZome
+/// and prints the decoded response as JSON.
+///
+/// The call is made over the first app interface of the conductor,
+/// attaching one if there is none.
+/// It is signed by a key which is granted a capability to call every
+/// function of the cell the first time it is used. The key is stored
+/// in the `.hc_auth` file in the current directory for later calls.
+pub struct CallZome {
+    /// The InstalledAppId of the app to call.
+    pub app_id: String,
+
+    /// The role of the cell to call.
+    pub role_name: String,
+
+    /// The zome containing the function.
+    pub zome_name: String,
+
+    /// The function to call.
+    pub fn_name: String,
+
+    #[structopt(short, long, default_value = "null")]
+    /// The payload to call the function with, as JSON or YAML.
+    /// e.g. `--payload '{"content": "hello"}'`
+    pub payload: String,
+}
+
 #[doc(hidden)]
 pub async fn call(holochain_path: &Path, req: Call) -> anyhow::Result<()> {
     let Call {
         existing,
         running,
+        running_socket,
         call,
     } = req;
-    let cmds = if running.is_empty() {
+    let cmds = if !running_socket.is_empty() {
+        let mut cmds = Vec::with_capacity(running_socket.len());
+        for path in running_socket {
+            cmds.push((CmdRunner::try_new_unix(&path).await?, None, None));
+        }
+        cmds
+    } else if running.is_empty() {
         let paths = if existing.is_empty() {
             crate::save::load(std::env::current_dir()?)?
         } else {
@@ -260,8 +352,20 @@ async fn call_inner(cmd: &mut CmdRunner, call: AdminRequestCli) -> anyhow::Resul
             msg!("Added Admin port {}", port);
         }
         AdminRequestCli::AddAppWs(args) => {
+            let unix_socket = args.unix_socket.clone();
             let port = attach_app_interface(cmd, args).await?;
-            msg!("Added App port {}", port);
+            match unix_socket {
+                Some(path) => msg!("Added App unix socket {}", path.display()),
+                None => msg!("Added App port {}", port),
+            }
+        }
+        AdminRequestCli::IssueAppToken(args) => {
+            let issued = issue_app_authentication_token(cmd, args).await?;
+            msg!(
+                "Issued app authentication token {:?}, expires at {:?}",
+                issued.token,
+                issued.expires_at
+            );
         }
         AdminRequestCli::ListAppWs => {
             let ports = list_app_ws(cmd).await?;
@@ -310,6 +414,10 @@ async fn call_inner(cmd: &mut CmdRunner, call: AdminRequestCli) -> anyhow::Resul
             let state = dump_state(cmd, args).await?;
             msg!("DUMP STATE \n{}", state);
         }
+        AdminRequestCli::Zome(args) => {
+            let response = call_zome(cmd, args).await?;
+            msg!("{}", serde_json::to_string_pretty(&response)?);
+        }
         AdminRequestCli::AddAgents => todo!("Adding agent info via cli is coming soon"),
         AdminRequestCli::ListAgents(args) => {
             use std::fmt::Write;
@@ -540,7 +648,11 @@ pub async fn disable_app(cmd: &mut CmdRunner, args: DisableApp) -> anyhow::Resul
 /// Calls [`AdminRequest::AttachAppInterface`] and adds another app interface.
 pub async fn attach_app_interface(cmd: &mut CmdRunner, args: AddAppWs) -> anyhow::Result<u16> {
     let resp = cmd
-        .command(AdminRequest::AttachAppInterface { port: args.port })
+        .command(AdminRequest::AttachAppInterface {
+            port: args.port,
+            unix_socket: args.unix_socket.clone(),
+            allowed_origins: args.allowed_origins(),
+        })
         .await?;
     tracing::debug!(?resp);
     match resp {
@@ -553,6 +665,147 @@ pub async fn attach_app_interface(cmd: &mut CmdRunner, args: AddAppWs) -> anyhow
     }
 }
 
+/// Calls [`AdminRequest::IssueAppAuthenticationToken`] and issues a token
+/// for connecting to an app interface.
+pub async fn issue_app_authentication_token(
+    cmd: &mut CmdRunner,
+    args: IssueAppToken,
+) -> anyhow::Result<AppAuthenticationTokenIssued> {
+    let resp = cmd
+        .command(AdminRequest::IssueAppAuthenticationToken(
+            IssueAppAuthenticationTokenPayload {
+                installed_app_id: args.app_id.clone(),
+                expiry_seconds: args.expiry_seconds,
+                single_use: !args.multi_use,
+            },
+        ))
+        .await?;
+    tracing::debug!(?resp);
+    match resp {
+        AdminResponse::AppAuthenticationTokenIssued(issued) => Ok(issued),
+        _ => Err(anyhow!(
+            "Failed to issue app authentication token for {}, got: {:?}",
+            args.app_id,
+            resp
+        )),
+    }
+}
+
+/// Calls [`AppRequest::CallZome`] on the cell of an app's role
+/// and decodes the response into JSON.
+///
+/// Reuses an app interface and signing credentials when there are some,
+/// otherwise attaches an interface with [`AdminRequest::AttachAppInterface`]
+/// and grants a capability to new credentials with
+/// [`AdminRequest::GrantZomeCallCapability`].
+pub async fn call_zome(cmd: &mut CmdRunner, args: CallZome) -> anyhow::Result<serde_json::Value> {
+    let CallZome {
+        app_id,
+        role_name,
+        zome_name,
+        fn_name,
+        payload,
+    } = args;
+
+    let app = list_apps(cmd, ListApps { status: None })
+        .await?
+        .into_iter()
+        .find(|app| app.installed_app_id == app_id)
+        .ok_or_else(|| anyhow!("App {} is not installed", app_id))?;
+    let cell_id = app
+        .cell_info
+        .get(&role_name)
+        .and_then(|cells| {
+            cells.iter().find_map(|cell| match cell {
+                CellInfo::Provisioned(cell) => Some(cell.cell_id.clone()),
+                _ => None,
+            })
+        })
+        .ok_or_else(|| {
+            anyhow!(
+                "App {} has no provisioned cell for role {}",
+                app_id,
+                role_name
+            )
+        })?;
+
+    let hc_dir = std::env::current_dir()?;
+    let credentials = match crate::zome_call::load_credentials(hc_dir.clone(), &cell_id)? {
+        Some(credentials) => credentials,
+        None => {
+            let credentials = SigningCredentials::generate().await?;
+            grant_zome_call_capability(
+                cmd,
+                GrantZomeCallCapabilityPayload {
+                    cell_id: cell_id.clone(),
+                    cap_grant: credentials.cap_grant(),
+                },
+            )
+            .await?;
+            crate::zome_call::save_credentials(hc_dir, &cell_id, credentials.clone())?;
+            credentials
+        }
+    };
+
+    let port = match list_app_ws(cmd).await?.into_iter().find(|p| *p != 0) {
+        Some(port) => port,
+        None => {
+            attach_app_interface(
+                cmd,
+                AddAppWs {
+                    port: None,
+                    unix_socket: None,
+                    allowed_origins: Vec::new(),
+                },
+            )
+            .await?
+        }
+    };
+    let token = issue_app_authentication_token(
+        cmd,
+        IssueAppToken {
+            app_id,
+            expiry_seconds: 30,
+            multi_use: false,
+        },
+    )
+    .await?
+    .token;
+    let mut app_client = get_app_api(port, token).await?;
+
+    let payload: serde_yaml::Value = serde_yaml::from_str(&payload)?;
+    let call = credentials
+        .sign(
+            cell_id,
+            zome_name.into(),
+            fn_name.into(),
+            ExternIO::encode(payload)?,
+        )
+        .await?;
+    let resp: AppResponse = app_client
+        .request(AppRequest::CallZome(Box::new(call)))
+        .await?;
+    let output = expect_match!(resp => AppResponse::ZomeCalled, "Failed to call zome");
+    crate::zome_call::msgpack_to_json(output.as_bytes())
+}
+
+/// Calls [`AdminRequest::GrantZomeCallCapability`] and grants a capability
+/// to call the zomes of a cell.
+pub async fn grant_zome_call_capability(
+    cmd: &mut CmdRunner,
+    payload: GrantZomeCallCapabilityPayload,
+) -> anyhow::Result<()> {
+    let resp = cmd
+        .command(AdminRequest::GrantZomeCallCapability(Box::new(payload)))
+        .await?;
+    ensure!(
+        matches!(resp, AdminResponse::ZomeCallCapabilityGranted),
+        "Failed to grant zome call capability, got: {:?}",
+        resp
+    );
+    Ok(())
+}
+
 /// Calls [`AdminRequest::DumpState`] and dumps the current cell's state.
 // TODO: Add pretty print.
 // TODO: Default to dumping all cell state.
@@ -69,7 +69,8 @@ pub enum HcSandboxSubcommand {
     /// Run conductor(s) from existing sandbox(es).
     Run(Run),
 
-    /// Make a call to a conductor's admin interface.
+    /// Make a call to a conductor's admin interface,
+    /// or to a zome function of one of its apps.
     Call(crate::calls::Call),
 
     /// List sandboxes found in `$(pwd)/.hc`.
@@ -84,6 +85,12 @@ pub enum HcSandboxSubcommand {
 
     /// Create a fresh sandbox with no apps installed.
     Create(Create),
+
+    /// Generate and run a network of conductors described by a scenario file.
+    ///
+    /// The conductors keep running until `ctrl-c` is pressed.
+    /// Their admin ports and agent keys are written to `$(pwd)/.hc_scenario`.
+    Scenario(crate::scenario::ScenarioCmd),
 }
 
 /// Options for running a sandbox
@@ -159,6 +166,9 @@ impl HcSandbox {
                 crate::save::list(std::env::current_dir()?, verbose)?
             }
             HcSandboxSubcommand::Clean => crate::save::clean(std::env::current_dir()?, Vec::new())?,
+            HcSandboxSubcommand::Scenario(scenario) => {
+                crate::scenario::run(&self.holochain_path, scenario).await?
+            }
             HcSandboxSubcommand::Create(Create {
                 num_sandboxes,
                 network,
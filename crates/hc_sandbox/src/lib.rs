@@ -73,6 +73,18 @@
 //! ```shell
 //! hc sandbox call list-cells
 //! ```
+//! It can also call a zome function of an installed app,
+//! printing the response as JSON:
+//! ```shell
+//! hc sandbox call zome my-app my-role my-zome my_fn --payload '{"content": "hello"}'
+//! ```
+//! #### Scenario
+//! Generates and runs a network of conductors described in a YAML file,
+//! optionally with a local bootstrap server and proxy.
+//! See [`scenario`] for the format.
+//! ```shell
+//! hc sandbox scenario ./my-scenario.yaml
+//! ```
 //! #### List and Clean
 //! These commands allow you to list the persisted sandboxes
 //! in the current directory (from the`.hcXXX`) file.
@@ -115,6 +127,7 @@ use holochain_conductor_api::{AdminRequest, AdminResponse};
 use holochain_websocket::WebsocketResult;
 use holochain_websocket::WebsocketSender;
 use ports::get_admin_api;
+use ports::get_admin_api_unix;
 
 pub use ports::force_admin_port;
 
@@ -138,6 +151,8 @@ pub mod generate;
 pub mod run;
 pub mod sandbox;
 pub mod save;
+pub mod scenario;
+pub mod zome_call;
 pub use cli::HcSandbox;
 
 mod ports;
@@ -163,6 +178,13 @@ impl CmdRunner {
         Ok(Self { client })
     }
 
+    /// Create a new connection for calling admin interface commands
+    /// over the unix domain socket at this path.
+    pub async fn try_new_unix(path: &Path) -> WebsocketResult<Self> {
+        let client = get_admin_api_unix(path).await?;
+        Ok(Self { client })
+    }
+
     /// Create a command runner from a sandbox path.
     /// This expects holochain to be on the path.
     pub async fn from_sandbox(
@@ -1,9 +1,11 @@
 //! Helpers for working with websockets and ports.
+use std::path::Path;
 use std::path::PathBuf;
 use std::sync::Arc;
 
 use holochain_conductor_api::{
-    config::conductor::ConductorConfig, AdminInterfaceConfig, InterfaceDriver,
+    config::conductor::ConductorConfig, AdminInterfaceConfig, AppAuthenticationRequest,
+    AppAuthenticationToken, InterfaceDriver,
 };
 use holochain_websocket::{self as ws, WebsocketConfig, WebsocketReceiver, WebsocketSender};
 use url2::prelude::*;
@@ -21,6 +23,8 @@ pub fn force_admin_port(path: PathBuf, port: u16) -> anyhow::Result<()> {
 }
 
 /// List the admin ports for each sandbox.
+/// Errors if a sandbox's admin interface is a unix domain socket,
+/// which has to be called with `--running-socket` instead.
 pub async fn get_admin_ports(paths: Vec<PathBuf>) -> anyhow::Result<Vec<u16>> {
     let live_ports = crate::save::find_ports(std::env::current_dir()?, &paths[..])?;
     let mut ports = Vec::new();
@@ -29,13 +33,22 @@ pub async fn get_admin_ports(paths: Vec<PathBuf>) -> anyhow::Result<Vec<u16>> {
             ports.push(port);
             continue;
         }
-        if let Some(config) = read_config(p)? {
+        if let Some(config) = read_config(p.clone())? {
             if let Some(ai) = config.admin_interfaces {
-                if let Some(AdminInterfaceConfig {
-                    driver: InterfaceDriver::Websocket { port },
-                }) = ai.get(0)
-                {
-                    ports.push(*port)
+                match ai.get(0) {
+                    Some(AdminInterfaceConfig {
+                        driver: InterfaceDriver::Websocket { port },
+                    }) => ports.push(*port),
+                    Some(AdminInterfaceConfig {
+                        driver: InterfaceDriver::UnixSocket { path },
+                    }) => anyhow::bail!(
+                        "The admin interface of the sandbox at {} is the unix domain socket {}, \
+                        use `--running-socket {}` to call it",
+                        p.display(),
+                        path.display(),
+                        path.display()
+                    ),
+                    None => (),
                 }
             }
         }
@@ -48,6 +61,36 @@ pub(crate) async fn get_admin_api(port: u16) -> WebsocketResult<WebsocketSender>
     websocket_client_by_port(port).await.map(|p| p.0)
 }
 
+pub(crate) async fn get_admin_api_unix(path: &Path) -> WebsocketResult<WebsocketSender> {
+    tracing::debug!(?path);
+    #[cfg(unix)]
+    {
+        ws::connect_unix(path, Arc::new(WebsocketConfig::default()))
+            .await
+            .map(|p| p.0)
+    }
+    #[cfg(not(unix))]
+    {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "unix domain sockets are not supported on this platform",
+        )
+        .into())
+    }
+}
+
+/// Connect to an app interface and authenticate the connection
+/// with a token issued for the app.
+pub(crate) async fn get_app_api(
+    port: u16,
+    token: AppAuthenticationToken,
+) -> WebsocketResult<WebsocketSender> {
+    tracing::debug!(port);
+    let (mut client, _) = websocket_client_by_port(port).await?;
+    client.signal(AppAuthenticationRequest { token }).await?;
+    Ok(client)
+}
+
 async fn websocket_client_by_port(
     port: u16,
 ) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
@@ -67,6 +110,10 @@ pub(crate) fn random_admin_port(config: &mut ConductorConfig) {
                 *port = 0;
             }
         }
+        // A unix domain socket can't clash with another sandbox's port
+        Some(AdminInterfaceConfig {
+            driver: InterfaceDriver::UnixSocket { .. },
+        }) => (),
         None => {
             let port = 0;
             config.admin_interfaces = Some(vec![AdminInterfaceConfig {
@@ -45,6 +45,8 @@ pub async fn run(
             &mut cmd,
             AddAppWs {
                 port: Some(app_port),
+                unix_socket: None,
+                allowed_origins: Vec::new(),
             },
         )
         .await?;
@@ -59,6 +59,8 @@ pub fn clean(mut hc_dir: PathBuf, sandboxes: Vec<usize>) -> anyhow::Result<()> {
                 }
             }
         }
+        crate::zome_call::clean(hc_dir.clone())?;
+        crate::scenario::clean(hc_dir.clone())?;
         hc_dir.push(".hc");
         if hc_dir.exists() {
             std::fs::remove_file(hc_dir)?;
@@ -0,0 +1,460 @@
+//! # Multi-conductor scenarios
+//! A scenario is a YAML file describing a network of conductors,
+//! the apps installed on each of them and the services they share.
+//! For example three conductors with app `a` and two with apps `a` and `b`,
+//! all discovering each other through a local bootstrap server
+//! and connecting through a local proxy:
+//! ```yaml
+//! bootstrap: true
+//! proxy: true
+//! network: quic
+//! apps:
+//!   a: ./a.happ
+//!   b: ./b.happ
+//! conductors:
+//!   - count: 3
+//!     apps: [a]
+//!   - count: 2
+//!     apps: [a, b]
+//! ```
+//! Running a scenario generates a sandbox for every conductor,
+//! installs its apps with a new agent per app and keeps everything running
+//! until `ctrl-c`. The sandboxes are saved to the `.hc` file and the
+//! admin ports and agent keys to the `.hc_scenario` file, so the
+//! conductors can be reached with `hc sandbox call` while they run.
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Stdio;
+
+use anyhow::bail;
+use anyhow::ensure;
+use holochain_types::prelude::AgentPubKey;
+use holochain_types::prelude::InstalledAppId;
+use holochain_types::prelude::NetworkSeed;
+use serde::Deserialize;
+use serde::Serialize;
+use structopt::StructOpt;
+use tokio::io::AsyncBufReadExt;
+use tokio::io::BufReader;
+use tokio::process::Child;
+use tokio::process::Command;
+use url2::Url2;
+
+use crate::calls::InstallApp;
+use crate::cmds::Network;
+use crate::cmds::NetworkType;
+use crate::cmds::Quic;
+use crate::run::run_async;
+use crate::CmdRunner;
+
+/// Name of the file the running scenario is written to.
+pub const SCENARIO_FILE: &str = ".hc_scenario";
+
+/// How long to wait for a service to report its address.
+const SERVICE_START_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
+
+/// Options for running a scenario
+#[derive(Debug, StructOpt, Clone)]
+pub struct ScenarioCmd {
+    /// Path to the scenario file.
+    pub path: PathBuf,
+
+    /// Set the path to the kitsune-bootstrap binary,
+    /// used when the scenario asks for a bootstrap server.
+    #[structopt(long, env = "HC_BOOTSTRAP_PATH", default_value = "kitsune-bootstrap")]
+    pub bootstrap_path: PathBuf,
+
+    /// Set the path to the kitsune-p2p-tx2-proxy binary,
+    /// used when the scenario asks for a proxy.
+    #[structopt(long, env = "HC_PROXY_PATH", default_value = "kitsune-p2p-tx2-proxy")]
+    pub proxy_path: PathBuf,
+}
+
+/// A network of conductors to generate and run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Scenario {
+    /// Start a local bootstrap server for the conductors.
+    #[serde(default)]
+    pub bootstrap: bool,
+
+    /// Start a local proxy for the conductors.
+    /// Only possible with the quic network.
+    #[serde(default)]
+    pub proxy: bool,
+
+    /// The network the conductors use.
+    #[serde(default)]
+    pub network: ScenarioNetwork,
+
+    /// Network seed to override for every app.
+    #[serde(default)]
+    pub network_seed: Option<NetworkSeed>,
+
+    /// The root directory for the sandboxes.
+    /// Defaults to the system's temp directory.
+    #[serde(default)]
+    pub root: Option<PathBuf>,
+
+    /// The hApp bundles to install, by the app id they are installed with.
+    /// Relative paths are relative to the scenario file.
+    pub apps: BTreeMap<InstalledAppId, PathBuf>,
+
+    /// The groups of conductors to generate.
+    pub conductors: Vec<ConductorGroup>,
+}
+
+/// The network conductors in a scenario use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScenarioNetwork {
+    /// A transport that uses the QUIC protocol.
+    Quic,
+    /// A transport that uses the MDNS protocol.
+    Mdns,
+}
+
+impl Default for ScenarioNetwork {
+    fn default() -> Self {
+        Self::Quic
+    }
+}
+
+/// A number of conductors with the same apps.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConductorGroup {
+    /// How many conductors are in the group.
+    #[serde(default = "default_count")]
+    pub count: usize,
+
+    /// The ids of the apps to install on each conductor.
+    pub apps: Vec<InstalledAppId>,
+}
+
+fn default_count() -> usize {
+    1
+}
+
+/// A conductor of a running scenario.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ScenarioConductor {
+    /// The sandbox of the conductor.
+    pub path: PathBuf,
+    /// The port of the conductor's admin interface.
+    pub admin_port: u16,
+    /// The agent each app is installed with.
+    pub agents: BTreeMap<InstalledAppId, AgentPubKey>,
+}
+
+/// A running scenario, as saved to the [`SCENARIO_FILE`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunningScenario {
+    /// The url of the local bootstrap server, if one was started.
+    pub bootstrap: Option<Url2>,
+    /// The url of the local proxy, if one was started.
+    pub proxy: Option<Url2>,
+    /// The conductors in the order they were generated.
+    pub conductors: Vec<ScenarioConductor>,
+}
+
+impl Scenario {
+    /// Read a scenario from a YAML file.
+    /// Relative app paths are made relative to the file.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        let mut scenario: Self = serde_yaml::from_str(&std::fs::read_to_string(path)?)?;
+        if let Some(dir) = path.parent() {
+            for app in scenario.apps.values_mut() {
+                if app.is_relative() {
+                    *app = dir.join(&app);
+                }
+            }
+        }
+        scenario.check()?;
+        Ok(scenario)
+    }
+
+    /// Check the scenario is consistent.
+    pub fn check(&self) -> anyhow::Result<()> {
+        ensure!(
+            !self.proxy || self.network == ScenarioNetwork::Quic,
+            "A proxy can only be used with the quic network"
+        );
+        for group in &self.conductors {
+            for app_id in &group.apps {
+                ensure!(
+                    self.apps.contains_key(app_id),
+                    "App {} is not listed in the scenario's apps",
+                    app_id
+                );
+            }
+        }
+        Ok(())
+    }
+
+    fn network(&self, bootstrap: Option<Url2>, proxy: Option<Url2>) -> Network {
+        let transport = match self.network {
+            ScenarioNetwork::Mdns => NetworkType::Mdns,
+            ScenarioNetwork::Quic => NetworkType::Quic(Quic {
+                bind_to: None,
+                override_host: None,
+                override_port: None,
+                proxy,
+            }),
+        };
+        Network {
+            transport,
+            bootstrap,
+        }
+    }
+}
+
+/// Generate and run the conductors of a scenario until `ctrl-c` is pressed.
+pub async fn run(holochain_path: &Path, cmd: ScenarioCmd) -> anyhow::Result<()> {
+    let scenario = Scenario::load(&cmd.path)?;
+    let hc_dir = std::env::current_dir()?;
+
+    // The services and conductors are killed when their handles are dropped.
+    let mut services = Vec::new();
+    let proxy = if scenario.proxy {
+        let (child, url) = start_service(
+            Command::new(&cmd.proxy_path)
+                .arg("--bind-to")
+                .arg("kitsune-quic://127.0.0.1:0"),
+        )
+        .await?;
+        msg!("Started proxy at {}", url);
+        services.push(child);
+        Some(url)
+    } else {
+        None
+    };
+    let bootstrap = if scenario.bootstrap {
+        let mut command = Command::new(&cmd.bootstrap_path);
+        command.arg("--interface").arg("127.0.0.1:0");
+        if let Some(proxy) = &proxy {
+            command.arg("--proxy").arg(proxy.as_str());
+        }
+        let (child, url) = start_service(&mut command).await?;
+        msg!("Started bootstrap server at {}", url);
+        services.push(child);
+        Some(url)
+    } else {
+        None
+    };
+
+    let network = scenario.network(bootstrap.clone(), proxy.clone());
+    let mut conductors = Vec::new();
+    let mut processes = Vec::new();
+    for group in &scenario.conductors {
+        for _ in 0..group.count {
+            let path = crate::generate::generate(
+                Some(network.clone().into()),
+                scenario.root.clone(),
+                None,
+            )?;
+            let (admin_port, holochain, lair) =
+                run_async(holochain_path, path.clone(), None).await?;
+            processes.push((holochain, lair));
+
+            let mut cmd = CmdRunner::try_new(admin_port).await?;
+            let mut agents = BTreeMap::new();
+            for app_id in &group.apps {
+                let agent = crate::calls::generate_agent_pub_key(&mut cmd).await?;
+                crate::calls::install_app_bundle(
+                    &mut cmd,
+                    InstallApp {
+                        app_id: Some(app_id.clone()),
+                        agent_key: Some(agent.clone()),
+                        path: scenario.apps[app_id].clone(),
+                        network_seed: scenario.network_seed.clone(),
+                    },
+                )
+                .await?;
+                agents.insert(app_id.clone(), agent);
+            }
+
+            crate::save::save(hc_dir.clone(), vec![path.clone()])?;
+            crate::save::lock_live(hc_dir.clone(), &path, admin_port).await?;
+            msg!(
+                "Running conductor {} on admin port {} with apps {:?}",
+                path.display(),
+                admin_port,
+                group.apps
+            );
+            conductors.push(ScenarioConductor {
+                path,
+                admin_port,
+                agents,
+            });
+        }
+    }
+
+    let running = RunningScenario {
+        bootstrap,
+        proxy,
+        conductors,
+    };
+    save(hc_dir.clone(), &running)?;
+    msg!(
+        "Running scenario with {} conductors",
+        running.conductors.len()
+    );
+
+    tokio::signal::ctrl_c().await?;
+    crate::save::release_ports(hc_dir).await?;
+    drop(processes);
+    drop(services);
+    Ok(())
+}
+
+/// Save a running scenario to the [`SCENARIO_FILE`] in the `hc_dir` directory.
+pub fn save(mut hc_dir: PathBuf, running: &RunningScenario) -> anyhow::Result<()> {
+    std::fs::create_dir_all(&hc_dir)?;
+    hc_dir.push(SCENARIO_FILE);
+    std::fs::write(hc_dir, serde_yaml::to_string(running)?)?;
+    Ok(())
+}
+
+/// Load the running scenario from the [`SCENARIO_FILE`] in the `hc_dir` directory.
+pub fn load(mut hc_dir: PathBuf) -> anyhow::Result<Option<RunningScenario>> {
+    hc_dir.push(SCENARIO_FILE);
+    if !hc_dir.exists() {
+        return Ok(None);
+    }
+    Ok(Some(serde_yaml::from_str(&std::fs::read_to_string(
+        hc_dir,
+    )?)?))
+}
+
+/// Remove the [`SCENARIO_FILE`] from the `hc_dir` directory.
+pub fn clean(mut hc_dir: PathBuf) -> anyhow::Result<()> {
+    hc_dir.push(SCENARIO_FILE);
+    if hc_dir.exists() {
+        std::fs::remove_file(hc_dir)?;
+    }
+    Ok(())
+}
+
+/// Start a service which prints the url it can be reached at
+/// as the first line of its output.
+async fn start_service(command: &mut Command) -> anyhow::Result<(Child, Url2)> {
+    command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::inherit())
+        .kill_on_drop(true);
+    msg!("{:?}", command);
+    let mut child = command.spawn()?;
+    let stdout = child.stdout.take().expect("stdout is piped");
+    let mut lines = BufReader::new(stdout).lines();
+    let line = match tokio::time::timeout(SERVICE_START_TIMEOUT, lines.next_line()).await {
+        Ok(Ok(Some(line))) => line,
+        Ok(Ok(None)) => bail!("{:?} exited before reporting its address", command),
+        Ok(Err(e)) => return Err(e.into()),
+        Err(_) => bail!("Timed out waiting for {:?} to report its address", command),
+    };
+    let url = Url2::try_parse(line.trim())
+        .map_err(|e| anyhow::anyhow!("{:?} reported a bad address {}: {:?}", command, line, e))?;
+    // Keep reading so the service doesn't block on a full pipe.
+    tokio::task::spawn(async move {
+        while let Ok(Some(line)) = lines.next_line().await {
+            println!("{}", line);
+        }
+    });
+    Ok((child, url))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Write a scenario file to a new directory and load it.
+    fn load_yaml(name: &str, yaml: &str) -> (PathBuf, anyhow::Result<Scenario>) {
+        let dir =
+            std::env::temp_dir().join(format!("hc_scenario_test_{}_{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("scenario.yaml");
+        std::fs::write(&path, yaml).unwrap();
+        let scenario = Scenario::load(&path);
+        std::fs::remove_dir_all(&dir).unwrap();
+        (dir, scenario)
+    }
+
+    #[test]
+    fn app_paths_are_relative_to_the_file() {
+        let (dir, scenario) = load_yaml(
+            "relative",
+            "
+apps:
+  a: ./a.happ
+  b: /abs/b.happ
+conductors:
+  - count: 2
+    apps: [a, b]
+",
+        );
+        let scenario = scenario.unwrap();
+        assert_eq!(scenario.apps["a"], dir.join("./a.happ"));
+        assert_eq!(scenario.apps["b"], PathBuf::from("/abs/b.happ"));
+        assert_eq!(scenario.network, ScenarioNetwork::Quic);
+        assert_eq!(scenario.conductors[0].count, 2);
+    }
+
+    #[test]
+    fn unknown_app_ids_are_rejected() {
+        let (_, scenario) = load_yaml(
+            "unknown_app",
+            "
+apps:
+  a: ./a.happ
+conductors:
+  - apps: [a, b]
+",
+        );
+        assert!(scenario
+            .unwrap_err()
+            .to_string()
+            .contains("App b is not listed"));
+    }
+
+    #[test]
+    fn proxy_needs_the_quic_network() {
+        let (_, scenario) = load_yaml(
+            "proxy",
+            "
+proxy: true
+network: mdns
+apps: {}
+conductors: []
+",
+        );
+        assert!(scenario
+            .unwrap_err()
+            .to_string()
+            .contains("only be used with the quic network"));
+    }
+
+    #[test]
+    fn unknown_fields_and_networks_are_rejected() {
+        let (_, scenario) = load_yaml(
+            "unknown_field",
+            "
+apps: {}
+conductors:
+  - apps: []
+    agents: 2
+",
+        );
+        assert!(scenario.is_err());
+        let (_, scenario) = load_yaml(
+            "mem_network",
+            "
+network: mem
+apps: {}
+conductors: []
+",
+        );
+        assert!(scenario.is_err());
+    }
+}
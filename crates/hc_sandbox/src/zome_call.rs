@@ -0,0 +1,388 @@
+//! # Signing zome calls from the sandbox
+//! Zome calls made over an app interface must be signed by the key
+//! they claim as provenance. The sandbox doesn't have access to the
+//! conductor's keystore, so it generates its own signing key per cell,
+//! grants it a capability to call any function in the cell and keeps both
+//! in a `.hc_auth` file next to the `.hc` file so later calls can reuse them.
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::io::Write;
+use std::path::PathBuf;
+
+use anyhow::anyhow;
+use holochain_conductor_api::ZomeCall;
+use holochain_types::prelude::AgentPubKey;
+use holochain_types::prelude::AnyLinkableHash;
+use holochain_types::prelude::CapAccess;
+use holochain_types::prelude::CapSecret;
+use holochain_types::prelude::CellId;
+use holochain_types::prelude::DnaHash;
+use holochain_types::prelude::ExternIO;
+use holochain_types::prelude::FunctionName;
+use holochain_types::prelude::GrantedFunctions;
+use holochain_types::prelude::Nonce256Bits;
+use holochain_types::prelude::Signature;
+use holochain_types::prelude::Timestamp;
+use holochain_types::prelude::ZomeCallCapGrant;
+use holochain_types::prelude::ZomeCallUnsigned;
+use holochain_types::prelude::ZomeName;
+use holochain_types::prelude::CAP_SECRET_BYTES;
+use serde::Deserialize;
+use serde::Serialize;
+
+/// How long a signed zome call is valid for.
+const ZOME_CALL_EXPIRY: std::time::Duration = std::time::Duration::from_secs(5 * 60);
+
+/// A key which has been granted a capability to call the zomes of a cell.
+#[derive(Clone, Serialize, Deserialize)]
+pub struct SigningCredentials {
+    /// The ed25519 secret key used to sign calls.
+    secret_key: Vec<u8>,
+    /// The public half of the key, which is the provenance of the calls.
+    signing_key: AgentPubKey,
+    /// The secret of the capability granted to the key.
+    cap_secret: CapSecret,
+}
+
+impl std::fmt::Debug for SigningCredentials {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SigningCredentials")
+            .field("signing_key", &self.signing_key)
+            .finish()
+    }
+}
+
+impl SigningCredentials {
+    /// Generate a new signing key and capability secret.
+    pub async fn generate() -> anyhow::Result<Self> {
+        let pub_key = sodoken::BufWriteSized::new_no_lock();
+        let sec_key = sodoken::BufWriteSized::new_no_lock();
+        sodoken::sign::keypair(pub_key.clone(), sec_key.clone()).await?;
+        let secret = sodoken::BufWrite::new_no_lock(CAP_SECRET_BYTES);
+        sodoken::random::bytes_buf(secret.clone()).await?;
+
+        let signing_key = AgentPubKey::from_raw_32(pub_key.read_lock().to_vec());
+        let cap_secret = CapSecret::try_from(secret.read_lock().to_vec())
+            .map_err(|e| anyhow!("Failed to create cap secret: {:?}", e))?;
+        Ok(Self {
+            secret_key: sec_key.read_lock().to_vec(),
+            signing_key,
+            cap_secret,
+        })
+    }
+
+    /// The key which signs calls made with these credentials.
+    pub fn signing_key(&self) -> &AgentPubKey {
+        &self.signing_key
+    }
+
+    /// The grant to make for these credentials so they can call
+    /// every function of a cell.
+    pub fn cap_grant(&self) -> ZomeCallCapGrant {
+        let mut assignees = BTreeSet::new();
+        assignees.insert(self.signing_key.clone());
+        ZomeCallCapGrant::new(
+            "hc-sandbox".to_string(),
+            CapAccess::Assigned {
+                secret: self.cap_secret,
+                assignees,
+            },
+            GrantedFunctions::All,
+        )
+    }
+
+    /// Make a zome call to a cell signed with these credentials, with a
+    /// fresh nonce which expires after five minutes.
+    pub async fn sign(
+        &self,
+        cell_id: CellId,
+        zome_name: ZomeName,
+        fn_name: FunctionName,
+        payload: ExternIO,
+    ) -> anyhow::Result<ZomeCall> {
+        let nonce = sodoken::BufWrite::new_no_lock(32);
+        sodoken::random::bytes_buf(nonce.clone()).await?;
+        let mut nonce_bytes = [0; 32];
+        nonce_bytes.copy_from_slice(&nonce.read_lock()[..]);
+
+        let call = ZomeCallUnsigned {
+            provenance: self.signing_key.clone(),
+            cell_id,
+            zome_name,
+            fn_name,
+            cap_secret: Some(self.cap_secret),
+            payload,
+            nonce: Nonce256Bits::from(nonce_bytes),
+            expires_at: Timestamp(
+                Timestamp::now().as_micros() + ZOME_CALL_EXPIRY.as_micros() as i64,
+            ),
+        };
+
+        let sec_key: [u8; 64] = self.secret_key[..]
+            .try_into()
+            .map_err(|_| anyhow!("The stored signing key has the wrong length"))?;
+        let sec_key = sodoken::BufReadSized::new_no_lock(sec_key);
+        let signature = sodoken::BufWriteSized::new_no_lock();
+        sodoken::sign::detached(signature.clone(), call.data_to_sign()?.to_vec(), sec_key).await?;
+        let mut signature_bytes = [0; 64];
+        signature_bytes.copy_from_slice(&signature.read_lock()[..]);
+
+        Ok(ZomeCall {
+            cell_id: call.cell_id,
+            zome_name: call.zome_name,
+            fn_name: call.fn_name,
+            payload: call.payload,
+            cap_secret: call.cap_secret,
+            provenance: call.provenance,
+            signature: Signature::from(signature_bytes),
+            nonce: call.nonce,
+            expires_at: call.expires_at,
+        })
+    }
+}
+
+/// Load the signing credentials stored for a cell in the `.hc_auth` file
+/// in the `hc_dir` directory.
+pub fn load_credentials(
+    hc_dir: PathBuf,
+    cell_id: &CellId,
+) -> anyhow::Result<Option<SigningCredentials>> {
+    Ok(load_all(hc_dir)?.remove(&cell_id.to_string()))
+}
+
+/// Store the signing credentials for a cell in the `.hc_auth` file
+/// in the `hc_dir` directory, replacing any stored for it before.
+/// The file holds secret keys, so on unix only its owner may read it.
+pub fn save_credentials(
+    hc_dir: PathBuf,
+    cell_id: &CellId,
+    credentials: SigningCredentials,
+) -> anyhow::Result<()> {
+    let mut all = load_all(hc_dir.clone())?;
+    all.insert(cell_id.to_string(), credentials);
+    std::fs::create_dir_all(&hc_dir)?;
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(auth_path(hc_dir))?;
+    // The mode only applies to new files, so tighten files made before.
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(serde_yaml::to_string(&all)?.as_bytes())?;
+    Ok(())
+}
+
+/// Remove the `.hc_auth` file from the `hc_dir` directory.
+pub fn clean(hc_dir: PathBuf) -> anyhow::Result<()> {
+    let path = auth_path(hc_dir);
+    if path.exists() {
+        std::fs::remove_file(path)?;
+    }
+    Ok(())
+}
+
+fn load_all(hc_dir: PathBuf) -> anyhow::Result<BTreeMap<String, SigningCredentials>> {
+    let path = auth_path(hc_dir);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    Ok(serde_yaml::from_str(&std::fs::read_to_string(path)?)?)
+}
+
+fn auth_path(mut hc_dir: PathBuf) -> PathBuf {
+    hc_dir.push(".hc_auth");
+    hc_dir
+}
+
+/// Decode the msgpack returned by a zome call into JSON.
+///
+/// Binary values which are holo hashes are shown in their base64 form,
+/// other binary values as arrays of bytes.
+pub fn msgpack_to_json(bytes: &[u8]) -> anyhow::Result<serde_json::Value> {
+    let value = rmpv::decode::read_value(&mut &bytes[..])?;
+    Ok(to_json(value))
+}
+
+fn to_json(value: rmpv::Value) -> serde_json::Value {
+    use rmpv::Value;
+    match value {
+        Value::Nil => serde_json::Value::Null,
+        Value::Boolean(b) => serde_json::Value::Bool(b),
+        Value::Integer(i) => match (i.as_u64(), i.as_i64()) {
+            (Some(u), _) => u.into(),
+            (_, Some(i)) => i.into(),
+            _ => serde_json::Value::Null,
+        },
+        Value::F32(f) => float_to_json(f as f64),
+        Value::F64(f) => float_to_json(f),
+        Value::String(s) => match s.into_str() {
+            Some(s) => serde_json::Value::String(s),
+            None => serde_json::Value::Null,
+        },
+        Value::Binary(b) => binary_to_json(b),
+        Value::Array(a) => serde_json::Value::Array(a.into_iter().map(to_json).collect()),
+        Value::Map(m) => serde_json::Value::Object(
+            m.into_iter()
+                .map(|(k, v)| {
+                    let k = match k {
+                        Value::String(s) => s.into_str().unwrap_or_default(),
+                        k => to_json(k).to_string(),
+                    };
+                    (k, to_json(v))
+                })
+                .collect(),
+        ),
+        Value::Ext(_, b) => binary_to_json(b),
+    }
+}
+
+fn float_to_json(f: f64) -> serde_json::Value {
+    serde_json::Number::from_f64(f)
+        .map(serde_json::Value::Number)
+        .unwrap_or(serde_json::Value::Null)
+}
+
+fn binary_to_json(b: Vec<u8>) -> serde_json::Value {
+    if b.len() == 39 {
+        if let Ok(h) = AgentPubKey::from_raw_39(b.clone()) {
+            return serde_json::Value::String(h.to_string());
+        }
+        if let Ok(h) = AnyLinkableHash::from_raw_39(b.clone()) {
+            return serde_json::Value::String(h.to_string());
+        }
+        if let Ok(h) = DnaHash::from_raw_39(b.clone()) {
+            return serde_json::Value::String(h.to_string());
+        }
+    }
+    serde_json::Value::Array(b.into_iter().map(Into::into).collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_hc_dir(credentials: &SigningCredentials) -> PathBuf {
+        std::env::temp_dir().join(format!("hc_auth_test_{}", credentials.signing_key()))
+    }
+
+    fn cell_id() -> CellId {
+        CellId::new(
+            DnaHash::from_raw_32(vec![1; 32]),
+            AgentPubKey::from_raw_32(vec![2; 32]),
+        )
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn signed_calls_verify_against_the_signing_key() {
+        let credentials = SigningCredentials::generate().await.unwrap();
+        let call = credentials
+            .sign(
+                cell_id(),
+                "zome".into(),
+                "fn".into(),
+                ExternIO::encode(()).unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(&call.provenance, credentials.signing_key());
+        assert_eq!(call.cap_secret, Some(credentials.cap_secret));
+        assert!(call.expires_at > Timestamp::now());
+
+        let unsigned = ZomeCallUnsigned {
+            provenance: call.provenance.clone(),
+            cell_id: call.cell_id.clone(),
+            zome_name: call.zome_name.clone(),
+            fn_name: call.fn_name.clone(),
+            cap_secret: call.cap_secret,
+            payload: call.payload.clone(),
+            nonce: call.nonce,
+            expires_at: call.expires_at,
+        };
+        let mut pub_key = [0; 32];
+        pub_key.copy_from_slice(credentials.signing_key().get_raw_32());
+        assert!(sodoken::sign::verify_detached(
+            sodoken::BufReadSized::new_no_lock(call.signature.0),
+            sodoken::BufRead::new_no_lock(&unsigned.data_to_sign().unwrap()),
+            sodoken::BufReadSized::new_no_lock(pub_key),
+        )
+        .await
+        .unwrap());
+
+        // Every call gets its own nonce
+        let other = credentials
+            .sign(
+                cell_id(),
+                "zome".into(),
+                "fn".into(),
+                ExternIO::encode(()).unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_ne!(call.nonce.into_inner(), other.nonce.into_inner());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn credentials_round_trip_through_the_auth_file() {
+        let credentials = SigningCredentials::generate().await.unwrap();
+        let hc_dir = temp_hc_dir(&credentials);
+        let cell_id = cell_id();
+        assert!(load_credentials(hc_dir.clone(), &cell_id)
+            .unwrap()
+            .is_none());
+
+        save_credentials(hc_dir.clone(), &cell_id, credentials.clone()).unwrap();
+        let loaded = load_credentials(hc_dir.clone(), &cell_id).unwrap().unwrap();
+        assert_eq!(loaded.signing_key(), credentials.signing_key());
+        assert_eq!(loaded.cap_secret, credentials.cap_secret);
+        assert_eq!(loaded.secret_key, credentials.secret_key);
+
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(auth_path(hc_dir.clone()))
+                .unwrap()
+                .permissions()
+                .mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        clean(hc_dir.clone()).unwrap();
+        assert!(load_credentials(hc_dir.clone(), &cell_id)
+            .unwrap()
+            .is_none());
+        std::fs::remove_dir(hc_dir).unwrap();
+    }
+
+    #[test]
+    fn msgpack_is_decoded_to_json() {
+        let hash = AgentPubKey::from_raw_32(vec![3; 32]);
+        let value = rmpv::Value::Map(vec![
+            (
+                "hash".into(),
+                rmpv::Value::Binary(hash.get_raw_39().to_vec()),
+            ),
+            ("bytes".into(), rmpv::Value::Binary(vec![1, 2])),
+            (
+                "list".into(),
+                rmpv::Value::Array(vec![rmpv::Value::Nil, true.into(), (-1).into(), 1.5.into()]),
+            ),
+        ]);
+        let mut bytes = Vec::new();
+        rmpv::encode::write_value(&mut bytes, &value).unwrap();
+        assert_eq!(
+            msgpack_to_json(&bytes).unwrap(),
+            serde_json::json!({
+                "hash": hash.to_string(),
+                "bytes": [1, 2],
+                "list": [null, true, -1, 1.5],
+            })
+        );
+    }
+}
@@ -48,11 +48,11 @@ fn root_ensures() {
     mock.expect_hash().returning(hash_entry_mock);
     mock.expect_get_links()
         .once()
-        .with(eq(vec![GetLinksInput {
-            base_address: root_hash().unwrap(),
-            link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
-            tag_prefix: Some(Path::from("foo").make_tag().unwrap()),
-        }]))
+        .with(eq(vec![GetLinksInput::new(
+            root_hash().unwrap(),
+            LinkTypeFilter::single_type(0.into(), 0.into()),
+            Some(Path::from("foo").make_tag().unwrap()),
+        )]))
         .returning(|_| {
             Ok(vec![vec![Link {
                 target: Path::from("foo").path_entry_hash().unwrap().into(),
@@ -207,11 +207,11 @@ fn paths_exists() {
     // Return links that match the input.
     mock.expect_get_links()
         .once()
-        .with(eq(vec![GetLinksInput {
-            base_address: Path::from("foo").path_entry_hash().unwrap().into(),
-            link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
-            tag_prefix: Some(Path::from("bar").make_tag().unwrap()),
-        }]))
+        .with(eq(vec![GetLinksInput::new(
+            Path::from("foo").path_entry_hash().unwrap().into(),
+            LinkTypeFilter::single_type(0.into(), 0.into()),
+            Some(Path::from("bar").make_tag().unwrap()),
+        )]))
         .returning(|_| {
             Ok(vec![vec![Link {
                 target: Path::from("foo.bar").path_entry_hash().unwrap().into(),
@@ -224,11 +224,11 @@ fn paths_exists() {
         });
     mock.expect_get_links()
         .once()
-        .with(eq(vec![GetLinksInput {
-            base_address: Path::from("foo.bar").path_entry_hash().unwrap().into(),
-            link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
-            tag_prefix: Some(Path::from("baz").make_tag().unwrap()),
-        }]))
+        .with(eq(vec![GetLinksInput::new(
+            Path::from("foo.bar").path_entry_hash().unwrap().into(),
+            LinkTypeFilter::single_type(0.into(), 0.into()),
+            Some(Path::from("baz").make_tag().unwrap()),
+        )]))
         .returning(|_| {
             Ok(vec![vec![Link {
                 target: Path::from("foo.bar.baz").path_entry_hash().unwrap().into(),
@@ -312,101 +312,101 @@ fn children() {
     mock.expect_hash().returning(hash_entry_mock);
     // ROOT -[foo]-> foo
     mock.expect_get_links()
-        .with(eq(vec![GetLinksInput {
-            base_address: root_hash().unwrap(),
-            link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
-            tag_prefix: Some(Path::from("foo").make_tag().unwrap()),
-        }]))
+        .with(eq(vec![GetLinksInput::new(
+            root_hash().unwrap(),
+            LinkTypeFilter::single_type(0.into(), 0.into()),
+            Some(Path::from("foo").make_tag().unwrap()),
+        )]))
         .returning({
             let foo = foo.clone();
             move |_| Ok(vec![vec![foo.clone()]])
         });
     // foo -[bar]-> foo.bar
     mock.expect_get_links()
-        .with(eq(vec![GetLinksInput {
-            base_address: Path::from("foo").path_entry_hash().unwrap().into(),
-            link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
-            tag_prefix: Some(Path::from("bar").make_tag().unwrap()),
-        }]))
+        .with(eq(vec![GetLinksInput::new(
+            Path::from("foo").path_entry_hash().unwrap().into(),
+            LinkTypeFilter::single_type(0.into(), 0.into()),
+            Some(Path::from("bar").make_tag().unwrap()),
+        )]))
         .returning({
             let foo_bar = foo_bar.clone();
             move |_| Ok(vec![vec![foo_bar.clone()]])
         });
     // foo -[bar2]-> foo.bar2
     mock.expect_get_links()
-        .with(eq(vec![GetLinksInput {
-            base_address: Path::from("foo").path_entry_hash().unwrap().into(),
-            link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
-            tag_prefix: Some(Path::from("bar2").make_tag().unwrap()),
-        }]))
+        .with(eq(vec![GetLinksInput::new(
+            Path::from("foo").path_entry_hash().unwrap().into(),
+            LinkTypeFilter::single_type(0.into(), 0.into()),
+            Some(Path::from("bar2").make_tag().unwrap()),
+        )]))
         .returning({
             let foo_bar2 = foo_bar2.clone();
             move |_| Ok(vec![vec![foo_bar2.clone()]])
         });
     // foo.bar -[baz]-> foo.bar.baz
     mock.expect_get_links()
-        .with(eq(vec![GetLinksInput {
-            base_address: Path::from("foo.bar").path_entry_hash().unwrap().into(),
-            link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
-            tag_prefix: Some(Path::from("baz").make_tag().unwrap()),
-        }]))
+        .with(eq(vec![GetLinksInput::new(
+            Path::from("foo.bar").path_entry_hash().unwrap().into(),
+            LinkTypeFilter::single_type(0.into(), 0.into()),
+            Some(Path::from("baz").make_tag().unwrap()),
+        )]))
         .returning({
             let foo_bar_baz = foo_bar_baz.clone();
             move |_| Ok(vec![vec![foo_bar_baz.clone()]])
         });
     // foo.bar2 -[baz2]-> foo.bar2.baz2
     mock.expect_get_links()
-        .with(eq(vec![GetLinksInput {
-            base_address: Path::from("foo.bar2").path_entry_hash().unwrap().into(),
-            link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
-            tag_prefix: Some(Path::from("baz2").make_tag().unwrap()),
-        }]))
+        .with(eq(vec![GetLinksInput::new(
+            Path::from("foo.bar2").path_entry_hash().unwrap().into(),
+            LinkTypeFilter::single_type(0.into(), 0.into()),
+            Some(Path::from("baz2").make_tag().unwrap()),
+        )]))
         .returning({
             let foo_bar2_baz2 = foo_bar2_baz2.clone();
             move |_| Ok(vec![vec![foo_bar2_baz2.clone()]])
         });
     // foo -[]-> (foo.bar, foo.bar2)
     mock.expect_get_links()
-        .with(eq(vec![GetLinksInput {
-            base_address: Path::from("foo").path_entry_hash().unwrap().into(),
-            link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
-            tag_prefix: None,
-        }]))
+        .with(eq(vec![GetLinksInput::new(
+            Path::from("foo").path_entry_hash().unwrap().into(),
+            LinkTypeFilter::single_type(0.into(), 0.into()),
+            None,
+        )]))
         .returning(move |_| Ok(vec![vec![foo_bar.clone(), foo_bar2.clone()]]));
     // foo.bar -[]-> foo.bar.baz
     mock.expect_get_links()
-        .with(eq(vec![GetLinksInput {
-            base_address: Path::from("foo.bar").path_entry_hash().unwrap().into(),
-            link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
-            tag_prefix: None,
-        }]))
+        .with(eq(vec![GetLinksInput::new(
+            Path::from("foo.bar").path_entry_hash().unwrap().into(),
+            LinkTypeFilter::single_type(0.into(), 0.into()),
+            None,
+        )]))
         .returning(move |_| Ok(vec![vec![foo_bar_baz.clone()]]));
     // foo.bar2 -[]-> foo.bar2.baz2
     mock.expect_get_links()
-        .with(eq(vec![GetLinksInput {
-            base_address: Path::from("foo.bar2").path_entry_hash().unwrap().into(),
-            link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
-            tag_prefix: None,
-        }]))
+        .with(eq(vec![GetLinksInput::new(
+            Path::from("foo.bar2").path_entry_hash().unwrap().into(),
+            LinkTypeFilter::single_type(0.into(), 0.into()),
+            None,
+        )]))
         .returning(move |_| Ok(vec![vec![foo_bar2_baz2.clone()]]));
     // foo.bar.baz -[]-> ()
     mock.expect_get_links()
-        .with(eq(vec![GetLinksInput {
-            base_address: Path::from("foo.bar.baz").path_entry_hash().unwrap().into(),
-            link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
-            tag_prefix: None,
-        }]))
+        .with(eq(vec![GetLinksInput::new(
+            Path::from("foo.bar.baz").path_entry_hash().unwrap().into(),
+            LinkTypeFilter::single_type(0.into(), 0.into()),
+            None,
+        )]))
         .returning(|_| Ok(vec![vec![]]));
     // foo.bar2.baz2 -[]-> ()
     mock.expect_get_links()
-        .with(eq(vec![GetLinksInput {
-            base_address: Path::from("foo.bar2.baz2")
+        .with(eq(vec![GetLinksInput::new(
+            Path::from("foo.bar2.baz2")
                 .path_entry_hash()
                 .unwrap()
                 .into(),
-            link_type: LinkTypeFilter::single_type(0.into(), 0.into()),
-            tag_prefix: None,
-        }]))
+            LinkTypeFilter::single_type(0.into(), 0.into()),
+            None,
+        )]))
         .returning(|_| Ok(vec![vec![]]));
     set_hdk(mock);
 
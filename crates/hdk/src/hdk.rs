@@ -52,6 +52,7 @@ pub trait HdkT: HdiT {
         &self,
         get_links_input: Vec<GetLinksInput>,
     ) -> ExternResult<Vec<LinkDetails>>;
+    fn count_links(&self, count_links_input: CountLinksInput) -> ExternResult<LinkCount>;
     // P2P
     fn call(&self, call: Vec<Call>) -> ExternResult<Vec<ZomeCallResponse>>;
     fn emit_signal(&self, app_signal: AppSignal) -> ExternResult<()>;
@@ -122,6 +123,7 @@ mockall::mock! {
             &self,
             get_links_input: Vec<GetLinksInput>,
         ) -> ExternResult<Vec<LinkDetails>>;
+        fn count_links(&self, count_links_input: CountLinksInput) -> ExternResult<LinkCount>;
         // P2P
         fn call(&self, call: Vec<Call>) -> ExternResult<Vec<ZomeCallResponse>>;
         fn emit_signal(&self, app_signal: AppSignal) -> ExternResult<()>;
@@ -323,6 +325,9 @@ impl HdkT for ErrHdk {
     fn get_link_details(&self, _: Vec<GetLinksInput>) -> ExternResult<Vec<LinkDetails>> {
         Self::err()
     }
+    fn count_links(&self, _: CountLinksInput) -> ExternResult<LinkCount> {
+        Self::err()
+    }
     // P2P
     fn call(&self, _: Vec<Call>) -> ExternResult<Vec<ZomeCallResponse>> {
         Self::err()
@@ -519,6 +524,9 @@ impl HdkT for HostHdk {
     ) -> ExternResult<Vec<LinkDetails>> {
         host_call::<Vec<GetLinksInput>, Vec<LinkDetails>>(__get_link_details, get_links_input)
     }
+    fn count_links(&self, count_links_input: CountLinksInput) -> ExternResult<LinkCount> {
+        host_call::<CountLinksInput, LinkCount>(__count_links, count_links_input)
+    }
     fn call(&self, call: Vec<Call>) -> ExternResult<Vec<ZomeCallResponse>> {
         host_call::<Vec<Call>, Vec<ZomeCallResponse>>(__call, call)
     }
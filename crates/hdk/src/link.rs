@@ -155,6 +155,33 @@ pub fn get_links(
         .unwrap())
 }
 
+/// Returns the links that match a [`GetLinksInput`], which can also filter by the author
+/// of the links and the time they were created, and return them a page at a time.
+///
+/// Links are returned in the order they were created. Pass the cursor of the last link
+/// of a page to [`GetLinksInput::cursor`] to get the next page.
+///
+/// ```ignore
+/// let input = GetLinksInput::new(base.into(), LinkTypes::Comment.try_into_filter()?, None)
+///     .author(agent)
+///     .after(since)
+///     .limit(20);
+/// let page = get_links_filtered(input.clone())?;
+/// let next_page = match page.last() {
+///     Some(link) => get_links_filtered(input.cursor(link.cursor()))?,
+///     None => vec![],
+/// };
+/// ```
+///
+/// See [ `get_links` ].
+pub fn get_links_filtered(input: GetLinksInput) -> ExternResult<Vec<Link>> {
+    Ok(HDK
+        .with(|h| h.borrow().get_links(vec![input]))?
+        .into_iter()
+        .next()
+        .unwrap())
+}
+
 /// Get all link creates and deletes that reference a base entry hash, optionally filtered by type or tag.
 ///
 /// Type can be filtered by providing a variant of the link types, or a range of them. To get links of
@@ -195,3 +222,38 @@ pub fn get_link_details(
         .next()
         .unwrap())
 }
+
+/// Returns the link creates and deletes that match a [`GetLinksInput`], which can also
+/// filter by the author of the links and the time they were created, and return them
+/// a page at a time.
+///
+/// Pass [`LinkDetails::next_cursor`] to [`GetLinksInput::cursor`] to get the next page.
+///
+/// See [ `get_link_details` ] and [ `get_links_filtered` ].
+pub fn get_link_details_filtered(input: GetLinksInput) -> ExternResult<LinkDetails> {
+    Ok(HDK
+        .with(|h| h.borrow().get_link_details(vec![input]))?
+        .into_iter()
+        .next()
+        .unwrap())
+}
+
+/// Counts the links that match a [`GetLinksInput`] without getting them.
+///
+/// Only the hashes of the links cross the network, so this is much cheaper than
+/// `get_links(..)?.len()` for showing how many links there are, e.g. "42 likes".
+///
+/// See [ `get_links_filtered` ] for how links can be filtered and paged.
+pub fn count_links(input: GetLinksInput) -> ExternResult<usize> {
+    HDK.with(|h| h.borrow().count_links(CountLinksInput::new(input, false)))
+        .map(|count| count.count as usize)
+}
+
+/// Returns the hashes of the create actions of the links that match a [`GetLinksInput`],
+/// in the order the links were created, without getting the links.
+///
+/// See [ `count_links` ].
+pub fn get_link_hashes(input: GetLinksInput) -> ExternResult<Vec<ActionHash>> {
+    HDK.with(|h| h.borrow().count_links(CountLinksInput::new(input, true)))
+        .map(|count| count.create_link_hashes.unwrap_or_default())
+}
@@ -40,10 +40,14 @@ pub use crate::info::agent_info;
 pub use crate::info::call_info;
 pub use crate::info::dna_info;
 pub use crate::info::zome_info;
+pub use crate::link::count_links;
 pub use crate::link::create_link;
 pub use crate::link::delete_link;
 pub use crate::link::get_link_details;
+pub use crate::link::get_link_details_filtered;
+pub use crate::link::get_link_hashes;
 pub use crate::link::get_links;
+pub use crate::link::get_links_filtered;
 pub use crate::link::LinkTypeFilterExt;
 pub use crate::map_extern;
 pub use crate::map_extern::ExternResult;
@@ -129,6 +133,7 @@ macro_rules! holochain_externs {
             __get_details,
             __get_links,
             __get_link_details,
+            __count_links,
             __get_agent_activity,
             __must_get_entry,
             __must_get_valid_record,
@@ -0,0 +1,35 @@
+use std::net::ToSocketAddrs;
+
+use structopt::StructOpt;
+
+#[derive(Debug, StructOpt)]
+#[structopt(
+    name = "holochain-chc-server",
+    about = "A reference Chain Head Coordinator server, holding chains in memory. For testing only."
+)]
+struct Opt {
+    /// Bind to this interface
+    #[structopt(short, long, default_value = "127.0.0.1:0")]
+    interface: String,
+}
+
+#[tokio::main(flavor = "multi_thread")]
+async fn main() {
+    let opt = Opt::from_args();
+
+    let addr = opt
+        .interface
+        .as_str()
+        .to_socket_addrs()
+        .expect("Invalid interface address")
+        .next()
+        .expect("Interface address did not resolve");
+
+    match holochain::conductor::chc::chc_server::run(addr).await {
+        Ok((driver, addr, _shutdown)) => {
+            println!("http://{}", addr);
+            driver.await;
+        }
+        Err(err) => eprintln!("{}", err),
+    }
+}
@@ -29,12 +29,14 @@ pub mod interactive;
 pub mod interface;
 pub mod kitsune_host_impl;
 pub mod manager;
+pub mod metrics;
 pub mod p2p_agent_store;
 pub mod paths;
 #[allow(missing_docs)]
 pub mod ribosome_store;
 pub mod space;
 pub mod state;
+pub mod warrant_queue;
 
 pub use cell::error::CellError;
 pub use cell::Cell;
@@ -165,12 +165,7 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 Ok(AdminResponse::DnasListed(dna_list))
             }
             GenerateAgentPubKey => {
-                let agent_pub_key = self
-                    .conductor_handle
-                    .keystore()
-                    .clone()
-                    .new_sign_keypair_random()
-                    .await?;
+                let agent_pub_key = self.conductor_handle.generate_agent_key().await?;
                 Ok(AdminResponse::AgentPubKeyGenerated(agent_pub_key))
             }
             ListCellIds => {
@@ -227,14 +222,42 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::AppStarted(app.status().is_running()))
             }
-            AttachAppInterface { port } => {
-                let port = port.unwrap_or(0);
-                let port = self
+            AttachAppInterface {
+                port,
+                unix_socket,
+                allowed_origins,
+            } => {
+                let port = match (port, unix_socket) {
+                    (None, Some(path)) => {
+                        self.conductor_handle
+                            .clone()
+                            .add_unix_socket_app_interface(path, allowed_origins)
+                            .await?;
+                        0
+                    }
+                    (Some(_), Some(_)) => {
+                        return Err(ConductorApiError::other(
+                            "An app interface can't have both a port and a unix socket",
+                        ))
+                    }
+                    (port, None) => {
+                        self.conductor_handle
+                            .clone()
+                            .add_app_interface(
+                                either::Either::Left(port.unwrap_or(0)),
+                                allowed_origins,
+                            )
+                            .await?
+                    }
+                };
+                Ok(AdminResponse::AppInterfaceAttached { port })
+            }
+            IssueAppAuthenticationToken(payload) => {
+                let issued = self
                     .conductor_handle
-                    .clone()
-                    .add_app_interface(either::Either::Left(port))
+                    .issue_app_authentication_token(payload)
                     .await?;
-                Ok(AdminResponse::AppInterfaceAttached { port })
+                Ok(AdminResponse::AppAuthenticationTokenIssued(issued))
             }
             ListAppInterfaces => {
                 let interfaces = self.conductor_handle.list_app_interfaces().await?;
@@ -258,6 +281,27 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                 let dump = self.conductor_handle.dump_network_metrics(dna_hash).await?;
                 Ok(AdminResponse::NetworkMetricsDumped(dump))
             }
+            GetGossipThrottle => {
+                let status = self.conductor_handle.get_gossip_throttle().await?;
+                Ok(AdminResponse::GossipThrottle(status))
+            }
+            UpdateGossipThrottle { config } => {
+                self.conductor_handle.update_gossip_throttle(config).await?;
+                Ok(AdminResponse::GossipThrottleUpdated)
+            }
+            GetTuningParams { dna_hash } => {
+                let tuning_params = self.conductor_handle.get_tuning_params(dna_hash).await?;
+                Ok(AdminResponse::TuningParams(tuning_params))
+            }
+            UpdateTuningParams {
+                dna_hash,
+                tuning_params,
+            } => {
+                self.conductor_handle
+                    .update_tuning_params(dna_hash, tuning_params)
+                    .await?;
+                Ok(AdminResponse::TuningParamsUpdated)
+            }
             AddAgentInfo { agent_infos } => {
                 self.conductor_handle.add_agent_infos(agent_infos).await?;
                 Ok(AdminResponse::AgentInfoAdded)
@@ -291,6 +335,41 @@ impl AdminInterfaceApi for RealAdminInterfaceApi {
                     .await?;
                 Ok(AdminResponse::CloneCellDeleted)
             }
+            EnableAppRole(payload) => {
+                let installed_app_id = payload.app_id.clone();
+                self.conductor_handle
+                    .clone()
+                    .enable_app_role(*payload)
+                    .await?;
+                let app_info = self
+                    .conductor_handle
+                    .get_app_info(&installed_app_id)
+                    .await?
+                    .ok_or(ConductorError::AppNotInstalled(installed_app_id))?;
+                Ok(AdminResponse::AppRoleEnabled(app_info))
+            }
+            MigrateAgent(payload) => {
+                let cell_id = self
+                    .conductor_handle
+                    .clone()
+                    .migrate_agent(*payload)
+                    .await?;
+                Ok(AdminResponse::AgentMigrated(cell_id))
+            }
+            RevokeAgentKey { agent_key } => {
+                let key_state = self.conductor_handle.revoke_agent_key(agent_key).await?;
+                Ok(AdminResponse::AgentKeyRevoked(key_state))
+            }
+            RotateAgentKey { agent_key } => {
+                let new_key = self.conductor_handle.rotate_agent_key(agent_key).await?;
+                Ok(AdminResponse::AgentKeyRotated(new_key))
+            }
+            UnblockAgent { dna_hash, agent } => {
+                self.conductor_handle
+                    .unblock_agent(&dna_hash, agent)
+                    .await?;
+                Ok(AdminResponse::AgentUnblocked)
+            }
         }
     }
 }
@@ -458,6 +537,65 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread")]
+    async fn update_gossip_throttle() -> Result<()> {
+        use kitsune_p2p::{GossipBandwidth, GossipScheduleWindow, GossipThrottleConfig};
+
+        observability::test_run().ok();
+        let env_dir = test_db_dir();
+        let handle = Conductor::builder().test(env_dir.path(), &[]).await?;
+        let shutdown = handle.take_shutdown_handle().unwrap();
+        let admin_api = RealAdminInterfaceApi::new(handle.clone());
+
+        let bandwidth = GossipBandwidth {
+            recent_inbound_mbps: 1.0,
+            recent_outbound_mbps: 2.0,
+            historic_inbound_mbps: 3.0,
+            historic_outbound_mbps: 4.0,
+        };
+        let config = GossipThrottleConfig {
+            bandwidth: None,
+            // All day long.
+            schedule: vec![GossipScheduleWindow {
+                start_hour_utc: 0,
+                end_hour_utc: 24,
+                bandwidth,
+            }],
+            ignore_host_pressure: true,
+        };
+        let response = admin_api
+            .handle_admin_request(AdminRequest::UpdateGossipThrottle {
+                config: config.clone(),
+            })
+            .await;
+        assert_matches!(response, AdminResponse::GossipThrottleUpdated);
+
+        let response = admin_api
+            .handle_admin_request(AdminRequest::GetGossipThrottle)
+            .await;
+        assert_matches!(
+            response,
+            AdminResponse::GossipThrottle(status) if status.config == config && status.bandwidth == bandwidth
+        );
+
+        // Invalid configurations are rejected.
+        let response = admin_api
+            .handle_admin_request(AdminRequest::UpdateGossipThrottle {
+                config: GossipThrottleConfig {
+                    bandwidth: Some(bandwidth.scale(-1.0)),
+                    ..Default::default()
+                },
+            })
+            .await;
+        assert_matches!(response, AdminResponse::Error(_));
+
+        handle.shutdown();
+        tokio::time::timeout(std::time::Duration::from_secs(1), shutdown)
+            .await
+            .ok();
+        Ok(())
+    }
+
     // @todo fix test by using new InstallApp call
     // #[tokio::test(flavor = "multi_thread")]
     // async fn install_list_dna_app() {
@@ -1,15 +1,22 @@
 use super::InterfaceApi;
+use crate::conductor::api::error::ConductorApiError;
 use crate::conductor::api::error::ConductorApiResult;
 use crate::conductor::api::error::ExternalApiWireError;
 use crate::conductor::api::error::SerializationError;
+use crate::conductor::error::ConductorError;
 use crate::conductor::interface::error::InterfaceError;
 use crate::conductor::interface::error::InterfaceResult;
+use crate::conductor::interface::SignalSubscriptions;
 use crate::conductor::ConductorHandle;
 
 use holochain_serialized_bytes::prelude::*;
 
 use holochain_types::prelude::*;
+use holochain_types::signal::Signal;
+use std::collections::HashSet;
+use std::sync::Arc;
 
+use holochain_conductor_api::signal_subscription::SignalSubscription;
 pub use holochain_conductor_api::*;
 
 /// The interface that a Conductor exposes to the outside world.
@@ -41,12 +48,139 @@ pub trait AppInterfaceApi: 'static + Send + Sync + Clone {
 #[derive(Clone)]
 pub struct RealAppInterfaceApi {
     conductor_handle: ConductorHandle,
+    signal_subscriptions: SignalSubscriptions,
+    authorized_app: Option<AuthorizedApp>,
+}
+
+/// The app a connection has authenticated for, which is the only app it can
+/// make requests for.
+#[derive(Clone)]
+struct AuthorizedApp {
+    installed_app_id: InstalledAppId,
+    /// The cells of the app, as of authentication or the last request on the
+    /// connection which added a cell. Only app signals from these cells are
+    /// sent over the connection.
+    cell_ids: Arc<parking_lot::RwLock<HashSet<CellId>>>,
 }
 
 impl RealAppInterfaceApi {
-    /// Create a new instance from a shared Conductor reference
+    /// Create a new instance from a shared Conductor reference.
+    /// It can make requests for any app.
     pub fn new(conductor_handle: ConductorHandle) -> Self {
-        Self { conductor_handle }
+        Self {
+            conductor_handle,
+            signal_subscriptions: SignalSubscriptions::default(),
+            authorized_app: None,
+        }
+    }
+
+    /// Create an instance for a new connection which has authenticated with
+    /// a token. It has no signal subscriptions, and can only make requests
+    /// for the app the token was issued for.
+    pub async fn for_connection(&self, token: &[u8]) -> ConductorApiResult<Self> {
+        let installed_app_id = self
+            .conductor_handle
+            .authenticate_app_token(token)
+            .ok_or(ConductorError::AppAuthenticationFailed)?;
+        let cell_ids = self
+            .conductor_handle
+            .app_cell_ids(&installed_app_id)
+            .await?;
+        Ok(Self {
+            authorized_app: Some(AuthorizedApp {
+                installed_app_id,
+                cell_ids: Arc::new(parking_lot::RwLock::new(cell_ids)),
+            }),
+            ..Self::new(self.conductor_handle.clone())
+        })
+    }
+
+    /// The signal subscriptions made through this instance
+    pub fn signal_subscriptions(&self) -> &SignalSubscriptions {
+        &self.signal_subscriptions
+    }
+
+    /// Whether a signal should be sent over the connection of this instance.
+    /// App signals must come from a cell of the authorized app, if there is
+    /// one, and get through the signal subscriptions.
+    pub fn allows_signal(&self, signal: &Signal) -> bool {
+        if let (Some(app), Signal::App { cell_id, .. }) = (&self.authorized_app, signal) {
+            if !app.cell_ids.read().contains(cell_id) {
+                return false;
+            }
+        }
+        self.signal_subscriptions.allows(signal)
+    }
+
+    /// Refuse requests for any app other than the authorized one.
+    /// Cells are checked against the cached cells of the app, which are only
+    /// reloaded when a cell is missing, in case it has been added since.
+    async fn check_authorized(&self, request: &AppRequest) -> ConductorApiResult<()> {
+        let app = match &self.authorized_app {
+            Some(app) => app,
+            None => return Ok(()),
+        };
+        let authorized = &app.installed_app_id;
+        let forbidden = |requested: String| {
+            Err(ConductorApiError::ForbiddenForApp {
+                authorized: authorized.clone(),
+                requested,
+            })
+        };
+        let requested_app = match request {
+            AppRequest::AppInfo { installed_app_id }
+            | AppRequest::SignalSubscription(SignalSubscription {
+                installed_app_id, ..
+            }) => installed_app_id,
+            AppRequest::CreateCloneCell(payload) => &payload.app_id,
+            AppRequest::ProvisionDeferredCell(payload) => &payload.app_id,
+            AppRequest::DisableCloneCell(payload) | AppRequest::EnableCloneCell(payload) => {
+                &payload.app_id
+            }
+            AppRequest::CallZome(call) => {
+                let cached = app.cell_ids.read().contains(&call.cell_id);
+                if !cached {
+                    self.refresh_authorized_cells().await?;
+                    if !app.cell_ids.read().contains(&call.cell_id) {
+                        return forbidden(format!("the cell {:?}", call.cell_id));
+                    }
+                }
+                return Ok(());
+            }
+            AppRequest::NetworkInfo(payload) => {
+                let missing_dna = |cell_ids: &HashSet<CellId>| {
+                    payload
+                        .dnas
+                        .iter()
+                        .find(|dna| !cell_ids.iter().any(|id| id.dna_hash() == *dna))
+                        .cloned()
+                };
+                let cached = missing_dna(&app.cell_ids.read()).is_none();
+                if !cached {
+                    self.refresh_authorized_cells().await?;
+                    if let Some(dna) = missing_dna(&app.cell_ids.read()) {
+                        return forbidden(format!("the DNA {}", dna));
+                    }
+                }
+                return Ok(());
+            }
+        };
+        if requested_app != authorized {
+            return forbidden(format!("the app {}", requested_app));
+        }
+        Ok(())
+    }
+
+    /// Catch up with cells added to the authorized app.
+    async fn refresh_authorized_cells(&self) -> ConductorApiResult<()> {
+        if let Some(app) = &self.authorized_app {
+            let cell_ids = self
+                .conductor_handle
+                .app_cell_ids(&app.installed_app_id)
+                .await?;
+            *app.cell_ids.write() = cell_ids;
+        }
+        Ok(())
     }
 }
 
@@ -57,6 +191,7 @@ impl AppInterfaceApi for RealAppInterfaceApi {
         &self,
         request: AppRequest,
     ) -> ConductorApiResult<AppResponse> {
+        self.check_authorized(&request).await?;
         match request {
             AppRequest::AppInfo { installed_app_id } => Ok(AppResponse::AppInfo(
                 self.conductor_handle
@@ -91,6 +226,7 @@ impl AppInterfaceApi for RealAppInterfaceApi {
                     .clone()
                     .create_clone_cell(*payload)
                     .await?;
+                self.refresh_authorized_cells().await?;
                 Ok(AppResponse::CloneCellCreated(installed_clone_cell))
             }
             AppRequest::DisableCloneCell(payload) => {
@@ -100,19 +236,46 @@ impl AppInterfaceApi for RealAppInterfaceApi {
                     .await?;
                 Ok(AppResponse::CloneCellDisabled)
             }
+            AppRequest::ProvisionDeferredCell(payload) => {
+                let provisioned_cell = self
+                    .conductor_handle
+                    .clone()
+                    .provision_deferred_cell(*payload)
+                    .await?;
+                self.refresh_authorized_cells().await?;
+                Ok(AppResponse::DeferredCellProvisioned(provisioned_cell))
+            }
             AppRequest::EnableCloneCell(payload) => {
                 let enabled_cell = self
                     .conductor_handle
                     .clone()
                     .enable_clone_cell(&payload)
                     .await?;
+                self.refresh_authorized_cells().await?;
                 Ok(AppResponse::CloneCellEnabled(enabled_cell))
             }
             AppRequest::NetworkInfo(payload) => {
                 let info = self.conductor_handle.network_info(&payload.dnas).await?;
                 Ok(AppResponse::NetworkInfo(info))
             }
-            AppRequest::SignalSubscription(_) => Ok(AppResponse::Unimplemented(request)),
+            AppRequest::SignalSubscription(SignalSubscription {
+                installed_app_id,
+                filters,
+            }) => {
+                let cell_ids = self
+                    .conductor_handle
+                    .app_cell_ids(&installed_app_id)
+                    .await?;
+                if let Some(cell_id) = filters.cell_ids().find(|id| !cell_ids.contains(id)) {
+                    return Ok(AppResponse::Error(ExternalApiWireError::internal(format!(
+                        "Cell {:?} is not a cell of the app {}",
+                        cell_id, installed_app_id
+                    ))));
+                }
+                self.signal_subscriptions
+                    .subscribe(installed_app_id, cell_ids, filters);
+                Ok(AppResponse::SignalSubscribed)
+            }
         }
     }
 }
@@ -107,6 +107,18 @@ pub enum ConductorApiError {
     #[error(transparent)]
     ChcError(#[from] ChcError),
 
+    /// A request over an app interface connection for something outside the
+    /// app the connection authenticated for
+    #[error(
+        "This connection can only make requests for the app {authorized}, not for {requested}"
+    )]
+    ForbiddenForApp {
+        /// The app the connection authenticated for
+        authorized: InstalledAppId,
+        /// What the request was for
+        requested: String,
+    },
+
     /// Other
     #[error("Other: {0}")]
     Other(Box<dyn std::error::Error + Send + Sync>),
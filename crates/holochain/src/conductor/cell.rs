@@ -22,6 +22,7 @@ use crate::core::workflow::countersigning_workflow::countersigning_success;
 use crate::core::workflow::countersigning_workflow::incoming_countersigning;
 use crate::core::workflow::genesis_workflow::genesis_workflow;
 use crate::core::workflow::initialize_zomes_workflow;
+use crate::core::workflow::migrate_agent_workflow::MigrateAgentChain;
 use crate::core::workflow::CallZomeWorkflowArgs;
 use crate::core::workflow::GenesisWorkflowArgs;
 use crate::core::workflow::GenesisWorkspace;
@@ -384,6 +385,7 @@ impl Cell {
             | QueryAgentInfoSignedNearBasis { .. }
             | QueryPeerDensity { .. }
             | Publish { .. }
+            | PublishWarrant { .. }
             | FetchOpData { .. } => {
                 // These events are aggregated over a set of cells, so need to be handled at the conductor level.
                 unreachable!()
@@ -470,6 +472,24 @@ impl Cell {
                 .await;
             }
 
+            CountLinks {
+                span_context: _,
+                respond,
+                link_key,
+                options,
+                ..
+            } => {
+                async {
+                    let res = self
+                        .handle_count_links(link_key, options)
+                        .await
+                        .map_err(holochain_p2p::HolochainP2pError::other);
+                    respond.respond(Ok(async move { res }.boxed().into()));
+                }
+                .instrument(debug_span!("cell_handle_count_links"))
+                .await;
+            }
+
             GetAgentActivity {
                 span_context: _,
                 respond,
@@ -677,6 +697,20 @@ impl Cell {
             .map_err(Into::into)
     }
 
+    #[instrument(skip(self, options))]
+    /// a remote node is asking us for the hashes of links
+    async fn handle_count_links(
+        &self,
+        link_key: WireLinkKey,
+        options: holochain_p2p::event::GetLinksOptions,
+    ) -> CellResult<WireLinkHashes> {
+        debug!(id = ?self.id());
+        let db = self.space.dht_db.clone();
+        authority::handle_count_links(db.into(), link_key, options)
+            .await
+            .map_err(Into::into)
+    }
+
     #[instrument(skip(self, options))]
     async fn handle_get_agent_activity(
         &self,
@@ -966,6 +1000,27 @@ impl Cell {
         Ok(())
     }
 
+    /// Gather this Cell's source chain, network and ribosome for migrating
+    /// its agent to or from another DNA.
+    pub(crate) async fn migrate_agent_chain(&self) -> CellResult<MigrateAgentChain<RealRibosome>> {
+        let ribosome = self.get_ribosome()?;
+        let workspace = SourceChainWorkspace::new(
+            self.authored_db().clone(),
+            self.dht_db().clone(),
+            self.space.dht_query_cache.clone(),
+            self.cache().clone(),
+            self.conductor_api.keystore().clone(),
+            self.id.agent_pubkey().clone(),
+            Arc::new(ribosome.dna_def().content.clone()),
+        )
+        .await?;
+        Ok(MigrateAgentChain {
+            workspace,
+            network: self.holochain_p2p_cell.clone(),
+            ribosome,
+        })
+    }
+
     /// Instantiate a Ribosome for use by this Cell's workflows
     pub(crate) fn get_ribosome(&self) -> CellResult<RealRibosome> {
         Ok(self
@@ -69,6 +69,8 @@ pub enum CellError {
     ConductorApiError(#[from] Box<ConductorApiError>),
     #[error(transparent)]
     SerializedBytesError(#[from] holochain_serialized_bytes::SerializedBytesError),
+    #[error(transparent)]
+    ChcError(#[from] ChcError),
     #[error("Todo")]
     Todo,
     #[error("The op: {0:?} is missing for this receipt")]
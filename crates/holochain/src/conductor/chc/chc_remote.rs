@@ -4,18 +4,25 @@
 //! as it will change!
 
 use std::collections::{HashMap, HashSet};
+use std::time::Duration;
 
 use ::bytes::Bytes;
 use holo_hash::{ActionHash, EntryHash};
 use holochain_serialized_bytes::{decode, encode};
 use holochain_types::chc::{ChainHeadCoordinator, ChcError, ChcResult};
 use holochain_zome_types::prelude::*;
-use reqwest::Url;
+use reqwest::{StatusCode, Url};
+
+/// How many times a request to the CHC will be attempted before giving up,
+/// if no connection to the service can be made.
+pub const CHC_REMOTE_MAX_ATTEMPTS: usize = 3;
+
+/// The delay before the first retry. Each subsequent retry doubles the delay.
+pub const CHC_REMOTE_RETRY_BACKOFF: Duration = Duration::from_millis(100);
 
 /// An HTTP client which can talk to a remote CHC implementation
 pub struct ChcRemote {
-    actions: ChcRemoteClient,
-    entries: ChcRemoteClient,
+    client: ChcRemoteClient,
 }
 
 #[async_trait::async_trait]
@@ -23,76 +30,269 @@ impl ChainHeadCoordinator for ChcRemote {
     type Item = SignedActionHashed;
 
     async fn head(&self) -> ChcResult<Option<ActionHash>> {
-        let response = self.actions.get("/head").await?;
+        let response = self.client.get("head").await?;
         Ok(decode(&response)?)
     }
 
     async fn add_actions(&self, actions: Vec<Self::Item>) -> ChcResult<()> {
         let body = encode(&actions)?;
-        let _response = self.actions.post("/add_actions", body).await?;
+        let _response = self.client.post("add_actions", body).await?;
         Ok(())
     }
 
     async fn add_entries(&self, entries: Vec<EntryHashed>) -> ChcResult<()> {
         let body = encode(&entries)?;
-        let _response = self.entries.post("/add_entries", body).await?;
+        let _response = self.client.post("add_entries", body).await?;
         Ok(())
     }
 
     async fn get_actions_since_hash(&self, hash: Option<ActionHash>) -> ChcResult<Vec<Self::Item>> {
         let body = encode(&hash)?;
-        let response = self.actions.post("/get_actions_since_hash", body).await?;
+        let response = self.client.post("get_actions_since_hash", body).await?;
         Ok(decode(&response)?)
     }
 
     async fn get_entries(
         &self,
-        _hashes: HashSet<&EntryHash>,
+        hashes: HashSet<&EntryHash>,
     ) -> ChcResult<HashMap<EntryHash, Entry>> {
-        todo!()
+        let hashes: Vec<EntryHash> = hashes.into_iter().cloned().collect();
+        let body = encode(&hashes)?;
+        let response = self.client.post("get_entries", body).await?;
+        let entries: HashMap<EntryHash, Entry> = decode(&response)?;
+        let missing: HashSet<EntryHash> = hashes
+            .into_iter()
+            .filter(|h| !entries.contains_key(h))
+            .collect();
+        if !missing.is_empty() {
+            Err(ChcError::MissingEntries(missing))
+        } else {
+            Ok(entries)
+        }
     }
 }
 
 impl ChcRemote {
-    /// Constructor
-    pub fn new(_namespace: &str, _cell_id: &CellId) -> Self {
-        todo!("Implement remote CHC client")
+    /// Constructor. The namespace is the base URL of the CHC service.
+    /// Each cell gets its own chain at `{namespace}/{dna_hash}/{agent_pubkey}/`.
+    pub fn new(namespace: &str, cell_id: &CellId) -> ChcResult<Self> {
+        let base_url = Url::parse(namespace)
+            .and_then(|mut url| {
+                // Without a trailing slash, the last segment of the namespace
+                // would be replaced by the cell path when joining.
+                if !url.path().ends_with('/') {
+                    url.set_path(&format!("{}/", url.path()));
+                }
+                url.join(&cell_path(cell_id))
+            })
+            .map_err(|e| ChcError::InvalidUrl(format!("{}: {}", namespace, e)))?;
+        Ok(Self {
+            client: ChcRemoteClient {
+                base_url,
+                client: reqwest::Client::new(),
+                max_attempts: CHC_REMOTE_MAX_ATTEMPTS,
+                backoff: CHC_REMOTE_RETRY_BACKOFF,
+            },
+        })
+    }
+
+    /// Override the retry policy of this client.
+    pub fn with_retries(mut self, max_attempts: usize, backoff: Duration) -> Self {
+        self.client.max_attempts = max_attempts.max(1);
+        self.client.backoff = backoff;
+        self
     }
 }
 
+/// The path, relative to the CHC base URL, under which a cell's chain is stored.
+pub(crate) fn cell_path(cell_id: &CellId) -> String {
+    format!("{}/{}/", cell_id.dna_hash(), cell_id.agent_pubkey())
+}
+
 /// Client for a single CHC server
 pub struct ChcRemoteClient {
-    base_url: url::Url,
+    base_url: Url,
+    client: reqwest::Client,
+    max_attempts: usize,
+    backoff: Duration,
 }
 
 impl ChcRemoteClient {
-    fn url(&self, path: &str) -> Url {
-        assert!(path.chars().nth(0) == Some('/'));
-        Url::parse(&format!("{}{}", self.base_url, path)).expect("invalid URL")
+    fn url(&self, path: &str) -> ChcResult<Url> {
+        self.base_url
+            .join(path)
+            .map_err(|e| ChcError::InvalidUrl(format!("{}{}: {}", self.base_url, path, e)))
     }
 
     async fn get(&self, path: &str) -> ChcResult<Bytes> {
-        let bytes = reqwest::get(self.url(path))
+        let url = self.url(path)?;
+        self.send_with_retries(|| self.client.get(url.clone()))
             .await
-            .map_err(extract_string)?
-            .bytes()
-            .await
-            .map_err(extract_string)?;
-        Ok(bytes)
     }
 
     async fn post(&self, path: &str, body: Vec<u8>) -> ChcResult<Bytes> {
-        let client = reqwest::Client::new();
-        let response = client
-            .post(self.url(path))
-            .body(body)
-            .send()
+        let url = self.url(path)?;
+        self.send_with_retries(|| self.client.post(url.clone()).body(body.clone()))
             .await
-            .map_err(extract_string)?;
-        Ok(response.bytes().await.map_err(extract_string)?)
+    }
+
+    /// Send the request built by `request`, retrying with exponential backoff
+    /// if no connection to the service can be made.
+    /// Once a request may have reached the service it is never retried, since
+    /// requests like `add_actions` and `add_entries` are not idempotent.
+    async fn send_with_retries(
+        &self,
+        request: impl Fn() -> reqwest::RequestBuilder,
+    ) -> ChcResult<Bytes> {
+        let mut delay = self.backoff;
+        let mut attempt = 1;
+        loop {
+            match request().send().await {
+                Err(e) if e.is_connect() && attempt < self.max_attempts => {
+                    tracing::warn!(
+                        attempt,
+                        ?e,
+                        "Couldn't connect to the CHC, retrying in {:?}",
+                        delay
+                    );
+                    tokio::time::sleep(delay).await;
+                    delay *= 2;
+                    attempt += 1;
+                }
+                Err(e) => return Err(extract_string(e)),
+                Ok(response) => return read_response(response).await,
+            }
+        }
+    }
+}
+
+/// Read the body of a response, or the error reported by the CHC service.
+async fn read_response(response: reqwest::Response) -> ChcResult<Bytes> {
+    let status = response.status();
+    let bytes = response.bytes().await.map_err(extract_string)?;
+    if status.is_success() {
+        Ok(bytes)
+    } else {
+        Err(error_from_response(status, &bytes))
+    }
+}
+
+/// Reconstruct the error reported by the CHC service, so that the caller can
+/// distinguish e.g. a moved chain head from a general failure.
+fn error_from_response(status: StatusCode, body: &[u8]) -> ChcError {
+    match status {
+        StatusCode::CONFLICT => match decode::<_, (Option<u32>, String)>(body) {
+            Ok((seq, message)) => ChcError::InvalidChain(seq, message),
+            Err(e) => e.into(),
+        },
+        StatusCode::NOT_FOUND => match decode::<_, Vec<EntryHash>>(body) {
+            Ok(hashes) => ChcError::MissingEntries(hashes.into_iter().collect()),
+            Err(_) => ChcError::ServiceError(status.as_u16(), "Not Found".to_string()),
+        },
+        _ => ChcError::ServiceError(status.as_u16(), String::from_utf8_lossy(body).into_owned()),
     }
 }
 
 fn extract_string(e: reqwest::Error) -> ChcError {
     ChcError::ServiceUnreachable(e.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use ::fixt::prelude::*;
+    use holochain_conductor_api::conductor::ConductorConfig;
+
+    use crate::conductor::chc::chc_server;
+    use crate::prelude::CellIdFixturator;
+    use crate::sweettest::*;
+
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn unreachable_chc() {
+        let chc = ChcRemote::new("http://127.0.0.1:1", &fixt!(CellId))
+            .unwrap()
+            .with_retries(2, Duration::from_millis(1));
+        assert!(matches!(
+            chc.head().await,
+            Err(ChcError::ServiceUnreachable(_))
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn chc_namespace_with_a_path() {
+        let cell_id = fixt!(CellId);
+        let expected = format!("http://127.0.0.1:1/chc/{}", cell_path(&cell_id));
+        for namespace in ["http://127.0.0.1:1/chc", "http://127.0.0.1:1/chc/"] {
+            let chc = ChcRemote::new(namespace, &cell_id).unwrap();
+            assert_eq!(chc.client.base_url.as_str(), expected);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn invalid_chc_url() {
+        assert!(matches!(
+            ChcRemote::new("not a url", &fixt!(CellId)),
+            Err(ChcError::InvalidUrl(_))
+        ));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn remote_chc_sync() {
+        use holochain::test_utils::inline_zomes::simple_crud_zome;
+
+        let (driver, addr, shutdown) = chc_server::run(([127, 0, 0, 1], 0)).await.unwrap();
+        tokio::spawn(driver);
+
+        let config = ConductorConfig {
+            chc_namespace: Some(format!("http://{}", addr)),
+            ..Default::default()
+        };
+        let mut conductors = SweetConductorBatch::from_configs([config.clone(), config]).await;
+
+        let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+        let (agent, _) = SweetAgents::alice_and_bob();
+
+        let (c0,) = conductors[0]
+            .setup_app_for_agent("app", agent.clone(), [&dna_file])
+            .await
+            .unwrap()
+            .into_tuple();
+        let cell_id = c0.cell_id().clone();
+
+        let chc = ChcRemote::new(&format!("http://{}", addr), &cell_id).unwrap();
+        let actions = chc.get_actions_since_hash(None).await.unwrap();
+        assert_eq!(actions.len(), 3);
+        assert_eq!(
+            chc.head().await.unwrap().as_ref(),
+            Some(actions[2].action_address())
+        );
+
+        // The head has moved, so genesis on the second conductor must be rejected
+        let install_result = conductors[1]
+            .setup_app_for_agent("app", agent.clone(), [&dna_file])
+            .await;
+        assert!(format!("{:?}", install_result).contains("ChcHeadMoved"));
+
+        conductors[1]
+            .raw_handle()
+            .chc_sync(cell_id.clone(), None)
+            .await
+            .unwrap();
+
+        let dump0 = conductors[0]
+            .dump_full_cell_state(&cell_id, None)
+            .await
+            .unwrap();
+        let dump1 = conductors[1]
+            .dump_full_cell_state(&cell_id, None)
+            .await
+            .unwrap();
+        assert_eq!(
+            dump0.source_chain_dump.records,
+            dump1.source_chain_dump.records
+        );
+
+        shutdown();
+    }
+}
@@ -0,0 +1,177 @@
+//! A reference HTTP server for Chain Head Coordination, for use with [`ChcRemote`].
+//!
+//! Each chain is stored in memory in a [`ChcLocal`], keyed by the DNA hash and
+//! agent key found in the request path, so that a single server can coordinate
+//! any number of cells. This is meant for local testing only.
+//!
+//! **NOTE** this API is not set in stone. Do not design a CHC against this API yet,
+//! as it will change!
+//!
+//! [`ChcRemote`]: super::ChcRemote
+
+// Fixes some warnings introduced by `warp`
+#![allow(opaque_hidden_inferred_bound)]
+
+use std::collections::{HashMap, HashSet};
+use std::fmt::Debug;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use holochain_serialized_bytes::{decode, encode};
+use holochain_types::prelude::*;
+use warp::http::StatusCode;
+use warp::hyper::body::Bytes;
+use warp::reply::Response;
+use warp::{Filter, Reply};
+
+use super::ChcLocal;
+
+/// No single request body may be larger than this.
+const SIZE_LIMIT: u64 = 16 * 1024 * 1024;
+
+/// The future which drives the server. Must be polled for the server to run.
+pub type ChcServerDriver = futures::future::BoxFuture<'static, ()>;
+
+/// Call this to gracefully shut down the server.
+pub type ChcServerShutdown = Box<dyn FnOnce() + 'static + Send>;
+
+/// The `{dna_hash}/{agent_pubkey}` path segments identifying a chain.
+type ChainKey = (String, String);
+
+/// The chains held by the server.
+#[derive(Clone, Default)]
+struct ChcServerStore(Arc<parking_lot::Mutex<HashMap<ChainKey, Arc<ChcLocal>>>>);
+
+impl ChcServerStore {
+    fn chain(&self, dna_hash: String, agent: String) -> Arc<ChcLocal> {
+        self.0
+            .lock()
+            .entry((dna_hash, agent))
+            .or_insert_with(|| Arc::new(ChcLocal::new()))
+            .clone()
+    }
+}
+
+/// Run a CHC server bound to the given address.
+pub async fn run(
+    addr: impl Into<SocketAddr> + 'static,
+) -> Result<(ChcServerDriver, SocketAddr, ChcServerShutdown), String> {
+    let store = ChcServerStore::default();
+
+    let chain =
+        warp::path!(String / String / ..).map(move |dna_hash, agent| store.chain(dna_hash, agent));
+    let body = warp::body::content_length_limit(SIZE_LIMIT).and(warp::body::bytes());
+
+    let head = chain
+        .clone()
+        .and(warp::path!("head"))
+        .and(warp::get())
+        .and_then(head);
+    let add_actions = chain
+        .clone()
+        .and(warp::path!("add_actions"))
+        .and(warp::post())
+        .and(body)
+        .and_then(add_actions);
+    let add_entries = chain
+        .clone()
+        .and(warp::path!("add_entries"))
+        .and(warp::post())
+        .and(body)
+        .and_then(add_entries);
+    let get_actions_since_hash = chain
+        .clone()
+        .and(warp::path!("get_actions_since_hash"))
+        .and(warp::post())
+        .and(body)
+        .and_then(get_actions_since_hash);
+    let get_entries = chain
+        .and(warp::path!("get_entries"))
+        .and(warp::post())
+        .and(body)
+        .and_then(get_entries);
+
+    let routes = head
+        .or(add_actions)
+        .or(add_entries)
+        .or(get_actions_since_hash)
+        .or(get_entries);
+
+    let (s, r) = tokio::sync::oneshot::channel();
+    let shutdown = Box::new(move || {
+        let _ = s.send(());
+    });
+
+    match warp::serve(routes).try_bind_with_graceful_shutdown(addr, async move {
+        let _ = r.await;
+    }) {
+        Ok((addr, server)) => {
+            let driver = futures::future::FutureExt::boxed(server);
+            Ok((driver, addr, shutdown))
+        }
+        Err(e) => Err(format!("Failed to bind socket: {:?}", e)),
+    }
+}
+
+async fn head(chc: Arc<ChcLocal>) -> Result<Response, warp::Rejection> {
+    Ok(respond(chc.head().await))
+}
+
+async fn add_actions(chc: Arc<ChcLocal>, body: Bytes) -> Result<Response, warp::Rejection> {
+    Ok(match decode(&body) {
+        Ok(actions) => respond(chc.add_actions(actions).await),
+        Err(e) => bad_request(e),
+    })
+}
+
+async fn add_entries(chc: Arc<ChcLocal>, body: Bytes) -> Result<Response, warp::Rejection> {
+    Ok(match decode(&body) {
+        Ok(entries) => respond(chc.add_entries(entries).await),
+        Err(e) => bad_request(e),
+    })
+}
+
+async fn get_actions_since_hash(
+    chc: Arc<ChcLocal>,
+    body: Bytes,
+) -> Result<Response, warp::Rejection> {
+    Ok(match decode(&body) {
+        Ok(hash) => respond(chc.get_actions_since_hash(hash).await),
+        Err(e) => bad_request(e),
+    })
+}
+
+async fn get_entries(chc: Arc<ChcLocal>, body: Bytes) -> Result<Response, warp::Rejection> {
+    Ok(match decode::<_, Vec<EntryHash>>(&body) {
+        Ok(hashes) => respond(chc.get_entries(hashes.iter().collect::<HashSet<_>>()).await),
+        Err(e) => bad_request(e),
+    })
+}
+
+/// Encode a successful result as msgpack, or map the error to a status code
+/// which [`ChcRemote`](super::ChcRemote) knows how to interpret.
+fn respond<T: serde::Serialize + Debug>(result: ChcResult<T>) -> Response {
+    let (status, body) = match result {
+        Ok(value) => (StatusCode::OK, encode(&value)),
+        Err(ChcError::InvalidChain(seq, message)) => {
+            (StatusCode::CONFLICT, encode(&(seq, message)))
+        }
+        Err(ChcError::MissingEntries(hashes)) => (
+            StatusCode::NOT_FOUND,
+            encode(&hashes.into_iter().collect::<Vec<_>>()),
+        ),
+        Err(e) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Ok(e.to_string().into_bytes()),
+        ),
+    };
+    match body {
+        Ok(body) => warp::reply::with_status(body, status).into_response(),
+        Err(e) => warp::reply::with_status(e.to_string(), StatusCode::INTERNAL_SERVER_ERROR)
+            .into_response(),
+    }
+}
+
+fn bad_request(e: SerializedBytesError) -> Response {
+    warp::reply::with_status(e.to_string(), StatusCode::BAD_REQUEST).into_response()
+}
@@ -33,9 +33,14 @@ pub use self::share::RwShare;
 use super::api::RealAppInterfaceApi;
 use super::api::ZomeCall;
 use super::config::AdminInterfaceConfig;
-use super::config::InterfaceDriver;
+use super::config::AllowedOrigins;
+#[cfg(feature = "metrics_endpoint")]
+use super::config::MetricsEndpointConfig;
 use super::entry_def_store::get_entry_defs;
 use super::error::ConductorError;
+use super::interface::app_auth_token_store::AppAuthTokenStore;
+#[cfg(feature = "metrics_endpoint")]
+use super::interface::error::InterfaceError;
 use super::interface::error::InterfaceResult;
 use super::interface::websocket::spawn_admin_interface_task;
 use super::interface::websocket::spawn_app_interface_task;
@@ -48,6 +53,8 @@ use super::manager::spawn_task_manager;
 use super::manager::ManagedTaskAdd;
 use super::manager::ManagedTaskHandle;
 use super::manager::TaskManagerRunHandle;
+use super::metrics::DnaMetrics;
+use super::metrics::ZomeCallMetrics;
 use super::p2p_agent_store;
 use super::p2p_agent_store::P2pBatch;
 use super::p2p_agent_store::*;
@@ -58,6 +65,7 @@ use super::space::Spaces;
 use super::state::AppInterfaceConfig;
 use super::state::AppInterfaceId;
 use super::state::ConductorState;
+use super::warrant_queue::WarrantQueue;
 use super::CellError;
 use super::{api::RealAdminInterfaceApi, manager::TaskManagerClient};
 use crate::conductor::cell::Cell;
@@ -71,6 +79,9 @@ use crate::core::queue_consumer::QueueConsumerMap;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitArgs;
 use crate::core::ribosome::guest_callback::post_commit::POST_COMMIT_CHANNEL_BOUND;
 use crate::core::ribosome::guest_callback::post_commit::POST_COMMIT_CONCURRENT_LIMIT;
+use crate::core::ribosome::guest_callback::rate_limits::RateLimitsHostAccess;
+use crate::core::ribosome::guest_callback::rate_limits::RateLimitsInvocation;
+use crate::core::ribosome::guest_callback::rate_limits::RateLimitsResult;
 use crate::core::ribosome::RibosomeT;
 use crate::core::workflow::ZomeCallResult;
 use crate::{
@@ -83,11 +94,14 @@ use futures::future::TryFutureExt;
 use futures::stream::StreamExt;
 use holo_hash::DnaHash;
 use holochain_conductor_api::conductor::KeystoreConfig;
+use holochain_conductor_api::AppAuthenticationTokenIssued;
 use holochain_conductor_api::AppInfo;
 use holochain_conductor_api::AppStatusFilter;
+use holochain_conductor_api::DnaTuningParams;
 use holochain_conductor_api::FullIntegrationStateDump;
 use holochain_conductor_api::FullStateDump;
 use holochain_conductor_api::IntegrationStateDump;
+use holochain_conductor_api::IssueAppAuthenticationTokenPayload;
 use holochain_conductor_api::JsonDump;
 use holochain_keystore::lair_keystore::spawn_lair_keystore;
 use holochain_keystore::lair_keystore::spawn_lair_keystore_in_proc;
@@ -209,6 +223,26 @@ pub struct Conductor {
     /// Collection app interface data, keyed by id
     app_interfaces: RwShare<HashMap<AppInterfaceId, AppInterfaceRuntime>>,
 
+    /// The tokens issued for authenticating app interface connections
+    app_auth_token_store: RwShare<AppAuthTokenStore>,
+
+    /// The warranted agents blocked in each DNA, loaded from the DHT
+    /// database the first time they are needed.
+    blocked_agents: RwShare<HashMap<DnaHash, HashSet<AgentPubKey>>>,
+
+    /// Warrants received from other agents which are waiting to be checked.
+    warrant_queue: WarrantQueue,
+
+    /// The hashes of the DNAs fetched from each URL while installing apps,
+    /// so that they don't have to be fetched again.
+    remote_dnas: RwShare<HashMap<String, DnaHash>>,
+
+    /// The DPKI states of agent keys, with the time each was looked up.
+    dpki_key_states: RwShare<HashMap<AgentPubKey, (KeyState, Timestamp)>>,
+
+    /// The cell of the DPKI app, once it has been looked up.
+    dpki_cell_id: RwShare<Option<CellId>>,
+
     /// The channels and handles needed to interact with the task_manager task.
     /// If this is None, then the task manager has not yet been initialized.
     pub(crate) task_manager: RwShare<Option<TaskManagerClient>>,
@@ -224,6 +258,9 @@ pub struct Conductor {
 
     post_commit: tokio::sync::mpsc::Sender<PostCommitArgs>,
 
+    /// Latencies of the zome calls made on this conductor
+    zome_call_metrics: ZomeCallMetrics,
+
     scheduler: Arc<parking_lot::Mutex<Option<tokio::task::JoinHandle<()>>>>,
 }
 
@@ -273,6 +310,12 @@ mod startup_shutdown_impls {
                 config,
                 shutting_down: Arc::new(AtomicBool::new(false)),
                 app_interfaces: RwShare::new(HashMap::new()),
+                app_auth_token_store: RwShare::new(AppAuthTokenStore::default()),
+                blocked_agents: RwShare::new(HashMap::new()),
+                warrant_queue: WarrantQueue::default(),
+                remote_dnas: RwShare::new(HashMap::new()),
+                dpki_key_states: RwShare::new(HashMap::new()),
+                dpki_cell_id: RwShare::new(None),
                 task_manager: RwShare::new(None),
                 admin_websocket_ports: RwShare::new(Vec::new()),
                 scheduler: Arc::new(parking_lot::Mutex::new(None)),
@@ -280,6 +323,7 @@ mod startup_shutdown_impls {
                 keystore,
                 holochain_p2p,
                 post_commit,
+                zome_call_metrics: ZomeCallMetrics::default(),
             }
         }
 
@@ -352,6 +396,12 @@ mod startup_shutdown_impls {
 
             self.clone().add_admin_interfaces(admin_configs).await?;
             self.clone().startup_app_interfaces().await?;
+            if let Some(config) = self.config.metrics_endpoint.clone() {
+                #[cfg(feature = "metrics_endpoint")]
+                self.clone().add_metrics_endpoint(config).await?;
+                #[cfg(not(feature = "metrics_endpoint"))]
+                return Err(ConductorError::MetricsEndpointUnavailable(config.bind_to));
+            }
 
             // We don't care what fx are returned here, since all cells need to
             // be spun up
@@ -386,20 +436,15 @@ mod interface_impls {
                 let admin_api = admin_api.clone();
                 let stop_tx = stop_tx.clone();
                 async move {
-                    match driver {
-                        InterfaceDriver::Websocket { port } => {
-                            let (listener_handle, listener) =
-                                spawn_websocket_listener(port).await?;
-                            let port = listener_handle.local_addr().port().unwrap_or(port);
-                            let handle: ManagedTaskHandle = spawn_admin_interface_task(
-                                listener_handle,
-                                listener,
-                                admin_api.clone(),
-                                stop_tx.subscribe(),
-                            )?;
-                            InterfaceResult::Ok((port, handle))
-                        }
-                    }
+                    let (listener_handle, listener) = spawn_websocket_listener(&driver).await?;
+                    let local_addr = listener_handle.local_addr().clone();
+                    let handle: ManagedTaskHandle = spawn_admin_interface_task(
+                        listener_handle,
+                        listener,
+                        admin_api.clone(),
+                        stop_tx.subscribe(),
+                    )?;
+                    InterfaceResult::Ok((local_addr, handle))
                 }
             };
 
@@ -425,11 +470,12 @@ mod interface_impls {
                 .await?;
 
                 // Now that tasks are spawned, register them with the TaskManager
-                for (port, handle) in handles {
-                    ports.push(port);
+                for (local_addr, handle) in handles {
+                    // Interfaces on unix domain sockets have no port
+                    ports.extend(local_addr.port());
                     self.manage_task(ManagedTaskAdd::ignore(
                         handle,
-                        &format!("admin interface, port {}", port),
+                        &format!("admin interface, {}", local_addr),
                     ))
                     .await?
                 }
@@ -443,17 +489,19 @@ mod interface_impls {
         /// Spawn a new app interface task, register it with the TaskManager,
         /// and modify the conductor accordingly, based on the config passed in
         /// which is just a networking port number (or 0 to auto-select one).
-        /// Returns the given or auto-chosen port number if giving an Ok Result
+        /// Returns the given or auto-chosen port number if giving an Ok Result,
+        /// or 0 if the interface id is for a unix domain socket.
         pub async fn add_app_interface(
             self: Arc<Self>,
             port: either::Either<u16, AppInterfaceId>,
+            allowed_origins: AllowedOrigins,
         ) -> ConductorResult<u16> {
             let interface_id = match port {
                 either::Either::Left(port) => AppInterfaceId::new(port),
                 either::Either::Right(id) => id,
             };
-            let port = interface_id.port();
-            tracing::debug!("Attaching interface {}", port);
+            let driver = interface_id.driver();
+            tracing::debug!(?driver, "Attaching interface");
             let app_api = RealAppInterfaceApi::new(self.clone());
             // This receiver is thrown away because we can produce infinite new
             // receivers from the Sender
@@ -464,13 +512,19 @@ mod interface_impls {
                     .task_stop_broadcaster()
                     .subscribe()
             });
-            let (port, task) = spawn_app_interface_task(port, app_api, signal_tx.clone(), stop_rx)
-                .await
-                .map_err(Box::new)?;
+            let (driver, task) = spawn_app_interface_task(
+                driver,
+                allowed_origins.clone(),
+                app_api,
+                signal_tx.clone(),
+                stop_rx,
+            )
+            .await
+            .map_err(Box::new)?;
             // TODO: RELIABILITY: Handle this task by restarting it if it fails and log the error
             self.manage_task(ManagedTaskAdd::ignore(
                 task,
-                &format!("app interface, port {}", port),
+                &format!("app interface, {:?}", driver),
             ))
             .await?;
             let interface = AppInterfaceRuntime::Websocket { signal_tx };
@@ -485,7 +539,8 @@ mod interface_impls {
                 app_interfaces.insert(interface_id.clone(), interface);
                 Ok(())
             })?;
-            let config = AppInterfaceConfig::websocket(port);
+            let port = driver.port().unwrap_or(0);
+            let config = AppInterfaceConfig::new(driver, allowed_origins);
             self.update_state(|mut state| {
                 state.app_interfaces.insert(interface_id, config);
                 Ok(state)
@@ -495,6 +550,21 @@ mod interface_impls {
             Ok(port)
         }
 
+        /// Spawn a new app interface task listening on a unix domain socket
+        /// at this path, in the same way as [`Conductor::add_app_interface`].
+        pub async fn add_unix_socket_app_interface(
+            self: Arc<Self>,
+            path: std::path::PathBuf,
+            allowed_origins: AllowedOrigins,
+        ) -> ConductorResult<()> {
+            self.add_app_interface(
+                either::Either::Right(AppInterfaceId::unix_socket(path)),
+                allowed_origins,
+            )
+            .await?;
+            Ok(())
+        }
+
         /// Returns a port which is guaranteed to have a websocket listener with an Admin interface
         /// on it. Useful for specifying port 0 and letting the OS choose a free port.
         pub fn get_arbitrary_admin_websocket_port(&self) -> Option<u16> {
@@ -508,7 +578,7 @@ mod interface_impls {
                 .await?
                 .app_interfaces
                 .values()
-                .map(|config| config.driver.port())
+                .filter_map(|config| config.driver.port())
                 .collect())
         }
 
@@ -516,12 +586,75 @@ mod interface_impls {
         /// This should only be run at conductor initialization.
         #[allow(irrefutable_let_patterns)]
         pub(crate) async fn startup_app_interfaces(self: Arc<Self>) -> ConductorResult<()> {
-            for id in self.get_state().await?.app_interfaces.keys().cloned() {
+            for (id, config) in self.get_state().await?.app_interfaces {
                 tracing::debug!("Starting up app interface: {:?}", id);
-                let _ = self.clone().add_app_interface(either::Right(id)).await?;
+                let _ = self
+                    .clone()
+                    .add_app_interface(either::Right(id), config.allowed_origins)
+                    .await?;
             }
             Ok(())
         }
+
+        /// Spawn the HTTP server which serves this conductor's metrics,
+        /// and register it with the TaskManager.
+        #[cfg(feature = "metrics_endpoint")]
+        pub(crate) async fn add_metrics_endpoint(
+            self: Arc<Self>,
+            config: MetricsEndpointConfig,
+        ) -> ConductorResult<()> {
+            let mut stop_rx = self.task_manager.share_ref(|tm| {
+                tm.as_ref()
+                    .expect("Task manager not started yet")
+                    .task_stop_broadcaster()
+                    .subscribe()
+            });
+            let routes = super::metrics::metrics_route(self.clone());
+            let (local_addr, server) = warp::serve(routes)
+                .try_bind_with_graceful_shutdown(config.bind_to, async move {
+                    let _ = stop_rx.recv().await;
+                })
+                .map_err(|e| {
+                    Box::new(InterfaceError::Other(format!(
+                        "Failed to bind metrics endpoint to {}: {}",
+                        config.bind_to, e
+                    )))
+                })?;
+            tracing::info!("Serving metrics at http://{}/metrics", local_addr);
+            self.manage_task(ManagedTaskAdd::ignore(
+                tokio::spawn(async move {
+                    server.await;
+                    Ok(())
+                }),
+                &format!("metrics endpoint, {}", local_addr),
+            ))
+            .await
+        }
+
+        /// Issue a token which authenticates a connection to an app interface
+        /// for the given app.
+        pub async fn issue_app_authentication_token(
+            &self,
+            payload: IssueAppAuthenticationTokenPayload,
+        ) -> ConductorResult<AppAuthenticationTokenIssued> {
+            let IssueAppAuthenticationTokenPayload {
+                installed_app_id,
+                expiry_seconds,
+                single_use,
+            } = payload;
+            self.get_state().await?.get_app(&installed_app_id)?;
+            let (token, expires_at) = self
+                .app_auth_token_store
+                .share_mut(|store| store.issue_token(installed_app_id, expiry_seconds, single_use));
+            Ok(AppAuthenticationTokenIssued { token, expires_at })
+        }
+
+        /// Get the app an app interface connection is authenticated for by a
+        /// token, if the token is valid.
+        pub fn authenticate_app_token(&self, token: &[u8]) -> Option<InstalledAppId> {
+            self.app_auth_token_store
+                .share_mut(|store| store.authenticate_token(token))
+        }
     }
 }
 
@@ -551,14 +684,60 @@ mod dna_impls {
             self.ribosome_store().share_ref(|ds| ds.get_entry_def(key))
         }
 
+        /// Get the limits of every rate limiting bucket declared by the
+        /// integrity zomes of a Dna, running their `rate_limits` callbacks
+        /// the first time they are needed.
+        pub(crate) async fn get_rate_limits(
+            &self,
+            dna_hash: &DnaHash,
+        ) -> ConductorResult<RateLimits> {
+            if let Some(rate_limits) = self
+                .ribosome_store()
+                .share_ref(|ds| ds.get_rate_limits(dna_hash))
+            {
+                return Ok(rate_limits);
+            }
+            let ribosome = self.get_ribosome(dna_hash)?;
+            let result = tokio::task::spawn_blocking(move || {
+                ribosome.run_rate_limits(RateLimitsHostAccess, RateLimitsInvocation)
+            })
+            .await??;
+            match result {
+                RateLimitsResult::Limits(rate_limits) => {
+                    self.ribosome_store()
+                        .share_mut(|ds| ds.add_rate_limits(dna_hash.clone(), rate_limits.clone()));
+                    Ok(rate_limits)
+                }
+                RateLimitsResult::Err(zome_name, msg) => {
+                    Err(ConductorError::RateLimitsCallbackFailed(zome_name, msg))
+                }
+            }
+        }
+
+        /// Weigh an action of a Dna for rate limiting, with the `weigh`
+        /// callback of the integrity zome which defines its type.
+        pub(crate) async fn weigh_action(
+            &self,
+            dna_hash: &DnaHash,
+            zome_index: Option<ZomeIndex>,
+            input: WeighInput,
+        ) -> ConductorResult<RateWeight> {
+            let ribosome = self.get_ribosome(dna_hash)?;
+            Ok(tokio::task::spawn_blocking(move || {
+                crate::core::ribosome::weigh_action(&ribosome, zome_index, input)
+            })
+            .await??)
+        }
+
         /// Create a hash map of all existing DNA definitions, mapped to cell
-        /// ids.
+        /// ids. Includes the cells of roles which are not provisioned yet.
         pub fn get_dna_definitions(
             &self,
             app: &InstalledApp,
         ) -> ConductorResult<HashMap<CellId, DnaDefHashed>> {
             let mut dna_defs = HashMap::new();
-            for cell_id in app.all_cells() {
+            let unprovisioned_cells = app.unprovisioned_cells().map(|(_, cell_id)| cell_id);
+            for cell_id in app.all_cells().chain(unprovisioned_cells) {
                 let ribosome = self.get_ribosome(cell_id.dna_hash())?;
                 let dna_def = ribosome.dna_def();
                 dna_defs.insert(cell_id.to_owned(), dna_def.to_owned());
@@ -857,10 +1036,35 @@ mod network_impls {
             use HolochainP2pEvent::*;
             let dna_hash = event.dna_hash().clone();
             trace!(dispatch_event = ?event);
+            let blocked_caller = match &event {
+                CallRemote { from_agent, .. } => {
+                    self.blocked_agents(&dna_hash).await?.contains(from_agent)
+                }
+                _ => false,
+            };
             match event {
                 PutAgentInfoSigned {
-                    peer_data, respond, ..
+                    mut peer_data,
+                    respond,
+                    ..
                 } => {
+                    let blocked = self.blocked_agents(&dna_hash).await?;
+                    if !blocked.is_empty() {
+                        peer_data.retain(|info| {
+                            let agent =
+                                <AgentPubKey as holochain_p2p::AgentPubKeyExt>::from_kitsune(
+                                    &info.agent,
+                                );
+                            if blocked.contains(&agent) {
+                                // Keep track of where blocked agents are
+                                // running, so we can drop their messages.
+                                self.spaces.block_nodes(&dna_hash, agent, [info]);
+                                false
+                            } else {
+                                true
+                            }
+                        });
+                    }
                     let sender = self.p2p_batch_sender(&dna_hash);
                     let (result_sender, response) = tokio::sync::oneshot::channel();
                     let _ = sender
@@ -964,11 +1168,35 @@ mod network_impls {
                     let signature = to_agent.sign_raw(self.keystore(), data.into()).await?;
                     respond.respond(Ok(async move { Ok(signature) }.boxed().into()));
                 }
+                CallRemote {
+                    from_agent,
+                    respond,
+                    ..
+                } if blocked_caller => {
+                    let res = Err(holochain_p2p::HolochainP2pError::other(format!(
+                        "Refusing call from warranted agent {}",
+                        from_agent
+                    )));
+                    respond.respond(Ok(async move { res }.boxed().into()));
+                }
+                PublishWarrant {
+                    dna_hash,
+                    warrant,
+                    respond,
+                    ..
+                } => {
+                    let res = self
+                        .receive_warrant(&dna_hash, warrant)
+                        .await
+                        .map_err(holochain_p2p::HolochainP2pError::other);
+                    respond.respond(Ok(async move { res }.boxed().into()));
+                }
                 HolochainP2pEvent::CallRemote { .. }
                 | CountersigningSessionNegotiation { .. }
                 | Get { .. }
                 | GetMeta { .. }
                 | GetLinks { .. }
+                | CountLinks { .. }
                 | GetAgentActivity { .. }
                 | MustGetAgentActivity { .. }
                 | ValidationReceiptReceived { .. } => {
@@ -1043,7 +1271,15 @@ mod network_impls {
         /// Invoke a zome function on a Cell
         pub async fn call_zome(&self, call: ZomeCall) -> ConductorApiResult<ZomeCallResult> {
             let cell = self.cell_by_id(&call.cell_id)?;
-            Ok(cell.call_zome(call, None).await?)
+            let (dna_hash, zome_name, fn_name) = (
+                call.cell_id.dna_hash().clone(),
+                call.zome_name.clone(),
+                call.fn_name.clone(),
+            );
+            let start = std::time::Instant::now();
+            let result = cell.call_zome(call, None).await;
+            self.record_zome_call(&dna_hash, &zome_name, &fn_name, start.elapsed());
+            Ok(result?)
         }
 
         pub(crate) async fn call_zome_with_workspace(
@@ -1053,7 +1289,386 @@ mod network_impls {
         ) -> ConductorApiResult<ZomeCallResult> {
             debug!(cell_id = ?call.cell_id);
             let cell = self.cell_by_id(&call.cell_id)?;
-            Ok(cell.call_zome(call, Some(workspace_lock)).await?)
+            let (dna_hash, zome_name, fn_name) = (
+                call.cell_id.dna_hash().clone(),
+                call.zome_name.clone(),
+                call.fn_name.clone(),
+            );
+            let start = std::time::Instant::now();
+            let result = cell.call_zome(call, Some(workspace_lock)).await;
+            self.record_zome_call(&dna_hash, &zome_name, &fn_name, start.elapsed());
+            Ok(result?)
+        }
+
+        /// Record the latency of a zome call, if the function it named exists.
+        /// Callers can name any zome and function, so recording calls which
+        /// didn't resolve to a function would let them grow the metrics
+        /// without bound.
+        fn record_zome_call(
+            &self,
+            dna_hash: &DnaHash,
+            zome_name: &ZomeName,
+            fn_name: &FunctionName,
+            elapsed: std::time::Duration,
+        ) {
+            self.zome_call_metrics
+                .record(zome_name, fn_name, elapsed, || {
+                    self.get_ribosome(dna_hash)
+                        .ok()
+                        .and_then(|ribosome| {
+                            let zome = ribosome.dna_def().get_zome(zome_name).ok()?;
+                            ribosome.zome_info(zome).ok()
+                        })
+                        .map_or(false, |info| info.extern_fns.contains(fn_name))
+                });
+        }
+    }
+}
+
+/// Methods related to issuing, storing and acting on warrants
+mod warrant_impls {
+    use holochain_p2p::AgentPubKeyExt;
+    use holochain_sqlite::db::AsP2pStateTxExt;
+    use holochain_state::warrant;
+
+    use super::*;
+
+    impl Conductor {
+        /// Sign warrants for each proof with one of our agents in this DNA,
+        /// then store and publish them.
+        pub(crate) async fn issue_warrants(
+            &self,
+            dna_hash: &DnaHash,
+            proofs: Vec<WarrantProof>,
+        ) -> ConductorResult<()> {
+            if proofs.is_empty() {
+                return Ok(());
+            }
+            let author = match self
+                .list_cell_ids(Some(CellStatus::Joined))
+                .into_iter()
+                .find(|id| id.dna_hash() == dna_hash)
+            {
+                Some(cell_id) => cell_id.agent_pubkey().clone(),
+                None => {
+                    warn!(?dna_hash, "No running cell to issue warrants with");
+                    return Ok(());
+                }
+            };
+            let mut warrants = Vec::with_capacity(proofs.len());
+            for proof in proofs {
+                warrants.push(warrant::sign_warrant(self.keystore(), author.clone(), proof).await?);
+            }
+            let network = self.holochain_p2p.to_dna(dna_hash.clone(), None);
+            for w in self.store_warrants(dna_hash, warrants).await? {
+                if let Err(e) = network.publish_warrant(w).await {
+                    warn!(?e, "Failed to publish warrant");
+                }
+            }
+            Ok(())
+        }
+
+        /// Handle a warrant published by another authority, if it is genuine.
+        ///
+        /// A warrant claiming that an action is invalid is only stored once
+        /// the action has been validated again, which is too much work to do
+        /// for every warrant received. Those are queued instead, see
+        /// [`WarrantQueue`].
+        pub(crate) async fn receive_warrant(
+            &self,
+            dna_hash: &DnaHash,
+            warrant: SignedWarrant,
+        ) -> ConductorResult<()> {
+            if !warrant::verify_warrant(dna_hash, &warrant).await {
+                warn!(?warrant, "Dropping warrant which failed verification");
+                return Ok(());
+            }
+            if let WarrantProof::ChainIntegrity(ChainIntegrityWarrant::InvalidChainOp { .. }) =
+                &warrant.warrant.proof
+            {
+                if !self.warrant_queue.push(dna_hash.clone(), warrant) {
+                    debug!("Dropping warrant which can't be queued to be checked");
+                }
+                return Ok(());
+            }
+            self.store_warrants(dna_hash, vec![warrant]).await?;
+            Ok(())
+        }
+
+        /// Check the warrants in the queue one at a time, until the
+        /// conductor shuts down.
+        pub(crate) fn start_warrant_checks(self: Arc<Self>) {
+            let mut receiver = match self.warrant_queue.take_receiver() {
+                Some(receiver) => receiver,
+                None => return,
+            };
+            tokio::task::spawn(async move {
+                while let Some((dna_hash, warrant)) = receiver.recv().await {
+                    if self.check_running().is_err() {
+                        break;
+                    }
+                    if let Err(e) = self.check_warrant(&dna_hash, warrant.clone()).await {
+                        warn!(?e, "Failed to check warrant");
+                    }
+                    self.warrant_queue.checked(&dna_hash, &warrant);
+                }
+            });
+        }
+
+        /// Store a warrant which claims that an action is invalid, if the
+        /// action is found to be invalid. Another warrant for the same action
+        /// which was already stored is enough, so the action is only
+        /// validated again the first time.
+        pub(crate) async fn check_warrant(
+            &self,
+            dna_hash: &DnaHash,
+            warrant: SignedWarrant,
+        ) -> ConductorResult<()> {
+            if let WarrantProof::ChainIntegrity(ChainIntegrityWarrant::InvalidChainOp {
+                action,
+                validation_type,
+            }) = &warrant.warrant.proof
+            {
+                let warrantee = warrant.warrant.warrantee().clone();
+                let stored = self
+                    .spaces
+                    .dht_db(dna_hash)?
+                    .async_reader(move |txn| warrant::list_warrants(&txn, &warrantee))
+                    .await?;
+                let confirmed = stored.iter().any(|w| {
+                    matches!(
+                        &w.warrant.proof,
+                        WarrantProof::ChainIntegrity(ChainIntegrityWarrant::InvalidChainOp {
+                            action: a,
+                            ..
+                        }) if a == action
+                    )
+                });
+                if !confirmed
+                    && !self
+                        .confirm_invalid_action(dna_hash, action, *validation_type)
+                        .await?
+                {
+                    warn!(
+                        ?warrant,
+                        "Dropping warrant for an action which we couldn't find to be invalid"
+                    );
+                    return Ok(());
+                }
+            }
+            self.store_warrants(dna_hash, vec![warrant]).await?;
+            Ok(())
+        }
+
+        /// Fetch the record of an action which a warrant claims is invalid,
+        /// and validate its ops again. Returns true only if one of them is
+        /// rejected, so that a forged warrant can't get an honest agent blocked.
+        async fn confirm_invalid_action(
+            &self,
+            dna_hash: &DnaHash,
+            action: &SignedAction,
+            validation_type: ValidationType,
+        ) -> ConductorResult<bool> {
+            use crate::core::validation::OutcomeOrError;
+            use crate::core::workflow::app_validation_workflow;
+            use crate::core::workflow::sys_validation_workflow::sys_validate_record;
+            use crate::core::PrevActionError;
+            use crate::core::ValidationOutcome;
+
+            let space = self.get_or_create_space(dna_hash)?;
+            let dna_def = Arc::new(
+                self.get_dna_def(dna_hash)
+                    .ok_or_else(|| DnaError::DnaMissing(dna_hash.clone()))?,
+            );
+            let network = self.holochain_p2p.to_dna(dna_hash.clone(), None);
+            let workspace = app_validation_workflow::AppValidationWorkspace::new(
+                space.authored_db.clone().into(),
+                space.dht_db.clone(),
+                space.dht_query_cache.clone(),
+                space.cache_db.clone(),
+                self.keystore().clone(),
+                dna_def.clone(),
+            );
+            let mut cascade = workspace.full_cascade(network.clone());
+            let hash = ActionHash::with_data_sync(action.action());
+            let record = match cascade
+                .retrieve(hash.into(), Default::default())
+                .await
+                .map_err(ConductorError::other)?
+            {
+                Some(record) => record,
+                None => return Ok(false),
+            };
+
+            match validation_type {
+                ValidationType::Sys => {
+                    let host_fn_workspace =
+                        holochain_state::host_fn_workspace::HostFnWorkspace::new(
+                            space.authored_db,
+                            space.dht_db,
+                            space.dht_query_cache,
+                            space.cache_db,
+                            self.keystore().clone(),
+                            None,
+                            dna_def,
+                        )
+                        .await?;
+                    let outcome =
+                        sys_validate_record(&record, &host_fn_workspace, network, self).await;
+                    Ok(match outcome {
+                        Err(OutcomeOrError::Outcome(outcome)) => !matches!(
+                            outcome,
+                            ValidationOutcome::Counterfeit(_, _)
+                                | ValidationOutcome::DepMissingFromDht(_)
+                                | ValidationOutcome::NotHoldingDep(_)
+                                | ValidationOutcome::PrevActionError(PrevActionError::MissingMeta(
+                                    _
+                                ))
+                        ),
+                        _ => false,
+                    })
+                }
+                ValidationType::App => {
+                    let ribosome = self.get_ribosome(dna_hash)?;
+                    for op_type in action_to_op_types(record.action()) {
+                        let op = match app_validation_workflow::record_to_op(
+                            record.clone(),
+                            op_type,
+                            &mut cascade,
+                        )
+                        .await
+                        {
+                            Ok((op, _)) => op,
+                            // The op can't be validated by this conductor.
+                            Err(_) => continue,
+                        };
+                        let outcome = app_validation_workflow::validate_op(
+                            &op,
+                            workspace
+                                .validation_workspace()
+                                .await
+                                .map_err(ConductorError::other)?,
+                            &network,
+                            &ribosome,
+                        )
+                        .await;
+                        if let Ok(app_validation_workflow::Outcome::Rejected(_)) = outcome {
+                            return Ok(true);
+                        }
+                    }
+                    Ok(false)
+                }
+            }
+        }
+
+        /// Store warrants in the DHT database, returning the ones which
+        /// were not already stored.
+        async fn store_warrants(
+            &self,
+            dna_hash: &DnaHash,
+            warrants: Vec<SignedWarrant>,
+        ) -> ConductorResult<Vec<SignedWarrant>> {
+            let new_warrants = self
+                .spaces
+                .dht_db(dna_hash)?
+                .async_commit(move |txn| {
+                    let mut new_warrants = Vec::new();
+                    for w in warrants {
+                        if warrant::add_if_unique(txn, w.clone())? {
+                            new_warrants.push(w);
+                        }
+                    }
+                    StateMutationResult::Ok(new_warrants)
+                })
+                .await?;
+            if self.config.block_warranted_agents {
+                let agents: HashSet<_> = new_warrants
+                    .iter()
+                    .map(|w| w.warrant.warrantee().clone())
+                    .collect();
+                if !agents.is_empty() {
+                    // Make sure the blocked agents are loaded before adding to them.
+                    self.blocked_agents(dna_hash).await?;
+                    self.blocked_agents.share_mut(|blocked| {
+                        blocked
+                            .entry(dna_hash.clone())
+                            .or_default()
+                            .extend(agents.iter().cloned())
+                    });
+                    let removed = self
+                        .spaces
+                        .p2p_agents_db(dna_hash)?
+                        .async_commit(move |txn| {
+                            let mut removed = Vec::new();
+                            for agent in agents {
+                                let kagent = agent.to_kitsune();
+                                if let Some(info) = txn.p2p_get_agent(&kagent)? {
+                                    removed.push((agent, info));
+                                }
+                                txn.p2p_remove_agent(&kagent)?;
+                            }
+                            DatabaseResult::Ok(removed)
+                        })
+                        .await?;
+                    for (agent, info) in removed {
+                        self.spaces.block_nodes(dna_hash, agent, [&info]);
+                    }
+                }
+            }
+            Ok(new_warrants)
+        }
+
+        /// The agents in this DNA whose connections we refuse because they
+        /// have been warranted. Always empty unless
+        /// [`ConductorConfig::block_warranted_agents`] is set.
+        pub(crate) async fn blocked_agents(
+            &self,
+            dna_hash: &DnaHash,
+        ) -> ConductorResult<HashSet<AgentPubKey>> {
+            if !self.config.block_warranted_agents {
+                return Ok(HashSet::new());
+            }
+            if let Some(blocked) = self
+                .blocked_agents
+                .share_ref(|blocked| blocked.get(dna_hash).cloned())
+            {
+                return Ok(blocked);
+            }
+            let warrantees = self
+                .spaces
+                .dht_db(dna_hash)?
+                .async_reader(|txn| warrant::list_warrantees(&txn))
+                .await?;
+            // Merge rather than replace, in case an agent was blocked
+            // while the database was being read.
+            Ok(self.blocked_agents.share_mut(|blocked| {
+                let blocked = blocked.entry(dna_hash.clone()).or_default();
+                blocked.extend(warrantees);
+                blocked.clone()
+            }))
+        }
+
+        /// Stop blocking an agent in this DNA by removing the warrants
+        /// which have been issued against it.
+        pub async fn unblock_agent(
+            &self,
+            dna_hash: &DnaHash,
+            agent: AgentPubKey,
+        ) -> ConductorResult<()> {
+            self.spaces
+                .dht_db(dna_hash)?
+                .async_commit({
+                    let agent = agent.clone();
+                    move |txn| warrant::remove_warrants(txn, &agent)
+                })
+                .await?;
+            self.blocked_agents.share_mut(|blocked| {
+                if let Some(blocked) = blocked.get_mut(dna_hash) {
+                    blocked.remove(&agent);
+                }
+            });
+            self.spaces.unblock_nodes(dna_hash, &agent);
+            Ok(())
         }
     }
 }
@@ -1100,7 +1715,22 @@ mod app_impls {
             } = payload;
 
             let bundle: AppBundle = {
-                let original_bundle = source.resolve().await?;
+                let (original_bundle, fetched_dnas) = source
+                    .resolve()
+                    .await?
+                    .embed_remote_dnas(
+                        |hash| self.get_dna_file(hash),
+                        |url| self.remote_dnas.share_ref(|dnas| dnas.get(url).cloned()),
+                    )
+                    .await?;
+                // Keep the downloaded DNAs around, so that they don't have to
+                // be fetched again for other apps which use them.
+                for (url, dna) in fetched_dnas {
+                    let dna_hash = dna.dna_hash().clone();
+                    self.clone().register_dna(dna).await?;
+                    self.remote_dnas
+                        .share_mut(|dnas| dnas.insert(url, dna_hash));
+                }
                 if let Some(network_seed) = network_seed {
                     let mut manifest = original_bundle.manifest().to_owned();
                     manifest.set_network_seed(network_seed);
@@ -1112,8 +1742,13 @@ mod app_impls {
 
             let installed_app_id =
                 installed_app_id.unwrap_or_else(|| bundle.manifest().app_name().to_owned());
+            let gamut = self
+                .get_state()
+                .await?
+                .dna_gamut()
+                .with_dnas(self.list_dnas());
             let ops = bundle
-                .resolve_cells(agent_key.clone(), DnaGamut::placeholder(), membrane_proofs)
+                .resolve_cells(agent_key.clone(), gamut, membrane_proofs)
                 .await?;
 
             let cells_to_create = ops.cells_to_create();
@@ -1142,6 +1777,8 @@ mod app_impls {
             let self_clone = self.clone();
             let app = self.remove_app_from_db(installed_app_id).await?;
             tracing::debug!(msg = "Removed app from db.", app = ?app);
+            self.app_auth_token_store
+                .share_mut(|store| store.revoke_app_tokens(installed_app_id));
 
             // Remove cells which may now be dangling due to the removed app
             self_clone
@@ -1150,12 +1787,142 @@ mod app_impls {
             Ok(())
         }
 
+        /// Provision the cell of a role which was installed with the `disabled`
+        /// provisioning strategy, and start it if the app is running.
+        pub async fn enable_app_role(
+            self: Arc<Self>,
+            payload: EnableAppRolePayload,
+        ) -> ConductorResult<CellId> {
+            let EnableAppRolePayload {
+                app_id,
+                role_name,
+                membrane_proof,
+            } = payload;
+            // Claim the role before running genesis, so that concurrent
+            // calls can't both create a cell for it.
+            let (_, cell_id) = self
+                .update_state_prime({
+                    let app_id = app_id.clone();
+                    let role_name = role_name.clone();
+                    move |mut state| {
+                        let cell_id = state.get_app_mut(&app_id)?.provision_role(&role_name)?;
+                        Ok((state, cell_id))
+                    }
+                })
+                .await?;
+
+            let genesis = crate::conductor::conductor::genesis_cells(
+                self.clone(),
+                vec![(cell_id.clone(), membrane_proof)],
+            )
+            .await;
+            if let Err(e) = genesis {
+                self.update_state_prime({
+                    let app_id = app_id.clone();
+                    move |mut state| {
+                        state.get_app_mut(&app_id)?.unprovision_role(&role_name)?;
+                        Ok((state, ()))
+                    }
+                })
+                .await?;
+                return Err(e);
+            }
+
+            self.create_and_add_initialized_cells_for_running_apps(Some(&app_id))
+                .await?;
+            Ok(cell_id)
+        }
+
+        /// Provision the cell of a role whose provisioning was deferred by the
+        /// app manifest, applying the given modifiers to the role's DNA.
+        pub async fn provision_deferred_cell(
+            self: Arc<Self>,
+            payload: ProvisionDeferredCellPayload,
+        ) -> ConductorResult<InstalledCell> {
+            let ProvisionDeferredCellPayload {
+                app_id,
+                role_name,
+                modifiers,
+                membrane_proof,
+            } = payload;
+            let state = self.get_state().await?;
+            let role = state.get_app(&app_id)?.role(&role_name)?;
+            if !role.is_deferred() {
+                return Err(AppError::RoleNotDeferred(role_name).into());
+            }
+            let base_cell_id = role.cell_id().clone();
+
+            let dna = self
+                .get_dna_file(base_cell_id.dna_hash())
+                .ok_or_else(|| DnaError::DnaMissing(base_cell_id.dna_hash().clone()))?
+                .update_modifiers(modifiers.serialized()?);
+            let cell_id = CellId::new(dna.dna_hash().clone(), base_cell_id.agent_pubkey().clone());
+            self.register_dna(dna).await?;
+
+            // Claim the role before running genesis, so that concurrent
+            // calls can't both create a cell for it.
+            self.update_state_prime({
+                let app_id = app_id.clone();
+                let role_name = role_name.clone();
+                let base_cell_id = base_cell_id.clone();
+                let cell_id = cell_id.clone();
+                move |mut state| {
+                    let app = state.get_app_mut(&app_id)?;
+                    let role = app.role(&role_name)?;
+                    if !role.is_deferred() || role.cell_id() != &base_cell_id {
+                        return Err(AppError::RoleNotDeferred(role_name).into());
+                    }
+                    app.provision_deferred_role(&role_name, cell_id)?;
+                    Ok((state, ()))
+                }
+            })
+            .await?;
+
+            let genesis = crate::conductor::conductor::genesis_cells(
+                self.clone(),
+                vec![(cell_id.clone(), membrane_proof)],
+            )
+            .await;
+            if let Err(e) = genesis {
+                self.update_state_prime({
+                    let app_id = app_id.clone();
+                    let role_name = role_name.clone();
+                    move |mut state| {
+                        state
+                            .get_app_mut(&app_id)?
+                            .unprovision_deferred_role(&role_name, base_cell_id)?;
+                        Ok((state, ()))
+                    }
+                })
+                .await?;
+                return Err(e);
+            }
+
+            self.create_and_add_initialized_cells_for_running_apps(Some(&app_id))
+                .await?;
+            Ok(InstalledCell::new(cell_id, role_name))
+        }
+
         /// List active AppIds
         pub async fn list_running_apps(&self) -> ConductorResult<Vec<InstalledAppId>> {
             let state = self.get_state().await?;
             Ok(state.running_apps().map(|(id, _)| id).cloned().collect())
         }
 
+        /// The ids of all the cells of an installed app
+        pub async fn app_cell_ids(
+            &self,
+            installed_app_id: &InstalledAppId,
+        ) -> ConductorResult<HashSet<CellId>> {
+            let state = self.get_state().await?;
+            let cell_ids = state
+                .get_app(installed_app_id)?
+                .all_cells()
+                .cloned()
+                .collect();
+            Ok(cell_ids)
+        }
+
         /// List Apps with their information
         pub async fn list_apps(
             &self,
@@ -1261,6 +2028,292 @@ mod app_impls {
     }
 }
 
+/// Methods related to migrating agents between DNAs
+mod migrate_agent_impls {
+    use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
+    use crate::core::workflow::migrate_agent_workflow::migrate_agent_workflow;
+    use crate::core::workflow::migrate_agent_workflow::MigrateAgentWorkflowArgs;
+
+    use super::*;
+
+    impl Conductor {
+        /// Migrate the source chain of a cell's agent to the agent's cell of
+        /// another DNA, returning the id of that cell.
+        pub async fn migrate_agent(
+            self: Arc<Self>,
+            payload: MigrateAgentPayload,
+        ) -> ConductorResult<CellId> {
+            let MigrateAgentPayload {
+                cell_id,
+                new_dna_hash,
+            } = payload;
+            let new_cell_id = CellId::new(new_dna_hash, cell_id.agent_pubkey().clone());
+            let old = self.cell_by_id(&cell_id)?.migrate_agent_chain().await?;
+            let new = self.cell_by_id(&new_cell_id)?.migrate_agent_chain().await?;
+            let args = MigrateAgentWorkflowArgs {
+                conductor_handle: self.clone(),
+                old,
+                new,
+            };
+            match migrate_agent_workflow(args).await? {
+                MigrateAgentResult::Pass => Ok(new_cell_id),
+                MigrateAgentResult::Fail(zome_name, reason) => {
+                    Err(ConductorError::MigrateAgentFailed(zome_name, reason))
+                }
+            }
+        }
+    }
+}
+
+/// Methods related to the DPKI service
+mod dpki_impls {
+    use futures::future::BoxFuture;
+    use holochain_state::nonce::fresh_nonce;
+
+    use super::*;
+
+    /// How long the DPKI state of a key which isn't revoked is reused for
+    /// actions authored after it was looked up.
+    pub const DPKI_KEY_STATE_TTL: std::time::Duration = std::time::Duration::from_secs(60);
+
+    impl Conductor {
+        /// Install and enable the DPKI app if the conductor is configured with
+        /// DPKI and the app isn't installed yet, passing it the configured
+        /// `init_params`.
+        ///
+        /// Every op is checked against DPKI, so the conductor can't run
+        /// without the app, and an error is returned if it isn't installed
+        /// and there is no bundle to install it from.
+        pub(crate) async fn initialize_dpki(self: Arc<Self>) -> ConductorResult<()> {
+            let config = match &self.config.dpki {
+                Some(config) => config.clone(),
+                None => return Ok(()),
+            };
+            let state = self.get_state().await?;
+            if state.get_app(&config.instance_id).is_ok() {
+                return Ok(());
+            }
+            let bundle_path = config
+                .bundle_path
+                .ok_or_else(|| ConductorError::DpkiAppNotInstalled(config.instance_id.clone()))?;
+            let agent_key = self.keystore.new_sign_keypair_random().await?;
+            self.clone()
+                .install_app_bundle(InstallAppPayload {
+                    source: AppBundleSource::Path(bundle_path),
+                    agent_key,
+                    installed_app_id: Some(config.instance_id.clone()),
+                    membrane_proofs: HashMap::new(),
+                    network_seed: config.network_seed,
+                })
+                .await?;
+            self.enable_app(config.instance_id).await?;
+            let cell_id = self
+                .dpki_cell_id()
+                .await?
+                .ok_or(ConductorError::DpkiNotConfigured)?;
+            self.call_dpki(cell_id, DPKI_INIT_FN, config.init_params)
+                .await
+        }
+
+        /// The cell of the DPKI app, or None if the conductor isn't
+        /// configured with DPKI.
+        ///
+        /// The cell is cached once found, so that the conductor state is only
+        /// read again if the cell stops running.
+        pub(crate) async fn dpki_cell_id(&self) -> ConductorResult<Option<CellId>> {
+            let instance_id = match &self.config.dpki {
+                Some(config) => &config.instance_id,
+                None => return Ok(None),
+            };
+            let cached = self.dpki_cell_id.share_ref(|id| id.clone());
+            if let Some(cell_id) = cached {
+                let running = self
+                    .running_cells
+                    .share_ref(|cells| cells.get(&cell_id).map_or(false, |item| item.is_running()));
+                if running {
+                    return Ok(Some(cell_id));
+                }
+            }
+            let state = self.get_state().await?;
+            let app = state.get_app(instance_id)?;
+            if !app.status().is_running() {
+                return Err(ConductorError::AppNotRunning(instance_id.clone()));
+            }
+            let cell_id = app.provisioned_cells().next().map(|(_, id)| id.clone());
+            self.dpki_cell_id.share_mut(|id| *id = cell_id.clone());
+            Ok(cell_id)
+        }
+
+        /// Generate a new agent key and register it with the DPKI service.
+        /// The DPKI app is checked first so no key is generated if it isn't
+        /// running, but a key is still left behind if registering it fails.
+        pub async fn generate_agent_key(&self) -> ConductorResult<AgentPubKey> {
+            let cell_id = self.dpki_cell_id().await?;
+            let agent_key = self.keystore.new_sign_keypair_random().await?;
+            if let Some(cell_id) = cell_id {
+                self.call_dpki(cell_id, DPKI_REGISTER_KEY_FN, agent_key.clone())
+                    .await?;
+            }
+            Ok(agent_key)
+        }
+
+        /// Revoke an agent key in the DPKI service, returning its new state.
+        pub async fn revoke_agent_key(&self, agent_key: AgentPubKey) -> ConductorResult<KeyState> {
+            let cell_id = self
+                .dpki_cell_id()
+                .await?
+                .ok_or(ConductorError::DpkiNotConfigured)?;
+            let state: KeyState = self
+                .call_dpki(cell_id, DPKI_REVOKE_KEY_FN, agent_key.clone())
+                .await?;
+            self.dpki_key_states.share_mut(|states| {
+                states.insert(agent_key, (state.clone(), Timestamp::now()));
+            });
+            Ok(state)
+        }
+
+        /// Generate a new agent key and register it with the DPKI service as
+        /// the replacement of an existing one, returning the new key.
+        pub async fn rotate_agent_key(
+            &self,
+            agent_key: AgentPubKey,
+        ) -> ConductorResult<AgentPubKey> {
+            let cell_id = self
+                .dpki_cell_id()
+                .await?
+                .ok_or(ConductorError::DpkiNotConfigured)?;
+            let new_key = self.keystore.new_sign_keypair_random().await?;
+            let rotation = KeyRotation {
+                old_key: agent_key.clone(),
+                new_key: new_key.clone(),
+            };
+            self.call_dpki(cell_id, DPKI_ROTATE_KEY_FN, rotation)
+                .await?;
+            self.dpki_key_states.share_mut(|states| {
+                states.remove(&agent_key);
+            });
+            Ok(new_key)
+        }
+
+        /// Look up the state of an agent key in the DPKI service.
+        /// Returns None if the conductor isn't configured with DPKI.
+        ///
+        /// Sys validation calls this, and zome calls run sys validation,
+        /// so the future is boxed to break the cycle.
+        pub fn dpki_key_state<'a>(
+            &'a self,
+            agent_key: &'a AgentPubKey,
+        ) -> BoxFuture<'a, ConductorResult<Option<KeyState>>> {
+            async move {
+                match self.dpki_cell_id().await? {
+                    Some(cell_id) => Ok(Some(
+                        self.call_dpki(cell_id, DPKI_KEY_STATE_FN, agent_key)
+                            .await?,
+                    )),
+                    None => Ok(None),
+                }
+            }
+            .boxed()
+        }
+
+        /// Look up the state of an agent key for an action authored at the
+        /// given time, caching the result.
+        ///
+        /// Keys are revoked as of the time of revocation, so a key which was
+        /// valid when it was looked up stays valid for anything authored
+        /// before then, and a revoked key stays revoked. Lookups of keys which
+        /// aren't revoked are also reused for `DPKI_KEY_STATE_TTL`, so a
+        /// revocation can take that long to be noticed for new actions.
+        pub fn dpki_key_state_at<'a>(
+            &'a self,
+            agent_key: &'a AgentPubKey,
+            timestamp: Timestamp,
+        ) -> BoxFuture<'a, ConductorResult<Option<KeyState>>> {
+            async move {
+                let cached = self.dpki_key_states.share_ref(|states| {
+                    states
+                        .get(agent_key)
+                        .and_then(|(state, looked_up_at)| match state {
+                            KeyState::Revoked { .. } => Some(state.clone()),
+                            _ if timestamp < *looked_up_at => Some(state.clone()),
+                            _ if *looked_up_at
+                                >= Timestamp::now().saturating_sub(&DPKI_KEY_STATE_TTL) =>
+                            {
+                                Some(state.clone())
+                            }
+                            _ => None,
+                        })
+                });
+                if cached.is_some() {
+                    return Ok(cached);
+                }
+                let looked_up_at = Timestamp::now();
+                let state = self.dpki_key_state(agent_key).await?;
+                if let Some(state) = &state {
+                    self.dpki_key_states.share_mut(|states| {
+                        states.insert(agent_key.clone(), (state.clone(), looked_up_at));
+                    });
+                }
+                Ok(state)
+            }
+            .boxed()
+        }
+
+        /// Treat every cached DPKI key state as if it was looked up longer
+        /// than `DPKI_KEY_STATE_TTL` ago.
+        #[cfg(test)]
+        pub(crate) fn expire_dpki_key_states(&self) {
+            let expired = Timestamp::now().saturating_sub(&(DPKI_KEY_STATE_TTL * 2));
+            self.dpki_key_states.share_mut(|states| {
+                for (_, looked_up_at) in states.values_mut() {
+                    *looked_up_at = expired;
+                }
+            });
+        }
+
+        /// Call a function of the DPKI zome as the agent of the DPKI cell.
+        async fn call_dpki<I, O>(
+            &self,
+            cell_id: CellId,
+            fn_name: &str,
+            payload: I,
+        ) -> ConductorResult<O>
+        where
+            I: serde::Serialize + std::fmt::Debug,
+            O: serde::de::DeserializeOwned + std::fmt::Debug,
+        {
+            let (nonce, expires_at) = fresh_nonce(Timestamp::now())?;
+            let call = ZomeCall::try_from_unsigned_zome_call(
+                &self.keystore,
+                ZomeCallUnsigned {
+                    provenance: cell_id.agent_pubkey().clone(),
+                    cell_id,
+                    zome_name: DPKI_ZOME_NAME.into(),
+                    fn_name: fn_name.into(),
+                    cap_secret: None,
+                    payload: ExternIO::encode(payload)?,
+                    nonce,
+                    expires_at,
+                },
+            )
+            .await?;
+            let fn_name = call.fn_name.clone();
+            match self
+                .call_zome(call)
+                .await
+                .map_err(|e| ConductorError::DpkiCallFailed(fn_name.clone(), e.to_string()))?
+            {
+                Ok(ZomeCallResponse::Ok(output)) => Ok(output.decode()?),
+                Ok(response) => Err(ConductorError::DpkiCallFailed(
+                    fn_name,
+                    format!("{:?}", response),
+                )),
+                Err(e) => Err(ConductorError::DpkiCallFailed(fn_name, e.to_string())),
+            }
+        }
+    }
+}
+
 /// Methods related to cell access
 mod cell_impls {
     use super::*;
@@ -1339,15 +2392,10 @@ mod clone_cell_impls {
                         .to_string(),
                 ));
             }
+            // Roles which are not provisioned, e.g. those with the `disabled`
+            // strategy, can still be cloned from the DNA they refer to.
             let state = self.get_state().await?;
-            let app = state.get_app(&app_id)?;
-            app.provisioned_cells()
-                .find(|(app_role_name, _)| **app_role_name == role_name)
-                .ok_or_else(|| {
-                    ConductorError::CloneCellError(
-                        "no base cell found for provided role id".to_string(),
-                    )
-                })?;
+            state.get_app(&app_id)?.role(&role_name)?;
 
             // add cell to app
             let installed_clone_cell = self
@@ -1949,6 +2997,119 @@ mod misc_impls {
                 .map_err(crate::conductor::api::error::ConductorApiError::other)
         }
 
+        /// All of this conductor's metrics, in the OpenMetrics text format
+        pub async fn open_metrics(&self) -> ConductorApiResult<String> {
+            use holochain_p2p::HolochainP2pSender;
+            let conductor_db_pools = [
+                ("conductor", self.spaces.conductor_db.pool_state()),
+                ("wasm", self.spaces.wasm_db.pool_state()),
+            ];
+            let spaces = self.spaces.get_from_spaces(|space| {
+                (
+                    (*space.dna_hash).clone(),
+                    space.dht_db.clone(),
+                    vec![
+                        ("authored", space.authored_db.pool_state()),
+                        ("dht", space.dht_db.pool_state()),
+                        ("cache", space.cache_db.pool_state()),
+                        ("p2p_agents", space.p2p_agents_db.pool_state()),
+                        ("p2p_metrics", space.p2p_metrics_db.pool_state()),
+                    ],
+                )
+            });
+            let mut dnas = Vec::with_capacity(spaces.len());
+            for (dna_hash, dht_db, db_pools) in spaces {
+                // There are no network metrics for a space which
+                // hasn't been joined.
+                let network = self
+                    .holochain_p2p()
+                    .get_diagnostics(dna_hash.clone())
+                    .await
+                    .ok()
+                    .map(|diagnostics| diagnostics.metrics);
+                let integration = integration_dump(&dht_db.into()).await?;
+                dnas.push(DnaMetrics {
+                    dna_hash,
+                    network,
+                    integration,
+                    db_pools,
+                });
+            }
+            Ok(super::metrics::render(
+                &conductor_db_pools,
+                &dnas,
+                &self.zome_call_metrics,
+            ))
+        }
+
+        /// The gossip bandwidth throttling currently in effect
+        pub async fn get_gossip_throttle(
+            &self,
+        ) -> ConductorApiResult<kitsune_p2p::GossipThrottleStatus> {
+            use holochain_p2p::HolochainP2pSender;
+            self.holochain_p2p()
+                .get_gossip_throttle()
+                .await
+                .map_err(crate::conductor::api::error::ConductorApiError::other)
+        }
+
+        /// Replace the gossip bandwidth throttling configuration
+        pub async fn update_gossip_throttle(
+            &self,
+            config: kitsune_p2p::GossipThrottleConfig,
+        ) -> ConductorApiResult<()> {
+            use holochain_p2p::HolochainP2pSender;
+            self.holochain_p2p()
+                .set_gossip_throttle(config)
+                .await
+                .map_err(crate::conductor::api::error::ConductorApiError::other)
+        }
+
+        /// The network tuning params in effect for a DNA,
+        /// or for every DNA with a running cell
+        pub async fn get_tuning_params(
+            &self,
+            dna_hash: Option<DnaHash>,
+        ) -> ConductorApiResult<Vec<DnaTuningParams>> {
+            use holochain_p2p::HolochainP2pSender;
+            let dna_hashes = match dna_hash {
+                Some(dna_hash) => vec![dna_hash],
+                None => self
+                    .running_cell_ids()
+                    .into_iter()
+                    .map(|cell_id| cell_id.dna_hash().clone())
+                    .collect::<HashSet<_>>()
+                    .into_iter()
+                    .collect(),
+            };
+            let mut out = Vec::with_capacity(dna_hashes.len());
+            for dna_hash in dna_hashes {
+                let tuning_params = self
+                    .holochain_p2p()
+                    .get_tuning_params(dna_hash.clone())
+                    .await
+                    .map_err(crate::conductor::api::error::ConductorApiError::other)?;
+                out.push(DnaTuningParams {
+                    dna_hash,
+                    tuning_params,
+                });
+            }
+            Ok(out)
+        }
+
+        /// Change some network tuning params for a DNA, or for every DNA
+        pub async fn update_tuning_params(
+            &self,
+            dna_hash: Option<DnaHash>,
+            tuning_params: kitsune_p2p_types::config::TuningParamsPatch,
+        ) -> ConductorApiResult<()> {
+            use holochain_p2p::HolochainP2pSender;
+            self.holochain_p2p()
+                .update_tuning_params(dna_hash, tuning_params)
+                .await
+                .map_err(crate::conductor::api::error::ConductorApiError::other)
+        }
+
         /// Add signed agent info to the conductor
         pub async fn add_agent_infos(
             &self,
@@ -2245,6 +3406,7 @@ impl Conductor {
             let managed_task_stop_broadcaster = managed_task_stop_broadcaster.clone();
             let chc = handle.chc(cell_id);
             async move {
+                let chc = chc.map_err(|err| (cell_id.clone(), err.into()))?;
                 let holochain_p2p_cell =
                     handle.holochain_p2p.to_dna(cell_id.dna_hash().clone(), chc);
 
@@ -2494,7 +3656,7 @@ pub(crate) async fn genesis_cells(
             let dht_db = space.dht_db;
             let dht_db_cache = space.dht_query_cache;
             let conductor = conductor.clone();
-            let chc = conductor.chc(&cell_id);
+            let chc = conductor.chc(&cell_id)?;
             let cell_id_inner = cell_id.clone();
             let ribosome = conductor
                 .get_ribosome(cell_id.dna_hash())
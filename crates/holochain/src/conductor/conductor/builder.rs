@@ -218,10 +218,13 @@ impl ConductorBuilder {
 
         tokio::task::spawn(p2p_event_task(p2p_evt, conductor.clone()));
 
+        conductor.clone().start_warrant_checks();
+
         Self::spawn_post_commit(conductor.clone(), post_commit_receiver);
 
         let configs = conductor_config.admin_interfaces.unwrap_or_default();
         let cell_startup_errors = conductor.clone().initialize_conductor(configs).await?;
+        conductor.clone().initialize_dpki().await?;
 
         // TODO: This should probably be emitted over the admin interface
         if !cell_startup_errors.is_empty() {
@@ -4,12 +4,12 @@ use super::*;
 
 impl Conductor {
     #[allow(unused_variables)]
-    pub(crate) fn chc(&self, cell_id: &CellId) -> Option<ChcImpl> {
+    pub(crate) fn chc(&self, cell_id: &CellId) -> ChcResult<Option<ChcImpl>> {
         cfg_if::cfg_if! {
             if #[cfg(feature = "chc")] {
                 crate::conductor::chc::build_chc(self.config.chc_namespace.as_ref(), cell_id)
             } else {
-                None
+                Ok(None)
             }
         }
     }
@@ -21,7 +21,7 @@ impl Conductor {
         cell_id: CellId,
         enable_app: Option<InstalledAppId>,
     ) -> ConductorApiResult<()> {
-        if let Some(chc) = self.chc(&cell_id) {
+        if let Some(chc) = self.chc(&cell_id)? {
             let db = self.get_authored_db(cell_id.dna_hash())?;
             let author = cell_id.agent_pubkey().clone();
             let top_hash = db
@@ -1,3 +1,4 @@
+use std::path::PathBuf;
 use std::sync::atomic::AtomicU32;
 use std::sync::atomic::Ordering;
 
@@ -5,13 +6,23 @@ use super::Conductor;
 use super::ConductorState;
 use super::*;
 use crate::conductor::api::error::ConductorApiError;
+use crate::conductor::api::AdminInterfaceApi;
+use crate::conductor::api::AppInterfaceApi;
+use crate::conductor::api::RealAdminInterfaceApi;
+use crate::conductor::api::RealAppInterfaceApi;
 use crate::core::ribosome::guest_callback::validate::ValidateResult;
 use crate::sweettest::*;
+use crate::test_utils::inline_zomes::simple_crud_zome;
 use crate::{
     assert_eq_retry_10s, core::ribosome::guest_callback::genesis_self_check::GenesisSelfCheckResult,
 };
 use ::fixt::prelude::*;
-use holochain_conductor_api::AppInfoStatus;
+use holochain_conductor_api::conductor::DpkiConfig;
+use holochain_conductor_api::signal_subscription::*;
+use holochain_conductor_api::{
+    AdminRequest, AdminResponse, AppInfoStatus, AppRequest, AppResponse, CellInfo,
+    IssueAppAuthenticationTokenPayload,
+};
 use holochain_keystore::crude_mock_keystore::*;
 use holochain_state::prelude::test_keystore;
 use holochain_types::inline_zome::InlineZomeSet;
@@ -213,19 +224,6 @@ async fn common_genesis_test_app(
 ) -> ConductorApiResult<SweetApp> {
     let hardcoded_zome = InlineIntegrityZome::new_unique(Vec::new(), 0);
 
-    // Just a strong reminder that we need to be careful once we start using existing Cells:
-    // When a Cell panics or fails validation in general, we want to disable all Apps touching that Cell.
-    // However, if the panic/failure happens during Genesis, we want to completely
-    // destroy the app which is attempting to Create that Cell, but *NOT* any other apps
-    // which might be touching that Cell.
-    //
-    // It probably works out to be the same either way, since if we are creating a Cell,
-    // no other app could be possibly referencing it, but just in case we have some kind of complex
-    // behavior like installing two apps which reference each others' Cells at the same time,
-    // we need to be aware of this distinction.
-    holochain_types::app::we_must_remember_to_rework_cell_panic_handling_after_implementing_use_existing_cell_resolution(
-    );
-
     // Create one DNA which always works, and another from a zome that gets passed in
     let (dna_hardcoded, _, _) = mk_dna(("hardcoded", hardcoded_zome)).await;
     let (dna_custom, _, _) = mk_dna(custom_zomes).await;
@@ -270,6 +268,311 @@ async fn test_uninstall_app() {
     );
 }
 
+/// Build an AppBundle with the given roles. DNAs are not bundled, so the
+/// locations given are only resolved if a new cell needs to be created.
+async fn app_bundle_from_roles(
+    name: &str,
+    roles: Vec<(RoleName, CellProvisioning, DnaVersionSpec)>,
+) -> AppBundle {
+    let roles = roles
+        .into_iter()
+        .map(|(role_name, provisioning, version)| AppRoleManifest {
+            name: role_name,
+            provisioning: Some(provisioning),
+            dna: AppRoleDnaManifest {
+                location: Some(mr_bundle::Location::Path("nonexistent.dna".into())),
+                modifiers: DnaModifiersOpt::none(),
+                version: Some(version.into()),
+                clone_limit: 1,
+            },
+        })
+        .collect();
+    let manifest = AppManifestCurrentBuilder::default()
+        .name(name.into())
+        .description(None)
+        .roles(roles)
+        .build()
+        .unwrap();
+    AppBundle::new(manifest.into(), vec![], PathBuf::from("."))
+        .await
+        .unwrap()
+}
+
+async fn install_bundle(
+    conductor: &SweetConductor,
+    bundle: AppBundle,
+    agent_key: AgentPubKey,
+) -> ConductorResult<StoppedApp> {
+    conductor
+        .raw_handle()
+        .install_app_bundle(InstallAppPayload {
+            source: AppBundleSource::Bundle(bundle),
+            agent_key,
+            installed_app_id: None,
+            membrane_proofs: Default::default(),
+            network_seed: None,
+        })
+        .await
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_use_existing_cell_and_dependency_tracking() {
+    observability::test_run().ok();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let agent = SweetAgents::one(conductor.keystore()).await;
+    let (dna, _, _) = mk_dna(("zome", InlineIntegrityZome::new_unique(vec![], 0))).await;
+    let version = DnaVersionSpec::from(vec![dna.dna_hash().clone().into()]);
+    let use_existing = |name| {
+        app_bundle_from_roles(
+            name,
+            vec![(
+                "profile".into(),
+                CellProvisioning::UseExisting { deferred: false },
+                version.clone(),
+            )],
+        )
+    };
+
+    // The app can't be installed before its dependency is present
+    assert_matches!(
+        install_bundle(&conductor, use_existing("b").await, agent.clone()).await,
+        Err(ConductorError::AppBundleError(
+            AppBundleError::CellResolutionFailure(_, _)
+        ))
+    );
+
+    let (cell,) = conductor
+        .setup_app_for_agent("a", agent.clone(), [&dna])
+        .await
+        .unwrap()
+        .into_tuple();
+    let cell_id = cell.cell_id().clone();
+
+    // Both UseExisting and CreateIfNotExists bind to the cell created by app "a"
+    let create_if_not_exists = app_bundle_from_roles(
+        "c",
+        vec![(
+            "profile".into(),
+            CellProvisioning::CreateIfNotExists { deferred: false },
+            version.clone(),
+        )],
+    )
+    .await;
+    for bundle in [use_existing("b").await, create_if_not_exists] {
+        let app = install_bundle(&conductor, bundle, agent.clone())
+            .await
+            .unwrap();
+        let role = app.role(&"profile".into()).unwrap();
+        assert_eq!(role.cell_id(), &cell_id);
+        assert!(role.is_dependency());
+    }
+
+    // Only one cell exists across the three apps
+    let state = conductor.get_state_from_handle().await.unwrap();
+    assert_eq!(
+        state
+            .installed_apps()
+            .values()
+            .flat_map(|app| app.all_cells().cloned().collect::<Vec<_>>())
+            .collect::<HashSet<_>>(),
+        hashset! { cell_id.clone() }
+    );
+
+    // App "a" can't be uninstalled while other apps depend on its cell
+    assert_matches!(
+        conductor.raw_handle().uninstall_app(&"a".to_string()).await,
+        Err(ConductorError::AppHasDependents(id, dependents))
+            if id == "a" && dependents == vec!["b".to_string(), "c".to_string()]
+    );
+
+    for app_id in ["b", "c", "a"] {
+        conductor
+            .raw_handle()
+            .uninstall_app(&app_id.to_string())
+            .await
+            .unwrap();
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_disabled_role_can_be_cloned_and_enabled() {
+    observability::test_run().ok();
+    let conductor = SweetConductor::from_standard_config().await;
+    let agent = SweetAgents::one(conductor.keystore()).await;
+    let (dna, _, _) = mk_dna(("zome", InlineIntegrityZome::new_unique(vec![], 0))).await;
+    let version = DnaVersionSpec::from(vec![dna.dna_hash().clone().into()]);
+    let bundle = || {
+        app_bundle_from_roles(
+            "app",
+            vec![("role".into(), CellProvisioning::Disabled, version.clone())],
+        )
+    };
+    let app_id: InstalledAppId = "app".into();
+    let role_name: RoleName = "role".into();
+    let cell_id = CellId::new(dna.dna_hash().clone(), agent.clone());
+
+    // The DNA of a disabled role must already be registered
+    assert_matches!(
+        install_bundle(&conductor, bundle().await, agent.clone()).await,
+        Err(ConductorError::AppBundleError(
+            AppBundleError::CellResolutionFailure(_, _)
+        ))
+    );
+    conductor.register_dna(dna.clone()).await.unwrap();
+    install_bundle(&conductor, bundle().await, agent.clone())
+        .await
+        .unwrap();
+    conductor
+        .raw_handle()
+        .enable_app(app_id.clone())
+        .await
+        .unwrap();
+
+    // The role is reported, but no cell is running for it
+    let app_info = conductor.get_app_info(&app_id).await.unwrap().unwrap();
+    assert_matches!(
+        &app_info.cell_info[&role_name][..],
+        [CellInfo::Stem(stem)] if &stem.dna == dna.dna_hash()
+    );
+    assert!(!conductor.list_cell_ids(None).contains(&cell_id));
+
+    // Clones of the role's DNA can be created before the role is enabled
+    let clone = conductor
+        .raw_handle()
+        .create_clone_cell(CreateCloneCellPayload {
+            app_id: app_id.clone(),
+            role_name: role_name.clone(),
+            modifiers: DnaModifiersOpt::none().with_network_seed("clone".into()),
+            membrane_proof: None,
+            name: None,
+        })
+        .await
+        .unwrap();
+    assert!(conductor.list_cell_ids(None).contains(clone.as_id()));
+
+    let enable_role = || {
+        conductor
+            .raw_handle()
+            .enable_app_role(EnableAppRolePayload {
+                app_id: app_id.clone(),
+                role_name: role_name.clone(),
+                membrane_proof: None,
+            })
+    };
+    assert_eq!(enable_role().await.unwrap(), cell_id);
+    assert!(conductor.list_cell_ids(None).contains(&cell_id));
+    let app_info = conductor.get_app_info(&app_id).await.unwrap().unwrap();
+    assert_matches!(
+        &app_info.cell_info[&role_name][..],
+        [CellInfo::Provisioned(cell), CellInfo::Cloned(_)] if cell.cell_id == cell_id && cell.enabled
+    );
+
+    assert_matches!(
+        enable_role().await,
+        Err(ConductorError::AppError(AppError::RoleAlreadyProvisioned(
+            _
+        )))
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_provision_deferred_cell() {
+    observability::test_run().ok();
+    let conductor = SweetConductor::from_standard_config().await;
+    let agent = SweetAgents::one(conductor.keystore()).await;
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Foo]).await;
+    let app_id: InstalledAppId = "app".into();
+    let role_name: RoleName = "role".into();
+
+    // The DNA of a deferred role is registered at installation, but no cell
+    // is created
+    let dna_path = PathBuf::from("role.dna");
+    let manifest = AppManifestCurrentBuilder::default()
+        .name(app_id.clone())
+        .description(None)
+        .roles(vec![AppRoleManifest {
+            name: role_name.clone(),
+            provisioning: Some(CellProvisioning::Create { deferred: true }),
+            dna: AppRoleDnaManifest {
+                location: Some(mr_bundle::Location::Bundled(dna_path.clone())),
+                modifiers: DnaModifiersOpt::none(),
+                version: None,
+                clone_limit: 0,
+            },
+        }])
+        .build()
+        .unwrap();
+    let bundle = AppBundle::new(
+        manifest.into(),
+        vec![(
+            dna_path,
+            DnaBundle::from_dna_file(dna.clone()).await.unwrap(),
+        )],
+        PathBuf::from("."),
+    )
+    .await
+    .unwrap();
+    let app = install_bundle(&conductor, bundle, agent.clone())
+        .await
+        .unwrap();
+    let base_cell_id = app.role(&role_name).unwrap().cell_id().clone();
+    assert_eq!(base_cell_id.agent_pubkey(), &agent);
+    conductor
+        .raw_handle()
+        .enable_app(app_id.clone())
+        .await
+        .unwrap();
+
+    let app_info = conductor.get_app_info(&app_id).await.unwrap().unwrap();
+    assert_matches!(
+        &app_info.cell_info[&role_name][..],
+        [CellInfo::Deferred(stem)] if &stem.dna == base_cell_id.dna_hash()
+    );
+    assert!(!conductor.list_cell_ids(None).contains(&base_cell_id));
+
+    // A deferred role can't be provisioned without its modifiers
+    assert_matches!(
+        conductor
+            .raw_handle()
+            .enable_app_role(EnableAppRolePayload {
+                app_id: app_id.clone(),
+                role_name: role_name.clone(),
+                membrane_proof: None,
+            })
+            .await,
+        Err(ConductorError::AppError(AppError::RoleDeferred(_)))
+    );
+
+    let provision = || {
+        conductor
+            .raw_handle()
+            .provision_deferred_cell(ProvisionDeferredCellPayload {
+                app_id: app_id.clone(),
+                role_name: role_name.clone(),
+                modifiers: DnaModifiersOpt::none().with_network_seed("runtime seed".into()),
+                membrane_proof: None,
+            })
+    };
+    let installed_cell = provision().await.unwrap();
+    let cell_id = installed_cell.as_id().clone();
+    assert_ne!(cell_id, base_cell_id);
+    assert_eq!(cell_id.agent_pubkey(), &agent);
+    assert!(conductor.list_cell_ids(None).contains(&cell_id));
+
+    let app_info = conductor.get_app_info(&app_id).await.unwrap().unwrap();
+    assert_matches!(
+        &app_info.cell_info[&role_name][..],
+        [CellInfo::Provisioned(cell)]
+            if cell.cell_id == cell_id && cell.dna_modifiers.network_seed == "runtime seed"
+    );
+
+    // The role can only be provisioned once
+    assert_matches!(
+        provision().await,
+        Err(ConductorError::AppError(AppError::RoleNotDeferred(_)))
+    );
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn test_reconciliation_idempotency() {
     observability::test_run().ok();
@@ -937,3 +1240,936 @@ async fn test_init_concurrency() {
     assert_eq!(num_calls_clone.fetch_add(0, Ordering::SeqCst), 100);
     assert_eq!(num_inits_clone.fetch_add(0, Ordering::SeqCst), 1);
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn warrants_are_stored_once_and_block_the_warrantee() {
+    observability::test_run().ok();
+    let mut config = SweetConductorConfig::standard();
+    config.block_warranted_agents = true;
+    let mut conductor = SweetConductor::from_config(config).await;
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+    let dna_hash = dna_file.dna_hash().clone();
+    conductor.setup_app("app", [&dna_file]).await.unwrap();
+
+    let keystore = conductor.keystore().clone();
+    let offender = keystore.new_sign_keypair_random().await.unwrap();
+    let mut forked = Vec::new();
+    for _ in 0..2 {
+        let mut create = fixt!(Create);
+        create.author = offender.clone();
+        create.action_seq = 3;
+        let action = Action::Create(create);
+        let signature = offender.sign(&keystore, &action).await.unwrap();
+        forked.push(SignedAction(action, signature));
+    }
+    let b = forked.pop().unwrap();
+    let a = forked.pop().unwrap();
+    let proof = WarrantProof::ChainIntegrity(ChainIntegrityWarrant::chain_fork(a, b));
+
+    assert!(conductor
+        .blocked_agents(&dna_hash)
+        .await
+        .unwrap()
+        .is_empty());
+
+    // Issuing the same warrant twice only stores it once
+    conductor
+        .issue_warrants(&dna_hash, vec![proof.clone()])
+        .await
+        .unwrap();
+    conductor
+        .issue_warrants(&dna_hash, vec![proof])
+        .await
+        .unwrap();
+
+    let activity = holochain_cascade::authority::handle_get_agent_activity(
+        conductor.get_dht_db(&dna_hash).unwrap().into(),
+        offender.clone(),
+        ChainQueryFilter::new(),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(activity.warrants.len(), 1);
+    assert_eq!(activity.warrants[0].warrant.warrantee(), &offender);
+
+    assert_eq!(
+        conductor.blocked_agents(&dna_hash).await.unwrap(),
+        hashset![offender.clone()]
+    );
+
+    conductor.unblock_agent(&dna_hash, offender).await.unwrap();
+    assert!(conductor
+        .blocked_agents(&dna_hash)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn chain_fork_warrants_issued_by_one_authority_are_accepted_by_another() {
+    use crate::core::workflow::sys_validation_workflow::SysValidationWorkspace;
+
+    observability::test_run().ok();
+    let mut conductors = SweetConductorBatch::from_standard_config(2).await;
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+    let dna_hash = dna_file.dna_hash().clone();
+    let apps = conductors.setup_app("app", [&dna_file]).await.unwrap();
+    let ((issuer,), _) = apps.into_tuples();
+
+    let keystore = conductors[0].keystore().clone();
+    let offender = keystore.new_sign_keypair_random().await.unwrap();
+    let create = |prev_action: ActionHash| {
+        let keystore = keystore.clone();
+        let offender = offender.clone();
+        async move {
+            let mut create = fixt!(Create);
+            create.author = offender.clone();
+            create.action_seq = 3;
+            create.prev_action = prev_action;
+            let action = Action::Create(create);
+            let signature = offender.sign(&keystore, &action).await.unwrap();
+            SignedActionHashed::with_presigned(ActionHashed::from_content_sync(action), signature)
+        }
+    };
+    let prev_action = fixt!(ActionHash);
+    let held = create(prev_action.clone()).await;
+    let forked = create(prev_action).await;
+    // Diverged from the chain before seq 3, so isn't a fork at seq 3
+    let diverged = create(fixt!(ActionHash)).await;
+
+    let space = conductors[0].get_or_create_space(&dna_hash).unwrap();
+    space.dht_db.test_commit({
+        let held = held.clone();
+        move |txn| holochain_state::prelude::insert_action(txn, &held).unwrap()
+    });
+    let workspace = SysValidationWorkspace::new(
+        space.authored_db.into(),
+        space.dht_db.into(),
+        space.dht_query_cache,
+        space.cache_db,
+        Arc::new(dna_file.dna_def().clone()),
+    );
+    assert!(workspace
+        .find_chain_fork(diverged.action())
+        .await
+        .unwrap()
+        .is_none());
+    let other = workspace
+        .find_chain_fork(forked.action())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(other.action(), held.action());
+
+    let warrant = holochain_state::warrant::sign_warrant(
+        &keystore,
+        issuer.agent_pubkey().clone(),
+        WarrantProof::ChainIntegrity(ChainIntegrityWarrant::chain_fork(
+            SignedAction(forked.action().clone(), forked.signature().clone()),
+            other,
+        )),
+    )
+    .await
+    .unwrap();
+    conductors[1]
+        .receive_warrant(&dna_hash, warrant)
+        .await
+        .unwrap();
+
+    let activity = holochain_cascade::authority::handle_get_agent_activity(
+        conductors[1].get_dht_db(&dna_hash).unwrap().into(),
+        offender,
+        ChainQueryFilter::new(),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    assert_eq!(activity.warrants.len(), 1);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn forged_invalid_op_warrants_dont_block_the_warrantee() {
+    observability::test_run().ok();
+    let mut config = SweetConductorConfig::standard();
+    config.block_warranted_agents = true;
+    let mut conductor = SweetConductor::from_config(config).await;
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+    let dna_hash = dna_file.dna_hash().clone();
+    let app = conductor.setup_app("app", [&dna_file]).await.unwrap();
+    let (cell,) = app.into_tuple();
+    let zome = cell.zome(SweetInlineZomes::COORDINATOR);
+
+    let hash: ActionHash = conductor.call(&zome, "create_unit", ()).await;
+    let record: Option<Record> = conductor.call(&zome, "read", hash).await;
+    let record = record.unwrap();
+    let action = SignedAction(record.action().clone(), record.signature().clone());
+
+    // Another agent claims the valid action is invalid
+    let keystore = conductor.keystore().clone();
+    let authority = keystore.new_sign_keypair_random().await.unwrap();
+    let proof = WarrantProof::ChainIntegrity(ChainIntegrityWarrant::InvalidChainOp {
+        action,
+        validation_type: ValidationType::App,
+    });
+    let warrant = holochain_state::warrant::sign_warrant(&keystore, authority, proof)
+        .await
+        .unwrap();
+    conductor.check_warrant(&dna_hash, warrant).await.unwrap();
+
+    // The action validates, so the warrant is dropped
+    assert!(conductor
+        .blocked_agents(&dna_hash)
+        .await
+        .unwrap()
+        .is_empty());
+    let activity = holochain_cascade::authority::handle_get_agent_activity(
+        conductor.get_dht_db(&dna_hash).unwrap().into(),
+        cell.agent_pubkey().clone(),
+        ChainQueryFilter::new(),
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    assert!(activity.warrants.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn migrate_agent_closes_old_chain_and_opens_new_one() {
+    observability::test_run().ok();
+    let zomes = |migrate: MigrateAgentCallbackResult| {
+        InlineZomeSet::new_unique_single("integrity", "coordinator", vec![], 0)
+            .function(
+                "coordinator",
+                "migrate_agent",
+                move |_api, _: MigrateAgent| Ok(migrate.clone()),
+            )
+            .function("coordinator", "query", |api, action_type: ActionType| {
+                Ok(api.query(ChainQueryFilter::new().action_type(action_type))?)
+            })
+    };
+    let (old_dna, _, _) =
+        SweetDnaFile::unique_from_inline_zomes(zomes(MigrateAgentCallbackResult::Pass)).await;
+    let (new_dna, _, _) =
+        SweetDnaFile::unique_from_inline_zomes(zomes(MigrateAgentCallbackResult::Pass)).await;
+    let (refusing_dna, _, _) = SweetDnaFile::unique_from_inline_zomes(zomes(
+        MigrateAgentCallbackResult::Fail("not today".into()),
+    ))
+    .await;
+
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let agent = SweetAgents::one(conductor.keystore()).await;
+    let mut cells = Vec::new();
+    for (app_id, dna) in [
+        ("old", &old_dna),
+        ("new", &new_dna),
+        ("refusing", &refusing_dna),
+    ] {
+        let app = conductor
+            .setup_app_for_agent(app_id, agent.clone(), [dna])
+            .await
+            .unwrap();
+        let (cell,) = app.into_tuple();
+        cells.push(cell);
+    }
+    let (old_cell, new_cell) = (&cells[0], &cells[1]);
+
+    // A refusal from either side leaves both chains untouched
+    let result = conductor
+        .raw_handle()
+        .migrate_agent(MigrateAgentPayload {
+            cell_id: old_cell.cell_id().clone(),
+            new_dna_hash: refusing_dna.dna_hash().clone(),
+        })
+        .await;
+    assert_matches!(result, Err(ConductorError::MigrateAgentFailed(_, reason)) if reason == "not today");
+    let closed: Vec<Record> = conductor
+        .call(
+            &old_cell.zome("coordinator"),
+            "query",
+            ActionType::CloseChain,
+        )
+        .await;
+    assert!(closed.is_empty());
+
+    // Migrating again after success is a no-op
+    for _ in 0..2 {
+        let cell_id = conductor
+            .raw_handle()
+            .migrate_agent(MigrateAgentPayload {
+                cell_id: old_cell.cell_id().clone(),
+                new_dna_hash: new_dna.dna_hash().clone(),
+            })
+            .await
+            .unwrap();
+        assert_eq!(&cell_id, new_cell.cell_id());
+    }
+
+    let closed: Vec<Record> = conductor
+        .call(
+            &old_cell.zome("coordinator"),
+            "query",
+            ActionType::CloseChain,
+        )
+        .await;
+    assert_matches!(
+        closed.as_slice(),
+        [record] if matches!(record.action(), Action::CloseChain(c) if &c.new_dna_hash == new_dna.dna_hash())
+    );
+    let opened: Vec<Record> = conductor
+        .call(
+            &new_cell.zome("coordinator"),
+            "query",
+            ActionType::OpenChain,
+        )
+        .await;
+    assert_matches!(
+        opened.as_slice(),
+        [record] if matches!(record.action(), Action::OpenChain(o) if &o.prev_dna_hash == old_dna.dna_hash())
+    );
+
+    // The closed chain can't be migrated anywhere else
+    let result = conductor
+        .raw_handle()
+        .migrate_agent(MigrateAgentPayload {
+            cell_id: old_cell.cell_id().clone(),
+            new_dna_hash: refusing_dna.dna_hash().clone(),
+        })
+        .await;
+    assert_matches!(result, Err(ConductorError::WorkflowError(_)));
+}
+
+/// A DPKI service which keeps its registry in memory
+fn in_memory_dpki_zome() -> InlineZomeSet {
+    let registry: Arc<parking_lot::Mutex<HashMap<AgentPubKey, KeyState>>> = Default::default();
+    let revoke = |registry: &mut HashMap<AgentPubKey, KeyState>,
+                  key: AgentPubKey,
+                  replaced_by: Option<AgentPubKey>| {
+        let state = KeyState::Revoked {
+            revoked_at: Timestamp::now(),
+            replaced_by,
+        };
+        registry.insert(key, state.clone());
+        state
+    };
+    InlineZomeSet::new_unique_single("integrity", DPKI_ZOME_NAME, vec![], 0)
+        .function(DPKI_ZOME_NAME, DPKI_REGISTER_KEY_FN, {
+            let registry = registry.clone();
+            move |_api, key: AgentPubKey| {
+                registry.lock().insert(key, KeyState::Valid);
+                Ok(())
+            }
+        })
+        .function(DPKI_ZOME_NAME, DPKI_REVOKE_KEY_FN, {
+            let registry = registry.clone();
+            move |_api, key: AgentPubKey| Ok(revoke(&mut registry.lock(), key, None))
+        })
+        .function(DPKI_ZOME_NAME, DPKI_ROTATE_KEY_FN, {
+            let registry = registry.clone();
+            move |_api, rotation: KeyRotation| {
+                let mut registry = registry.lock();
+                revoke(
+                    &mut registry,
+                    rotation.old_key,
+                    Some(rotation.new_key.clone()),
+                );
+                registry.insert(rotation.new_key, KeyState::Valid);
+                Ok(())
+            }
+        })
+        .function(
+            DPKI_ZOME_NAME,
+            DPKI_KEY_STATE_FN,
+            move |_api, key: AgentPubKey| {
+                Ok(registry
+                    .lock()
+                    .get(&key)
+                    .cloned()
+                    .unwrap_or(KeyState::NotFound))
+            },
+        )
+}
+
+/// A conductor configured with the in memory DPKI service as its DPKI app.
+/// The app is installed before DPKI is configured, because the conductor
+/// can't start with DPKI configured but not installed.
+async fn conductor_with_in_memory_dpki() -> SweetConductor {
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let (dpki_dna, _, _) = SweetDnaFile::unique_from_inline_zomes(in_memory_dpki_zome()).await;
+    conductor.setup_app("dpki", [&dpki_dna]).await.unwrap();
+    conductor.shutdown().await;
+    conductor.update_config(|config| {
+        config.dpki = Some(DpkiConfig {
+            instance_id: "dpki".into(),
+            init_params: "".into(),
+            network_seed: None,
+            bundle_path: None,
+        });
+    });
+    conductor.startup().await;
+    conductor
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dpki_registers_revokes_and_rotates_agent_keys() {
+    observability::test_run().ok();
+    let mut conductor = conductor_with_in_memory_dpki().await;
+    let (app_dna, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+
+    let handle = conductor.raw_handle();
+    let admin_api = RealAdminInterfaceApi::new(handle.clone());
+    let generate = || async {
+        match admin_api
+            .handle_admin_request(AdminRequest::GenerateAgentPubKey)
+            .await
+        {
+            AdminResponse::AgentPubKeyGenerated(key) => key,
+            response => panic!("unexpected response {:?}", response),
+        }
+    };
+    let key_state = |key: AgentPubKey| {
+        let handle = handle.clone();
+        async move { handle.dpki_key_state(&key).await.unwrap().unwrap() }
+    };
+
+    // Generated keys are registered
+    let agent = generate().await;
+    assert_eq!(key_state(agent.clone()).await, KeyState::Valid);
+    let unregistered = conductor
+        .keystore()
+        .new_sign_keypair_random()
+        .await
+        .unwrap();
+    assert_eq!(key_state(unregistered).await, KeyState::NotFound);
+
+    let app = conductor
+        .setup_app_for_agent("app", agent.clone(), [&app_dna])
+        .await
+        .unwrap();
+    let (cell,) = app.into_tuple();
+    let zome = cell.zome(SweetInlineZomes::COORDINATOR);
+    let _: ActionHash = conductor.call(&zome, "create_unit", ()).await;
+
+    // Actions signed after a key is revoked are rejected
+    let revoked = admin_api
+        .handle_admin_request(AdminRequest::RevokeAgentKey {
+            agent_key: agent.clone(),
+        })
+        .await;
+    let state = match revoked {
+        AdminResponse::AgentKeyRevoked(state) => state,
+        response => panic!("unexpected response {:?}", response),
+    };
+    assert_eq!(key_state(agent.clone()).await, state);
+    let revoked_at = match state {
+        KeyState::Revoked {
+            revoked_at,
+            replaced_by: None,
+        } => revoked_at,
+        state => panic!("unexpected key state {:?}", state),
+    };
+    assert!(KeyState::Valid.is_valid_at(revoked_at));
+    assert!(!key_state(agent.clone()).await.is_valid_at(revoked_at));
+    let result: ConductorApiResult<ActionHash> =
+        conductor.call_fallible(&zome, "create_unit", ()).await;
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("was not valid when the action was authored"));
+
+    // Rotating revokes the old key in favour of a new, registered one
+    let old_key = generate().await;
+    let new_key = match admin_api
+        .handle_admin_request(AdminRequest::RotateAgentKey {
+            agent_key: old_key.clone(),
+        })
+        .await
+    {
+        AdminResponse::AgentKeyRotated(key) => key,
+        response => panic!("unexpected response {:?}", response),
+    };
+    assert_matches!(
+        key_state(old_key).await,
+        KeyState::Revoked { replaced_by: Some(key), .. } if key == new_key
+    );
+    assert_eq!(key_state(new_key).await, KeyState::Valid);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dpki_key_states_are_cached_and_keys_need_a_running_dpki_app() {
+    observability::test_run().ok();
+    let mut conductor = conductor_with_in_memory_dpki().await;
+    let (app_dna, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+
+    let agent = conductor.generate_agent_key().await.unwrap();
+    let (cell,) = conductor
+        .setup_app_for_agent("app", agent.clone(), [&app_dna])
+        .await
+        .unwrap()
+        .into_tuple();
+    let zome = cell.zome(SweetInlineZomes::COORDINATOR);
+    let authored_at = Timestamp::now();
+    assert_eq!(
+        conductor
+            .dpki_key_state_at(&agent, authored_at)
+            .await
+            .unwrap(),
+        Some(KeyState::Valid)
+    );
+
+    conductor
+        .disable_app("dpki".to_string(), DisabledAppReason::User)
+        .await
+        .unwrap();
+
+    // Actions authored before the last lookup are served from the cache,
+    // as are newer ones until the cached state expires
+    assert_eq!(
+        conductor
+            .dpki_key_state_at(&agent, authored_at)
+            .await
+            .unwrap(),
+        Some(KeyState::Valid)
+    );
+    assert_eq!(
+        conductor
+            .dpki_key_state_at(&agent, Timestamp::now())
+            .await
+            .unwrap(),
+        Some(KeyState::Valid)
+    );
+    conductor.expire_dpki_key_states();
+    assert!(conductor
+        .dpki_key_state_at(&agent, Timestamp::now())
+        .await
+        .is_err());
+
+    // Actions whose key state can't be looked up aren't accepted
+    let result: ConductorApiResult<ActionHash> =
+        conductor.call_fallible(&zome, "create_unit", ()).await;
+    assert!(result
+        .unwrap_err()
+        .to_string()
+        .contains("could not be looked up"));
+
+    // No key is generated while the DPKI app isn't running
+    assert_matches!(
+        conductor.generate_agent_key().await,
+        Err(ConductorError::AppNotRunning(id)) if id == "dpki"
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dpki_app_is_installed_from_its_bundle_and_required_at_startup() {
+    observability::test_run().ok();
+    let dpki_config = |bundle_path| DpkiConfig {
+        instance_id: "dpki".into(),
+        init_params: "some params".into(),
+        network_seed: Some("dpki network".into()),
+        bundle_path,
+    };
+
+    // Without the app or a bundle to install it from, startup fails
+    let mut config: ConductorConfig = SweetConductorConfig::standard().into();
+    config.dpki = Some(dpki_config(None));
+    let db_dir = test_db_dir();
+    let error = Conductor::builder()
+        .config(config)
+        .no_print_setup()
+        .test(db_dir.path(), &[])
+        .await
+        .err()
+        .expect("The conductor started without its DPKI app");
+    assert_matches!(error, ConductorError::DpkiAppNotInstalled(id) if id == "dpki");
+
+    let (dna, _, _) = SweetDnaFile::unique_from_test_wasms(vec![TestWasm::Dpki]).await;
+    let dna_path = PathBuf::from("dpki.dna");
+    let manifest = AppManifestCurrentBuilder::default()
+        .name("dpki".into())
+        .description(None)
+        .roles(vec![AppRoleManifest {
+            name: "dpki".into(),
+            provisioning: Some(CellProvisioning::Create { deferred: false }),
+            dna: AppRoleDnaManifest {
+                location: Some(mr_bundle::Location::Bundled(dna_path.clone())),
+                modifiers: DnaModifiersOpt::none(),
+                version: None,
+                clone_limit: 0,
+            },
+        }])
+        .build()
+        .unwrap();
+    let bundle = AppBundle::new(
+        manifest.into(),
+        vec![(dna_path, DnaBundle::from_dna_file(dna).await.unwrap())],
+        PathBuf::from("."),
+    )
+    .await
+    .unwrap();
+    let bundle_dir = tempfile::tempdir().unwrap();
+    let bundle_path = bundle_dir.path().join("dpki.happ");
+    bundle.write_to_file(&bundle_path).await.unwrap();
+
+    let mut config = SweetConductorConfig::standard();
+    config.dpki = Some(dpki_config(Some(bundle_path)));
+    let conductor = SweetConductor::from_config(config).await;
+
+    let app_info = conductor
+        .get_app_info(&"dpki".to_string())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_matches!(app_info.status, AppInfoStatus::Running);
+    let cell_id = conductor.dpki_cell_id().await.unwrap().unwrap();
+    assert_eq!(
+        conductor
+            .get_dna_def(cell_id.dna_hash())
+            .unwrap()
+            .modifiers
+            .network_seed,
+        "dpki network"
+    );
+    let init_params: Option<String> = conductor
+        .call(
+            &SweetZome::new(cell_id, TestWasm::Dpki.coordinator_zome_name()),
+            "init_params",
+            (),
+        )
+        .await;
+    assert_eq!(init_params.as_deref(), Some("some params"));
+
+    // Generated keys are registered with the installed app
+    let agent = conductor.generate_agent_key().await.unwrap();
+    assert_eq!(
+        conductor.dpki_key_state(&agent).await.unwrap(),
+        Some(KeyState::Valid)
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn signal_subscriptions_filter_signals_per_connection() {
+    observability::test_run().ok();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+    let (other_dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+    let app = conductor
+        .setup_app("app", [&dna_file, &other_dna_file])
+        .await
+        .unwrap();
+    let (cell, other_cell) = app.into_tuple();
+    let (unrelated_dna_file, _, _) =
+        SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+    let (unrelated_cell,) = conductor
+        .setup_app("unrelated", [&unrelated_dna_file])
+        .await
+        .unwrap()
+        .into_tuple();
+
+    #[derive(Debug, serde::Serialize)]
+    #[serde(tag = "type")]
+    enum ChatSignal {
+        Message,
+        Typing,
+    }
+    let signal = |cell: &SweetCell, zome_name: &str, chat_signal: ChatSignal| Signal::App {
+        cell_id: cell.cell_id().clone(),
+        zome_name: zome_name.into(),
+        signal: AppSignal::new(ExternIO::encode(chat_signal).unwrap()),
+    };
+
+    let api = RealAppInterfaceApi::new(conductor.raw_handle());
+    let connection = authenticated_connection(&api, &conductor, "app").await;
+    let other_connection = authenticated_connection(&api, &conductor, "app").await;
+    let filter = SignalFilter {
+        zome_names: ["chat".into()].into(),
+        signal_types: ["Message".to_string()].into(),
+    };
+    let response = connection
+        .handle_app_request(AppRequest::SignalSubscription(SignalSubscription {
+            installed_app_id: "app".into(),
+            filters: SignalFilterSet::Include([(cell.cell_id().clone(), filter)].into()),
+        }))
+        .await;
+    assert_matches!(response, AppResponse::SignalSubscribed);
+
+    let subscriptions = connection.signal_subscriptions();
+    assert!(subscriptions.allows(&signal(&cell, "chat", ChatSignal::Message)));
+    assert!(!subscriptions.allows(&signal(&cell, "chat", ChatSignal::Typing)));
+    assert!(!subscriptions.allows(&signal(&cell, "profiles", ChatSignal::Message)));
+    assert!(!subscriptions.allows(&signal(&other_cell, "chat", ChatSignal::Message)));
+    // Cells of other apps and system signals aren't affected
+    assert!(subscriptions.allows(&signal(&unrelated_cell, "chat", ChatSignal::Typing)));
+    assert!(subscriptions.allows(&Signal::System(SystemSignal::Test("test".into()))));
+    // Nor are other connections
+    assert!(other_connection.signal_subscriptions().allows(&signal(
+        &cell,
+        "chat",
+        ChatSignal::Typing
+    )));
+
+    // Filters may only refer to cells of the app
+    let response = other_connection
+        .handle_app_request(AppRequest::SignalSubscription(SignalSubscription {
+            installed_app_id: "app".into(),
+            filters: SignalFilterSet::Exclude(
+                [(unrelated_cell.cell_id().clone(), SignalFilter::empty())].into(),
+            ),
+        }))
+        .await;
+    assert_matches!(response, AppResponse::Error(_));
+
+    // A new subscription replaces the old one
+    let response = connection
+        .handle_app_request(AppRequest::SignalSubscription(SignalSubscription {
+            installed_app_id: "app".into(),
+            filters: SignalFilterSet::block_all(),
+        }))
+        .await;
+    assert_matches!(response, AppResponse::SignalSubscribed);
+    assert!(!subscriptions.allows(&signal(&cell, "chat", ChatSignal::Message)));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn app_interface_connections_only_make_requests_for_their_app() {
+    observability::test_run().ok();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+    let (cell,) = conductor
+        .setup_app("app", [&dna_file])
+        .await
+        .unwrap()
+        .into_tuple();
+    let (other_dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+    let (other_cell,) = conductor
+        .setup_app("other app", [&other_dna_file])
+        .await
+        .unwrap()
+        .into_tuple();
+    let api = RealAppInterfaceApi::new(conductor.raw_handle());
+
+    // Tokens are single use by default, and only issued for installed apps
+    let issued = conductor
+        .issue_app_authentication_token(IssueAppAuthenticationTokenPayload::for_installed_app_id(
+            "app".into(),
+        ))
+        .await
+        .unwrap();
+    let connection = api.for_connection(&issued.token).await.unwrap();
+    assert!(api.for_connection(&issued.token).await.is_err());
+    assert!(api.for_connection(b"guess").await.is_err());
+    assert!(conductor
+        .issue_app_authentication_token(IssueAppAuthenticationTokenPayload::for_installed_app_id(
+            "missing app".into(),
+        ))
+        .await
+        .is_err());
+
+    let response = connection
+        .handle_app_request(AppRequest::AppInfo {
+            installed_app_id: "app".into(),
+        })
+        .await;
+    assert_matches!(response, AppResponse::AppInfo(Some(_)));
+    let response = connection
+        .handle_app_request(AppRequest::AppInfo {
+            installed_app_id: "other app".into(),
+        })
+        .await;
+    assert_matches!(response, AppResponse::Error(_));
+    let response = connection
+        .handle_app_request(AppRequest::NetworkInfo(Box::new(
+            NetworkInfoRequestPayload {
+                dnas: vec![other_dna_file.dna_hash().clone()],
+            },
+        )))
+        .await;
+    assert_matches!(response, AppResponse::Error(_));
+    let response = connection
+        .handle_app_request(AppRequest::SignalSubscription(SignalSubscription {
+            installed_app_id: "other app".into(),
+            filters: SignalFilterSet::block_all(),
+        }))
+        .await;
+    assert_matches!(response, AppResponse::Error(_));
+
+    // Only app signals from the cells of the app are sent
+    let signal = |cell: &SweetCell| Signal::App {
+        cell_id: cell.cell_id().clone(),
+        zome_name: "zome".into(),
+        signal: AppSignal::new(ExternIO::encode(()).unwrap()),
+    };
+    assert!(connection.allows_signal(&signal(&cell)));
+    assert!(!connection.allows_signal(&signal(&other_cell)));
+    assert!(connection.allows_signal(&Signal::System(SystemSignal::Test("test".into()))));
+}
+
+/// An api for an app interface connection which has authenticated for an app
+async fn authenticated_connection(
+    api: &RealAppInterfaceApi,
+    conductor: &SweetConductor,
+    installed_app_id: &str,
+) -> RealAppInterfaceApi {
+    let issued = conductor
+        .issue_app_authentication_token(IssueAppAuthenticationTokenPayload::for_installed_app_id(
+            installed_app_id.into(),
+        ))
+        .await
+        .unwrap();
+    api.for_connection(&issued.token).await.unwrap()
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn tuning_params_can_be_updated_per_dna_at_runtime() {
+    observability::test_run().ok();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let (dna_a, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+    let (dna_b, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+    conductor.setup_app("app", [&dna_a, &dna_b]).await.unwrap();
+    let a = dna_a.dna_hash().clone();
+    let b = dna_b.dna_hash().clone();
+
+    let get = |dna_hash: &DnaHash| {
+        let dna_hash = dna_hash.clone();
+        let conductor = conductor.raw_handle();
+        async move {
+            let mut all = conductor
+                .get_tuning_params(Some(dna_hash.clone()))
+                .await
+                .unwrap();
+            assert_eq!(all.len(), 1);
+            let DnaTuningParams {
+                dna_hash: got,
+                tuning_params,
+            } = all.pop().unwrap();
+            assert_eq!(got, dna_hash);
+            tuning_params
+        }
+    };
+    let patch = |key: &str, value: &str| -> HashMap<String, String> {
+        [(key.to_string(), value.to_string())].into_iter().collect()
+    };
+
+    // Every running DNA is listed.
+    let all = conductor.get_tuning_params(None).await.unwrap();
+    assert_eq!(
+        all.into_iter().map(|p| p.dna_hash).collect::<HashSet<_>>(),
+        hashset![a.clone(), b.clone()]
+    );
+
+    // A change for one DNA leaves the others alone.
+    conductor
+        .update_tuning_params(Some(a.clone()), patch("gossip_max_batch_size", "1234"))
+        .await
+        .unwrap();
+    assert_eq!(get(&a).await.gossip_max_batch_size, 1234);
+    assert_ne!(get(&b).await.gossip_max_batch_size, 1234);
+
+    // A change without a DNA applies to all of them, keeping
+    // the params which were changed for a single DNA.
+    conductor
+        .update_tuning_params(None, patch("default_rpc_single_timeout_ms", "4321"))
+        .await
+        .unwrap();
+    assert_eq!(get(&a).await.default_rpc_single_timeout_ms, 4321);
+    assert_eq!(get(&b).await.default_rpc_single_timeout_ms, 4321);
+    assert_eq!(get(&a).await.gossip_max_batch_size, 1234);
+
+    // Ignore host pressure, so that the throttles run at exactly their targets.
+    conductor
+        .update_gossip_throttle(kitsune_p2p::GossipThrottleConfig {
+            ignore_host_pressure: true,
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let before = get(&b).await;
+    assert_ne!(before.gossip_inbound_target_mbps, 1.0);
+    let throttle = conductor.get_gossip_throttle().await.unwrap();
+    assert_eq!(
+        throttle.bandwidth.recent_inbound_mbps,
+        before.gossip_inbound_target_mbps
+    );
+
+    // Params shared by every DNA can only be changed for all of them.
+    assert!(conductor
+        .update_tuning_params(Some(a.clone()), patch("gossip_inbound_target_mbps", "1.0"))
+        .await
+        .is_err());
+    conductor
+        .update_tuning_params(None, patch("gossip_inbound_target_mbps", "1.0"))
+        .await
+        .unwrap();
+    assert_eq!(get(&b).await.gossip_inbound_target_mbps, 1.0);
+    // The new target reaches the gossip throttles straight away.
+    let throttle = conductor.get_gossip_throttle().await.unwrap();
+    assert_eq!(throttle.bandwidth.recent_inbound_mbps, 1.0);
+    assert_eq!(
+        throttle.bandwidth.historic_inbound_mbps,
+        before.gossip_historic_inbound_target_mbps
+    );
+
+    // Params which are only read at startup can't be changed.
+    assert!(conductor
+        .update_tuning_params(None, patch("gossip_strategy", "none"))
+        .await
+        .is_err());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[cfg(feature = "metrics_endpoint")]
+async fn metrics_are_served_in_the_open_metrics_format() {
+    observability::test_run().ok();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+    let dna_hash = dna_file.dna_hash().clone();
+    let app = conductor.setup_app("app", [&dna_file]).await.unwrap();
+    let (cell,) = app.into_tuple();
+    let zome = cell.zome(SweetInlineZomes::COORDINATOR);
+    let _: ActionHash = conductor.call(&zome, "create_unit", ()).await;
+
+    let route = crate::conductor::metrics::metrics_route(conductor.raw_handle());
+    let res = warp::test::request()
+        .method("GET")
+        .path("/metrics")
+        .reply(&route)
+        .await;
+    assert_eq!(res.status(), 200);
+    assert!(res.headers()["Content-Type"]
+        .to_str()
+        .unwrap()
+        .starts_with("application/openmetrics-text"));
+    let body = String::from_utf8(res.body().to_vec()).unwrap();
+
+    assert!(body.contains(&format!(
+        "holochain_zome_call_duration_seconds_count{{zome=\"{}\",fn=\"create_unit\"}} 1\n",
+        SweetInlineZomes::COORDINATOR
+    )));
+    assert!(body.contains(&format!(
+        "holochain_validation_limbo_ops{{dna=\"{}\"}}",
+        dna_hash
+    )));
+    assert!(body.contains(&format!(
+        "holochain_db_pool_max_connections{{db=\"dht\",dna=\"{}\"}}",
+        dna_hash
+    )));
+    assert!(body.contains("holochain_db_pool_connections{db=\"conductor\"}"));
+    assert!(body.ends_with("# EOF\n"));
+}
+
+#[tokio::test(flavor = "multi_thread")]
+#[cfg(not(feature = "metrics_endpoint"))]
+async fn a_configured_metrics_endpoint_requires_the_feature() {
+    let mut config: ConductorConfig = SweetConductorConfig::standard().into();
+    config.metrics_endpoint = Some(crate::conductor::config::MetricsEndpointConfig {
+        bind_to: "127.0.0.1:0".parse().unwrap(),
+    });
+    let db_dir = test_db_dir();
+    let error = Conductor::builder()
+        .config(config)
+        .no_print_setup()
+        .test(db_dir.path(), &[])
+        .await
+        .err()
+        .expect("The conductor started without serving its metrics endpoint");
+    assert_matches!(error, ConductorError::MetricsEndpointUnavailable(_));
+}
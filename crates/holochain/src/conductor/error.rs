@@ -89,9 +89,35 @@ pub enum ConductorError {
     #[error("Tried to install an app using an already-used InstalledAppId: {0}")]
     AppAlreadyInstalled(InstalledAppId),
 
+    #[error("Cannot uninstall app {0} because these apps depend on its cells: {1:?}")]
+    AppHasDependents(InstalledAppId, Vec<InstalledAppId>),
+
     #[error("Tried to perform an operation on an app that was not running: {0}")]
     AppNotRunning(InstalledAppId),
 
+    #[error("The app interface connection could not be authenticated")]
+    AppAuthenticationFailed,
+
+    #[error("The conductor is not configured with DPKI")]
+    DpkiNotConfigured,
+
+    #[error(
+        "The DPKI app {0} is not installed and no bundle_path is configured to install it from"
+    )]
+    DpkiAppNotInstalled(InstalledAppId),
+
+    #[error("A metrics endpoint is configured at {0}, but holochain was built without the metrics_endpoint feature")]
+    MetricsEndpointUnavailable(std::net::SocketAddr),
+
+    #[error("Call to the DPKI zome function {0} failed: {1}")]
+    DpkiCallFailed(FunctionName, String),
+
+    #[error("The rate_limits callback of zome {0} failed: {1}")]
+    RateLimitsCallbackFailed(ZomeName, String),
+
+    #[error("The migrate_agent callback of zome {0} refused the migration: {1}")]
+    MigrateAgentFailed(ZomeName, String),
+
     #[error(transparent)]
     HolochainP2pError(#[from] holochain_p2p::HolochainP2pError),
 
@@ -2,16 +2,24 @@
 //! and dispatch them to the appropriate handlers within Holochain.
 //! They also allow emitting responses and one-way Signals.
 //!
-//! Currently the only InterfaceDriver is a Websocket-based one, whose
-//! implementation can be found in the `websocket` module here.
+//! Both InterfaceDrivers are Websocket-based, listening either on a port
+//! or on a unix domain socket, and their implementation can be found in
+//! the `websocket` module here.
 
 use crate::conductor::api::*;
 use error::InterfaceError;
 use error::InterfaceResult;
+use holochain_conductor_api::signal_subscription::SignalFilterSet;
+use holochain_types::app::InstalledAppId;
 use holochain_types::signal::Signal;
+use holochain_zome_types::cell::CellId;
+use std::collections::HashMap;
+use std::collections::HashSet;
 use std::convert::TryInto;
+use std::sync::Arc;
 use tokio::sync::broadcast;
 
+pub mod app_auth_token_store;
 #[allow(missing_docs)]
 pub mod error;
 pub mod websocket;
@@ -98,4 +106,44 @@ impl SignalBroadcaster {
     }
 }
 
+/// The signal subscriptions made over a single app interface connection.
+/// Each app's filters apply only to the cells the app had when the
+/// subscription was made.
+#[derive(Clone, Debug, Default)]
+pub struct SignalSubscriptions(Arc<parking_lot::RwLock<HashMap<InstalledAppId, AppSubscription>>>);
+
+/// The cells of an app when it was subscribed to, and the filters for them
+type AppSubscription = (HashSet<CellId>, SignalFilterSet);
+
+impl SignalSubscriptions {
+    /// Replace the subscription for an app
+    pub(crate) fn subscribe(
+        &self,
+        installed_app_id: InstalledAppId,
+        cell_ids: HashSet<CellId>,
+        filters: SignalFilterSet,
+    ) {
+        self.0.write().insert(installed_app_id, (cell_ids, filters));
+    }
+
+    /// Whether a signal should be sent over the connection.
+    /// App signals must get through the filters of every app they were
+    /// emitted by. System signals are always sent.
+    pub fn allows(&self, signal: &Signal) -> bool {
+        match signal {
+            Signal::App {
+                cell_id,
+                zome_name,
+                signal,
+            } => self
+                .0
+                .read()
+                .values()
+                .filter(|(cell_ids, _)| cell_ids.contains(cell_id))
+                .all(|(_, filters)| filters.allows(cell_id, zome_name, signal)),
+            Signal::System(_) => true,
+        }
+    }
+}
+
 pub use holochain_conductor_api::config::InterfaceDriver;
@@ -0,0 +1,121 @@
+//! Tokens which authenticate connections to app interfaces.
+//!
+//! A token is issued over the admin interface for a single app. A client
+//! which sends it as the first message on a new app interface connection
+//! can then make requests for that app, and only that app.
+
+use holochain_conductor_api::AppAuthenticationToken;
+use holochain_types::prelude::*;
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// The number of random bytes in a token
+const TOKEN_LENGTH: usize = 32;
+
+/// The tokens which have been issued and can still be used.
+/// Tokens only live in memory, so they are all invalidated by a restart.
+#[derive(Default)]
+pub struct AppAuthTokenStore {
+    issued: HashMap<AppAuthenticationToken, IssuedToken>,
+}
+
+struct IssuedToken {
+    installed_app_id: InstalledAppId,
+    expires_at: Option<Timestamp>,
+    single_use: bool,
+}
+
+impl IssuedToken {
+    fn is_expired(&self, now: Timestamp) -> bool {
+        self.expires_at
+            .map_or(false, |expires_at| expires_at <= now)
+    }
+}
+
+impl AppAuthTokenStore {
+    /// Issue a new token for an app. An `expiry_seconds` of 0 means the
+    /// token never expires.
+    pub fn issue_token(
+        &mut self,
+        installed_app_id: InstalledAppId,
+        expiry_seconds: u64,
+        single_use: bool,
+    ) -> (AppAuthenticationToken, Option<Timestamp>) {
+        let now = Timestamp::now();
+        self.issued.retain(|_, issued| !issued.is_expired(now));
+
+        let token: AppAuthenticationToken = (0..TOKEN_LENGTH).map(|_| rand::random()).collect();
+        let expires_at = if expiry_seconds == 0 {
+            None
+        } else {
+            Some(now.saturating_add(&Duration::from_secs(expiry_seconds)))
+        };
+        self.issued.insert(
+            token.clone(),
+            IssuedToken {
+                installed_app_id,
+                expires_at,
+                single_use,
+            },
+        );
+        (token, expires_at)
+    }
+
+    /// Get the app a token was issued for, if it is still valid.
+    /// A single use token is used up by this.
+    pub fn authenticate_token(&mut self, token: &[u8]) -> Option<InstalledAppId> {
+        let issued = self.issued.get(token)?;
+        if issued.is_expired(Timestamp::now()) {
+            self.issued.remove(token);
+            return None;
+        }
+        if issued.single_use {
+            return self
+                .issued
+                .remove(token)
+                .map(|issued| issued.installed_app_id);
+        }
+        Some(issued.installed_app_id.clone())
+    }
+
+    /// Invalidate every token issued for an app, so that they can't be used
+    /// for another app which is later installed with the same id.
+    pub fn revoke_app_tokens(&mut self, installed_app_id: &InstalledAppId) {
+        self.issued
+            .retain(|_, issued| &issued.installed_app_id != installed_app_id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_use_tokens_are_used_up() {
+        let mut store = AppAuthTokenStore::default();
+        let (token, expires_at) = store.issue_token("app".into(), 30, true);
+        assert!(expires_at.is_some());
+        assert_eq!(store.authenticate_token(&token), Some("app".into()));
+        assert_eq!(store.authenticate_token(&token), None);
+
+        let (token, expires_at) = store.issue_token("app".into(), 0, false);
+        assert!(expires_at.is_none());
+        assert_eq!(store.authenticate_token(&token), Some("app".into()));
+        assert_eq!(store.authenticate_token(&token), Some("app".into()));
+        assert_eq!(store.authenticate_token(b"not a token"), None);
+    }
+
+    #[test]
+    fn expired_and_revoked_tokens_are_refused() {
+        let mut store = AppAuthTokenStore::default();
+        let (expired, _) = store.issue_token("app".into(), 1, false);
+        store.issued.get_mut(&expired).unwrap().expires_at = Some(Timestamp::now());
+        assert_eq!(store.authenticate_token(&expired), None);
+
+        let (revoked, _) = store.issue_token("app".into(), 30, false);
+        let (other, _) = store.issue_token("other app".into(), 30, false);
+        store.revoke_app_tokens(&"app".into());
+        assert_eq!(store.authenticate_token(&revoked), None);
+        assert_eq!(store.authenticate_token(&other), Some("other app".into()));
+    }
+}
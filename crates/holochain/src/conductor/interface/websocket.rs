@@ -1,5 +1,6 @@
 //! Module for establishing Websocket-based Interfaces,
 //! i.e. those configured with `InterfaceDriver::Websocket`
+//! or `InterfaceDriver::UnixSocket`
 
 use super::error::InterfaceError;
 use super::error::InterfaceResult;
@@ -9,8 +10,10 @@ use crate::conductor::manager::ManagedTaskHandle;
 use crate::conductor::manager::ManagedTaskResult;
 use holochain_serialized_bytes::SerializedBytes;
 use holochain_types::signal::Signal;
+use holochain_websocket::AllowedOrigins;
 use holochain_websocket::ListenerHandle;
 use holochain_websocket::ListenerItem;
+use holochain_websocket::ListenerStream;
 use holochain_websocket::WebsocketConfig;
 use holochain_websocket::WebsocketListener;
 use holochain_websocket::WebsocketMessage;
@@ -31,24 +34,38 @@ use url2::url2;
 /// back pressure.
 pub(crate) const SIGNAL_BUFFER_SIZE: usize = 50;
 const MAX_CONNECTIONS: isize = 400;
+/// How long a new app interface connection has to authenticate before it is
+/// closed.
+const APP_AUTHENTICATION_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(10);
 
 /// Create a WebsocketListener to be used in interfaces
 pub async fn spawn_websocket_listener(
-    port: u16,
-) -> InterfaceResult<(
-    ListenerHandle,
-    impl futures::stream::Stream<Item = ListenerItem>,
-)> {
+    driver: &InterfaceDriver,
+) -> InterfaceResult<(ListenerHandle, ListenerStream)> {
     trace!("Initializing Admin interface");
-    let listener = WebsocketListener::bind_with_handle(
-        url2!("ws://127.0.0.1:{}", port),
-        Arc::new(WebsocketConfig::default()),
-    )
-    .await?;
+    let listener = bind_listener(driver, WebsocketConfig::default()).await?;
     trace!("LISTENING AT: {}", listener.0.local_addr());
     Ok(listener)
 }
 
+/// Bind a listener on the port or unix domain socket of this driver.
+async fn bind_listener(
+    driver: &InterfaceDriver,
+    config: WebsocketConfig,
+) -> InterfaceResult<(ListenerHandle, ListenerStream)> {
+    let config = Arc::new(config);
+    let listener = match driver {
+        InterfaceDriver::Websocket { port } => {
+            WebsocketListener::bind(url2!("ws://127.0.0.1:{}", port), config).await?
+        }
+        #[cfg(unix)]
+        InterfaceDriver::UnixSocket { path } => WebsocketListener::bind_unix(path, config).await?,
+        #[cfg(not(unix))]
+        InterfaceDriver::UnixSocket { .. } => return Err(InterfaceError::UnixSocketUnsupported),
+    };
+    Ok(listener.into_handle_and_stream())
+}
+
 /// Create an Admin Interface, which only receives AdminRequest messages
 /// from the external client
 pub fn spawn_admin_interface_task<A: InterfaceApi>(
@@ -90,24 +107,33 @@ pub fn spawn_admin_interface_task<A: InterfaceApi>(
 }
 
 /// Create an App Interface, which includes the ability to receive signals
-/// from Cells via a broadcast channel
-pub async fn spawn_app_interface_task<A: InterfaceApi>(
-    port: u16,
-    api: A,
+/// from Cells via a broadcast channel.
+/// Only connections from the allowed origins are accepted, and each
+/// connection must authenticate before it can make requests.
+/// Returns the driver with the port chosen by the OS, if the driver's port was 0.
+pub async fn spawn_app_interface_task(
+    driver: InterfaceDriver,
+    allowed_origins: AllowedOrigins,
+    api: RealAppInterfaceApi,
     signal_broadcaster: broadcast::Sender<Signal>,
     mut stop_rx: StopReceiver,
-) -> InterfaceResult<(u16, ManagedTaskHandle)> {
+) -> InterfaceResult<(InterfaceDriver, ManagedTaskHandle)> {
     trace!("Initializing App interface");
-    let (handle, mut listener) = WebsocketListener::bind_with_handle(
-        url2!("ws://127.0.0.1:{}", port),
-        Arc::new(WebsocketConfig::default()),
+    let (handle, mut listener) = bind_listener(
+        &driver,
+        WebsocketConfig::default().allowed_origins(allowed_origins),
     )
     .await?;
     trace!("LISTENING AT: {}", handle.local_addr());
-    let port = handle
-        .local_addr()
-        .port()
-        .ok_or(InterfaceError::PortError)?;
+    let driver = match driver {
+        InterfaceDriver::Websocket { .. } => InterfaceDriver::Websocket {
+            port: handle
+                .local_addr()
+                .port()
+                .ok_or(InterfaceError::PortError)?,
+        },
+        driver => driver,
+    };
     // Task that will kill the listener and all child connections.
     tokio::task::spawn(
         handle.close_on(async move { stop_rx.recv().await.map(|_| true).unwrap_or(true) }),
@@ -117,13 +143,26 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
         while let Some(connection) = listener.next().await {
             match connection {
                 Ok((tx_to_iface, rx_from_iface)) => {
-                    let rx_from_cell = signal_broadcaster.subscribe();
-                    spawn_recv_incoming_msgs_and_outgoing_signals(
-                        api.clone(),
-                        rx_from_iface,
-                        rx_from_cell,
-                        tx_to_iface,
-                    );
+                    let api = api.clone();
+                    let signal_broadcaster = signal_broadcaster.clone();
+                    tokio::task::spawn(async move {
+                        let (api, rx_from_iface) =
+                            match authenticate_app_connection(&api, rx_from_iface).await {
+                                Ok(authenticated) => authenticated,
+                                Err(err) => {
+                                    // Dropping both ends closes the connection
+                                    warn!(?err, "App interface connection failed to authenticate");
+                                    return;
+                                }
+                            };
+                        let rx_from_cell = signal_broadcaster.subscribe();
+                        spawn_recv_incoming_msgs_and_outgoing_signals(
+                            api,
+                            rx_from_iface,
+                            rx_from_cell,
+                            tx_to_iface,
+                        );
+                    });
                 }
                 Err(err) => {
                     warn!("Admin socket connection failed: {}", err);
@@ -133,7 +172,26 @@ pub async fn spawn_app_interface_task<A: InterfaceApi>(
 
         ManagedTaskResult::Ok(())
     });
-    Ok((port, task))
+    Ok((driver, task))
+}
+
+/// Wait for the first message on a new app interface connection, which must
+/// be an [`AppAuthenticationRequest`] with a valid token, and get an api for
+/// the connection which is bound to the app the token was issued for.
+async fn authenticate_app_connection(
+    api: &RealAppInterfaceApi,
+    mut rx_from_iface: WebsocketReceiver,
+) -> InterfaceResult<(RealAppInterfaceApi, WebsocketReceiver)> {
+    let (bytes, _) = tokio::time::timeout(APP_AUTHENTICATION_TIMEOUT, rx_from_iface.next())
+        .await
+        .map_err(|_| InterfaceError::Other("Timed out waiting to authenticate".into()))?
+        .ok_or(InterfaceError::Closed)?;
+    let AppAuthenticationRequest { token } = bytes.try_into()?;
+    let api = api
+        .for_connection(&token)
+        .await
+        .map_err(|e| InterfaceError::Other(e.to_string()))?;
+    Ok((api, rx_from_iface))
 }
 
 /// Polls for messages coming in from the external client.
@@ -160,9 +218,10 @@ async fn recv_incoming_admin_msgs<A: InterfaceApi>(
 
 /// Polls for messages coming in from the external client while simultaneously
 /// polling for signals being broadcast from the Cells associated with this
-/// App interface.
-fn spawn_recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
-    api: A,
+/// App interface. Signals which don't get through the connection's signal
+/// subscriptions are dropped before they are serialized.
+fn spawn_recv_incoming_msgs_and_outgoing_signals(
+    api: RealAppInterfaceApi,
     rx_from_iface: WebsocketReceiver,
     rx_from_cell: broadcast::Receiver<Signal>,
     tx_to_iface: WebsocketSender,
@@ -179,6 +238,11 @@ fn spawn_recv_incoming_msgs_and_outgoing_signals<A: InterfaceApi>(
         }
     });
 
+    let rx_from_cell = tokio_stream::StreamExt::filter(rx_from_cell, {
+        let api = api.clone();
+        move |signal| api.allows_signal(signal)
+    });
+
     tokio::task::spawn(rx_from_cell.for_each_concurrent(4096, move |signal| {
         let mut tx_to_iface = tx_to_iface.clone();
         async move {
@@ -687,7 +751,11 @@ pub mod test {
         let (_tmpdir, conductor_handle) = setup_admin().await;
         let shutdown = conductor_handle.take_shutdown_handle().unwrap();
         let admin_api = RealAdminInterfaceApi::new(conductor_handle.clone());
-        let msg = AdminRequest::AttachAppInterface { port: None };
+        let msg = AdminRequest::AttachAppInterface {
+            port: None,
+            unix_socket: None,
+            allowed_origins: AllowedOrigins::Any,
+        };
         let msg = msg.try_into().unwrap();
         let respond = |bytes: SerializedBytes| {
             let response: AdminResponse = bytes.try_into().unwrap();
@@ -1,7 +1,7 @@
 //! Implementation of the Kitsune Host API
 
 mod query_region_op_hashes;
-mod query_region_set;
+pub mod query_region_set;
 mod query_size_limited_regions;
 
 use std::sync::Arc;
@@ -13,18 +13,27 @@ use holochain_p2p::{
     dht::{spacetime::Topology, ArqStrat},
     DnaHashExt,
 };
-use holochain_sqlite::prelude::AsP2pStateTxExt;
+use holochain_sqlite::prelude::{AsP2pFetchQueueTxExt, AsP2pStateTxExt};
 use holochain_types::{
     db::PermittedConn,
     prelude::{DhtOpHash, DnaError},
     share::RwShare,
 };
 use kitsune_p2p::{
-    agent_store::AgentInfoSigned, dependencies::kitsune_p2p_fetch::OpHashSized,
-    event::GetAgentInfoSignedEvt, KitsuneHost, KitsuneHostResult,
+    agent_store::AgentInfoSigned,
+    dependencies::kitsune_p2p_fetch::{FetchKey, FetchQueueRecord, OpHashSized},
+    event::GetAgentInfoSignedEvt,
+    KitsuneHost, KitsuneHostResult,
 };
 use kitsune_p2p_types::{config::KitsuneP2pTuningParams, KOpData, KOpHash};
 
+/// CPU or disk usage, as a fraction, above which we report gossip pressure.
+/// The pressure rises to its maximum at [`FULL_PRESSURE_USAGE`].
+const PRESSURE_START_USAGE: f64 = 0.8;
+
+/// CPU or disk usage, as a fraction, at which gossip pressure is at its maximum.
+const FULL_PRESSURE_USAGE: f64 = 0.95;
+
 /// Implementation of the Kitsune Host API.
 /// Lets Kitsune make requests of Holochain
 pub struct KitsuneHostImpl {
@@ -120,6 +129,50 @@ impl KitsuneHost for KitsuneHostImpl {
         .into()
     }
 
+    fn load_fetch_queue(
+        &self,
+        space: Arc<kitsune_p2p::KitsuneSpace>,
+    ) -> KitsuneHostResult<Vec<FetchQueueRecord>> {
+        let db = self.spaces.p2p_agents_db(&DnaHash::from_kitsune(&space));
+        async move {
+            Ok(db?
+                .async_reader(move |txn| txn.p2p_list_fetch_queue())
+                .await?)
+        }
+        .boxed()
+        .into()
+    }
+
+    fn put_fetch_queue_items(
+        &self,
+        space: Arc<kitsune_p2p::KitsuneSpace>,
+        records: Vec<FetchQueueRecord>,
+    ) -> KitsuneHostResult<()> {
+        let db = self.spaces.p2p_agents_db(&DnaHash::from_kitsune(&space));
+        async move {
+            Ok(db?
+                .async_commit(move |txn| txn.p2p_put_fetch_queue_items(&records))
+                .await?)
+        }
+        .boxed()
+        .into()
+    }
+
+    fn remove_fetch_queue_items(
+        &self,
+        space: Arc<kitsune_p2p::KitsuneSpace>,
+        keys: Vec<FetchKey>,
+    ) -> KitsuneHostResult<()> {
+        let db = self.spaces.p2p_agents_db(&DnaHash::from_kitsune(&space));
+        async move {
+            Ok(db?
+                .async_commit(move |txn| txn.p2p_remove_fetch_queue_items(&keys))
+                .await?)
+        }
+        .boxed()
+        .into()
+    }
+
     fn query_region_set(
         &self,
         space: Arc<kitsune_p2p::KitsuneSpace>,
@@ -128,10 +181,15 @@ impl KitsuneHost for KitsuneHostImpl {
         let dna_hash = DnaHash::from_kitsune(&space);
         async move {
             let topology = self.get_topology(space.clone()).await?;
-            let db = self.spaces.dht_db(&dna_hash)?;
-            let region_set =
-                query_region_set::query_region_set(db, topology.clone(), &self.strat, dht_arc_set)
-                    .await?;
+            let space = self.spaces.get_or_create_space(&dna_hash)?;
+            let region_set = query_region_set::query_region_set(
+                space.dht_db,
+                &space.region_set_cache,
+                topology,
+                &self.strat,
+                dht_arc_set,
+            )
+            .await?;
             Ok(region_set)
         }
         .boxed()
@@ -245,4 +303,58 @@ impl KitsuneHost for KitsuneHostImpl {
         .boxed()
         .into()
     }
+
+    fn gossip_pressure(&self) -> KitsuneHostResult<f64> {
+        let db_dir: std::path::PathBuf = (*self.spaces.db_dir).clone().into();
+        async move {
+            let usage =
+                tokio::task::spawn_blocking(move || cpu_usage().max(disk_usage(&db_dir))).await?;
+            Ok(
+                ((usage - PRESSURE_START_USAGE) / (FULL_PRESSURE_USAGE - PRESSURE_START_USAGE))
+                    .clamp(0.0, 1.0),
+            )
+        }
+        .boxed()
+        .into()
+    }
+
+    fn is_blocked(
+        &self,
+        space: Arc<kitsune_p2p::KitsuneSpace>,
+        cert: kitsune_p2p_types::Tx2Cert,
+    ) -> KitsuneHostResult<bool> {
+        let blocked = self
+            .spaces
+            .is_node_blocked(&DnaHash::from_kitsune(&space), &cert);
+        async move { Ok(blocked) }.boxed().into()
+    }
+}
+
+/// The system wide CPU load, as the one minute load average
+/// per core. This includes the load from other processes on the host,
+/// not just the conductor.
+/// The load average isn't available on Windows, where this is always 0.
+fn cpu_usage() -> f64 {
+    use sysinfo::{RefreshKind, SystemExt};
+    let cores = std::thread::available_parallelism()
+        .map(|n| n.get())
+        .unwrap_or(1);
+    let system = sysinfo::System::new_with_specifics(RefreshKind::new());
+    system.get_load_average().one / cores as f64
+}
+
+/// The used share of the disk holding the databases.
+fn disk_usage(db_dir: &std::path::Path) -> f64 {
+    use sysinfo::{DiskExt, RefreshKind, SystemExt};
+    let db_dir = db_dir.canonicalize().unwrap_or_else(|_| db_dir.to_owned());
+    let system = sysinfo::System::new_with_specifics(RefreshKind::new().with_disks_list());
+    // The disk with the longest mount point containing the databases.
+    system
+        .get_disks()
+        .iter()
+        .filter(|disk| db_dir.starts_with(disk.get_mount_point()))
+        .max_by_key(|disk| disk.get_mount_point().as_os_str().len())
+        .filter(|disk| disk.get_total_space() > 0)
+        .map(|disk| 1.0 - disk.get_available_space() as f64 / disk.get_total_space() as f64)
+        .unwrap_or(0.0)
 }
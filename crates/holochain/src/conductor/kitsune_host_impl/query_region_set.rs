@@ -5,7 +5,7 @@ use std::sync::{
 
 use holochain_p2p::{dht::prelude::*, dht_arc::DhtArcSet};
 use holochain_sqlite::prelude::*;
-use rusqlite::named_params;
+use rusqlite::{named_params, Transaction};
 
 use crate::conductor::error::ConductorResult;
 
@@ -13,8 +13,12 @@ static LAST_LOG_MS: AtomicI64 = AtomicI64::new(0);
 const LOG_RATE_MS: i64 = 1000;
 
 /// The network module needs info about various groupings ("regions") of ops
+///
+/// The regions are served from the space's [`RegionSetCache`], and are only
+/// computed from the database when the requested regions change.
 pub async fn query_region_set(
     db: DbWrite<DbKindDht>,
+    cache: &RegionSetCache,
     topology: Topology,
     strat: &ArqStrat,
     dht_arc_set: Arc<DhtArcSet>,
@@ -49,17 +53,167 @@ pub async fn query_region_set(
     let times = TelescopingTimes::historical(&topology);
     let coords = RegionCoordSetLtcs::new(times, arq_set);
 
-    let region_set = db
-        .async_reader(move |txn| {
-            let sql = holochain_sqlite::sql::sql_cell::FETCH_OP_REGION;
-            let mut stmt = txn.prepare_cached(sql).map_err(DatabaseError::from)?;
-            let regions = coords
-                .into_region_set(|(_, coords)| query_region_data(&mut stmt, &topology, coords))?;
-            DatabaseResult::Ok(regions)
-        })
-        .await?;
+    Ok(cache.get(db, topology, coords).await?)
+}
+
+/// The region set of a space's DHT database, kept up to date as ops are
+/// added to it, so that each gossip round doesn't recompute every region
+/// from the database.
+///
+/// Only the most recently requested set is kept. When different coords are
+/// requested, each of their regions is combined from the cached regions with
+/// [`RegionSetLtcs::query`] where possible, and only the regions which the
+/// cache doesn't cover, such as the newest time segments, are computed from
+/// the database. Ops are tracked by rowid, so ops added by any workflow are
+/// picked up, whether by [`RegionSetCache::update`] after integration or when
+/// the set is next requested.
+///
+/// The lock is only held to copy the cached set or replace it, never while
+/// reading the database.
+#[derive(Clone, Default)]
+pub struct RegionSetCache(Arc<parking_lot::Mutex<Option<CachedRegionSet>>>);
 
-    Ok(region_set)
+#[derive(Clone)]
+struct CachedRegionSet {
+    topology: Topology,
+    region_set: RegionSetLtcs,
+    /// The rowid of the last op in the region set.
+    last_rowid: i64,
+}
+
+impl RegionSetCache {
+    /// Get the region set for the given coords, computing only the regions
+    /// which can't be combined from the cached set from the database.
+    pub async fn get(
+        &self,
+        db: DbWrite<DbKindDht>,
+        topology: Topology,
+        coords: RegionCoordSetLtcs,
+    ) -> DatabaseResult<RegionSetLtcs> {
+        // A set for a different topology is quantized differently, so none
+        // of its regions can be reused.
+        let cached = self
+            .0
+            .lock()
+            .clone()
+            .filter(|cached| cached.topology == topology);
+        let updated = db
+            .async_reader(move |txn| CachedRegionSet::get(&txn, cached, topology, coords))
+            .await?;
+        let region_set = updated.region_set.clone();
+        self.store(updated);
+        Ok(region_set)
+    }
+
+    /// Add the ops which were added to the database since the set was last
+    /// updated to the cached set.
+    pub async fn update(&self, db: &DbWrite<DbKindDht>) -> DatabaseResult<()> {
+        let mut cached = match self.0.lock().clone() {
+            Some(cached) => cached,
+            None => return Ok(()),
+        };
+        let updated = db
+            .async_reader(move |txn| {
+                cached.add_new_ops(&txn)?;
+                DatabaseResult::Ok(cached)
+            })
+            .await?;
+        self.store(updated);
+        Ok(())
+    }
+
+    /// Replace the cached set, unless it is the same set and was updated by
+    /// a concurrent call with more recent ops.
+    fn store(&self, updated: CachedRegionSet) {
+        let mut cached = self.0.lock();
+        match cached.as_ref() {
+            Some(cached)
+                if cached.topology == updated.topology
+                    && cached.region_set.coords == updated.region_set.coords
+                    && cached.last_rowid > updated.last_rowid => {}
+            _ => *cached = Some(updated),
+        }
+    }
+}
+
+impl CachedRegionSet {
+    /// Get the set for the given coords within a single transaction, so the
+    /// regions combined from the cached set and those read from the database
+    /// include the same ops.
+    fn get(
+        txn: &Transaction,
+        cached: Option<Self>,
+        topology: Topology,
+        coords: RegionCoordSetLtcs,
+    ) -> DatabaseResult<Self> {
+        let sql = holochain_sqlite::sql::sql_cell::FETCH_OP_REGION;
+        let mut stmt = txn.prepare_cached(sql).map_err(DatabaseError::from)?;
+        match cached {
+            Some(mut cached) => {
+                cached.add_new_ops(txn)?;
+                if cached.region_set.coords == coords {
+                    return Ok(cached);
+                }
+                let topo = &cached.topology;
+                let region_set = coords.into_region_set(|(_, coords)| {
+                    match cached.region_set.query(topo, &coords.to_bounds(topo)) {
+                        Some(data) => Ok(data),
+                        None => query_region_data(&mut stmt, topo, coords),
+                    }
+                })?;
+                Ok(Self {
+                    region_set,
+                    ..cached
+                })
+            }
+            None => {
+                let last_rowid: i64 =
+                    txn.query_row("SELECT IFNULL(MAX(rowid), 0) FROM DhtOp", [], |row| {
+                        row.get(0)
+                    })?;
+                let region_set = coords.into_region_set(|(_, coords)| {
+                    query_region_data(&mut stmt, &topology, coords)
+                })?;
+                Ok(Self {
+                    topology,
+                    region_set,
+                    last_rowid,
+                })
+            }
+        }
+    }
+
+    fn add_new_ops(&mut self, txn: &Transaction) -> DatabaseResult<()> {
+        let sql = holochain_sqlite::sql::sql_cell::FETCH_OP_REGION_DATA_SINCE;
+        let mut stmt = txn.prepare_cached(sql).map_err(DatabaseError::from)?;
+        let ops = stmt
+            .query_map(named_params! { ":last_rowid": self.last_rowid }, |row| {
+                let hash: Vec<u8> = row.get("hash")?;
+                let action_size: u64 = row.get("action_size")?;
+                let entry_size: u64 = row.get("entry_size")?;
+                Ok((
+                    row.get::<_, i64>("rowid")?,
+                    Loc::new(row.get("storage_center_loc")?),
+                    row.get::<_, Timestamp>("authored_timestamp")?,
+                    RegionData {
+                        hash: RegionHash::from_vec(hash_slice_32(&hash).to_vec())
+                            .expect("region hash must be 32 bytes"),
+                        size: (action_size + entry_size).min(u32::MAX as u64) as u32,
+                        count: 1,
+                    },
+                ))
+            })?
+            .collect::<Result<Vec<_>, _>>()?;
+        for (rowid, loc, timestamp, data) in ops {
+            let coords = SpacetimeQuantumCoords {
+                space: self.topology.space_quantum(loc),
+                time: self.topology.time_quantum(timestamp),
+            };
+            self.region_set.update(&self.topology, coords, data);
+            self.last_rowid = self.last_rowid.max(rowid);
+        }
+        Ok(())
+    }
 }
 
 pub(super) fn query_region_data(
@@ -99,7 +253,10 @@ mod tests {
     use super::*;
     use holochain_serialized_bytes::UnsafeBytes;
     use holochain_state::prelude::StateMutationResult;
-    use holochain_state::{prelude::insert_op, test_utils::test_dht_db};
+    use holochain_state::{
+        prelude::insert_op,
+        test_utils::{test_dht_db, TestDb},
+    };
     use holochain_types::fixt::*;
     use holochain_types::prelude::{DhtOp, DhtOpHashed, NewEntryAction};
     use holochain_zome_types::{AppEntryBytes, Entry};
@@ -113,9 +270,15 @@ mod tests {
         let strat = ArqStrat::default();
         let arcset = Arc::new(DhtArcSet::Full);
 
-        let regions_empty = query_region_set(db.to_db(), topo.clone(), &strat, arcset.clone())
-            .await
-            .unwrap();
+        let regions_empty = query_region_set(
+            db.to_db(),
+            &RegionSetCache::default(),
+            topo.clone(),
+            &strat,
+            arcset.clone(),
+        )
+        .await
+        .unwrap();
         {
             let sum: RegionData = regions_empty.regions().map(|r| r.data).sum();
             assert_eq!(sum.count, 0);
@@ -158,9 +321,10 @@ mod tests {
         })
         .unwrap();
 
-        let regions = query_region_set(db.to_db(), topo, &strat, arcset)
-            .await
-            .unwrap();
+        let regions =
+            query_region_set(db.to_db(), &RegionSetCache::default(), topo, &strat, arcset)
+                .await
+                .unwrap();
 
         let diff = regions.diff(regions_empty).unwrap();
         {
@@ -170,4 +334,92 @@ mod tests {
             assert!(wire_bytes as u32 - sum.size < 32 * num as u32);
         }
     }
+
+    fn insert_ops(db: &TestDb<DbKindDht>, num: usize) {
+        db.test_commit(|txn| {
+            for _ in 0..num {
+                let mut create = fixt::fixt!(Create);
+                create.timestamp = Timestamp::now();
+                let op = DhtOpHashed::from_content_sync(DhtOp::RegisterAgentActivity(
+                    fixt::fixt!(Signature),
+                    create.into(),
+                ));
+                insert_op(txn, &op).unwrap();
+            }
+            StateMutationResult::Ok(())
+        })
+        .unwrap();
+    }
+
+    /// The cached region set stays the same as one computed from scratch as
+    /// ops are added, whether or not the cache is updated after integration.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn region_set_cache_tracks_new_ops() {
+        let db = test_dht_db();
+        let topo = Topology::standard(Timestamp::now(), Duration::ZERO);
+        let strat = ArqStrat::default();
+        let arcset = Arc::new(DhtArcSet::Full);
+        let cache = RegionSetCache::default();
+
+        query_region_set(db.to_db(), &cache, topo.clone(), &strat, arcset.clone())
+            .await
+            .unwrap();
+        insert_ops(&db, 10);
+        cache.update(&db.to_db()).await.unwrap();
+        insert_ops(&db, 5);
+
+        let cached = query_region_set(db.to_db(), &cache, topo.clone(), &strat, arcset.clone())
+            .await
+            .unwrap();
+        let fresh = query_region_set(db.to_db(), &RegionSetCache::default(), topo, &strat, arcset)
+            .await
+            .unwrap();
+        assert_eq!(cached, fresh);
+        let sum: RegionData = cached.regions().map(|r| r.data).sum();
+        assert_eq!(sum.count, 15);
+    }
+
+    /// When the requested coords change, the regions combined from the cached
+    /// set and those read from the database match a set computed from scratch.
+    #[tokio::test(flavor = "multi_thread")]
+    async fn region_set_cache_serves_new_coords() {
+        let db = test_dht_db();
+        let now = Timestamp::now();
+        // Start an hour ago, so the ops are spread over several time segments.
+        let topo = Topology::standard(
+            Timestamp::from_micros(now.as_micros() - 3_600_000_000),
+            Duration::ZERO,
+        );
+        let (arq_set, _) =
+            ArqBoundsSet::from_dht_arc_set_rounded(&topo, &ArqStrat::default(), &DhtArcSet::Full);
+        let now_quantum = topo.time_quantum(now);
+        let coords = |later: u32| {
+            RegionCoordSetLtcs::new(
+                TelescopingTimes::new(now_quantum + TimeQuantum::from(later)),
+                arq_set.clone(),
+            )
+        };
+        let cache = RegionSetCache::default();
+
+        insert_ops(&db, 10);
+        cache
+            .get(db.to_db(), topo.clone(), coords(1))
+            .await
+            .unwrap();
+        insert_ops(&db, 5);
+
+        // Later times merge the older time segments into larger ones, which
+        // are combined from the cached regions.
+        let cached = cache
+            .get(db.to_db(), topo.clone(), coords(9))
+            .await
+            .unwrap();
+        let fresh = RegionSetCache::default()
+            .get(db.to_db(), topo, coords(9))
+            .await
+            .unwrap();
+        assert_eq!(cached, fresh);
+        let sum: RegionData = cached.regions().map(|r| r.data).sum();
+        assert_eq!(sum.count, 15);
+    }
 }
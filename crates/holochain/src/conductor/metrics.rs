@@ -0,0 +1,446 @@
+//! Metrics about a conductor and its network, served over HTTP in the
+//! [OpenMetrics] text format when a
+//! [`MetricsEndpointConfig`](holochain_conductor_api::config::conductor::MetricsEndpointConfig)
+//! is given in the conductor config and the `metrics_endpoint` feature is enabled.
+//!
+//! The metrics are gathered when they are scraped, except for zome call
+//! latencies, which are recorded by the conductor as calls are made.
+//!
+//! [OpenMetrics]: https://github.com/OpenObservability/OpenMetrics/blob/main/specification/OpenMetrics.md
+
+// Fixes some warnings introduced by `warp`
+#![allow(opaque_hidden_inferred_bound)]
+
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::fmt::Write;
+use std::sync::Arc;
+use std::time::Duration;
+
+use holochain_conductor_api::IntegrationStateDump;
+use holochain_p2p::AgentPubKeyExt;
+use holochain_sqlite::db::DbPoolState;
+use holochain_types::prelude::*;
+use kitsune_p2p::metrics::MetricsSync;
+#[cfg(feature = "metrics_endpoint")]
+use warp::Filter;
+
+#[cfg(feature = "metrics_endpoint")]
+use super::ConductorHandle;
+
+/// The content type of the OpenMetrics text format.
+#[cfg(feature = "metrics_endpoint")]
+const CONTENT_TYPE: &str = "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// The upper bounds of the zome call latency histogram buckets, in seconds.
+const ZOME_CALL_LATENCY_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+/// Latencies of the zome calls made on this conductor,
+/// for each zome and function.
+#[derive(Clone, Default)]
+pub struct ZomeCallMetrics(Arc<parking_lot::Mutex<HashMap<(ZomeName, FunctionName), Histogram>>>);
+
+#[derive(Default)]
+struct Histogram {
+    buckets: [u64; ZOME_CALL_LATENCY_BUCKETS.len()],
+    count: u64,
+    sum: Duration,
+}
+
+impl ZomeCallMetrics {
+    /// Record how long a call to a zome function took.
+    /// The first call to a function is only recorded if `exists` confirms
+    /// the function exists, so that the label values stay bounded.
+    pub fn record(
+        &self,
+        zome_name: &ZomeName,
+        fn_name: &FunctionName,
+        elapsed: Duration,
+        exists: impl FnOnce() -> bool,
+    ) {
+        let key = (zome_name.clone(), fn_name.clone());
+        let known = self.0.lock().contains_key(&key);
+        if !known && !exists() {
+            return;
+        }
+        let mut calls = self.0.lock();
+        let histogram = calls.entry(key).or_default();
+        let secs = elapsed.as_secs_f64();
+        for (bucket, le) in histogram
+            .buckets
+            .iter_mut()
+            .zip(ZOME_CALL_LATENCY_BUCKETS.iter())
+        {
+            if secs <= *le {
+                *bucket += 1;
+            }
+        }
+        histogram.count += 1;
+        histogram.sum += elapsed;
+    }
+}
+
+/// The metrics gathered for one DNA when metrics are scraped.
+pub(crate) struct DnaMetrics {
+    pub dna_hash: DnaHash,
+    /// The gossip and peer metrics of the DNA's network space,
+    /// if it has been joined.
+    pub network: Option<MetricsSync>,
+    /// How many ops are waiting in each of the workflow queues.
+    pub integration: IntegrationStateDump,
+    /// The connection pool usage of each of the DNA's databases.
+    pub db_pools: Vec<(&'static str, DbPoolState)>,
+}
+
+/// A route serving the conductor's metrics at `/metrics`.
+#[cfg(feature = "metrics_endpoint")]
+pub(crate) fn metrics_route(
+    conductor: ConductorHandle,
+) -> impl Filter<Extract = impl warp::Reply, Error = warp::Rejection> + Clone {
+    warp::get()
+        .and(warp::path("metrics"))
+        .and(warp::path::end())
+        .and(warp::any().map(move || conductor.clone()))
+        .and_then(get_metrics)
+}
+
+#[cfg(feature = "metrics_endpoint")]
+async fn get_metrics(conductor: ConductorHandle) -> Result<impl warp::Reply, warp::Rejection> {
+    let body = match conductor.open_metrics().await {
+        Ok(body) => body,
+        Err(e) => {
+            tracing::error!(?e, "Failed to gather metrics");
+            return Err(warp::reject());
+        }
+    };
+    Ok(warp::reply::with_header(body, "Content-Type", CONTENT_TYPE))
+}
+
+/// Render the gathered metrics in the OpenMetrics text format.
+pub(crate) fn render(
+    conductor_db_pools: &[(&'static str, DbPoolState)],
+    dnas: &[DnaMetrics],
+    zome_calls: &ZomeCallMetrics,
+) -> String {
+    let mut out = OpenMetricsWriter::default();
+
+    // Network metrics are read out once, so the locks aren't held
+    // while writing out every family.
+    let networks: Vec<_> = dnas
+        .iter()
+        .filter_map(|dna| {
+            let metrics = dna.network.as_ref()?.read();
+            let rounds: Vec<_> = metrics
+                .round_totals()
+                .iter()
+                .map(|(gossip_type, totals)| (gossip_type_label(*gossip_type), *totals))
+                .collect();
+            let peers: Vec<_> = metrics
+                .peer_agent_histories()
+                .iter()
+                .map(|(agent, history)| {
+                    (
+                        AgentPubKey::from_kitsune(agent).to_string(),
+                        *history.reachability_quotient,
+                        *history.latency_micros,
+                    )
+                })
+                .collect();
+            Some((dna.dna_hash.to_string(), rounds, peers))
+        })
+        .collect();
+
+    out.family(
+        "holochain_gossip_rounds",
+        "counter",
+        "Gossip rounds completed in each DNA, by gossip type and outcome.",
+    );
+    for (dna, rounds, _) in &networks {
+        for (gossip_type, totals) in rounds {
+            for (outcome, count) in [("success", totals.successes), ("error", totals.errors)] {
+                out.sample(
+                    "holochain_gossip_rounds_total",
+                    &[
+                        ("dna", dna),
+                        ("gossip_type", gossip_type),
+                        ("outcome", outcome),
+                    ],
+                    count,
+                );
+            }
+        }
+    }
+
+    out.family(
+        "holochain_gossip_round_duration_seconds",
+        "counter",
+        "Time spent in completed gossip rounds in each DNA, by gossip type and outcome.",
+    );
+    for (dna, rounds, _) in &networks {
+        for (gossip_type, totals) in rounds {
+            for (outcome, duration) in [
+                ("success", totals.success_duration),
+                ("error", totals.error_duration),
+            ] {
+                out.sample(
+                    "holochain_gossip_round_duration_seconds_total",
+                    &[
+                        ("dna", dna),
+                        ("gossip_type", gossip_type),
+                        ("outcome", outcome),
+                    ],
+                    duration.as_secs_f64(),
+                );
+            }
+        }
+    }
+
+    out.family(
+        "holochain_peer_reachability",
+        "gauge",
+        "Reachability quotient of each remote agent, from 1 (unreachable) to 100.",
+    );
+    for (dna, _, peers) in &networks {
+        for (agent, reachability, _) in peers {
+            out.sample(
+                "holochain_peer_reachability",
+                &[("dna", dna), ("agent", agent)],
+                reachability,
+            );
+        }
+    }
+
+    out.family(
+        "holochain_peer_latency_seconds",
+        "gauge",
+        "Running average latency of requests to each remote agent.",
+    );
+    for (dna, _, peers) in &networks {
+        for (agent, _, latency_micros) in peers {
+            out.sample(
+                "holochain_peer_latency_seconds",
+                &[("dna", dna), ("agent", agent)],
+                *latency_micros as f64 / 1_000_000.0,
+            );
+        }
+    }
+
+    out.family(
+        "holochain_validation_limbo_ops",
+        "gauge",
+        "Ops in each DNA waiting for sys or app validation.",
+    );
+    for dna in dnas {
+        out.sample(
+            "holochain_validation_limbo_ops",
+            &[("dna", &dna.dna_hash.to_string())],
+            dna.integration.validation_limbo,
+        );
+    }
+
+    out.family(
+        "holochain_integration_limbo_ops",
+        "gauge",
+        "Validated ops in each DNA waiting to be integrated.",
+    );
+    for dna in dnas {
+        out.sample(
+            "holochain_integration_limbo_ops",
+            &[("dna", &dna.dna_hash.to_string())],
+            dna.integration.integration_limbo,
+        );
+    }
+
+    let db_pools: Vec<_> = conductor_db_pools
+        .iter()
+        .map(|(db, state)| (None, *db, *state))
+        .chain(dnas.iter().flat_map(|dna| {
+            let dna_hash = dna.dna_hash.to_string();
+            dna.db_pools
+                .iter()
+                .map(move |(db, state)| (Some(dna_hash.clone()), *db, *state))
+        }))
+        .collect();
+    out.db_pool_family(
+        "holochain_db_pool_connections",
+        "Open connections in each database's connection pool.",
+        &db_pools,
+        |state| state.connections as usize,
+    );
+    out.db_pool_family(
+        "holochain_db_pool_idle_connections",
+        "Open connections in each database's connection pool which are not in use.",
+        &db_pools,
+        |state| state.idle_connections as usize,
+    );
+    out.db_pool_family(
+        "holochain_db_pool_max_connections",
+        "The most connections each database's connection pool will open.",
+        &db_pools,
+        |state| state.max_connections as usize,
+    );
+    out.db_pool_family(
+        "holochain_db_pool_waiting_readers",
+        "Readers waiting for a connection from each database's connection pool.",
+        &db_pools,
+        |state| state.waiting_readers,
+    );
+
+    out.family(
+        "holochain_zome_call_duration_seconds",
+        "histogram",
+        "Time taken by zome calls, by zome and function.",
+    );
+    let zome_calls = zome_calls.0.lock();
+    let mut zome_fns: Vec<_> = zome_calls.keys().collect();
+    zome_fns.sort();
+    for zome_fn in zome_fns {
+        let (zome_name, fn_name) = zome_fn;
+        let histogram = &zome_calls[zome_fn];
+        let (zome_name, fn_name) = (zome_name.to_string(), fn_name.to_string());
+        for (le, count) in ZOME_CALL_LATENCY_BUCKETS
+            .iter()
+            .map(|le| le.to_string())
+            .zip(histogram.buckets.iter())
+        {
+            out.sample(
+                "holochain_zome_call_duration_seconds_bucket",
+                &[("zome", &zome_name), ("fn", &fn_name), ("le", &le)],
+                count,
+            );
+        }
+        out.sample(
+            "holochain_zome_call_duration_seconds_bucket",
+            &[("zome", &zome_name), ("fn", &fn_name), ("le", "+Inf")],
+            histogram.count,
+        );
+        out.sample(
+            "holochain_zome_call_duration_seconds_count",
+            &[("zome", &zome_name), ("fn", &fn_name)],
+            histogram.count,
+        );
+        out.sample(
+            "holochain_zome_call_duration_seconds_sum",
+            &[("zome", &zome_name), ("fn", &fn_name)],
+            histogram.sum.as_secs_f64(),
+        );
+    }
+
+    out.finish()
+}
+
+fn gossip_type_label(gossip_type: kitsune_p2p::GossipModuleType) -> &'static str {
+    match gossip_type {
+        kitsune_p2p::GossipModuleType::ShardedRecent => "recent",
+        kitsune_p2p::GossipModuleType::ShardedHistorical => "historical",
+    }
+}
+
+/// Writes out metric families and their samples.
+#[derive(Default)]
+struct OpenMetricsWriter(String);
+
+impl OpenMetricsWriter {
+    fn family(&mut self, name: &str, kind: &str, help: &str) {
+        let _ = writeln!(self.0, "# TYPE {} {}", name, kind);
+        let _ = writeln!(self.0, "# HELP {} {}", name, help);
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: impl Display) {
+        self.0.push_str(name);
+        if !labels.is_empty() {
+            self.0.push('{');
+            for (i, (label, value)) in labels.iter().enumerate() {
+                if i > 0 {
+                    self.0.push(',');
+                }
+                let _ = write!(self.0, "{}=\"{}\"", label, escape_label_value(value));
+            }
+            self.0.push('}');
+        }
+        let _ = writeln!(self.0, " {}", value);
+    }
+
+    /// Write out a family with a sample for each database, labelled with
+    /// the DNA it belongs to unless it is one of the conductor's databases.
+    fn db_pool_family(
+        &mut self,
+        name: &str,
+        help: &str,
+        db_pools: &[(Option<String>, &str, DbPoolState)],
+        value: impl Fn(&DbPoolState) -> usize,
+    ) {
+        self.family(name, "gauge", help);
+        for (dna, db, state) in db_pools {
+            match dna {
+                Some(dna) => self.sample(name, &[("db", db), ("dna", dna)], value(state)),
+                None => self.sample(name, &[("db", db)], value(state)),
+            }
+        }
+    }
+
+    fn finish(mut self) -> String {
+        self.0.push_str("# EOF\n");
+        self.0
+    }
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn zome_call_latencies_are_rendered_as_histograms() {
+        let zome_calls = ZomeCallMetrics::default();
+        let zome_name = ZomeName::from("zome");
+        let fn_name = FunctionName::from("fn");
+        zome_calls.record(&zome_name, &fn_name, Duration::from_millis(20), || true);
+        zome_calls.record(&zome_name, &fn_name, Duration::from_secs(20), || false);
+        // Calls to functions which don't exist aren't recorded
+        zome_calls.record(
+            &zome_name,
+            &"missing".into(),
+            Duration::from_millis(20),
+            || false,
+        );
+
+        let out = render(&[], &[], &zome_calls);
+
+        assert!(out.contains(
+            "holochain_zome_call_duration_seconds_bucket{zome=\"zome\",fn=\"fn\",le=\"0.01\"} 0\n"
+        ));
+        assert!(out.contains(
+            "holochain_zome_call_duration_seconds_bucket{zome=\"zome\",fn=\"fn\",le=\"0.025\"} 1\n"
+        ));
+        assert!(out.contains(
+            "holochain_zome_call_duration_seconds_bucket{zome=\"zome\",fn=\"fn\",le=\"10\"} 1\n"
+        ));
+        assert!(out.contains(
+            "holochain_zome_call_duration_seconds_bucket{zome=\"zome\",fn=\"fn\",le=\"+Inf\"} 2\n"
+        ));
+        assert!(
+            out.contains("holochain_zome_call_duration_seconds_count{zome=\"zome\",fn=\"fn\"} 2\n")
+        );
+        assert!(!out.contains("fn=\"missing\""));
+        assert!(out.ends_with("# EOF\n"));
+    }
+
+    #[test]
+    fn label_values_are_escaped() {
+        let mut out = OpenMetricsWriter::default();
+        out.sample("metric", &[("label", "a \"quoted\"\\value")], 1);
+        assert_eq!(
+            out.finish(),
+            "metric{label=\"a \\\"quoted\\\"\\\\value\"} 1\n# EOF\n"
+        );
+    }
+}
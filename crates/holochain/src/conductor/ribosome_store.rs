@@ -9,6 +9,7 @@ use crate::core::ribosome::{error::RibosomeResult, real_ribosome::RealRibosome,
 pub struct RibosomeStore {
     ribosomes: HashMap<DnaHash, RealRibosome>,
     entry_defs: HashMap<EntryDefBufferKey, EntryDef>,
+    rate_limits: HashMap<DnaHash, RateLimits>,
 }
 
 impl RibosomeStore {
@@ -16,6 +17,7 @@ impl RibosomeStore {
         RwShare::new(RibosomeStore {
             ribosomes: HashMap::new(),
             entry_defs: HashMap::new(),
+            rate_limits: HashMap::new(),
         })
     }
 
@@ -73,4 +75,12 @@ impl RibosomeStore {
     pub fn get_entry_def(&self, k: &EntryDefBufferKey) -> Option<EntryDef> {
         self.entry_defs.get(k).cloned()
     }
+
+    pub fn add_rate_limits(&mut self, dna_hash: DnaHash, rate_limits: RateLimits) {
+        self.rate_limits.insert(dna_hash, rate_limits);
+    }
+
+    pub fn get_rate_limits(&self, dna_hash: &DnaHash) -> Option<RateLimits> {
+        self.rate_limits.get(dna_hash).cloned()
+    }
 }
@@ -1,7 +1,11 @@
 //! This module contains data and functions for running operations
 //! at the level of a [`DnaHash`] space.
 //! Multiple [`Cell`](crate::conductor::Cell)'s could share the same space.
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
 
 use holo_hash::{AgentPubKey, DhtOpHash, DnaHash};
 use holochain_conductor_api::conductor::{ConductorConfig, DatabaseRootPath};
@@ -39,9 +43,12 @@ use holochain_types::{
 };
 use holochain_zome_types::{Entry, EntryVisibility, SignedAction, Timestamp};
 use kitsune_p2p::{
+    agent_store::AgentInfoSigned,
+    dependencies::kitsune_p2p_proxy::ProxyUrl,
     event::{TimeWindow, TimeWindowInclusive},
     KitsuneP2pConfig,
 };
+use kitsune_p2p_types::Tx2Cert;
 use rusqlite::{named_params, OptionalExtension};
 use tracing::instrument;
 
@@ -59,6 +66,7 @@ use crate::core::{
 use super::{
     conductor::RwShare,
     error::ConductorResult,
+    kitsune_host_impl::query_region_set::RegionSetCache,
     p2p_agent_store::{self, P2pBatch},
 };
 use std::convert::TryInto;
@@ -79,6 +87,8 @@ pub struct Spaces {
     pub(crate) conductor_db: DbWrite<DbKindConductor>,
     pub(crate) wasm_db: DbWrite<DbKindWasm>,
     network_config: KitsuneP2pConfig,
+    /// The certificates of the nodes running blocked agents, per space.
+    blocked_nodes: RwShare<HashMap<DnaHash, HashMap<AgentPubKey, HashSet<Tx2Cert>>>>,
 }
 
 #[derive(Clone)]
@@ -117,6 +127,9 @@ pub struct Space {
     /// A cache for slow database queries.
     pub dht_query_cache: DhtDbQueryCache,
 
+    /// The regions of the dht database last requested for gossip.
+    pub region_set_cache: RegionSetCache,
+
     /// Countersigning workspace that is shared across this cell.
     pub countersigning_workspace: CountersigningWorkspace,
 
@@ -160,6 +173,7 @@ impl Spaces {
             conductor_db,
             wasm_db,
             network_config: config.network.clone().unwrap_or_default(),
+            blocked_nodes: RwShare::new(HashMap::new()),
         })
     }
 
@@ -295,6 +309,51 @@ impl Spaces {
         self.get_or_create_space_ref(dna_hash, |space| space.p2p_batch_sender.clone())
     }
 
+    /// Block every message in this space from the nodes this blocked agent
+    /// was running on, as found in its agent infos.
+    pub fn block_nodes<'a>(
+        &self,
+        dna_hash: &DnaHash,
+        agent: AgentPubKey,
+        infos: impl IntoIterator<Item = &'a AgentInfoSigned>,
+    ) {
+        let certs: HashSet<_> = infos
+            .into_iter()
+            .flat_map(|info| info.url_list.iter())
+            .filter_map(|url| ProxyUrl::from_full(url.as_str()).ok())
+            .map(|url| Tx2Cert::from(url.digest()))
+            .collect();
+        if certs.is_empty() {
+            return;
+        }
+        self.blocked_nodes.share_mut(|blocked| {
+            blocked
+                .entry(dna_hash.clone())
+                .or_default()
+                .entry(agent)
+                .or_default()
+                .extend(certs)
+        });
+    }
+
+    /// Stop blocking the nodes this agent was running on.
+    pub fn unblock_nodes(&self, dna_hash: &DnaHash, agent: &AgentPubKey) {
+        self.blocked_nodes.share_mut(|blocked| {
+            if let Some(blocked) = blocked.get_mut(dna_hash) {
+                blocked.remove(agent);
+            }
+        });
+    }
+
+    /// Is a blocked agent running on the node with this certificate?
+    pub fn is_node_blocked(&self, dna_hash: &DnaHash, cert: &Tx2Cert) -> bool {
+        self.blocked_nodes.share_ref(|blocked| {
+            blocked
+                .get(dna_hash)
+                .map_or(false, |agents| agents.values().any(|c| c.contains(cert)))
+        })
+    }
+
     #[instrument(skip(self))]
     /// the network module is requesting a list of dht op hashes
     /// Get the [`DhtOpHash`]es and authored timestamps for a given time window.
@@ -706,6 +765,7 @@ impl Space {
             incoming_op_hashes,
             incoming_ops_batch,
             dht_query_cache,
+            region_set_cache: RegionSetCache::default(),
             conductor_db,
         };
         Ok(r)
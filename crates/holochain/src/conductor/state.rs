@@ -1,12 +1,13 @@
 //! Structs which allow the Conductor's state to be persisted across
 //! startups and shutdowns
 
-use holochain_conductor_api::config::InterfaceDriver;
+use holochain_conductor_api::config::{AllowedOrigins, InterfaceDriver};
 use holochain_conductor_api::signal_subscription::SignalSubscription;
 use holochain_types::prelude::*;
 use serde::Deserialize;
 use serde::Serialize;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
 
 use super::error::{ConductorError, ConductorResult};
@@ -50,6 +51,10 @@ pub struct AppInterfaceId {
     /// If the port is 0 then it will be assigned by the OS
     /// so we need a unique identifier for that case.
     id: Option<String>,
+    /// The unix domain socket used to create this interface,
+    /// in which case the port is unused.
+    #[serde(default)]
+    path: Option<PathBuf>,
 }
 
 impl Default for AppInterfaceId {
@@ -66,12 +71,31 @@ impl AppInterfaceId {
         } else {
             None
         };
-        Self { port, id }
+        Self {
+            port,
+            id,
+            path: None,
+        }
+    }
+    /// Create an id from the path of a unix domain socket
+    pub fn unix_socket(path: PathBuf) -> Self {
+        Self {
+            port: 0,
+            id: None,
+            path: Some(path),
+        }
     }
     /// Get the port intended for this interface
     pub fn port(&self) -> u16 {
         self.port
     }
+    /// Get the driver intended for this interface
+    pub fn driver(&self) -> InterfaceDriver {
+        match &self.path {
+            Some(path) => InterfaceDriver::UnixSocket { path: path.clone() },
+            None => InterfaceDriver::Websocket { port: self.port },
+        }
+    }
 }
 
 impl ConductorState {
@@ -155,13 +179,50 @@ impl ConductorState {
             .ok_or_else(|| ConductorError::AppNotInstalled(id.clone()))
     }
 
-    /// Getter for a single app. Returns error if app missing.
+    /// Remove a single app. Returns error if app missing, or if other apps
+    /// depend on cells provisioned by this app.
     pub fn remove_app(&mut self, id: &InstalledAppId) -> ConductorResult<InstalledApp> {
+        let dependents = self.get_dependent_apps(id)?;
+        if !dependents.is_empty() {
+            return Err(ConductorError::AppHasDependents(id.clone(), dependents));
+        }
         self.installed_apps
             .remove(id)
             .ok_or_else(|| ConductorError::AppNotInstalled(id.clone()))
     }
 
+    /// Get the IDs of all other apps which use cells provisioned by this app.
+    pub fn get_dependent_apps(&self, id: &InstalledAppId) -> ConductorResult<Vec<InstalledAppId>> {
+        let owned_cells: HashSet<&CellId> = self
+            .get_app(id)?
+            .owned_provisioned_cells()
+            .map(|(_, cell_id)| cell_id)
+            .collect();
+        let mut dependents: Vec<InstalledAppId> = self
+            .installed_apps
+            .iter()
+            .filter(|(other_id, app)| {
+                *other_id != id
+                    && app
+                        .dependency_cells()
+                        .any(|(_, cell_id)| owned_cells.contains(cell_id))
+            })
+            .map(|(other_id, _)| other_id.clone())
+            .collect();
+        dependents.sort();
+        Ok(dependents)
+    }
+
+    /// All cells which were provisioned by installed apps, which are therefore
+    /// available to be used by other apps.
+    pub fn dna_gamut(&self) -> DnaGamut {
+        DnaGamut::new(self.installed_apps.values().flat_map(|app| {
+            app.owned_provisioned_cells()
+                .map(|(_, cell_id)| cell_id.clone())
+                .collect::<Vec<_>>()
+        }))
+    }
+
     /// Add an app in the Deactivated state. Returns an error if an app is already
     /// present at the given ID.
     pub fn add_app(&mut self, app: InstalledAppCommon) -> ConductorResult<StoppedApp> {
@@ -199,9 +260,7 @@ impl ConductorState {
 /// GUIs, browser based web UIs, local native UIs, other local applications and scripts.
 /// We currently have:
 /// * websockets
-///
-/// We will also soon develop
-/// * Unix domain sockets
+/// * websockets over unix domain sockets
 ///
 /// The cells (referenced by ID) that are to be made available via that interface should be listed.
 #[derive(Clone, Deserialize, Serialize, Debug)]
@@ -212,14 +271,24 @@ pub struct AppInterfaceConfig {
 
     /// The driver for the interface, e.g. Websocket
     pub driver: InterfaceDriver,
+
+    /// The origins which may connect to the interface
+    #[serde(default)]
+    pub allowed_origins: AllowedOrigins,
 }
 
 impl AppInterfaceConfig {
     /// Create config for a websocket interface
-    pub fn websocket(port: u16) -> Self {
+    pub fn websocket(port: u16, allowed_origins: AllowedOrigins) -> Self {
+        Self::new(InterfaceDriver::Websocket { port }, allowed_origins)
+    }
+
+    /// Create config for an interface with any driver
+    pub fn new(driver: InterfaceDriver, allowed_origins: AllowedOrigins) -> Self {
         Self {
             signal_subscriptions: HashMap::new(),
-            driver: InterfaceDriver::Websocket { port },
+            driver,
+            allowed_origins,
         }
     }
 }
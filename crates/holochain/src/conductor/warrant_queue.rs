@@ -0,0 +1,179 @@
+//! A queue of warrants received from other agents which claim that an action
+//! is invalid. Checking such a warrant means validating the action again, so
+//! the queue is bounded, drops warrants which are already waiting and limits
+//! how many warrants each author can have checked.
+
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::time::Duration;
+use std::time::Instant;
+
+use holo_hash::ActionHash;
+use holo_hash::AgentPubKey;
+use holo_hash::DnaHash;
+use holochain_zome_types::prelude::*;
+use tokio::sync::mpsc;
+
+/// How many warrants can wait to be checked. Any more are dropped.
+pub const WARRANT_QUEUE_BOUND: usize = 100;
+
+/// How many warrants by a single author are queued within each
+/// [`WARRANT_RATE_WINDOW`]. Any more are dropped.
+pub const WARRANTS_PER_AUTHOR: u32 = 10;
+
+/// The window over which the warrants of each author are counted.
+pub const WARRANT_RATE_WINDOW: Duration = Duration::from_secs(60);
+
+/// A warrant waiting to be checked, and the DNA it was received in.
+pub type QueuedWarrant = (DnaHash, SignedWarrant);
+
+/// Warrants waiting to be checked by the conductor.
+pub struct WarrantQueue {
+    sender: mpsc::Sender<QueuedWarrant>,
+    receiver: parking_lot::Mutex<Option<mpsc::Receiver<QueuedWarrant>>>,
+    state: parking_lot::Mutex<QueueState>,
+}
+
+#[derive(Default)]
+struct QueueState {
+    /// The actions whose warrants are queued or being checked.
+    pending: HashSet<(DnaHash, ActionHash)>,
+    /// When each author's window started, and how many of their warrants
+    /// were queued since.
+    authors: HashMap<AgentPubKey, (Instant, u32)>,
+}
+
+impl Default for WarrantQueue {
+    fn default() -> Self {
+        let (sender, receiver) = mpsc::channel(WARRANT_QUEUE_BOUND);
+        Self {
+            sender,
+            receiver: parking_lot::Mutex::new(Some(receiver)),
+            state: Default::default(),
+        }
+    }
+}
+
+impl WarrantQueue {
+    /// Queue a warrant to be checked. Returns false if it was dropped
+    /// because a warrant for the same action is already queued, its author
+    /// has had too many warrants queued recently, or the queue is full.
+    ///
+    /// Only warrants claiming that an action is invalid need to be checked,
+    /// so any other warrant is dropped.
+    pub fn push(&self, dna_hash: DnaHash, warrant: SignedWarrant) -> bool {
+        let key = match warrant_key(&dna_hash, &warrant) {
+            Some(key) => key,
+            None => return false,
+        };
+        let mut state = self.state.lock();
+        if state.pending.contains(&key) {
+            return false;
+        }
+
+        let now = Instant::now();
+        state
+            .authors
+            .retain(|_, (start, _)| now.duration_since(*start) < WARRANT_RATE_WINDOW);
+        let (_, count) = state
+            .authors
+            .entry(warrant.warrant.author.clone())
+            .or_insert((now, 0));
+        if *count >= WARRANTS_PER_AUTHOR {
+            return false;
+        }
+
+        if self.sender.try_send((dna_hash, warrant)).is_err() {
+            return false;
+        }
+        *count += 1;
+        state.pending.insert(key);
+        true
+    }
+
+    /// Mark a warrant as checked, so that warrants for the same action
+    /// can be queued again.
+    pub fn checked(&self, dna_hash: &DnaHash, warrant: &SignedWarrant) {
+        if let Some(key) = warrant_key(dna_hash, warrant) {
+            self.state.lock().pending.remove(&key);
+        }
+    }
+
+    /// Take the receiving end of the queue. Only the first call gets it.
+    pub fn take_receiver(&self) -> Option<mpsc::Receiver<QueuedWarrant>> {
+        self.receiver.lock().take()
+    }
+}
+
+fn warrant_key(dna_hash: &DnaHash, warrant: &SignedWarrant) -> Option<(DnaHash, ActionHash)> {
+    match &warrant.warrant.proof {
+        WarrantProof::ChainIntegrity(ChainIntegrityWarrant::InvalidChainOp { action, .. }) => {
+            Some((
+                dna_hash.clone(),
+                ActionHash::with_data_sync(action.action()),
+            ))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixt::*;
+    use ::fixt::prelude::*;
+
+    fn invalid_op_warrant(author: AgentPubKey) -> SignedWarrant {
+        let action = Action::Create(fixt!(Create));
+        SignedWarrant {
+            warrant: Warrant::new(
+                WarrantProof::ChainIntegrity(ChainIntegrityWarrant::InvalidChainOp {
+                    action: SignedAction(action, fixt!(Signature)),
+                    validation_type: ValidationType::App,
+                }),
+                author,
+            ),
+            signature: fixt!(Signature),
+        }
+    }
+
+    #[test]
+    fn queued_warrants_are_deduplicated() {
+        let queue = WarrantQueue::default();
+        let dna_hash = fixt!(DnaHash);
+        let warrant = invalid_op_warrant(fixt!(AgentPubKey));
+        let mut from_another_author = warrant.clone();
+        from_another_author.warrant.author = fixt!(AgentPubKey);
+
+        assert!(queue.push(dna_hash.clone(), warrant.clone()));
+        assert!(!queue.push(dna_hash.clone(), warrant.clone()));
+        assert!(!queue.push(dna_hash.clone(), from_another_author));
+        // The same action can be warranted in another DNA
+        assert!(queue.push(fixt!(DnaHash), warrant.clone()));
+
+        queue.checked(&dna_hash, &warrant);
+        assert!(queue.push(dna_hash, warrant));
+    }
+
+    #[test]
+    fn warrants_are_rate_limited_per_author() {
+        let queue = WarrantQueue::default();
+        let dna_hash = fixt!(DnaHash);
+        let author = fixt!(AgentPubKey);
+        for _ in 0..WARRANTS_PER_AUTHOR {
+            assert!(queue.push(dna_hash.clone(), invalid_op_warrant(author.clone())));
+        }
+        assert!(!queue.push(dna_hash.clone(), invalid_op_warrant(author)));
+        assert!(queue.push(dna_hash, invalid_op_warrant(fixt!(AgentPubKey))));
+    }
+
+    #[test]
+    fn the_queue_is_bounded() {
+        let queue = WarrantQueue::default();
+        let dna_hash = fixt!(DnaHash);
+        for _ in 0..WARRANT_QUEUE_BOUND {
+            assert!(queue.push(dna_hash.clone(), invalid_op_warrant(fixt!(AgentPubKey))));
+        }
+        assert!(!queue.push(dna_hash, invalid_op_warrant(fixt!(AgentPubKey))));
+    }
+}
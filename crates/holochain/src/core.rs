@@ -5,7 +5,7 @@
 pub mod queue_consumer;
 #[allow(missing_docs)]
 pub mod ribosome;
-mod validation;
+pub(crate) mod validation;
 #[allow(missing_docs)]
 pub mod workflow;
 
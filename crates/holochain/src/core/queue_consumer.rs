@@ -86,6 +86,7 @@ pub async fn spawn_queue_consumer_tasks(
         dht_db,
         cache_db: cache,
         dht_query_cache,
+        region_set_cache,
         ..
     } = space;
 
@@ -142,6 +143,7 @@ pub async fn spawn_queue_consumer_tasks(
                 dna_hash.clone(),
                 dht_db.clone(),
                 dht_query_cache.clone(),
+                region_set_cache.clone(),
                 stop.subscribe(),
                 tx_receipt.clone(),
                 network.clone(),
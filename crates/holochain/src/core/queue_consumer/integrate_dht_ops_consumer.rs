@@ -1,6 +1,7 @@
 //! The workflow and queue consumer for DhtOp integration
 
 use super::*;
+use crate::conductor::kitsune_host_impl::query_region_set::RegionSetCache;
 use crate::conductor::manager::ManagedTaskResult;
 use crate::core::workflow::integrate_dht_ops_workflow::integrate_dht_ops_workflow;
 use holochain_types::db_cache::DhtDbQueryCache;
@@ -8,11 +9,12 @@ use tokio::task::JoinHandle;
 use tracing::*;
 
 /// Spawn the QueueConsumer for DhtOpIntegration workflow
-#[instrument(skip(env, stop, trigger_receipt, network, dht_query_cache))]
+#[instrument(skip(env, stop, trigger_receipt, network, dht_query_cache, region_set_cache))]
 pub fn spawn_integrate_dht_ops_consumer(
     dna_hash: Arc<DnaHash>,
     env: DbWrite<DbKindDht>,
     dht_query_cache: DhtDbQueryCache,
+    region_set_cache: RegionSetCache,
     mut stop: sync::broadcast::Receiver<()>,
     trigger_receipt: TriggerSender,
     network: HolochainP2pDna,
@@ -33,6 +35,7 @@ pub fn spawn_integrate_dht_ops_consumer(
             match integrate_dht_ops_workflow(
                 env.clone(),
                 &dht_query_cache,
+                &region_set_cache,
                 trigger_receipt.clone(),
                 network.clone(),
             )
@@ -23,10 +23,14 @@ use crate::core::ribosome::guest_callback::init::InitResult;
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation;
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitInvocation;
+use crate::core::ribosome::guest_callback::rate_limits::RateLimitsInvocation;
+use crate::core::ribosome::guest_callback::rate_limits::RateLimitsResult;
 use crate::core::ribosome::guest_callback::validate::ValidateInvocation;
 use crate::core::ribosome::guest_callback::validate::ValidateResult;
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageInvocation;
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageResult;
+use crate::core::ribosome::guest_callback::weigh::WeighInvocation;
+use crate::core::ribosome::guest_callback::weigh::WeighResult;
 use crate::core::ribosome::guest_callback::CallIterator;
 use derive_more::Constructor;
 use error::RibosomeResult;
@@ -34,8 +38,10 @@ use guest_callback::entry_defs::EntryDefsHostAccess;
 use guest_callback::init::InitHostAccess;
 use guest_callback::migrate_agent::MigrateAgentHostAccess;
 use guest_callback::post_commit::PostCommitHostAccess;
+use guest_callback::rate_limits::RateLimitsHostAccess;
 use guest_callback::validate::ValidateHostAccess;
 use guest_callback::validation_package::ValidationPackageHostAccess;
+use guest_callback::weigh::WeighHostAccess;
 use holo_hash::AgentPubKey;
 use holochain_keystore::MetaLairClient;
 use holochain_p2p::HolochainP2pDna;
@@ -104,8 +110,10 @@ pub enum HostContext {
     Init(InitHostAccess),
     MigrateAgent(MigrateAgentHostAccess),
     PostCommit(PostCommitHostAccess), // MAYBE: add emit_signal access here?
+    RateLimits(RateLimitsHostAccess),
     Validate(ValidateHostAccess),
     ValidationPackage(ValidationPackageHostAccess),
+    Weigh(WeighHostAccess),
     ZomeCall(ZomeCallHostAccess),
 }
 
@@ -120,6 +128,8 @@ impl From<&HostContext> for HostFnAccess {
             HostContext::MigrateAgent(access) => access.into(),
             HostContext::ValidationPackage(access) => access.into(),
             HostContext::PostCommit(access) => access.into(),
+            HostContext::RateLimits(access) => access.into(),
+            HostContext::Weigh(access) => access.into(),
         }
     }
 }
@@ -649,6 +659,18 @@ pub trait RibosomeT: Sized + std::fmt::Debug + Send + Sync {
         invocation: EntryDefsInvocation,
     ) -> RibosomeResult<EntryDefsResult>;
 
+    fn run_rate_limits(
+        &self,
+        access: RateLimitsHostAccess,
+        invocation: RateLimitsInvocation,
+    ) -> RibosomeResult<RateLimitsResult>;
+
+    fn run_weigh(
+        &self,
+        access: WeighHostAccess,
+        invocation: WeighInvocation,
+    ) -> RibosomeResult<WeighResult>;
+
     fn run_validation_package(
         &self,
         access: ValidationPackageHostAccess,
@@ -681,10 +703,29 @@ pub trait RibosomeT: Sized + std::fmt::Debug + Send + Sync {
 }
 
 /// Placeholder for weighing. Currently produces zero weight.
+/// Still used for countersigned entries, whose weight must be agreed
+/// upon by every party to the session.
 pub fn weigh_placeholder() -> EntryRateWeight {
     EntryRateWeight::default()
 }
 
+/// Weigh an action for rate limiting by running the `weigh` callback of the
+/// integrity zome which defines its type.
+/// Actions which aren't defined by an integrity zome, or whose zome doesn't
+/// implement the callback, get the default weight.
+pub fn weigh_action(
+    ribosome: &impl RibosomeT,
+    zome_index: Option<ZomeIndex>,
+    input: WeighInput,
+) -> RibosomeResult<RateWeight> {
+    match zome_index.and_then(|zome_index| ribosome.get_integrity_zome(&zome_index)) {
+        Some(zome) => Ok(ribosome
+            .run_weigh(WeighHostAccess, WeighInvocation::new(zome, input))?
+            .0),
+        None => Ok(RateWeight::default()),
+    }
+}
+
 #[cfg(test)]
 pub mod wasm_test {
     use crate::core::ribosome::FnComponents;
@@ -0,0 +1,176 @@
+use crate::core::ribosome::FnComponents;
+use crate::core::ribosome::HostContext;
+use crate::core::ribosome::Invocation;
+use crate::core::ribosome::InvocationAuth;
+use crate::core::ribosome::ZomesToInvoke;
+use derive_more::Constructor;
+use holochain_serialized_bytes::prelude::*;
+use holochain_types::prelude::*;
+
+#[derive(Debug, Clone)]
+pub struct RateLimitsInvocation;
+
+impl RateLimitsInvocation {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+#[derive(Clone, Constructor, Debug)]
+pub struct RateLimitsHostAccess;
+
+impl From<&HostContext> for RateLimitsHostAccess {
+    fn from(_: &HostContext) -> Self {
+        Self
+    }
+}
+
+impl From<RateLimitsHostAccess> for HostContext {
+    fn from(rate_limits_host_access: RateLimitsHostAccess) -> Self {
+        Self::RateLimits(rate_limits_host_access)
+    }
+}
+
+impl From<&RateLimitsHostAccess> for HostFnAccess {
+    fn from(_: &RateLimitsHostAccess) -> Self {
+        Self::none()
+    }
+}
+
+impl Invocation for RateLimitsInvocation {
+    fn zomes(&self) -> ZomesToInvoke {
+        ZomesToInvoke::AllIntegrity
+    }
+    fn fn_components(&self) -> FnComponents {
+        vec!["rate_limits".into()].into()
+    }
+    fn host_input(self) -> Result<ExternIO, SerializedBytesError> {
+        ExternIO::encode(())
+    }
+    fn auth(&self) -> InvocationAuth {
+        InvocationAuth::LocalCallback
+    }
+}
+
+/// the aggregate result of _all_ rate limits callbacks
+#[derive(PartialEq, Debug, Clone)]
+pub enum RateLimitsResult {
+    /// the limits of every bucket declared by any integrity zome
+    Limits(RateLimits),
+    /// ZomeName is the first zome which declared a bucket that another
+    /// zome had already declared with different limits
+    Err(ZomeName, String),
+}
+
+impl From<Vec<(ZomeName, RateLimitsCallbackResult)>> for RateLimitsResult {
+    fn from(callback_results: Vec<(ZomeName, RateLimitsCallbackResult)>) -> Self {
+        callback_results
+            .into_iter()
+            .fold(
+                RateLimitsResult::Limits(RateLimits::new()),
+                |acc, x| match (acc, x) {
+                    (
+                        Self::Limits(mut all),
+                        (zome_name, RateLimitsCallbackResult::Limits(limits)),
+                    ) => {
+                        for (bucket_id, limit) in limits {
+                            match all.insert(bucket_id, limit) {
+                                Some(existing) if existing != limit => {
+                                    return Self::Err(
+                                        zome_name,
+                                        format!(
+                                        "Rate bucket {} was already declared with different limits",
+                                        bucket_id
+                                    ),
+                                    );
+                                }
+                                _ => (),
+                            }
+                        }
+                        Self::Limits(all)
+                    }
+                    (acc @ Self::Err(_, _), _) => acc,
+                },
+            )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::RateLimitsHostAccess;
+    use super::RateLimitsResult;
+    use crate::core::ribosome::Invocation;
+    use crate::core::ribosome::ZomesToInvoke;
+    use crate::fixt::RateLimitsInvocationFixturator;
+    use crate::fixt::ZomeNameFixturator;
+    use holochain_types::prelude::*;
+    use std::collections::BTreeMap;
+
+    fn limit(capacity: RateBucketCapacity) -> RateLimit {
+        RateLimit {
+            capacity,
+            drain_amount: 1,
+            drain_interval_ms: 1000,
+        }
+    }
+
+    #[test]
+    fn rate_limits_callback_result_fold() {
+        let mut zome_name_fixturator = ZomeNameFixturator::new(::fixt::Unpredictable);
+
+        // zero limits
+        assert_eq!(RateLimitsResult::Limits(BTreeMap::new()), vec![].into());
+
+        // limits from two zomes are merged
+        let zome_one = zome_name_fixturator.next().unwrap();
+        let zome_two = zome_name_fixturator.next().unwrap();
+        let expected: BTreeMap<_, _> = [(0, limit(3)), (1, limit(5))].into_iter().collect();
+        assert_eq!(
+            RateLimitsResult::Limits(expected),
+            vec![
+                (
+                    zome_one.clone(),
+                    RateLimitsCallbackResult::Limits([(0, limit(3))].into_iter().collect())
+                ),
+                (
+                    zome_two.clone(),
+                    RateLimitsCallbackResult::Limits(
+                        [(0, limit(3)), (1, limit(5))].into_iter().collect()
+                    )
+                ),
+            ]
+            .into()
+        );
+
+        // conflicting limits for the same bucket are an error
+        let result: RateLimitsResult = vec![
+            (
+                zome_one,
+                RateLimitsCallbackResult::Limits([(0, limit(3))].into_iter().collect()),
+            ),
+            (
+                zome_two.clone(),
+                RateLimitsCallbackResult::Limits([(0, limit(4))].into_iter().collect()),
+            ),
+        ]
+        .into();
+        assert!(matches!(result, RateLimitsResult::Err(zome_name, _) if zome_name == zome_two));
+    }
+
+    #[test]
+    fn rate_limits_host_access() {
+        assert_eq!(
+            HostFnAccess::from(&RateLimitsHostAccess),
+            HostFnAccess::none()
+        );
+    }
+
+    #[test]
+    fn rate_limits_invocation_zomes() {
+        let rate_limits_invocation = RateLimitsInvocationFixturator::new(::fixt::Unpredictable)
+            .next()
+            .unwrap();
+        assert_eq!(ZomesToInvoke::AllIntegrity, rate_limits_invocation.zomes(),);
+    }
+}
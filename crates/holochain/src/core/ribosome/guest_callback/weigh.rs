@@ -0,0 +1,87 @@
+use crate::core::ribosome::FnComponents;
+use crate::core::ribosome::HostContext;
+use crate::core::ribosome::Invocation;
+use crate::core::ribosome::InvocationAuth;
+use crate::core::ribosome::ZomesToInvoke;
+use derive_more::Constructor;
+use holochain_serialized_bytes::prelude::*;
+use holochain_types::prelude::*;
+
+#[derive(Debug, Clone, Constructor)]
+pub struct WeighInvocation {
+    /// The integrity zome which defines the weighed action's type.
+    pub zome: IntegrityZome,
+    pub input: WeighInput,
+}
+
+#[derive(Clone, Constructor, Debug)]
+pub struct WeighHostAccess;
+
+impl From<&HostContext> for WeighHostAccess {
+    fn from(_: &HostContext) -> Self {
+        Self
+    }
+}
+
+impl From<WeighHostAccess> for HostContext {
+    fn from(weigh_host_access: WeighHostAccess) -> Self {
+        Self::Weigh(weigh_host_access)
+    }
+}
+
+impl From<&WeighHostAccess> for HostFnAccess {
+    fn from(_: &WeighHostAccess) -> Self {
+        Self::none()
+    }
+}
+
+impl Invocation for WeighInvocation {
+    fn zomes(&self) -> ZomesToInvoke {
+        ZomesToInvoke::OneIntegrity(self.zome.clone())
+    }
+    fn fn_components(&self) -> FnComponents {
+        vec!["weigh".into()].into()
+    }
+    fn host_input(self) -> Result<ExternIO, SerializedBytesError> {
+        ExternIO::encode(self.input)
+    }
+    fn auth(&self) -> InvocationAuth {
+        InvocationAuth::LocalCallback
+    }
+}
+
+/// The weight given to an action by its integrity zome.
+/// Zomes which don't implement the callback give the default weight.
+#[derive(PartialEq, Debug, Clone)]
+pub struct WeighResult(pub RateWeight);
+
+impl From<Vec<(ZomeName, WeighCallbackResult)>> for WeighResult {
+    fn from(callback_results: Vec<(ZomeName, WeighCallbackResult)>) -> Self {
+        callback_results.into_iter().fold(
+            Self(RateWeight::default()),
+            |_, (_, result)| match result {
+                WeighCallbackResult::Weight(weight) => Self(weight),
+            },
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::WeighResult;
+    use holochain_types::prelude::*;
+
+    #[test]
+    fn weigh_callback_result_fold() {
+        assert_eq!(WeighResult(RateWeight::default()), vec![].into());
+
+        let weight = RateWeight {
+            bucket_id: 1,
+            units: 2,
+        };
+        assert_eq!(
+            WeighResult(weight.clone()),
+            vec![("integrity".into(), WeighCallbackResult::Weight(weight))].into()
+        );
+    }
+}
@@ -120,6 +120,9 @@ host_fn_api_impls! {
     // Create a link between two entries.
     fn create_link (zt::link::CreateLinkInput) -> holo_hash::ActionHash;
 
+    // Count links by entry hash from the cascade, without getting them.
+    fn count_links (zt::link::CountLinksInput) -> zt::link::LinkCount;
+
     // Delete an entry.
     fn delete (zt::entry::DeleteInput) -> holo_hash::ActionHash;
 
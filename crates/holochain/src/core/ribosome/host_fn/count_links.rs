@@ -0,0 +1,95 @@
+use crate::core::ribosome::CallContext;
+use crate::core::ribosome::HostFnAccess;
+use crate::core::ribosome::RibosomeError;
+use crate::core::ribosome::RibosomeT;
+use holochain_cascade::Cascade;
+use holochain_p2p::actor::GetLinksOptions;
+use holochain_types::prelude::*;
+use holochain_wasmer_host::prelude::*;
+use std::sync::Arc;
+
+#[allow(clippy::extra_unused_lifetimes)]
+#[tracing::instrument(skip(_ribosome, call_context), fields(?call_context.zome, function = ?call_context.function_name))]
+pub fn count_links<'a>(
+    _ribosome: Arc<impl RibosomeT>,
+    call_context: Arc<CallContext>,
+    input: CountLinksInput,
+) -> Result<LinkCount, RuntimeError> {
+    match HostFnAccess::from(&call_context.host_context()) {
+        HostFnAccess {
+            read_workspace: Permission::Allow,
+            ..
+        } => {
+            let CountLinksInput {
+                query,
+                include_hashes,
+            } = input;
+            let key = WireLinkKey::from(query);
+            let links = tokio_helper::block_forever_on(async move {
+                Cascade::from_workspace_and_network(
+                    &call_context.host_context.workspace(),
+                    call_context.host_context.network().to_owned(),
+                )
+                .count_links(key, GetLinksOptions::default())
+                .await
+            })
+            .map_err(|cascade_error| -> RuntimeError {
+                wasm_error!(WasmErrorInner::Host(cascade_error.to_string())).into()
+            })?;
+            Ok(LinkCount {
+                count: links.len() as u32,
+                create_link_hashes: include_hashes.then(|| {
+                    links
+                        .into_iter()
+                        .map(|link| link.create_link_hash().clone())
+                        .collect()
+                }),
+            })
+        }
+        _ => Err(wasm_error!(WasmErrorInner::Host(
+            RibosomeError::HostFnPermissions(
+                call_context.zome.zome_name().clone(),
+                call_context.function_name().clone(),
+                "count_links".into(),
+            )
+            .to_string(),
+        ))
+        .into()),
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "slow_tests")]
+pub mod slow_tests {
+    use crate::core::ribosome::wasm_test::RibosomeTestFixture;
+    use hdk::prelude::*;
+    use holochain_wasm_test_utils::TestWasm;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn count_links_before_and_after_delete() {
+        observability::test_run().ok();
+        let RibosomeTestFixture {
+            conductor, alice, ..
+        } = RibosomeTestFixture::new(TestWasm::Link).await;
+
+        let (count, hashes): (usize, Vec<ActionHash>) =
+            conductor.call(&alice, "count_links", ()).await;
+        assert_eq!(count, 0);
+        assert!(hashes.is_empty());
+
+        let first: ActionHash = conductor.call(&alice, "create_link", ()).await;
+        let second: ActionHash = conductor.call(&alice, "create_link", ()).await;
+
+        let (count, hashes): (usize, Vec<ActionHash>) =
+            conductor.call(&alice, "count_links", ()).await;
+        assert_eq!(count, 2);
+        assert_eq!(hashes, vec![first.clone(), second.clone()]);
+
+        let _: ActionHash = conductor.call(&alice, "delete_link", first).await;
+
+        let (count, hashes): (usize, Vec<ActionHash>) =
+            conductor.call(&alice, "count_links", ()).await;
+        assert_eq!(count, 1);
+        assert_eq!(hashes, vec![second]);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::core::ribosome::weigh_action;
 use crate::core::ribosome::weigh_placeholder;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::HostFnAccess;
@@ -5,13 +6,14 @@ use crate::core::ribosome::RibosomeError;
 use crate::core::ribosome::RibosomeT;
 use holochain_wasmer_host::prelude::*;
 
+use holochain_state::source_chain::SourceChainError;
 use holochain_types::prelude::*;
 use std::sync::Arc;
 
 /// create record
 #[allow(clippy::extra_unused_lifetimes)]
 pub fn create<'a>(
-    _ribosome: Arc<impl RibosomeT>,
+    ribosome: Arc<impl RibosomeT>,
     call_context: Arc<CallContext>,
     input: CreateInput,
 ) -> Result<ActionHash, RuntimeError> {
@@ -27,11 +29,10 @@ pub fn create<'a>(
                 chain_top_ordering,
             } = input;
 
-            let weight = weigh_placeholder();
-
             // Countersigned entries have different action handling.
             match entry {
                 Entry::CounterSign(_, _) => tokio_helper::block_forever_on(async move {
+                    let weight = weigh_placeholder();
                     call_context
                         .host_context
                         .workspace_write()
@@ -62,6 +63,8 @@ pub fn create<'a>(
                         EntryDefLocation::CapClaim => EntryType::CapClaim,
                     };
 
+                    let zome_index = entry_type.zome_index();
+
                     // build an action for the entry being committed
                     let action_builder = builder::Create {
                         entry_type,
@@ -80,7 +83,20 @@ pub fn create<'a>(
                             .source_chain()
                             .as_ref()
                             .expect("Must have source chain if write_workspace access is given")
-                            .put_weightless(action_builder, Some(entry), chain_top_ordering)
+                            .put_weighed_by(
+                                action_builder,
+                                Some(entry.clone()),
+                                chain_top_ordering,
+                                |create| {
+                                    weigh_action(
+                                        ribosome.as_ref(),
+                                        zome_index,
+                                        WeighInput::Create(create.clone(), entry),
+                                    )
+                                    .map(EntryRateWeight::from)
+                                    .map_err(SourceChainError::other)
+                                },
+                            )
                             .await
                             .map_err(|source_chain_error| -> RuntimeError {
                                 wasm_error!(WasmErrorInner::Host(source_chain_error.to_string()))
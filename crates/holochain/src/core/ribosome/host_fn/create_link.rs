@@ -1,15 +1,17 @@
+use crate::core::ribosome::weigh_action;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::HostFnAccess;
 use crate::core::ribosome::RibosomeError;
 use crate::core::ribosome::RibosomeT;
 use holochain_wasmer_host::prelude::*;
 
+use holochain_state::source_chain::SourceChainError;
 use holochain_types::prelude::*;
 use std::sync::Arc;
 
 #[allow(clippy::extra_unused_lifetimes)]
 pub fn create_link<'a>(
-    _ribosome: Arc<impl RibosomeT>,
+    ribosome: Arc<impl RibosomeT>,
     call_context: Arc<CallContext>,
     input: CreateLinkInput,
 ) -> Result<ActionHash, RuntimeError> {
@@ -31,7 +33,7 @@ pub fn create_link<'a>(
             let action_builder =
                 builder::CreateLink::new(base_address, target_address, zome_index, link_type, tag);
 
-            let action_hash = tokio_helper::block_forever_on(tokio::task::spawn(async move {
+            let action_hash = tokio_helper::block_forever_on(async move {
                 // push the action into the source chain
                 let action_hash = call_context
                     .host_context
@@ -39,13 +41,17 @@ pub fn create_link<'a>(
                     .source_chain()
                     .as_ref()
                     .expect("Must have source chain if write_workspace access is given")
-                    .put_weightless(action_builder, None, chain_top_ordering)
+                    .put_weighed_by(action_builder, None, chain_top_ordering, |create_link| {
+                        weigh_action(
+                            ribosome.as_ref(),
+                            Some(zome_index),
+                            WeighInput::Link(create_link.clone()),
+                        )
+                        .map_err(SourceChainError::other)
+                    })
                     .await?;
                 Ok::<ActionHash, RibosomeError>(action_hash)
-            }))
-            .map_err(|join_error| -> RuntimeError {
-                wasm_error!(WasmErrorInner::Host(join_error.to_string())).into()
-            })?
+            })
             .map_err(|ribosome_error| -> RuntimeError {
                 wasm_error!(WasmErrorInner::Host(ribosome_error.to_string())).into()
             })?;
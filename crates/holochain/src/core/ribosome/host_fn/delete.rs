@@ -1,4 +1,5 @@
 use crate::core::ribosome::error::RibosomeError;
+use crate::core::ribosome::weigh_action;
 use crate::core::ribosome::CallContext;
 use crate::core::ribosome::RibosomeT;
 use holochain_cascade::error::CascadeError;
@@ -8,12 +9,13 @@ use holochain_wasmer_host::prelude::*;
 use crate::core::ribosome::HostFnAccess;
 use holo_hash::ActionHash;
 use holo_hash::EntryHash;
+use holochain_state::source_chain::SourceChainError;
 use holochain_types::prelude::*;
 use std::sync::Arc;
 
 #[allow(clippy::extra_unused_lifetimes)]
 pub fn delete<'a>(
-    _ribosome: Arc<impl RibosomeT>,
+    ribosome: Arc<impl RibosomeT>,
     call_context: Arc<CallContext>,
     input: DeleteInput,
 ) -> Result<ActionHash, RuntimeError> {
@@ -26,7 +28,7 @@ pub fn delete<'a>(
                 deletes_action_hash,
                 chain_top_ordering,
             } = input;
-            let (deletes_entry_address, _) =
+            let (deletes_entry_address, deletes_entry_type) =
                 get_original_entry_data(call_context.clone(), deletes_action_hash.clone())?;
 
             let host_access = call_context.host_context();
@@ -43,7 +45,14 @@ pub fn delete<'a>(
                     deletes_entry_address,
                 };
                 let action_hash = source_chain
-                    .put_weightless(action_builder, None, chain_top_ordering)
+                    .put_weighed_by(action_builder, None, chain_top_ordering, |delete| {
+                        weigh_action(
+                            ribosome.as_ref(),
+                            deletes_entry_type.zome_index(),
+                            WeighInput::Delete(delete.clone()),
+                        )
+                        .map_err(SourceChainError::other)
+                    })
                     .await
                     .map_err(|source_chain_error| {
                         wasm_error!(WasmErrorInner::Host(source_chain_error.to_string()))
@@ -2,8 +2,10 @@ use super::guest_callback::entry_defs::EntryDefsHostAccess;
 use super::guest_callback::init::InitHostAccess;
 use super::guest_callback::migrate_agent::MigrateAgentHostAccess;
 use super::guest_callback::post_commit::PostCommitHostAccess;
+use super::guest_callback::rate_limits::RateLimitsHostAccess;
 use super::guest_callback::validate::ValidateHostAccess;
 use super::guest_callback::validation_package::ValidationPackageHostAccess;
+use super::guest_callback::weigh::WeighHostAccess;
 use super::host_fn::get_agent_activity::get_agent_activity;
 use super::host_fn::HostFnApi;
 use super::HostContext;
@@ -20,10 +22,14 @@ use crate::core::ribosome::guest_callback::init::InitResult;
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation;
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitInvocation;
+use crate::core::ribosome::guest_callback::rate_limits::RateLimitsInvocation;
+use crate::core::ribosome::guest_callback::rate_limits::RateLimitsResult;
 use crate::core::ribosome::guest_callback::validate::ValidateInvocation;
 use crate::core::ribosome::guest_callback::validate::ValidateResult;
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageInvocation;
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageResult;
+use crate::core::ribosome::guest_callback::weigh::WeighInvocation;
+use crate::core::ribosome::guest_callback::weigh::WeighResult;
 use crate::core::ribosome::guest_callback::CallIterator;
 use crate::core::ribosome::host_fn::accept_countersigning_preflight_request::accept_countersigning_preflight_request;
 use crate::core::ribosome::host_fn::agent_info::agent_info;
@@ -32,6 +38,7 @@ use crate::core::ribosome::host_fn::call_info::call_info;
 use crate::core::ribosome::host_fn::capability_claims::capability_claims;
 use crate::core::ribosome::host_fn::capability_grants::capability_grants;
 use crate::core::ribosome::host_fn::capability_info::capability_info;
+use crate::core::ribosome::host_fn::count_links::count_links;
 use crate::core::ribosome::host_fn::create::create;
 use crate::core::ribosome::host_fn::create_link::create_link;
 use crate::core::ribosome::host_fn::create_x25519_keypair::create_x25519_keypair;
@@ -570,6 +577,7 @@ impl RealRibosome {
             .with_host_function(&mut ns, "__get_details", get_details)
             .with_host_function(&mut ns, "__get_links", get_links)
             .with_host_function(&mut ns, "__get_link_details", get_link_details)
+            .with_host_function(&mut ns, "__count_links", count_links)
             .with_host_function(&mut ns, "__get_agent_activity", get_agent_activity)
             .with_host_function(&mut ns, "__must_get_entry", must_get_entry)
             .with_host_function(&mut ns, "__must_get_action", must_get_action)
@@ -917,6 +925,22 @@ impl RibosomeT for RealRibosome {
         do_callback!(self, host_access, invocation, MigrateAgentCallbackResult)
     }
 
+    fn run_rate_limits(
+        &self,
+        host_access: RateLimitsHostAccess,
+        invocation: RateLimitsInvocation,
+    ) -> RibosomeResult<RateLimitsResult> {
+        do_callback!(self, host_access, invocation, RateLimitsCallbackResult)
+    }
+
+    fn run_weigh(
+        &self,
+        host_access: WeighHostAccess,
+        invocation: WeighInvocation,
+    ) -> RibosomeResult<WeighResult> {
+        do_callback!(self, host_access, invocation, WeighCallbackResult)
+    }
+
     fn run_validation_package(
         &self,
         host_access: ValidationPackageHostAccess,
@@ -10,10 +10,12 @@ use crate::conductor::space::Space;
 use crate::conductor::Conductor;
 use holochain_keystore::AgentPubKeyExt;
 use holochain_p2p::HolochainP2pDna;
+use holochain_p2p::HolochainP2pDnaT;
 use holochain_types::prelude::*;
 use holochain_zome_types::countersigning::CounterSigningSessionData;
 use std::convert::TryInto;
 use std::sync::Arc;
+use tracing::warn;
 
 pub use error::*;
 pub use holo_hash::*;
@@ -51,10 +53,154 @@ pub async fn verify_action_signature(sig: &Signature, action: &Action) -> SysVal
 }
 
 /// Verify the author key was valid at the time
-/// of signing with dpki
-/// TODO: This is just a stub until we have dpki.
-pub async fn author_key_is_valid(_author: &AgentPubKey) -> SysValidationResult<()> {
-    Ok(())
+/// of signing with dpki.
+/// Actions of the DPKI DNA itself are not checked, and if the
+/// key state can't be looked up the action is left pending so it
+/// is checked again later.
+/// Key states are cached by the conductor, so most actions don't
+/// need a call into the DPKI cell.
+pub async fn author_key_is_valid(
+    action: &Action,
+    dna_hash: &DnaHash,
+    conductor_handle: &Conductor,
+) -> SysValidationResult<()> {
+    match conductor_handle.dpki_cell_id().await {
+        Ok(Some(cell_id)) if cell_id.dna_hash() != dna_hash => (),
+        Ok(_) => return Ok(()),
+        Err(e) => {
+            warn!(?e, "Couldn't find the DPKI cell to check an author key");
+            return Err(ValidationOutcome::DpkiUnavailable(action.author().clone()).into());
+        }
+    }
+    match conductor_handle
+        .dpki_key_state_at(action.author(), action.timestamp())
+        .await
+    {
+        Ok(Some(key_state)) if !key_state.is_valid_at(action.timestamp()) => {
+            Err(ValidationOutcome::RevokedKey(action.author().clone(), key_state).into())
+        }
+        Ok(_) => Ok(()),
+        Err(e) => {
+            warn!(?e, "Couldn't look up the DPKI state of an author key");
+            Err(ValidationOutcome::DpkiUnavailable(action.author().clone()).into())
+        }
+    }
+}
+
+/// Verify the action was weighed for rate limiting the way its integrity
+/// zome's `weigh` callback weighs it.
+/// Only links, deletes, and entries which are present, can be weighed.
+/// Deletes are weighed by the zome of the entry they delete, which is
+/// fetched through the cascade. Actions of DNAs which declare no rate limits,
+/// and countersigned entries, which are weighed by their session, are not checked.
+pub async fn check_weight(
+    action: &Action,
+    maybe_entry: Option<&Entry>,
+    workspace: &SysValidationWorkspace,
+    network: HolochainP2pDna,
+    conductor_handle: &Conductor,
+) -> SysValidationResult<()> {
+    let dna_hash = network.dna_hash();
+    let weighable = match (action, maybe_entry) {
+        (_, Some(Entry::CounterSign(_, _))) => false,
+        (Action::CreateLink(_), _) | (Action::Delete(_), _) => true,
+        (Action::Create(_), Some(_)) | (Action::Update(_), Some(_)) => true,
+        _ => false,
+    };
+    if !weighable
+        || conductor_handle
+            .get_rate_limits(&dna_hash)
+            .await
+            .map_err(|e| SysValidationError::ConductorApiError(Box::new(e.into())))?
+            .is_empty()
+    {
+        return Ok(());
+    }
+    let (zome_index, input) = match (action, maybe_entry) {
+        (Action::CreateLink(create_link), _) => (
+            Some(create_link.zome_index),
+            WeighInput::Link(create_link.clone().unweighed()),
+        ),
+        (Action::Delete(delete), _) => {
+            let deleted = workspace
+                .full_cascade(network)
+                .retrieve_action(delete.deletes_address.clone(), Default::default())
+                .await?
+                .ok_or_else(|| {
+                    ValidationOutcome::DepMissingFromDht(delete.deletes_address.clone().into())
+                })?;
+            (
+                deleted
+                    .action()
+                    .entry_type()
+                    .and_then(|entry_type| entry_type.zome_index()),
+                WeighInput::Delete(delete.clone().unweighed()),
+            )
+        }
+        (Action::Create(create), Some(entry)) => (
+            create.entry_type.zome_index(),
+            WeighInput::Create(create.clone().unweighed(), entry.clone()),
+        ),
+        (Action::Update(update), Some(entry)) => (
+            update.entry_type.zome_index(),
+            WeighInput::Update(update.clone().unweighed(), entry.clone()),
+        ),
+        _ => return Ok(()),
+    };
+    let weight = conductor_handle
+        .weigh_action(&dna_hash, zome_index, input)
+        .await
+        .map_err(|e| SysValidationError::ConductorApiError(Box::new(e.into())))?;
+    if action.rate_data() == weight {
+        Ok(())
+    } else {
+        Err(ValidationOutcome::IncorrectWeight(action.rate_data(), weight).into())
+    }
+}
+
+/// Check that the action doesn't overflow the rate limiting bucket it is
+/// weighed into, given the earlier actions in the author's chain.
+/// Actions without units, or in buckets the DNA doesn't declare, are
+/// never limited. The action is held until every earlier action in the
+/// window of the bucket is held.
+pub async fn check_rate_limits(
+    action: &Action,
+    dna_hash: &DnaHash,
+    workspace: &SysValidationWorkspace,
+    conductor_handle: &Conductor,
+) -> SysValidationResult<()> {
+    let RateWeight { bucket_id, units } = action.rate_data();
+    if units == 0 {
+        return Ok(());
+    }
+    let limit = match conductor_handle
+        .get_rate_limits(dna_hash)
+        .await
+        .map_err(|e| SysValidationError::ConductorApiError(Box::new(e.into())))?
+        .remove(&bucket_id)
+    {
+        Some(limit) => limit,
+        None => return Ok(()),
+    };
+    let mut bucket = RateBucket::new(limit);
+    let since = bucket
+        .history_window()
+        .map(|window| action.timestamp().saturating_sub(&window));
+    let history = workspace
+        .chain_history_since(action, since)
+        .await?
+        .map_err(|missing| ValidationOutcome::NotHoldingDep(missing.into()))?;
+    for earlier in history
+        .iter()
+        .map(|a| (a.rate_data(), a.timestamp()))
+        .filter(|(weight, _)| weight.bucket_id == bucket_id)
+    {
+        bucket.fill(earlier.0.units, earlier.1);
+    }
+    match bucket.fill(units, action.timestamp()) {
+        Some(_) => Ok(()),
+        None => Err(ValidationOutcome::RateLimitExceeded(bucket_id).into()),
+    }
 }
 
 /// Verify the countersigning session contains the specified action.
@@ -245,6 +391,16 @@ pub fn check_prev_timestamp(action: &Action, prev_action: &Action) -> SysValidat
     }
 }
 
+/// Check the previous action didn't close the chain
+pub fn check_prev_not_closed(prev_action: &SignedActionHashed) -> SysValidationResult<()> {
+    if let Action::CloseChain(_) = prev_action.action() {
+        Err(PrevActionError::ChainClosed(prev_action.as_hash().clone()))
+            .map_err(|e| ValidationOutcome::from(e).into())
+    } else {
+        Ok(())
+    }
+}
+
 /// Check the previous action is one less than the current
 pub fn check_prev_seq(action: &Action, prev_action: &Action) -> SysValidationResult<()> {
     let action_seq = action.action_seq();
@@ -107,6 +107,8 @@ pub enum ValidationOutcome {
     CounterSigningError(#[from] CounterSigningError),
     #[error("The dependency {0:?} was not found on the DHT")]
     DepMissingFromDht(AnyDhtHash),
+    #[error("The DPKI state of the author key {0} could not be looked up")]
+    DpkiUnavailable(AgentPubKey),
     #[error("The app entry def {0:?} entry def id was out of range")]
     EntryDefId(AppEntryDef),
     #[error("The entry has a different hash to the action's entry hash")]
@@ -129,8 +131,14 @@ pub enum ValidationOutcome {
     PreflightResponseSignature(PreflightResponse),
     #[error(transparent)]
     PrevActionError(#[from] PrevActionError),
+    #[error("The action was weighed as {0:?}, but its zome weighs it as {1:?}")]
+    IncorrectWeight(RateWeight, RateWeight),
     #[error("StoreEntry should not be gossiped for private entries")]
     PrivateEntry,
+    #[error("The action overflows the rate limiting bucket {0}")]
+    RateLimitExceeded(RateBucketId),
+    #[error("The author key {0} was not valid when the action was authored: {1:?}")]
+    RevokedKey(AgentPubKey, KeyState),
     #[error("Update original EntryType: {0:?} doesn't match new EntryType {1:?}")]
     UpdateTypeMismatch(EntryType, EntryType),
     #[error("Signature {0:?} failed to verify for Action {1:?}")]
@@ -158,6 +166,8 @@ impl ValidationOutcome {
 pub enum PrevActionError {
     #[error("The previous action hash specified in an action doesn't match the actual previous action. Seq: {0}")]
     HashMismatch(u32),
+    #[error("The source chain was closed at action {0}")]
+    ChainClosed(ActionHash),
     #[error("Root of source chain must be Dna")]
     InvalidRoot,
     #[error("Root of source chain must have a timestamp greater than the Dna's origin_time")]
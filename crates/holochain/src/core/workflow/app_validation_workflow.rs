@@ -97,6 +97,8 @@ async fn app_validation_workflow_inner(
     let iter = sorted_ops.into_iter().map({
         let network = network.clone();
         let workspace = workspace.clone();
+        let conductor = conductor.clone();
+        let dna_hash = dna_hash.clone();
         move |so| {
             let network = network.clone();
             let conductor = conductor.clone();
@@ -108,6 +110,7 @@ async fn app_validation_workflow_inner(
                 let action = op.action();
                 let dependency = get_dependency(op_type, &action);
                 let op_light = op.to_light();
+                let signed_action = SignedAction(action.clone(), op.signature().clone());
 
                 // If this is agent activity, track it for the cache.
                 let activity = matches!(op_type, DhtOpType::RegisterAgentActivity).then(|| {
@@ -126,7 +129,7 @@ async fn app_validation_workflow_inner(
                     }
                     Err(e) => Err(e),
                 };
-                (op_hash, dependency, op_light, r, activity)
+                (op_hash, dependency, op_light, r, activity, signed_action)
             }
         }
     });
@@ -164,15 +167,16 @@ async fn app_validation_workflow_inner(
             "Committing {} ops",
             chunk.iter().map(|c| c.len()).sum::<usize>()
         );
-        let (t, a, r, activity) = workspace
+        let (t, a, r, activity, warrants) = workspace
             .dht_db
             .async_commit(move |txn| {
                 let mut total = 0;
                 let mut awaiting = 0;
                 let mut rejected = 0;
                 let mut agent_activity = Vec::new();
+                let mut warrants = Vec::new();
                 for outcome in chunk.into_iter().flatten() {
-                    let (op_hash, dependency, op_light, outcome, activity) = outcome;
+                    let (op_hash, dependency, op_light, outcome, activity, signed_action) = outcome;
                     // Get the outcome or return the error
                     let outcome = outcome.or_else(|outcome_or_err| outcome_or_err.try_into())?;
 
@@ -207,7 +211,13 @@ async fn app_validation_workflow_inner(
                         }
                         Outcome::Rejected(_) => {
                             rejected += 1;
-                            tracing::warn!("Received invalid op! Warrants aren't implemented yet, so we can't do anything about this right now, but be warned that somebody on the network has maliciously hacked their node.\nOp: {:?}", op_light);
+                            tracing::warn!("Received invalid op! Issuing a warrant against its author.\nOp: {:?}", op_light);
+                            warrants.push(WarrantProof::ChainIntegrity(
+                                ChainIntegrityWarrant::InvalidChainOp {
+                                    action: signed_action,
+                                    validation_type: ValidationType::App,
+                                },
+                            ));
                             if let Dependency::Null = dependency {
                                 put_integrated(txn, &op_hash, ValidationStatus::Rejected)?;
                             } else {
@@ -216,9 +226,12 @@ async fn app_validation_workflow_inner(
                         }
                     }
                 }
-                WorkflowResult::Ok((total, awaiting, rejected, agent_activity))
+                WorkflowResult::Ok((total, awaiting, rejected, agent_activity, warrants))
             })
             .await?;
+        if let Err(e) = conductor.issue_warrants(&dna_hash, warrants).await {
+            tracing::error!(?e, "Failed to issue warrants for invalid ops");
+        }
         // Once the database transaction is committed, add agent activity to the cache
         // that is ready for integration.
         for (author, seq, has_no_dependency) in activity {
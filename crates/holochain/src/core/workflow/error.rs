@@ -51,6 +51,9 @@ pub enum WorkflowError {
     #[error("Capability token missing")]
     CapabilityMissing,
 
+    #[error("The source chain has already been migrated to DNA {0}")]
+    ChainClosed(DnaHash),
+
     #[error(transparent)]
     SerializedBytesError(#[from] SerializedBytesError),
 
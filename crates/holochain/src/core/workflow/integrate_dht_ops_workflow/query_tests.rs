@@ -105,6 +105,7 @@ async fn integrate_query() {
     integrate_dht_ops_workflow(
         db.to_db().into(),
         &db.to_db().into(),
+        &Default::default(),
         qt,
         holochain_p2p_cell,
     )
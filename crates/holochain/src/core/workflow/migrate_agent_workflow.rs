@@ -0,0 +1,173 @@
+//! Migrate Agent Workflow: Move an agent's source chain to another DNA.
+//!
+//! The `migrate_agent` callback is run on both chains first. Only if every
+//! zome on both sides passes is the old chain closed with a [`CloseChain`]
+//! action pointing to the new DNA, and the new chain opened with an
+//! [`OpenChain`] action pointing back to the old one.
+//!
+//! Either step which has already been done is skipped, so a migration which
+//! failed part way through can be retried.
+
+use super::error::WorkflowError;
+use super::error::WorkflowResult;
+use super::inline_validation;
+use crate::conductor::ConductorHandle;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentHostAccess;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation;
+use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentResult;
+use crate::core::ribosome::RibosomeT;
+use holochain_p2p::HolochainP2pDna;
+use holochain_state::host_fn_workspace::HostFnWorkspace;
+use holochain_state::host_fn_workspace::SourceChainWorkspace;
+use holochain_types::prelude::*;
+use holochain_zome_types::action::builder;
+
+/// One of the two chains taking part in a migration.
+pub struct MigrateAgentChain<Ribosome>
+where
+    Ribosome: RibosomeT + 'static,
+{
+    pub workspace: SourceChainWorkspace,
+    pub network: HolochainP2pDna,
+    pub ribosome: Ribosome,
+}
+
+pub struct MigrateAgentWorkflowArgs<Ribosome>
+where
+    Ribosome: RibosomeT + 'static,
+{
+    pub conductor_handle: ConductorHandle,
+    /// The chain being closed.
+    pub old: MigrateAgentChain<Ribosome>,
+    /// The chain being opened.
+    pub new: MigrateAgentChain<Ribosome>,
+}
+
+pub async fn migrate_agent_workflow<Ribosome>(
+    args: MigrateAgentWorkflowArgs<Ribosome>,
+) -> WorkflowResult<MigrateAgentResult>
+where
+    Ribosome: RibosomeT + Clone + 'static,
+{
+    let MigrateAgentWorkflowArgs {
+        conductor_handle,
+        old,
+        new,
+    } = args;
+    let old_dna_hash = old.ribosome.dna_def().as_hash().clone();
+    let new_dna_hash = new.ribosome.dna_def().as_hash().clone();
+
+    let needs_close = match closed_to(&old.workspace).await? {
+        None => true,
+        Some(dna_hash) if dna_hash == new_dna_hash => false,
+        Some(dna_hash) => return Err(WorkflowError::ChainClosed(dna_hash)),
+    };
+    let needs_open = !opened_from(&new.workspace, &old_dna_hash).await?;
+
+    // Every zome on both sides must agree before either chain is touched.
+    if needs_close {
+        let result = run_callback(&old, MigrateAgent::Close)?;
+        if result != MigrateAgentResult::Pass {
+            return Ok(result);
+        }
+    }
+    if needs_open {
+        let result = run_callback(&new, MigrateAgent::Open)?;
+        if result != MigrateAgentResult::Pass {
+            return Ok(result);
+        }
+    }
+
+    // --- END OF WORKFLOW, BEGIN FINISHER BOILERPLATE ---
+
+    if needs_close {
+        old.workspace
+            .source_chain()
+            .put(
+                builder::CloseChain { new_dna_hash },
+                None,
+                ChainTopOrdering::Strict,
+            )
+            .await?;
+        commit(old, conductor_handle.clone()).await?;
+    }
+    if needs_open {
+        new.workspace
+            .source_chain()
+            .put(
+                builder::OpenChain {
+                    prev_dna_hash: old_dna_hash,
+                },
+                None,
+                ChainTopOrdering::Strict,
+            )
+            .await?;
+        commit(new, conductor_handle).await?;
+    }
+    Ok(MigrateAgentResult::Pass)
+}
+
+fn run_callback<Ribosome>(
+    chain: &MigrateAgentChain<Ribosome>,
+    migrate_agent: MigrateAgent,
+) -> WorkflowResult<MigrateAgentResult>
+where
+    Ribosome: RibosomeT + 'static,
+{
+    let host_access = MigrateAgentHostAccess::new(chain.workspace.clone().into());
+    let invocation =
+        MigrateAgentInvocation::new(chain.ribosome.dna_def().content.clone(), migrate_agent);
+    Ok(chain.ribosome.run_migrate_agent(host_access, invocation)?)
+}
+
+async fn commit<Ribosome>(
+    chain: MigrateAgentChain<Ribosome>,
+    conductor_handle: ConductorHandle,
+) -> WorkflowResult<()>
+where
+    Ribosome: RibosomeT + Clone + 'static,
+{
+    let MigrateAgentChain {
+        workspace,
+        network,
+        ribosome,
+    } = chain;
+    inline_validation(
+        workspace.clone(),
+        network.clone(),
+        conductor_handle,
+        ribosome,
+    )
+    .await?;
+    HostFnWorkspace::from(workspace).flush(&network).await?;
+    Ok(())
+}
+
+/// The DNA this chain has been migrated to, if it has been closed.
+async fn closed_to(workspace: &SourceChainWorkspace) -> WorkflowResult<Option<DnaHash>> {
+    let closed = workspace
+        .source_chain()
+        .query(ChainQueryFilter::new().action_type(ActionType::CloseChain))
+        .await?;
+    Ok(closed.into_iter().find_map(|record| match record.action() {
+        Action::CloseChain(CloseChain { new_dna_hash, .. }) => Some(new_dna_hash.clone()),
+        _ => None,
+    }))
+}
+
+/// Whether this chain has already been opened as a migration from the given DNA.
+async fn opened_from(
+    workspace: &SourceChainWorkspace,
+    prev_dna_hash: &DnaHash,
+) -> WorkflowResult<bool> {
+    let opened = workspace
+        .source_chain()
+        .query(ChainQueryFilter::new().action_type(ActionType::OpenChain))
+        .await?;
+    Ok(opened.iter().any(|record| {
+        matches!(
+            record.action(),
+            Action::OpenChain(OpenChain { prev_dna_hash: h, .. }) if h == prev_dna_hash
+        )
+    }))
+}
@@ -92,6 +92,7 @@ async fn sys_validation_workflow_inner(
     // Process each op
     let iter = sorted_ops.into_iter().map({
         let space = space.clone();
+        let conductor_handle = conductor_handle.clone();
         move |so| {
             // Create an incoming ops sender for any dependencies we find
             // that we are meant to be holding but aren't.
@@ -116,7 +117,26 @@ async fn sys_validation_workflow_inner(
                     Some(incoming_dht_ops_sender),
                 )
                 .await;
-                r.map(|o| (op_hash, o, dependency))
+
+                // An otherwise valid action may still fork its author's chain.
+                let fork = match (&op, &r) {
+                    (DhtOp::RegisterAgentActivity(signature, action), Ok(Outcome::Accepted)) => {
+                        match workspace.find_chain_fork(action).await {
+                            Ok(fork) => fork.map(|other| {
+                                WarrantProof::ChainIntegrity(ChainIntegrityWarrant::chain_fork(
+                                    SignedAction(action.clone(), signature.clone()),
+                                    other,
+                                ))
+                            }),
+                            Err(e) => {
+                                tracing::error!(?e, "Failed to check for a chain fork");
+                                None
+                            }
+                        }
+                    }
+                    _ => None,
+                };
+                r.map(|o| (op_hash, o, dependency, fork))
             }
         }
     });
@@ -153,15 +173,17 @@ async fn sys_validation_workflow_inner(
     while let Some(chunk) = iter.next().await {
         let num_ops: usize = chunk.iter().map(|c| c.len()).sum();
         tracing::debug!("Committing {} ops", num_ops);
-        let (t, a, m, r) = space
+        let (t, a, m, r, forks) = space
             .dht_db
             .async_commit(move |txn| {
                 let mut total = 0;
                 let mut awaiting = 0;
                 let mut missing = 0;
                 let mut rejected = 0;
+                let mut forks = Vec::new();
                 for outcome in chunk.into_iter().flatten() {
-                    let (op_hash, outcome, dependency) = outcome?;
+                    let (op_hash, outcome, dependency, fork) = outcome?;
+                    forks.extend(fork);
                     match outcome {
                         Outcome::Accepted => {
                             total += 1;
@@ -200,9 +222,15 @@ async fn sys_validation_workflow_inner(
                         }
                     }
                 }
-                WorkflowResult::Ok((total, awaiting, missing, rejected))
+                WorkflowResult::Ok((total, awaiting, missing, rejected, forks))
             })
             .await?;
+        if let Err(e) = conductor_handle
+            .issue_warrants(&space.dna_hash, forks)
+            .await
+        {
+            tracing::error!(?e, "Failed to issue warrants for chain forks");
+        }
 
         total += t;
         if let (Some(start), Some(round_time)) = (start, &mut round_time) {
@@ -280,6 +308,7 @@ fn handle_failed(error: ValidationOutcome) -> Outcome {
         }
         ValidationOutcome::ActionNotInCounterSigningSession(_, _) => Rejected,
         ValidationOutcome::DepMissingFromDht(_) => MissingDhtDep,
+        ValidationOutcome::DpkiUnavailable(_) => MissingDhtDep,
         ValidationOutcome::EntryDefId(_) => Rejected,
         ValidationOutcome::EntryHash => Rejected,
         ValidationOutcome::EntryTooLarge(_, _) => Rejected,
@@ -293,7 +322,10 @@ fn handle_failed(error: ValidationOutcome) -> Outcome {
             AwaitingOpDep(dep.into())
         }
         ValidationOutcome::PrevActionError(_) => Rejected,
+        ValidationOutcome::IncorrectWeight(_, _) => Rejected,
         ValidationOutcome::PrivateEntry => Rejected,
+        ValidationOutcome::RateLimitExceeded(_) => Rejected,
+        ValidationOutcome::RevokedKey(_, _) => Rejected,
         ValidationOutcome::PreflightResponseSignature(_) => Rejected,
         ValidationOutcome::UpdateTypeMismatch(_, _) => Rejected,
         ValidationOutcome::VerifySignature(_, _) => Rejected,
@@ -309,9 +341,18 @@ async fn validate_op_inner(
     conductor_handle: &Conductor,
     incoming_dht_ops_sender: Option<IncomingDhtOpSender>,
 ) -> SysValidationResult<()> {
+    author_key_is_valid(&op.action(), &network.dna_hash(), conductor_handle).await?;
     match op {
         DhtOp::StoreRecord(_, action, entry) => {
             store_record(action, workspace, network.clone()).await?;
+            check_weight(
+                action,
+                entry.as_deref(),
+                workspace,
+                network.clone(),
+                conductor_handle,
+            )
+            .await?;
             if let Some(entry) = entry {
                 // Retrieve for all other actions on countersigned entry.
                 if let Entry::CounterSign(session_data, _) = &**entry {
@@ -377,12 +418,26 @@ async fn validate_op_inner(
             .await?;
 
             let action = action.clone().into();
-            store_record(&action, workspace, network).await?;
+            store_record(&action, workspace, network.clone()).await?;
+            check_weight(
+                &action,
+                Some(entry),
+                workspace,
+                network.clone(),
+                conductor_handle,
+            )
+            .await?;
             Ok(())
         }
         DhtOp::RegisterAgentActivity(_, action) => {
-            register_agent_activity(action, workspace, network.clone(), incoming_dht_ops_sender)
-                .await?;
+            register_agent_activity(
+                action,
+                workspace,
+                network.clone(),
+                conductor_handle,
+                incoming_dht_ops_sender,
+            )
+            .await?;
             store_record(action, workspace, network).await?;
             Ok(())
         }
@@ -423,11 +478,27 @@ async fn validate_op_inner(
             Ok(())
         }
         DhtOp::RegisterDeletedEntryAction(_, action) => {
+            check_weight(
+                &action.clone().into(),
+                None,
+                workspace,
+                network.clone(),
+                conductor_handle,
+            )
+            .await?;
             register_deleted_entry_action(action, workspace, network, incoming_dht_ops_sender)
                 .await?;
             Ok(())
         }
         DhtOp::RegisterAddLink(_, action) => {
+            check_weight(
+                &action.clone().into(),
+                None,
+                workspace,
+                network.clone(),
+                conductor_handle,
+            )
+            .await?;
             register_add_link(action, workspace, network, incoming_dht_ops_sender).await?;
             Ok(())
         }
@@ -479,6 +550,7 @@ async fn sys_validate_record_inner(
     let action = record.action();
     let maybe_entry = record.entry().as_option();
     counterfeit_check(signature, action).await?;
+    author_key_is_valid(action, &network.dna_hash(), conductor_handle).await?;
 
     async fn validate(
         action: &Action,
@@ -489,6 +561,7 @@ async fn sys_validate_record_inner(
     ) -> SysValidationResult<()> {
         let incoming_dht_ops_sender = None;
         store_record(action, workspace, network.clone()).await?;
+        check_rate_limits(action, &network.dna_hash(), workspace, conductor_handle).await?;
         if let Some((maybe_entry, EntryVisibility::Public)) =
             &maybe_entry.and_then(|e| action.entry_type().map(|et| (e, et.visibility())))
         {
@@ -551,7 +624,6 @@ async fn sys_validate_record_inner(
 /// Ops that fail this check should be dropped.
 pub async fn counterfeit_check(signature: &Signature, action: &Action) -> SysValidationResult<()> {
     verify_action_signature(signature, action).await?;
-    author_key_is_valid(action.author()).await?;
     Ok(())
 }
 
@@ -559,6 +631,7 @@ async fn register_agent_activity(
     action: &Action,
     workspace: &SysValidationWorkspace,
     network: HolochainP2pDna,
+    conductor_handle: &Conductor,
     incoming_dht_ops_sender: Option<IncomingDhtOpSender>,
 ) -> SysValidationResult<()> {
     // Get data ready to validate
@@ -571,13 +644,14 @@ async fn register_agent_activity(
         check_and_hold_register_agent_activity(
             prev_action_hash,
             workspace,
-            network,
+            network.clone(),
             incoming_dht_ops_sender,
-            |_| Ok(()),
+            |prev_record| check_prev_not_closed(prev_record.signed_action()),
         )
         .await?;
     }
     check_chain_rollback(action, workspace).await?;
+    check_rate_limits(action, &network.dna_hash(), workspace, conductor_handle).await?;
     Ok(())
 }
 
@@ -599,6 +673,7 @@ async fn store_record(
             .ok_or_else(|| ValidationOutcome::DepMissingFromDht(prev_action_hash.clone().into()))?;
         check_prev_timestamp(action, prev_action.action())?;
         check_prev_seq(action, prev_action.action())?;
+        check_prev_not_closed(&prev_action)?;
     }
     Ok(())
 }
@@ -900,6 +975,93 @@ impl SysValidationWorkspace {
         };
         Ok(!action_seq_is_not_empty)
     }
+
+    /// Find another action held by this authority which forks the chain of
+    /// this action's author, by the same rule that authorities receiving the
+    /// warrant will check it with.
+    pub async fn find_chain_fork(
+        &self,
+        action: &Action,
+    ) -> SourceChainResult<Option<SignedAction>> {
+        let author = action.author().clone();
+        let seq = action.action_seq();
+        let hash = ActionHash::with_data_sync(action);
+        let candidates = self
+            .dht_db
+            .async_reader(move |txn| {
+                let mut stmt = txn.prepare(
+                    "
+                    SELECT blob FROM Action
+                    WHERE
+                    Action.author = :author
+                    AND
+                    Action.seq = :seq
+                    AND
+                    Action.hash != :hash
+                    ",
+                )?;
+                let rows = stmt.query_and_then(
+                    named_params! {
+                        ":author": author,
+                        ":seq": seq,
+                        ":hash": hash,
+                    },
+                    |row| from_blob::<SignedAction>(row.get("blob")?),
+                )?;
+                rows.collect::<StateQueryResult<Vec<_>>>()
+            })
+            .await?;
+        Ok(candidates.into_iter().find(|other| {
+            holochain_state::warrant::is_chain_fork(self.dna_hash(), action, other.action())
+        }))
+    }
+
+    /// The actions authored before this action in the same chain, in chain
+    /// order, going back no further than actions authored at `since`.
+    ///
+    /// The history is followed back through each action's `prev_action`, so
+    /// actions of forks are never counted. If an action in the history isn't
+    /// held, its hash is returned instead, because a partial history would
+    /// make different authorities reach different verdicts.
+    pub async fn chain_history_since(
+        &self,
+        action: &Action,
+        since: Option<Timestamp>,
+    ) -> SourceChainResult<Result<Vec<Action>, ActionHash>> {
+        let author = action.author().clone();
+        let mut walk = ChainWalk::new(action, since);
+        let walk = match &self.scratch {
+            // This is an author node, so the chain is in the authored db
+            // and the scratch.
+            Some(scratch) => {
+                let mut newest_first = scratch.apply(|scratch| {
+                    scratch
+                        .actions()
+                        .filter(|shh| *shh.action().author() == author)
+                        .map(|shh| (shh.action_address().clone(), shh.action().clone()))
+                        .collect::<Vec<_>>()
+                })?;
+                newest_first.sort_by_key(|(_, a)| std::cmp::Reverse(a.action_seq()));
+                if newest_first
+                    .into_iter()
+                    .all(|(hash, action)| walk.visit(hash, action))
+                {
+                    self.authored_db
+                        .async_reader(move |txn| query_chain_history(&txn, author, walk))
+                        .await?
+                } else {
+                    walk
+                }
+            }
+            None => {
+                self.dht_db
+                    .async_reader(move |txn| query_chain_history(&txn, author, walk))
+                    .await?
+            }
+        };
+        Ok(walk.finish())
+    }
+
     /// Create a cascade with local data only
     pub fn local_cascade(&self) -> Cascade {
         let cascade = Cascade::empty().with_dht(self.dht_db.clone());
@@ -935,6 +1097,101 @@ impl SysValidationWorkspace {
     }
 }
 
+/// Follows a chain back from an action through the `prev_action` of each
+/// action, given the actions of its author newest first.
+struct ChainWalk {
+    /// The hash and seq of the next action to find, if the walk isn't done.
+    next: Option<(ActionHash, u32)>,
+    since: Option<Timestamp>,
+    history: Vec<Action>,
+}
+
+impl ChainWalk {
+    fn new(action: &Action, since: Option<Timestamp>) -> Self {
+        Self {
+            next: action
+                .prev_action()
+                .map(|prev| (prev.clone(), action.action_seq().saturating_sub(1))),
+            since,
+            history: Vec::new(),
+        }
+    }
+
+    /// Visit the next action of the author, returning whether the walk
+    /// needs older actions to continue.
+    fn visit(&mut self, hash: ActionHash, action: Action) -> bool {
+        let (next_hash, next_seq) = match &self.next {
+            Some(next) => next,
+            None => return false,
+        };
+        if action.action_seq() > *next_seq
+            || (action.action_seq() == *next_seq && hash != *next_hash)
+        {
+            // A later action, or a fork of the one we're looking for
+            return true;
+        }
+        if action.action_seq() < *next_seq {
+            // The next action isn't held
+            return false;
+        }
+        if self.since.map_or(false, |since| action.timestamp() < since) {
+            self.next = None;
+            return false;
+        }
+        self.next = action
+            .prev_action()
+            .map(|prev| (prev.clone(), action.action_seq().saturating_sub(1)));
+        self.history.push(action);
+        self.next.is_some()
+    }
+
+    /// The history in chain order, or the hash of the first action which
+    /// is missing from it.
+    fn finish(self) -> Result<Vec<Action>, ActionHash> {
+        match self.next {
+            Some((missing, _)) => Err(missing),
+            None => {
+                let mut history = self.history;
+                history.reverse();
+                Ok(history)
+            }
+        }
+    }
+}
+
+/// Continue the walk through the actions of the author in the database.
+fn query_chain_history(
+    txn: &Transaction,
+    author: AgentPubKey,
+    mut walk: ChainWalk,
+) -> StateQueryResult<ChainWalk> {
+    let seq = match &walk.next {
+        Some((_, seq)) => *seq,
+        None => return Ok(walk),
+    };
+    let mut stmt = txn.prepare(
+        "
+        SELECT hash, blob FROM Action
+        WHERE
+        Action.author = :author
+        AND
+        Action.seq <= :seq
+        ORDER BY Action.seq DESC
+        ",
+    )?;
+    let mut rows = stmt.query(named_params! {
+        ":author": author,
+        ":seq": seq,
+    })?;
+    while let Some(row) = rows.next()? {
+        let (action, _) = from_blob::<SignedAction>(row.get("blob")?)?.into();
+        if !walk.visit(row.get("hash")?, action) {
+            break;
+        }
+    }
+    Ok(walk)
+}
+
 fn put_validation_limbo(
     txn: &mut Transaction<'_>,
     hash: &DhtOpHash,
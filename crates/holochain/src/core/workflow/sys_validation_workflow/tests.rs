@@ -1,4 +1,9 @@
+use super::types::Outcome;
+use super::SysValidationWorkspace;
+use crate::conductor::api::error::ConductorApiResult;
 use crate::holochain_wasmer_host::prelude::*;
+use crate::sweettest::SweetAgents;
+use crate::sweettest::SweetConductor;
 use crate::sweettest::SweetConductorBatch;
 use crate::sweettest::SweetDnaFile;
 use crate::test_utils::host_fn_caller::*;
@@ -9,9 +14,12 @@ use hdk::prelude::LinkTag;
 use holo_hash::ActionHash;
 use holo_hash::AnyDhtHash;
 use holo_hash::EntryHash;
+use holochain_p2p::actor::HolochainP2pRefToDna;
 use holochain_state::prelude::fresh_reader_test;
 use holochain_state::prelude::from_blob;
 use holochain_state::prelude::StateQueryResult;
+use holochain_types::db_cache::DhtDbQueryCache;
+use holochain_types::inline_zome::InlineZomeSet;
 use holochain_types::prelude::*;
 use holochain_wasm_test_utils::TestWasm;
 use holochain_zome_types::cell::CellId;
@@ -21,6 +29,7 @@ use rusqlite::named_params;
 use rusqlite::Transaction;
 use std::convert::TryFrom;
 use std::convert::TryInto;
+use std::sync::Arc;
 use std::time::Duration;
 
 #[tokio::test(flavor = "multi_thread")]
@@ -43,6 +52,192 @@ async fn sys_validation_workflow_test() {
     run_test(alice_cell_id, bob_cell_id, conductors, dna_file).await;
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn rate_limited_actions_are_rejected() {
+    observability::test_run().ok();
+    let zomes = InlineZomeSet::new_unique_single(
+        "integrity",
+        "coordinator",
+        vec![EntryDef::from_id("unit")],
+        0,
+    )
+    .function("integrity", "rate_limits", |_api, ()| {
+        // Three units, draining far slower than the test runs.
+        let limit = RateLimit {
+            capacity: 3,
+            drain_amount: 1,
+            drain_interval_ms: 1_000_000,
+        };
+        Ok(RateLimitsCallbackResult::Limits(
+            [(0, limit)].into_iter().collect(),
+        ))
+    })
+    .function("integrity", "weigh", |_api, _: WeighInput| {
+        Ok(WeighCallbackResult::Weight(RateWeight {
+            bucket_id: 0,
+            units: 1,
+        }))
+    })
+    .function("coordinator", "create", |api, ()| {
+        let entry = Entry::app(().try_into().unwrap()).unwrap();
+        let hash = api.create(CreateInput::new(
+            InlineZomeSet::get_entry_location(&api, EntryDefIndex(0)),
+            EntryVisibility::Public,
+            entry,
+            ChainTopOrdering::default(),
+        ))?;
+        Ok(hash)
+    });
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(zomes).await;
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let app = conductor.setup_app("app", [&dna_file]).await.unwrap();
+    let (cell,) = app.into_tuple();
+    let zome = cell.zome("coordinator");
+
+    for _ in 0..3 {
+        let _: ActionHash = conductor.call(&zome, "create", ()).await;
+    }
+    let result: ConductorApiResult<ActionHash> = conductor.call_fallible(&zome, "create", ()).await;
+    let error = result.unwrap_err().to_string();
+    assert!(
+        error.contains("overflows the rate limiting bucket 0"),
+        "{}",
+        error
+    );
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn activity_after_a_closed_chain_is_rejected() {
+    observability::test_run().ok();
+    let zomes = || {
+        InlineZomeSet::new_unique_single("integrity", "coordinator", vec![], 0).function(
+            "coordinator",
+            "query",
+            |api, ()| Ok(api.query(ChainQueryFilter::new().action_type(ActionType::CloseChain))?),
+        )
+    };
+    let (old_dna, _, _) = SweetDnaFile::unique_from_inline_zomes(zomes()).await;
+    let (new_dna, _, _) = SweetDnaFile::unique_from_inline_zomes(zomes()).await;
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let agent = SweetAgents::one(conductor.keystore()).await;
+    let mut cells = Vec::new();
+    for (app_id, dna) in [("old", &old_dna), ("new", &new_dna)] {
+        let app = conductor
+            .setup_app_for_agent(app_id, agent.clone(), [dna])
+            .await
+            .unwrap();
+        let (cell,) = app.into_tuple();
+        cells.push(cell);
+    }
+    let old_cell = &cells[0];
+    conductor
+        .raw_handle()
+        .migrate_agent(MigrateAgentPayload {
+            cell_id: old_cell.cell_id().clone(),
+            new_dna_hash: new_dna.dna_hash().clone(),
+        })
+        .await
+        .unwrap();
+    let closed: Vec<Record> = conductor
+        .call(&old_cell.zome("coordinator"), "query", ())
+        .await;
+    let close = closed[0].signed_action();
+
+    // The agent keeps writing to the old chain after closing it
+    let action = Action::AgentValidationPkg(AgentValidationPkg {
+        author: agent.clone(),
+        timestamp: Timestamp::now(),
+        action_seq: close.action().action_seq() + 1,
+        prev_action: close.as_hash().clone(),
+        membrane_proof: None,
+    });
+    let keystore = conductor.keystore();
+    let signature = agent.sign(&keystore, &action).await.unwrap();
+    let op = DhtOp::RegisterAgentActivity(signature, action);
+
+    let dna_hash = old_dna.dna_hash();
+    let space = conductor.get_or_create_space(dna_hash).unwrap();
+    let workspace = SysValidationWorkspace::new(
+        space.authored_db.into(),
+        space.dht_db.into(),
+        space.dht_query_cache,
+        space.cache_db,
+        Arc::new(old_dna.dna_def().clone()),
+    );
+    let network = conductor.holochain_p2p().to_dna(dna_hash.clone(), None);
+    let outcome = super::validate_op(&op, &workspace, network, &conductor.raw_handle(), None)
+        .await
+        .unwrap();
+    matches::assert_matches!(outcome, Outcome::Rejected);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn rate_limit_history_must_be_complete() {
+    let authored_db = holochain_state::prelude::test_authored_db();
+    let dht_db = holochain_state::prelude::test_dht_db();
+    let cache_db = holochain_state::prelude::test_cache_db();
+    let workspace = SysValidationWorkspace::new(
+        authored_db.to_db().into(),
+        dht_db.to_db().into(),
+        DhtDbQueryCache::new(dht_db.to_db().into()),
+        cache_db.to_db(),
+        Arc::new(fixt!(DnaDef)),
+    );
+    let author = fixt!(AgentPubKey);
+    let signed = |action| {
+        SignedActionHashed::with_presigned(
+            ActionHashed::from_content_sync(action),
+            fixt!(Signature),
+        )
+    };
+    let create = |seq, prev_action: &ActionHash| {
+        let mut create = fixt!(Create);
+        create.author = author.clone();
+        create.action_seq = seq;
+        create.prev_action = prev_action.clone();
+        signed(Action::Create(create))
+    };
+    let mut dna = fixt!(Dna);
+    dna.author = author.clone();
+    let mut chain = vec![signed(Action::Dna(dna))];
+    for seq in 1..4 {
+        let prev_action = chain.last().unwrap().as_hash().clone();
+        chain.push(create(seq, &prev_action));
+    }
+    // A fork of the chain at seq 2, which must not be counted
+    let fork = create(2, chain[0].as_hash());
+    let insert = |actions: Vec<SignedActionHashed>| {
+        dht_db.to_db().test_commit(move |txn| {
+            for action in &actions {
+                holochain_state::prelude::insert_action(txn, action).unwrap();
+            }
+        })
+    };
+
+    // The authority is missing seq 1
+    insert(vec![
+        chain[0].clone(),
+        chain[2].clone(),
+        fork,
+        chain[3].clone(),
+    ]);
+    let history = workspace
+        .chain_history_since(chain[3].action(), None)
+        .await
+        .unwrap();
+    assert_eq!(history, Err(chain[1].as_hash().clone()));
+
+    insert(vec![chain[1].clone()]);
+    let history = workspace
+        .chain_history_since(chain[3].action(), None)
+        .await
+        .unwrap();
+    assert_eq!(
+        history,
+        Ok(chain[..3].iter().map(|a| a.action().clone()).collect())
+    );
+}
+
 async fn run_test(
     alice_cell_id: CellId,
     bob_cell_id: CellId,
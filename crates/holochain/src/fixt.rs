@@ -11,6 +11,8 @@ use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentHostAccess
 use crate::core::ribosome::guest_callback::migrate_agent::MigrateAgentInvocation;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitHostAccess;
 use crate::core::ribosome::guest_callback::post_commit::PostCommitInvocation;
+use crate::core::ribosome::guest_callback::rate_limits::RateLimitsHostAccess;
+use crate::core::ribosome::guest_callback::rate_limits::RateLimitsInvocation;
 use crate::core::ribosome::guest_callback::validate::ValidateHostAccess;
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageHostAccess;
 use crate::core::ribosome::guest_callback::validation_package::ValidationPackageInvocation;
@@ -388,6 +390,16 @@ fixturator!(
     constructor fn new(HostFnWorkspace);
 );
 
+fixturator!(
+    RateLimitsInvocation;
+    constructor fn new();
+);
+
+fixturator!(
+    RateLimitsHostAccess;
+    constructor fn new();
+);
+
 fixturator!(
     PostCommitInvocation;
     constructor fn new(CoordinatorZome, SignedActionHashedVec);
@@ -428,6 +440,7 @@ fixturator!(
         MigrateAgent(MigrateAgentHostAccess)
         ValidationPackage(ValidationPackageHostAccess)
         PostCommit(PostCommitHostAccess)
+        RateLimits(RateLimitsHostAccess)
     ];
 );
 
@@ -419,6 +419,15 @@ impl SweetConductor {
         }
     }
 
+    /// Change the config of this conductor while it is shut down, to take
+    /// effect when it is started up again.
+    pub fn update_config(&mut self, f: impl FnOnce(&mut ConductorConfig)) {
+        if self.handle.is_some() {
+            panic!("Attempted to update the config of a running conductor");
+        }
+        f(&mut self.config)
+    }
+
     /// Check if this conductor is running
     pub fn is_running(&self) -> bool {
         self.handle.is_some()
@@ -507,6 +516,21 @@ pub async fn websocket_client_by_port(
     .await
 }
 
+/// Connect to the app interface at this port and authenticate the connection
+/// with a token from [`AdminRequest::IssueAppAuthenticationToken`].
+///
+/// [`AdminRequest::IssueAppAuthenticationToken`]: holochain_conductor_api::AdminRequest::IssueAppAuthenticationToken
+pub async fn app_websocket_client_by_port(
+    port: u16,
+    token: holochain_conductor_api::AppAuthenticationToken,
+) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
+    let (mut sender, receiver) = websocket_client_by_port(port).await?;
+    sender
+        .signal(holochain_conductor_api::AppAuthenticationRequest { token })
+        .await?;
+    Ok((sender, receiver))
+}
+
 impl Drop for SweetConductor {
     fn drop(&mut self) {
         if let Some(handle) = self.handle.take() {
@@ -4,10 +4,32 @@ use ::fixt::prelude::StdRng;
 use futures::future;
 use hdk::prelude::*;
 use holochain_types::prelude::*;
+use kitsune_p2p::TransportConfig;
+use kitsune_p2p_types::tx2::{MemConfig, MemNetwork};
 
 /// A collection of SweetConductors, with methods for operating on the entire collection
-#[derive(derive_more::From, derive_more::Into, derive_more::IntoIterator)]
-pub struct SweetConductorBatch(Vec<SweetConductor>);
+pub struct SweetConductorBatch(Vec<SweetConductor>, Option<MemNetwork>);
+
+impl From<Vec<SweetConductor>> for SweetConductorBatch {
+    fn from(conductors: Vec<SweetConductor>) -> Self {
+        Self(conductors, None)
+    }
+}
+
+impl From<SweetConductorBatch> for Vec<SweetConductor> {
+    fn from(batch: SweetConductorBatch) -> Self {
+        batch.0
+    }
+}
+
+impl IntoIterator for SweetConductorBatch {
+    type Item = SweetConductor;
+    type IntoIter = std::vec::IntoIter<SweetConductor>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
 
 impl SweetConductorBatch {
     /// Map the given ConductorConfigs into SweetConductors, each with its own new TestEnvironments
@@ -32,6 +54,37 @@ impl SweetConductorBatch {
         Self::from_configs(std::iter::repeat_with(SweetConductorConfig::standard).take(num)).await
     }
 
+    /// Create the given number of new SweetConductors connected by a simulated
+    /// in-memory network, each with its own new TestEnvironments.
+    /// The network transport of the config is replaced; its tuning params are kept.
+    ///
+    /// The links between the conductors can then be controlled with
+    /// [`partition`](Self::partition), [`set_latency`](Self::set_latency)
+    /// and [`set_packet_loss`](Self::set_packet_loss).
+    pub async fn from_config_simulated_network<C: Clone + Into<ConductorConfig>>(
+        num: usize,
+        config: C,
+    ) -> SweetConductorBatch {
+        let network = MemNetwork::new();
+        let mut configs = Vec::with_capacity(num);
+        for _ in 0..num {
+            let (_, node) = network
+                .add_node(MemConfig::default())
+                .await
+                .expect("failed to add a node to the simulated network");
+            let mut config: ConductorConfig = config.clone().into();
+            let mut kitsune = config.network.take().unwrap_or_default();
+            kitsune.transport_pool = vec![TransportConfig::Mock {
+                mock_network: node.into(),
+            }];
+            config.network = Some(kitsune);
+            configs.push(config);
+        }
+        let mut batch = Self::from_configs(configs).await;
+        batch.1 = Some(network);
+        batch
+    }
+
     /// Iterate over the SweetConductors
     pub fn iter(&self) -> impl Iterator<Item = &SweetConductor> {
         self.0.iter()
@@ -153,6 +206,44 @@ impl SweetConductorBatch {
         crate::conductor::p2p_agent_store::reveal_peer_info(observer_envs, seen_envs).await;
     }
 
+    /// The simulated network connecting the conductors of this batch.
+    /// The node of each conductor has the conductor's index as its id.
+    ///
+    /// Panics if the batch wasn't created with
+    /// [`from_config_simulated_network`](Self::from_config_simulated_network).
+    pub fn simulated_network(&self) -> &MemNetwork {
+        self.1
+            .as_ref()
+            .expect("this batch was not created with a simulated network")
+    }
+
+    /// Partition the conductors into groups, by index, which can only reach
+    /// the conductors of their own group. Conductors not listed in any group
+    /// form one more group together.
+    pub fn partition(&self, groups: &[&[usize]]) {
+        self.simulated_network().partition(groups)
+    }
+
+    /// Remove the partition, so every conductor can reach every other again.
+    pub fn heal_partition(&self) {
+        self.simulated_network().heal()
+    }
+
+    /// Delay every message sent between two conductors by `latency`.
+    pub fn set_latency(&self, a: usize, b: usize, latency: std::time::Duration) {
+        self.simulated_network().set_latency(a, b, latency)
+    }
+
+    /// Drop each message sent between two conductors with the probability `loss`.
+    pub fn set_packet_loss(&self, a: usize, b: usize, loss: f64) {
+        self.simulated_network().set_packet_loss(a, b, loss)
+    }
+
+    /// The number of bytes a conductor has sent to the others in this batch.
+    pub fn bytes_sent(&self, i: usize) -> u64 {
+        self.simulated_network().bytes_sent(i)
+    }
+
     /// Force trigger all dht ops that haven't received
     /// enough validation receipts yet.
     pub async fn force_all_publish_dht_ops(&self) {
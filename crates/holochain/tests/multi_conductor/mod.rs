@@ -185,6 +185,139 @@ async fn sharded_consistency() {
     );
 }
 
+/// Test that conductors on the same machine find each other over mdns
+/// without exchanging peer info, and forget agents that leave.
+#[cfg(feature = "test_utils")]
+#[tokio::test(flavor = "multi_thread")]
+async fn mdns_discovery() -> anyhow::Result<()> {
+    use holochain::test_utils::{consistency_10s, inline_zomes::simple_create_read_zome};
+    use holochain_p2p::AgentPubKeyExt;
+    use holochain_types::app::DisabledAppReason;
+    use kitsune_p2p::KitsuneP2pConfig;
+
+    let _g = observability::test_run().ok();
+    const NUM_CONDUCTORS: usize = 2;
+
+    let mut network = KitsuneP2pConfig::default();
+    network.network_type = kitsune_p2p::NetworkType::QuicMdns;
+    network.transport_pool = vec![kitsune_p2p::TransportConfig::Quic {
+        bind_to: None,
+        override_host: None,
+        override_port: None,
+    }];
+    let config = ConductorConfig {
+        network: Some(network),
+        ..Default::default()
+    };
+    let mut conductors = SweetConductorBatch::from_config(NUM_CONDUCTORS, config).await;
+
+    let (dna_file, _, _) =
+        SweetDnaFile::unique_from_inline_zomes(("simple", simple_create_read_zome())).await;
+
+    let apps = conductors.setup_app("app", &[dna_file]).await.unwrap();
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    // Wait for each conductor to discover the other's agent.
+    let knows = |conductor: &SweetConductor, agent: AgentPubKey| {
+        let handle = conductor.raw_handle();
+        async move {
+            handle
+                .get_agent_infos(None)
+                .await
+                .unwrap()
+                .into_iter()
+                .any(|info| !info.url_list.is_empty() && *info.agent == *agent.to_kitsune())
+        }
+    };
+    tokio::time::timeout(std::time::Duration::from_secs(30), async {
+        while !knows(&conductors[0], bobbo.agent_pubkey().clone()).await
+            || !knows(&conductors[1], alice.agent_pubkey().clone()).await
+        {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .expect("conductors did not discover each other over mdns");
+
+    // Call the "create" zome fn on Alice's app
+    let hash: ActionHash = conductors[0]
+        .call(&alice.zome("simple"), "create", ())
+        .await;
+
+    // Wait long enough for Bob to receive gossip
+    consistency_10s([&alice, &bobbo]).await;
+
+    // Verify that bobbo can get alice's Action
+    let record: Option<Record> = conductors[1]
+        .call(&bobbo.zome("simple"), "read", hash)
+        .await;
+    let record = record.expect("Record was None: bobbo couldn't `get` it");
+    assert_eq!(record.action().author(), alice.agent_pubkey());
+
+    // When alice leaves, bobbo is told she has no urls anymore.
+    conductors[0]
+        .disable_app("app".into(), DisabledAppReason::User)
+        .await?;
+    tokio::time::timeout(std::time::Duration::from_secs(30), async {
+        while knows(&conductors[1], alice.agent_pubkey().clone()).await {
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        }
+    })
+    .await
+    .expect("bobbo did not see alice leave over mdns");
+
+    Ok(())
+}
+
+/// Test that conductors partitioned on a simulated network don't exchange
+/// any data, and become consistent once the partition is healed.
+#[cfg(feature = "test_utils")]
+#[tokio::test(flavor = "multi_thread")]
+async fn simulated_network_partition() {
+    use holochain::sweettest::SweetConductorConfig;
+    use holochain::test_utils::{consistency_10s, inline_zomes::simple_create_read_zome};
+
+    let _g = observability::test_run().ok();
+    const NUM_CONDUCTORS: usize = 2;
+
+    let mut conductors = SweetConductorBatch::from_config_simulated_network(
+        NUM_CONDUCTORS,
+        SweetConductorConfig::standard(),
+    )
+    .await;
+
+    let (dna_file, _, _) =
+        SweetDnaFile::unique_from_inline_zomes(("simple", simple_create_read_zome())).await;
+
+    let apps = conductors.setup_app("app", &[dna_file]).await.unwrap();
+    conductors.exchange_peer_info().await;
+
+    let ((alice,), (bobbo,)) = apps.into_tuples();
+
+    conductors.partition(&[&[0], &[1]]);
+    let sent = conductors.bytes_sent(0);
+
+    // Call the "create" zome fn on Alice's app
+    let hash: ActionHash = conductors[0]
+        .call(&alice.zome("simple"), "create", ())
+        .await;
+
+    // Nothing gets through while the conductors are partitioned
+    tokio::time::sleep(std::time::Duration::from_secs(2)).await;
+    assert_eq!(conductors.bytes_sent(0), sent);
+
+    conductors.heal_partition();
+    consistency_10s([&alice, &bobbo]).await;
+    assert!(conductors.bytes_sent(0) > sent);
+
+    // Verify that bobbo can run "read" on his cell and get alice's Action
+    let record: Option<Record> = conductors[1]
+        .call(&bobbo.zome("simple"), "read", hash)
+        .await;
+    let record = record.expect("Record was None: bobbo couldn't `get` it");
+    assert_eq!(record.action().author(), alice.agent_pubkey());
+}
+
 #[cfg(feature = "test_utils")]
 #[tokio::test(flavor = "multi_thread")]
 async fn private_entries_dont_leak() {
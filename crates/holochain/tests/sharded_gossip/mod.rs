@@ -488,6 +488,9 @@ async fn mock_network_sharded_gossip() {
                         holochain_p2p::WireMessage::ValidationReceipt { receipt: _ } => {
                             debug!("Validation Receipt")
                         }
+                        holochain_p2p::WireMessage::PublishWarrant { .. } => {
+                            debug!("PublishWarrant")
+                        }
                         holochain_p2p::WireMessage::Get { dht_hash, options } => {
                             let txn = conn
                                 .transaction_with_behavior(rusqlite::TransactionBehavior::Exclusive)
@@ -511,6 +514,7 @@ async fn mock_network_sharded_gossip() {
                         }
                         holochain_p2p::WireMessage::GetMeta { .. } => debug!("get_meta"),
                         holochain_p2p::WireMessage::GetLinks { .. } => debug!("get_links"),
+                        holochain_p2p::WireMessage::CountLinks { .. } => debug!("count_links"),
                         holochain_p2p::WireMessage::GetAgentActivity { .. } => {
                             debug!("get_agent_activity")
                         }
@@ -991,6 +995,9 @@ async fn mock_network_sharding() {
                         holochain_p2p::WireMessage::ValidationReceipt { receipt: _ } => {
                             debug!("Validation Receipt")
                         }
+                        holochain_p2p::WireMessage::PublishWarrant { .. } => {
+                            debug!("PublishWarrant")
+                        }
                         holochain_p2p::WireMessage::Get { dht_hash, options } => {
                             num_gets.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
                             let ops = if data.agent_to_arc[&agent].contains(dht_hash.get_loc()) {
@@ -1027,6 +1034,7 @@ async fn mock_network_sharding() {
                         }
                         holochain_p2p::WireMessage::GetMeta { .. } => debug!("get_meta"),
                         holochain_p2p::WireMessage::GetLinks { .. } => debug!("get_links"),
+                        holochain_p2p::WireMessage::CountLinks { .. } => debug!("count_links"),
                         holochain_p2p::WireMessage::GetAgentActivity { .. } => {
                             debug!("get_agent_activity")
                         }
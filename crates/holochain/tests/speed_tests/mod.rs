@@ -19,8 +19,6 @@ use std::sync::Arc;
 
 use ::fixt::prelude::*;
 use hdk::prelude::*;
-use holochain::conductor::api::AdminRequest;
-use holochain::conductor::api::AdminResponse;
 use holochain::conductor::api::AppRequest;
 use holochain::conductor::api::AppResponse;
 use holochain::conductor::api::ZomeCall;
@@ -177,14 +175,9 @@ async fn speed_test(n: Option<usize>) -> Arc<TempDir> {
 
     // Setup websocket handle and app interface
     let (mut client, _) = websocket_client(&handle).await.unwrap();
-    let request = AdminRequest::AttachAppInterface { port: None };
-    let response = client.request(request);
-    let response = response.await.unwrap();
-    let app_port = match response {
-        AdminResponse::AppInterfaceAttached { port } => port,
-        _ => panic!("Attach app interface failed: {:?}", response),
-    };
-    let (mut app_interface, _) = websocket_client_by_port(app_port).await.unwrap();
+    let app_port = attach_app_interface(&mut client, None).await;
+    let token = issue_app_authentication_token(&mut client, "test app").await;
+    let (mut app_interface, _) = app_websocket_client_by_port(app_port, token).await.unwrap();
 
     // /////////////
     // END CONDUCTOR
@@ -21,6 +21,7 @@ pub async fn websocket_client(
     Ok(websocket_client_by_port(port).await?)
 }
 
+pub use holochain::sweettest::app_websocket_client_by_port;
 pub use holochain::sweettest::websocket_client_by_port;
 
 use assert_cmd::prelude::*;
@@ -28,7 +29,10 @@ use futures::Future;
 use holochain_conductor_api::conductor::ConductorConfig;
 use holochain_conductor_api::conductor::KeystoreConfig;
 use holochain_conductor_api::AdminInterfaceConfig;
+use holochain_conductor_api::AllowedOrigins;
+use holochain_conductor_api::AppAuthenticationToken;
 use holochain_conductor_api::InterfaceDriver;
+use holochain_conductor_api::IssueAppAuthenticationTokenPayload;
 use matches::assert_matches;
 use serde::Serialize;
 use std::time::Duration;
@@ -158,7 +162,11 @@ pub async fn call_zome_fn<S>(
 }
 
 pub async fn attach_app_interface(client: &mut WebsocketSender, port: Option<u16>) -> u16 {
-    let request = AdminRequest::AttachAppInterface { port };
+    let request = AdminRequest::AttachAppInterface {
+        port,
+        unix_socket: None,
+        allowed_origins: AllowedOrigins::Any,
+    };
     let response = client.request(request);
     let response = check_timeout(response, 3000).await;
     match response {
@@ -167,6 +175,21 @@ pub async fn attach_app_interface(client: &mut WebsocketSender, port: Option<u16
     }
 }
 
+pub async fn issue_app_authentication_token(
+    client: &mut WebsocketSender,
+    installed_app_id: &str,
+) -> AppAuthenticationToken {
+    let request = AdminRequest::IssueAppAuthenticationToken(
+        IssueAppAuthenticationTokenPayload::for_installed_app_id(installed_app_id.into()),
+    );
+    let response = client.request(request);
+    let response = check_timeout(response, 3000).await;
+    match response {
+        AdminResponse::AppAuthenticationTokenIssued(issued) => issued.token,
+        _ => panic!("Issue app authentication token failed: {:?}", response),
+    }
+}
+
 pub async fn retry_admin_interface(
     port: u16,
     mut attempts: usize,
@@ -323,6 +346,8 @@ pub fn create_config(port: u16, environment_path: PathBuf) -> ConductorConfig {
         keystore: KeystoreConfig::DangerTestKeystore,
         db_sync_strategy: DbSyncStrategy::default(),
         chc_namespace: None,
+        block_warranted_agents: false,
+        metrics_endpoint: None,
     }
 }
 
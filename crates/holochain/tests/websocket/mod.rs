@@ -5,10 +5,13 @@ use hdk::prelude::RemoteSignal;
 use holochain::sweettest::SweetAgents;
 use holochain::sweettest::SweetConductor;
 use holochain::sweettest::SweetConductorBatch;
+use holochain::sweettest::SweetConductorConfig;
 use holochain::sweettest::SweetDnaFile;
+use holochain::test_utils::inline_zomes::simple_crud_zome;
 use holochain::{
     conductor::{
-        api::{AdminRequest, AdminResponse},
+        api::{AdminRequest, AdminResponse, AppRequest, AppResponse},
+        config::{AdminInterfaceConfig, InterfaceDriver},
         error::ConductorError,
         Conductor,
     },
@@ -471,6 +474,91 @@ async fn list_app_interfaces_succeeds() -> Result<()> {
     Ok(())
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn app_interface_requires_authentication() {
+    observability::test_run().ok();
+    let mut conductor = SweetConductor::from_standard_config().await;
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+    let (other_dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+    conductor.setup_app("app", [&dna_file]).await.unwrap();
+    conductor
+        .setup_app("other app", [&other_dna_file])
+        .await
+        .unwrap();
+    let (mut admin_tx, _) = conductor.admin_ws_client().await;
+    let app_port = attach_app_interface(&mut admin_tx, None).await;
+    let app_info = |installed_app_id: &str| AppRequest::AppInfo {
+        installed_app_id: installed_app_id.into(),
+    };
+
+    // An authenticated connection can only make requests for its app
+    let token = issue_app_authentication_token(&mut admin_tx, "app").await;
+    let (mut app_tx, _) = app_websocket_client_by_port(app_port, token.clone())
+        .await
+        .unwrap();
+    let response: AppResponse = app_tx.request(app_info("app")).await.unwrap();
+    assert_matches!(response, AppResponse::AppInfo(Some(_)));
+    let response: AppResponse = app_tx.request(app_info("other app")).await.unwrap();
+    assert_matches!(response, AppResponse::Error(_));
+
+    // The token has been used up
+    let (mut app_tx, _) = app_websocket_client_by_port(app_port, token).await.unwrap();
+    let response: WebsocketResult<AppResponse> = app_tx.request(app_info("app")).await;
+    assert!(response.is_err());
+
+    // A connection which doesn't authenticate first is closed
+    let (mut app_tx, _) = websocket_client_by_port(app_port).await.unwrap();
+    let response: WebsocketResult<AppResponse> = app_tx.request(app_info("app")).await;
+    assert!(response.is_err());
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn admin_and_app_interfaces_run_on_unix_sockets() -> Result<()> {
+    observability::test_run().ok();
+    let tmp_dir = TempDir::new().unwrap();
+    let admin_socket = tmp_dir.path().join("admin.sock");
+    let app_socket = tmp_dir.path().join("app.sock");
+    let mut config = SweetConductorConfig::standard();
+    config.admin_interfaces = Some(vec![AdminInterfaceConfig {
+        driver: InterfaceDriver::UnixSocket {
+            path: admin_socket.clone(),
+        },
+    }]);
+    let mut conductor = SweetConductor::from_config(config).await;
+    assert_eq!(conductor.get_arbitrary_admin_websocket_port(), None);
+    let (dna_file, _, _) = SweetDnaFile::unique_from_inline_zomes(simple_crud_zome()).await;
+    conductor.setup_app("app", [&dna_file]).await.unwrap();
+
+    let (mut admin_tx, _) =
+        connect_unix(&admin_socket, Arc::new(WebsocketConfig::default())).await?;
+    let response: AdminResponse = admin_tx
+        .request(AdminRequest::AttachAppInterface {
+            port: None,
+            unix_socket: Some(app_socket.clone()),
+            allowed_origins: Default::default(),
+        })
+        .await?;
+    assert_matches!(response, AdminResponse::AppInterfaceAttached { port: 0 });
+    // Only interfaces on ports are listed
+    let response: AdminResponse = admin_tx.request(AdminRequest::ListAppInterfaces).await?;
+    assert_matches!(response, AdminResponse::AppInterfacesListed(ports) if ports.is_empty());
+
+    let token = issue_app_authentication_token(&mut admin_tx, "app").await;
+    let (mut app_tx, _) = connect_unix(&app_socket, Arc::new(WebsocketConfig::default())).await?;
+    app_tx
+        .signal(holochain_conductor_api::AppAuthenticationRequest { token })
+        .await?;
+    let response: AppResponse = app_tx
+        .request(AppRequest::AppInfo {
+            installed_app_id: "app".into(),
+        })
+        .await?;
+    assert_matches!(response, AppResponse::AppInfo(Some(_)));
+
+    Ok(())
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn conductor_admin_interface_ends_with_shutdown() -> Result<()> {
     if let Err(e) = conductor_admin_interface_ends_with_shutdown_inner().await {
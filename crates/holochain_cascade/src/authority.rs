@@ -3,6 +3,7 @@
 use self::get_agent_activity_query::hashes::GetAgentActivityQuery;
 use self::get_agent_activity_query::must_get_agent_activity::must_get_agent_activity;
 use self::get_entry_ops_query::GetEntryOpsQuery;
+use self::get_link_hashes_ops_query::GetLinkHashesOpsQuery;
 use self::get_links_ops_query::GetLinksOpsQuery;
 use self::{
     get_agent_activity_query::deterministic::DeterministicGetAgentActivityQuery,
@@ -23,6 +24,7 @@ mod test;
 
 pub(crate) mod get_agent_activity_query;
 pub(crate) mod get_entry_ops_query;
+pub(crate) mod get_link_hashes_ops_query;
 pub(crate) mod get_links_ops_query;
 pub(crate) mod get_record_query;
 
@@ -62,9 +64,13 @@ pub async fn handle_get_agent_activity(
     query: ChainQueryFilter,
     options: holochain_p2p::event::GetActivityOptions,
 ) -> CascadeResult<AgentActivityResponse<ActionHash>> {
-    let query = GetAgentActivityQuery::new(agent, query, options);
+    let query = GetAgentActivityQuery::new(agent.clone(), query, options);
     let results = env
-        .async_reader(move |txn| query.run(Txn::from(&txn)))
+        .async_reader(move |txn| {
+            let mut response = query.run(Txn::from(&txn))?;
+            response.warrants = holochain_state::warrant::list_warrants(&txn, &agent)?;
+            holochain_state::query::StateQueryResult::Ok(response)
+        })
         .await?;
     Ok(results)
 }
@@ -107,3 +113,17 @@ pub async fn handle_get_links(
         .await?;
     Ok(results)
 }
+
+/// Handler for count_links query to a Record/Entry authority
+#[instrument(skip(env, _options))]
+pub async fn handle_count_links(
+    env: DbRead<DbKindDht>,
+    link_key: WireLinkKey,
+    _options: holochain_p2p::event::GetLinksOptions,
+) -> CascadeResult<WireLinkHashes> {
+    let query = GetLinkHashesOpsQuery::new(link_key);
+    let results = env
+        .async_reader(move |txn| query.run(Txn::from(&txn)))
+        .await?;
+    Ok(results)
+}
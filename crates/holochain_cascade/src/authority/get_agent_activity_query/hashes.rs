@@ -168,6 +168,8 @@ impl Query for GetAgentActivityQuery {
             rejected_activity,
             status,
             highest_observed,
+            // Warrants are not part of the chain, so they are added by the handler.
+            warrants: Vec::new(),
         })
     }
 }
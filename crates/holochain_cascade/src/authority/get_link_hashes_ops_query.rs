@@ -0,0 +1,106 @@
+use std::sync::Arc;
+
+use holo_hash::ActionHash;
+use holochain_sqlite::rusqlite::Row;
+use holochain_state::query::prelude::*;
+use holochain_state::query::StateQueryError;
+use holochain_types::dht_op::DhtOpType;
+use holochain_types::link::WireLinkHashes;
+use holochain_zome_types::HasValidationStatus;
+use holochain_zome_types::Judged;
+use holochain_zome_types::LinkCursor;
+use holochain_zome_types::Timestamp;
+use holochain_zome_types::ValidationStatus;
+
+use super::get_links_ops_query::GetLinksOpsQuery;
+use super::WireLinkKey;
+
+/// Get the hashes of the create links matching a key, and which of them
+/// are deleted, straight from the action table without reading the actions.
+#[derive(Debug, Clone)]
+pub struct GetLinkHashesOpsQuery(GetLinksOpsQuery);
+
+impl GetLinkHashesOpsQuery {
+    pub fn new(key: WireLinkKey) -> Self {
+        Self(GetLinksOpsQuery::new(key))
+    }
+}
+
+pub struct Item {
+    hash: ActionHash,
+    timestamp: Timestamp,
+    op_type: DhtOpType,
+}
+
+impl Query for GetLinkHashesOpsQuery {
+    type Item = Judged<Item>;
+    type State = WireLinkHashes;
+    type Output = Self::State;
+
+    fn query(&self) -> String {
+        self.0.query_with(
+            "
+            SELECT Action.hash AS hash, DhtOp.authored_timestamp AS timestamp,
+            DhtOp.type AS dht_type, DhtOp.validation_status AS status
+            ",
+            "
+            SELECT Action.create_link_hash AS hash, DhtOp.authored_timestamp AS timestamp,
+            DhtOp.type AS dht_type, DhtOp.validation_status AS status
+            ",
+        )
+    }
+
+    fn params(&self) -> Vec<Params> {
+        self.0.links_params()
+    }
+
+    fn as_map(&self) -> Arc<dyn Fn(&Row) -> StateQueryResult<Self::Item>> {
+        let f = |row: &Row| {
+            let hash = row.get(row.as_ref().column_index("hash")?)?;
+            let timestamp = row.get(row.as_ref().column_index("timestamp")?)?;
+            let op_type = row.get(row.as_ref().column_index("dht_type")?)?;
+            let validation_status = row.get(row.as_ref().column_index("status")?)?;
+            Ok(Judged::raw(
+                Item {
+                    hash,
+                    timestamp,
+                    op_type,
+                },
+                validation_status,
+            ))
+        };
+        Arc::new(f)
+    }
+
+    fn init_fold(&self) -> StateQueryResult<Self::State> {
+        Ok(WireLinkHashes::new())
+    }
+
+    fn fold(&self, mut state: Self::State, item: Self::Item) -> StateQueryResult<Self::State> {
+        // Only valid links are counted.
+        if item.validation_status() != Some(ValidationStatus::Valid) {
+            return Ok(state);
+        }
+        let Item {
+            hash,
+            timestamp,
+            op_type,
+        } = item.data;
+        match op_type {
+            DhtOpType::RegisterAddLink => state.creates.push(LinkCursor::new(timestamp, hash)),
+            DhtOpType::RegisterRemoveLink => state.deleted.push(hash),
+            _ => return Err(StateQueryError::UnexpectedOp(op_type)),
+        }
+        Ok(state)
+    }
+
+    fn render<S>(&self, mut state: Self::State, _stores: S) -> StateQueryResult<Self::Output>
+    where
+        S: Store,
+    {
+        state.creates.sort();
+        state.deleted.sort();
+        state.deleted.dedup();
+        Ok(state)
+    }
+}
@@ -1,5 +1,7 @@
 use std::sync::Arc;
 
+use holo_hash::ActionHash;
+use holo_hash::AgentPubKey;
 use holo_hash::AnyLinkableHash;
 use holochain_sqlite::rusqlite::named_params;
 use holochain_sqlite::rusqlite::Row;
@@ -16,6 +18,7 @@ use holochain_zome_types::Judged;
 use holochain_zome_types::LinkTag;
 use holochain_zome_types::LinkTypeFilter;
 use holochain_zome_types::SignedAction;
+use holochain_zome_types::Timestamp;
 
 use super::WireLinkKey;
 
@@ -24,6 +27,11 @@ pub struct GetLinksOpsQuery {
     base: Arc<AnyLinkableHash>,
     type_query: LinkTypeFilter,
     tag: Option<Arc<LinkTag>>,
+    author: Option<AgentPubKey>,
+    after: Option<Timestamp>,
+    before: Option<Timestamp>,
+    limit: Option<u32>,
+    cursor: Option<(Timestamp, ActionHash)>,
 }
 
 impl GetLinksOpsQuery {
@@ -32,6 +40,13 @@ impl GetLinksOpsQuery {
             base: Arc::new(key.base),
             type_query: key.type_query,
             tag: key.tag.map(Arc::new),
+            author: key.author,
+            after: key.after,
+            before: key.before,
+            limit: key.limit,
+            cursor: key
+                .cursor
+                .map(|c| (c.timestamp(), c.create_link_hash().clone())),
         }
     }
     pub fn tag_to_hex(tag: &LinkTag) -> String {
@@ -42,24 +57,11 @@ impl GetLinksOpsQuery {
         }
         s
     }
-}
 
-pub struct Item {
-    action: SignedAction,
-    op_type: DhtOpType,
-}
-
-impl Query for GetLinksOpsQuery {
-    type Item = Judged<Item>;
-    type State = WireLinkOps;
-    type Output = Self::State;
-
-    fn query(&self) -> String {
-        let create = "
-            SELECT Action.blob AS action_blob, DhtOp.type AS dht_type,
-            DhtOp.validation_status AS status
-            FROM DhtOp
-        ";
+    /// The query for the create links matching this key, along with their deletes,
+    /// selecting the given columns from each.
+    pub(super) fn query_with(&self, create_columns: &str, delete_columns: &str) -> String {
+        let create = format!("{} FROM DhtOp", create_columns);
         let sub_create = "
             SELECT Action.hash FROM DhtOp
         ";
@@ -84,6 +86,42 @@ impl Query for GetLinksOpsQuery {
                 common_query, tag
             );
         }
+        if self.author.is_some() {
+            common_query.push_str(
+                "
+                AND
+                Action.author = :author
+                ",
+            );
+        }
+        if self.after.is_some() {
+            common_query.push_str(
+                "
+                AND
+                DhtOp.authored_timestamp >= :after
+                ",
+            );
+        }
+        if self.before.is_some() {
+            common_query.push_str(
+                "
+                AND
+                DhtOp.authored_timestamp < :before
+                ",
+            );
+        }
+        if self.cursor.is_some() {
+            common_query.push_str(
+                "
+                AND
+                (
+                    DhtOp.authored_timestamp > :cursor_timestamp
+                    OR
+                    (DhtOp.authored_timestamp = :cursor_timestamp AND Action.hash > :cursor_hash)
+                )
+                ",
+            );
+        }
         common_query = format!(
             "
             {}
@@ -92,12 +130,24 @@ impl Query for GetLinksOpsQuery {
             common_query,
             self.type_query.to_sql_statement(),
         );
-        let create_query = format!("{}{}", create, common_query);
+        if self.limit.is_some() {
+            // Only the first page of creates is returned, along with
+            // the deletes of those creates.
+            common_query.push_str(
+                "
+                ORDER BY DhtOp.authored_timestamp, Action.hash
+                LIMIT :limit
+                ",
+            );
+        }
+        let mut create_query = format!("{}{}", create, common_query);
+        if self.limit.is_some() {
+            create_query = format!("SELECT * FROM ({})", create_query);
+        }
         let sub_create_query = format!("{}{}", sub_create, common_query);
         let delete_query = format!(
             "
-            SELECT Action.blob AS action_blob, DhtOp.type AS dht_type,
-            DhtOp.validation_status AS status
+            {}
             FROM DhtOp
             JOIN Action On DhtOp.action_hash = Action.hash
             WHERE DhtOp.type = :delete
@@ -106,18 +156,58 @@ impl Query for GetLinksOpsQuery {
             AND
             Action.create_link_hash IN ({})
             ",
-            sub_create_query
+            delete_columns, sub_create_query
         );
         format!("{} UNION ALL {}", create_query, delete_query)
     }
 
-    fn params(&self) -> Vec<Params> {
-        named_params! {
+    pub(super) fn links_params(&self) -> Vec<Params> {
+        let mut params = named_params! {
             ":create": DhtOpType::RegisterAddLink,
             ":delete": DhtOpType::RegisterRemoveLink,
             ":base_hash": self.base,
         }
-        .to_vec()
+        .to_vec();
+        if let Some(author) = &self.author {
+            params.push((":author", author));
+        }
+        if let Some(after) = &self.after {
+            params.push((":after", after));
+        }
+        if let Some(before) = &self.before {
+            params.push((":before", before));
+        }
+        if let Some((timestamp, hash)) = &self.cursor {
+            params.push((":cursor_timestamp", timestamp));
+            params.push((":cursor_hash", hash));
+        }
+        if let Some(limit) = &self.limit {
+            params.push((":limit", limit));
+        }
+        params
+    }
+}
+
+pub struct Item {
+    action: SignedAction,
+    op_type: DhtOpType,
+}
+
+impl Query for GetLinksOpsQuery {
+    type Item = Judged<Item>;
+    type State = WireLinkOps;
+    type Output = Self::State;
+
+    fn query(&self) -> String {
+        let columns = "
+            SELECT Action.blob AS action_blob, DhtOp.type AS dht_type,
+            DhtOp.validation_status AS status
+        ";
+        self.query_with(columns, columns)
+    }
+
+    fn params(&self) -> Vec<Params> {
+        self.links_params()
     }
 
     fn as_map(&self) -> Arc<dyn Fn(&Row) -> StateQueryResult<Self::Item>> {
@@ -1,11 +1,14 @@
 use super::*;
 use crate::authority::handle_get_agent_activity;
 use crate::test_utils::*;
+use ::fixt::prelude::*;
 use ghost_actor::dependencies::observability;
 use holochain_p2p::actor;
 use holochain_p2p::event::GetRequest;
 use holochain_state::prelude::test_dht_db;
 use holochain_types::activity::ChainItems;
+use holochain_zome_types::fixt::AgentPubKeyFixturator;
+use holochain_zome_types::LinkCursor;
 
 fn options() -> holochain_p2p::event::GetOptions {
     holochain_p2p::event::GetOptions {
@@ -189,6 +192,102 @@ async fn get_links() {
     assert_eq!(result, expected);
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn get_links_filtered() {
+    observability::test_run().ok();
+    let db = test_dht_db();
+
+    let td = EntryTestData::create();
+
+    fill_db(&db.to_db(), td.store_entry_op.clone());
+    fill_db(&db.to_db(), td.create_link_op.clone());
+    fill_db(&db.to_db(), td.delete_link_op.clone());
+    let options = actor::GetLinksOptions::default();
+    let create = td.create_link_action.action();
+
+    let get = |key: WireLinkKey| {
+        let db = db.to_db();
+        let options = (&options).into();
+        async move { handle_get_links(db.into(), key, options).await.unwrap() }
+    };
+    let all = WireLinkOps {
+        creates: vec![td.wire_create_link.clone()],
+        deletes: vec![td.wire_delete_link.clone()],
+    };
+
+    let mut key = td.link_key.clone();
+    key.author = Some(create.author().clone());
+    assert_eq!(get(key).await, all);
+
+    let mut key = td.link_key.clone();
+    key.author = Some(fixt!(AgentPubKey));
+    assert_eq!(get(key).await, WireLinkOps::new());
+
+    let mut key = td.link_key.clone();
+    key.after = Some(create.timestamp());
+    assert_eq!(get(key).await, all);
+
+    let mut key = td.link_key.clone();
+    key.before = Some(create.timestamp());
+    assert_eq!(get(key).await, WireLinkOps::new());
+
+    let mut key = td.link_key.clone();
+    key.limit = Some(1);
+    assert_eq!(get(key).await, all);
+
+    // The deletes of creates which are not in the page are not returned.
+    let mut key = td.link_key.clone();
+    key.limit = Some(0);
+    assert_eq!(get(key).await, WireLinkOps::new());
+
+    let mut key = td.link_key.clone();
+    key.cursor = Some(LinkCursor::new(
+        create.timestamp(),
+        td.create_link_action.as_hash().clone(),
+    ));
+    assert_eq!(get(key).await, WireLinkOps::new());
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn count_links() {
+    observability::test_run().ok();
+    let db = test_dht_db();
+
+    let td = EntryTestData::create();
+
+    fill_db(&db.to_db(), td.store_entry_op.clone());
+    fill_db(&db.to_db(), td.create_link_op.clone());
+    let options = actor::GetLinksOptions::default();
+    let cursor = LinkCursor::new(
+        td.create_link_action.action().timestamp(),
+        td.create_link_action.as_hash().clone(),
+    );
+
+    let result = handle_count_links(db.to_db().into(), td.link_key.clone(), (&options).into())
+        .await
+        .unwrap();
+    let expected = WireLinkHashes {
+        creates: vec![cursor.clone()],
+        deleted: vec![],
+    };
+    assert_eq!(result, expected);
+
+    fill_db(&db.to_db(), td.delete_link_op.clone());
+
+    let result = handle_count_links(
+        db.to_db().into(),
+        td.link_key_tag.clone(),
+        (&options).into(),
+    )
+    .await
+    .unwrap();
+    let expected = WireLinkHashes {
+        creates: vec![cursor.clone()],
+        deleted: vec![cursor.create_link_hash().clone()],
+    };
+    assert_eq!(result, expected);
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn get_agent_activity() {
     observability::test_run().ok();
@@ -224,6 +323,7 @@ async fn get_agent_activity() {
         rejected_activity: ChainItems::NotRequested,
         status: ChainStatus::Valid(td.chain_head.clone()),
         highest_observed: Some(td.highest_observed.clone()),
+        warrants: Vec::new(),
     };
     assert_eq!(result, expected);
 
@@ -23,6 +23,8 @@
 //!
 #![warn(missing_docs)]
 
+use std::collections::BTreeSet;
+use std::collections::HashSet;
 use std::sync::Arc;
 
 use error::CascadeResult;
@@ -44,6 +46,7 @@ use holochain_state::prelude::*;
 use holochain_state::query::entry_details::GetEntryDetailsQuery;
 use holochain_state::query::link::GetLinksQuery;
 use holochain_state::query::link_details::GetLinkDetailsQuery;
+use holochain_state::query::link_hashes::GetLinkHashesQuery;
 use holochain_state::query::live_entry::GetLiveEntryQuery;
 use holochain_state::query::live_record::GetLiveRecordQuery;
 use holochain_state::query::record_details::GetRecordDetailsQuery;
@@ -65,6 +68,10 @@ mod agent_activity;
 #[cfg(any(test, feature = "test_utils"))]
 pub mod test_utils;
 
+/// The most pages of links fetched from the network to fill a single
+/// page of a links query.
+pub const MAX_LINK_PAGES: usize = 10;
+
 /// Get an item from an option
 /// or return early from the function
 macro_rules! some_or_return {
@@ -374,17 +381,81 @@ where
     }
 
     #[instrument(skip(self, options))]
+    /// Fetch links from the network into the cache.
+    /// If the key only asks for a page of links, this returns the cursor
+    /// to fetch the next page from, if any authority may have more links.
     async fn fetch_links(
         &mut self,
         link_key: WireLinkKey,
         options: GetLinksOptions,
-    ) -> CascadeResult<()> {
-        let network = some_or_return!(self.network.as_mut());
+    ) -> CascadeResult<Option<LinkCursor>> {
+        let network = some_or_return!(self.network.as_mut(), None);
         let results = network.get_links(link_key.clone(), options).await?;
 
+        // An authority which returned less than a full page has no more links.
+        // Of those that returned a full page, continue from the earliest
+        // last link so no authority's links are skipped.
+        let next_page = link_key.limit.and_then(|limit| {
+            results
+                .iter()
+                .filter(|ops| ops.creates.len() >= limit as usize)
+                .filter_map(|ops| {
+                    ops.creates
+                        .iter()
+                        .filter_map(|create| {
+                            let op = create.clone().render(&link_key).ok()?;
+                            Some(LinkCursor::new(
+                                op.action.action().timestamp(),
+                                op.action.as_hash().clone(),
+                            ))
+                        })
+                        .max()
+                })
+                .min()
+        });
+
         self.merge_link_ops_into_cache(results, link_key.clone())
             .await?;
-        Ok(())
+        Ok(next_page)
+    }
+
+    /// Run a links query, fetching the links from the network first
+    /// if we are not an authority for the base.
+    /// If the key only asks for a page of links, pages are fetched until
+    /// the query has a full page, the authorities run out of links, the
+    /// cursor stops moving forward or [`MAX_LINK_PAGES`] have been fetched.
+    async fn fetch_and_query_links<Q, T>(
+        &mut self,
+        key: WireLinkKey,
+        options: GetLinksOptions,
+        query: Q,
+    ) -> CascadeResult<Vec<T>>
+    where
+        Q: Query<Item = Judged<SignedActionHashed>, Output = Vec<T>> + Clone + Send + 'static,
+        T: Send + 'static,
+    {
+        let authority = self.am_i_an_authority(key.base.clone()).await?;
+        if authority {
+            return self.cascading(query).await;
+        }
+        let limit = key.limit;
+        let mut fetch_key = key;
+        let mut pages = 0;
+        loop {
+            let next_page = self.fetch_links(fetch_key.clone(), options.clone()).await?;
+            let results = self.cascading(query.clone()).await?;
+            pages += 1;
+            match (limit, next_page) {
+                (Some(limit), Some(cursor))
+                    if results.len() < limit as usize
+                        && pages < MAX_LINK_PAGES
+                        && fetch_key.cursor.as_ref().map_or(true, |c| cursor > *c) =>
+                {
+                    fetch_key.cursor = Some(cursor);
+                }
+                _ => return Ok(results),
+            }
+        }
     }
 
     #[instrument(skip(self, options))]
@@ -842,13 +913,79 @@ where
         key: WireLinkKey,
         options: GetLinksOptions,
     ) -> CascadeResult<Vec<Link>> {
+        let query = GetLinksQuery::from_key(key.clone());
+        self.fetch_and_query_links(key, options, query).await
+    }
+
+    #[instrument(skip(self, options))]
+    /// Count the links matching a key without getting them, returning the
+    /// positions of the links which are not deleted, in the order they were created.
+    /// Authorities only send the hashes of the links, so nothing is cached.
+    pub async fn count_links(
+        &mut self,
+        key: WireLinkKey,
+        options: GetLinksOptions,
+    ) -> CascadeResult<Vec<LinkCursor>> {
         let authority = self.am_i_an_authority(key.base.clone()).await?;
-        if !authority {
-            self.fetch_links(key.clone(), options).await?;
+        let limit = key.limit.map(|l| l as usize);
+        let mut links = BTreeSet::new();
+        let mut page_key = key;
+        let mut fetched = 0;
+        loop {
+            fetched += 1;
+            let mut pages = vec![
+                self.cascading(GetLinkHashesQuery::from_key(page_key.clone()))
+                    .await?,
+            ];
+            if !authority {
+                if let Some(network) = self.network.as_mut() {
+                    pages.extend(
+                        network
+                            .count_links(page_key.clone(), options.clone())
+                            .await?,
+                    );
+                }
+            }
+            let deleted: HashSet<_> = pages.iter().flat_map(|p| p.deleted.iter()).collect();
+
+            // Each source sends a page of creates including the deleted ones.
+            // Links are only known to be complete up to the end of the shortest
+            // full page, so the next page starts from there.
+            let next_page = limit.and_then(|limit| {
+                pages
+                    .iter()
+                    .filter(|p| p.creates.len() >= limit)
+                    .filter_map(|p| p.creates.last())
+                    .min()
+                    .cloned()
+            });
+            for page in &pages {
+                links.extend(
+                    page.creates
+                        .iter()
+                        .filter(|c| {
+                            !deleted.contains(c.create_link_hash())
+                                && next_page.as_ref().map_or(true, |next| c <= next)
+                        })
+                        .cloned(),
+                );
+            }
+            match (limit, next_page) {
+                (Some(limit), Some(next))
+                    if links.len() < limit
+                        && fetched < MAX_LINK_PAGES
+                        && page_key.cursor.as_ref().map_or(true, |c| next > *c) =>
+                {
+                    page_key.cursor = Some(next)
+                }
+                _ => break,
+            }
         }
-        let query = GetLinksQuery::new(key.base, key.type_query, key.tag);
-        let results = self.cascading(query).await?;
-        Ok(results)
+        let mut links: Vec<_> = links.into_iter().collect();
+        if let Some(limit) = limit {
+            links.truncate(limit);
+        }
+        Ok(links)
     }
 
     #[instrument(skip(self, key, options))]
@@ -859,13 +996,8 @@ where
         key: WireLinkKey,
         options: GetLinksOptions,
     ) -> CascadeResult<Vec<(SignedActionHashed, Vec<SignedActionHashed>)>> {
-        let authority = self.am_i_an_authority(key.base.clone()).await?;
-        if !authority {
-            self.fetch_links(key.clone(), options).await?;
-        }
-        let query = GetLinkDetailsQuery::new(key.base, key.type_query, key.tag);
-        let results = self.cascading(query).await?;
-        Ok(results)
+        let query = GetLinkDetailsQuery::from_key(key.clone());
+        self.fetch_and_query_links(key, options, query).await
     }
 
     /// Request a hash bounded chain query.
@@ -994,6 +1126,7 @@ where
             rejected_activity,
             status,
             highest_observed,
+            warrants,
         } = merged_response;
         let valid_activity = match valid_activity {
             ChainItems::Hashes(hashes) => {
@@ -1048,6 +1181,7 @@ where
             rejected_activity,
             status,
             highest_observed,
+            warrants,
         };
         Ok(r)
     }
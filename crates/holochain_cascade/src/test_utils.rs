@@ -41,6 +41,7 @@ use holochain_types::dht_op::DhtOpLight;
 use holochain_types::dht_op::OpOrder;
 use holochain_types::dht_op::UniqueForm;
 use holochain_types::dht_op::WireOps;
+use holochain_types::link::WireLinkHashes;
 use holochain_types::link::WireLinkKey;
 use holochain_types::link::WireLinkOps;
 use holochain_types::metadata::MetadataSet;
@@ -160,6 +161,21 @@ impl HolochainP2pDnaT for PassThroughNetwork {
         Ok(out)
     }
 
+    async fn count_links(
+        &self,
+        link_key: WireLinkKey,
+        options: actor::GetLinksOptions,
+    ) -> actor::HolochainP2pResult<Vec<WireLinkHashes>> {
+        let mut out = Vec::new();
+        for env in &self.envs {
+            let r = authority::handle_count_links(env.clone(), link_key.clone(), (&options).into())
+                .await
+                .map_err(|e| HolochainP2pError::Other(e.into()))?;
+            out.push(r);
+        }
+        Ok(out)
+    }
+
     async fn get_agent_activity(
         &self,
         agent: AgentPubKey,
@@ -247,6 +263,13 @@ impl HolochainP2pDnaT for PassThroughNetwork {
         todo!()
     }
 
+    async fn publish_warrant(
+        &self,
+        _warrant: holochain_zome_types::SignedWarrant,
+    ) -> actor::HolochainP2pResult<()> {
+        todo!()
+    }
+
     async fn send_validation_receipt(
         &self,
         _to_agent: AgentPubKey,
@@ -377,6 +400,14 @@ impl HolochainP2pDnaT for MockNetwork {
         self.0.lock().await.get_links(link_key, options).await
     }
 
+    async fn count_links(
+        &self,
+        link_key: WireLinkKey,
+        options: actor::GetLinksOptions,
+    ) -> actor::HolochainP2pResult<Vec<WireLinkHashes>> {
+        self.0.lock().await.count_links(link_key, options).await
+    }
+
     async fn get_agent_activity(
         &self,
         agent: AgentPubKey,
@@ -449,6 +480,13 @@ impl HolochainP2pDnaT for MockNetwork {
         todo!()
     }
 
+    async fn publish_warrant(
+        &self,
+        _warrant: holochain_zome_types::SignedWarrant,
+    ) -> actor::HolochainP2pResult<()> {
+        todo!()
+    }
+
     async fn send_validation_receipt(
         &self,
         _to_agent: AgentPubKey,
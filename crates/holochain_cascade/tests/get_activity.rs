@@ -64,6 +64,7 @@ async fn get_activity() {
         rejected_activity: ChainItems::NotRequested,
         status: ChainStatus::Valid(td.chain_head.clone()),
         highest_observed: Some(td.highest_observed.clone()),
+        warrants: Vec::new(),
     };
     assert_eq!(r, expected);
 }
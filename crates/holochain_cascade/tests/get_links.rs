@@ -66,6 +66,48 @@ async fn links_not_authority() {
     );
 }
 
+#[tokio::test(flavor = "multi_thread")]
+async fn count_links_not_authority() {
+    observability::test_run().ok();
+
+    // Environments
+    let cache = test_cache_db();
+    let authority = test_dht_db();
+
+    // Data
+    let td = EntryTestData::create();
+    fill_db(&authority.to_db(), td.store_entry_op.clone());
+    fill_db(&authority.to_db(), td.create_link_op.clone());
+
+    // Network
+    let network = PassThroughNetwork::authority_for_nothing(vec![authority.to_db().clone().into()]);
+
+    // Cascade
+    let mut cascade = Cascade::empty().with_network(network, cache.to_db());
+
+    let r = cascade
+        .count_links(td.link_key.clone(), Default::default())
+        .await
+        .unwrap();
+
+    assert_eq!(r, vec![td.links[0].cursor()]);
+
+    let mut key = td.link_key.clone();
+    key.limit = Some(0);
+    let r = cascade.count_links(key, Default::default()).await.unwrap();
+
+    assert!(r.is_empty());
+
+    fill_db(&authority.to_db(), td.delete_link_op.clone());
+
+    let r = cascade
+        .count_links(td.link_key.clone(), Default::default())
+        .await
+        .unwrap();
+
+    assert!(r.is_empty());
+}
+
 #[tokio::test(flavor = "multi_thread")]
 async fn links_authority() {
     observability::test_run().ok();
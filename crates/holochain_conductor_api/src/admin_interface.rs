@@ -3,7 +3,7 @@ use holochain_types::prelude::*;
 use holochain_zome_types::cell::CellId;
 use kitsune_p2p::agent_store::AgentInfoSigned;
 
-use crate::{AppInfo, FullStateDump};
+use crate::{AllowedOrigins, AppAuthenticationToken, AppInfo, FullStateDump};
 
 /// Represents the available conductor functions to call over an admin interface.
 ///
@@ -96,6 +96,9 @@ pub enum AdminRequest {
 
     /// Generate a new [`AgentPubKey`].
     ///
+    /// If the conductor is configured with DPKI, the new key is registered
+    /// with the DPKI service.
+    ///
     /// # Returns
     ///
     /// [`AdminResponse::AgentPubKeyGenerated`]
@@ -154,7 +157,10 @@ pub enum AdminRequest {
 
     /// Open up a new websocket for processing [`AppRequest`]s.
     ///
-    /// Any active app will be callable via the attached app interface.
+    /// Any active app will be callable via the attached app interface, but
+    /// each connection must first authenticate with a token from
+    /// [`AdminRequest::IssueAppAuthenticationToken`], and can then only make
+    /// requests for the app the token was issued for.
     ///
     /// # Returns
     ///
@@ -166,18 +172,46 @@ pub enum AdminRequest {
     /// a free port is chosen by the conductor.
     /// The response will contain the port chosen by the conductor if `None` was passed.
     ///
+    /// Alternatively a `unix_socket` path can be passed to expose the interface
+    /// over a unix domain socket instead of a port, in which case `port` must be `None`
+    /// and the response will contain a port of 0.
+    ///
     /// [`AppRequest`]: super::AppRequest
     AttachAppInterface {
         /// Optional port number
         port: Option<u16>,
+
+        /// Optional path at which to create a unix domain socket for the interface
+        #[serde(default)]
+        unix_socket: Option<std::path::PathBuf>,
+
+        /// The origins which may connect to the interface, checked against
+        /// the `Origin` header of the websocket handshake.
+        /// Any origin is allowed if this is omitted.
+        #[serde(default)]
+        allowed_origins: AllowedOrigins,
     },
 
+    /// Issue a token which a client can use once to authenticate a connection
+    /// to an app interface. The connection can then only make requests for
+    /// the app the token was issued for.
+    ///
+    /// The client must send an [`AppAuthenticationRequest`] with the token as
+    /// the first message after connecting, otherwise the connection is closed.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AppAuthenticationTokenIssued`]
+    ///
+    /// [`AppAuthenticationRequest`]: super::AppAuthenticationRequest
+    IssueAppAuthenticationToken(IssueAppAuthenticationTokenPayload),
+
     /// List all the app interfaces currently attached with [`AttachAppInterface`].
     ///
     /// # Returns
     ///
     /// [`AdminResponse::AppInterfacesListed`], a list of websocket ports that can
-    /// process [`AppRequest`]s.
+    /// process [`AppRequest`]s. Interfaces attached to a unix domain socket are not listed.
     ///
     /// [`AttachAppInterface`]: AdminRequest::AttachAppInterface
     /// [`AppRequest`]: super::AppRequest
@@ -227,6 +261,64 @@ pub enum AdminRequest {
         dna_hash: Option<DnaHash>,
     },
 
+    /// Get the gossip bandwidth targets currently in effect, along with the
+    /// throttling configuration and the last pressure reported by the conductor.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::GossipThrottle`]
+    GetGossipThrottle,
+
+    /// Replace the gossip throttling configuration of the running conductor.
+    ///
+    /// This can override the gossip bandwidth targets from the tuning params,
+    /// set different targets for some hours of the day, and turn off the
+    /// back off when the conductor is under CPU or disk pressure.
+    /// It is not persisted, so the network config applies again on restart.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::GossipThrottleUpdated`]
+    UpdateGossipThrottle {
+        /// The new throttling configuration.
+        config: kitsune_p2p::GossipThrottleConfig,
+    },
+
+    /// Get the network tuning params currently in effect for each DNA.
+    ///
+    /// These start out as the `tuning_params` of the network config,
+    /// and can be changed with [`AdminRequest::UpdateTuningParams`].
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::TuningParams`]
+    GetTuningParams {
+        /// If set, only gets the tuning params of this DNA.
+        /// Otherwise gets them for every DNA with a running cell.
+        dna_hash: Option<DnaHash>,
+    },
+
+    /// Change some network tuning params of the running conductor.
+    ///
+    /// Params are given by name, with their values written the same way
+    /// as in the network config. Params which are only read at startup,
+    /// such as `gossip_strategy`, can't be changed, and params which are
+    /// shared by every DNA, such as the gossip bandwidth targets, can only
+    /// be changed for every DNA at once. Changes are not persisted, so the
+    /// network config applies again on restart.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::TuningParamsUpdated`]
+    UpdateTuningParams {
+        /// If set, only changes the tuning params of this DNA.
+        /// Otherwise changes them for every DNA, including DNAs
+        /// installed later.
+        dna_hash: Option<DnaHash>,
+        /// The values of the params to change, keyed by param name.
+        tuning_params: std::collections::HashMap<String, String>,
+    },
+
     /// Add a list of agents to this conductor's peer store.
     ///
     /// This is a way of shortcutting peer discovery and is useful for testing.
@@ -325,6 +417,70 @@ pub enum AdminRequest {
     ///
     /// [`AdminResponse::CloneCellDeleted`]
     DeleteCloneCell(Box<DeleteCloneCellPayload>),
+
+    /// Provision the cell of a role which was installed with the `disabled`
+    /// provisioning strategy.
+    ///
+    /// The DNA of the role must be registered in the conductor. If the app is
+    /// running, the new cell is started right away.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AppRoleEnabled`]
+    EnableAppRole(Box<EnableAppRolePayload>),
+
+    /// Migrate an agent's source chain to another version of its DNA, keeping
+    /// the agent's identity and history.
+    ///
+    /// The agent must already have a running cell of the new DNA. The
+    /// `migrate_agent` callback is run on both cells, and if every zome
+    /// passes, the old chain is closed with a `CloseChain` action and the new
+    /// chain opened with an `OpenChain` action which refers back to the old DNA.
+    /// A migration which failed part way through can be retried.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AgentMigrated`]
+    MigrateAgent(Box<MigrateAgentPayload>),
+
+    /// Revoke an agent key in the DPKI service, so that anything it signs
+    /// from now on is rejected by sys validation.
+    ///
+    /// Requires the conductor to be configured with DPKI.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AgentKeyRevoked`]
+    RevokeAgentKey {
+        /// The key to revoke.
+        agent_key: AgentPubKey,
+    },
+
+    /// Generate a new agent key to replace an existing one, and register the
+    /// rotation with the DPKI service. The old key is revoked.
+    ///
+    /// Requires the conductor to be configured with DPKI.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AgentKeyRotated`]
+    RotateAgentKey {
+        /// The key to replace.
+        agent_key: AgentPubKey,
+    },
+
+    /// Stop blocking an agent which was blocked because it was warranted,
+    /// by removing the warrants issued against it in a DNA.
+    ///
+    /// # Returns
+    ///
+    /// [`AdminResponse::AgentUnblocked`]
+    UnblockAgent {
+        /// The DNA to unblock the agent in.
+        dna_hash: DnaHash,
+        /// The agent to unblock.
+        agent: AgentPubKey,
+    },
 }
 
 /// Represents the possible responses to an [`AdminRequest`]
@@ -394,6 +550,7 @@ pub enum AdminResponse {
     /// `AppInterfaceApi` successfully attached.
     /// If no port was specified in the request, contains the port number that was
     /// selected by the conductor for running this app interface.
+    /// The port is 0 if the interface was attached to a unix domain socket.
     AppInterfaceAttached {
         /// Networking port of the new `AppInterfaceApi`
         port: u16,
@@ -402,6 +559,9 @@ pub enum AdminResponse {
     /// The list of attached app interfaces.
     AppInterfacesListed(Vec<u16>),
 
+    /// The successful response to an [`AdminRequest::IssueAppAuthenticationToken`].
+    AppAuthenticationTokenIssued(AppAuthenticationTokenIssued),
+
     /// The successful response to an [`AdminRequest::EnableApp`].
     ///
     /// It means the app was enabled successfully. If it was possible to
@@ -444,6 +604,22 @@ pub enum AdminResponse {
     /// The string is a JSON blob of the metrics results.
     NetworkMetricsDumped(String),
 
+    /// The successful result of a call to [`AdminRequest::GetGossipThrottle`].
+    GossipThrottle(kitsune_p2p::GossipThrottleStatus),
+
+    /// The successful response to an [`AdminRequest::UpdateGossipThrottle`].
+    ///
+    /// The new configuration is in effect.
+    GossipThrottleUpdated,
+
+    /// The successful result of a call to [`AdminRequest::GetTuningParams`].
+    TuningParams(Vec<DnaTuningParams>),
+
+    /// The successful response to an [`AdminRequest::UpdateTuningParams`].
+    ///
+    /// The new tuning params are in effect.
+    TuningParamsUpdated,
+
     /// The successful response to an [`AdminRequest::AddAgentInfo`].
     ///
     /// This means the agent info was successfully added to the peer store.
@@ -462,6 +638,88 @@ pub enum AdminResponse {
 
     /// The successful response to an [`AdminRequest::DeleteCloneCell`].
     CloneCellDeleted,
+
+    /// The successful response to an [`AdminRequest::EnableAppRole`].
+    ///
+    /// Contains the updated info of the app the role belongs to.
+    AppRoleEnabled(AppInfo),
+
+    /// The successful response to an [`AdminRequest::MigrateAgent`].
+    ///
+    /// Contains the id of the cell the agent was migrated to.
+    AgentMigrated(CellId),
+
+    /// The successful response to an [`AdminRequest::RevokeAgentKey`].
+    ///
+    /// Contains the state of the key in the DPKI service after revocation.
+    AgentKeyRevoked(KeyState),
+
+    /// The successful response to an [`AdminRequest::RotateAgentKey`].
+    ///
+    /// Contains the newly generated key which replaced the old one.
+    AgentKeyRotated(AgentPubKey),
+
+    /// The successful response to an [`AdminRequest::UnblockAgent`].
+    AgentUnblocked,
+}
+
+/// The arguments of an [`AdminRequest::IssueAppAuthenticationToken`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct IssueAppAuthenticationTokenPayload {
+    /// The app the token can be used to make requests for.
+    pub installed_app_id: InstalledAppId,
+
+    /// How long the token can be used for after it is issued.
+    /// A value of 0 means the token never expires. [default = 30]
+    #[serde(default = "default_token_expiry_seconds")]
+    pub expiry_seconds: u64,
+
+    /// Whether the token can only be used to authenticate a single
+    /// connection. [default = true]
+    #[serde(default = "default_token_single_use")]
+    pub single_use: bool,
+}
+
+impl IssueAppAuthenticationTokenPayload {
+    /// A single use token for this app, which expires after 30 seconds.
+    pub fn for_installed_app_id(installed_app_id: InstalledAppId) -> Self {
+        Self {
+            installed_app_id,
+            expiry_seconds: default_token_expiry_seconds(),
+            single_use: default_token_single_use(),
+        }
+    }
+}
+
+fn default_token_expiry_seconds() -> u64 {
+    30
+}
+
+fn default_token_single_use() -> bool {
+    true
+}
+
+/// The token returned by an [`AdminRequest::IssueAppAuthenticationToken`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct AppAuthenticationTokenIssued {
+    /// The token to send in an [`AppAuthenticationRequest`].
+    ///
+    /// [`AppAuthenticationRequest`]: super::AppAuthenticationRequest
+    pub token: AppAuthenticationToken,
+
+    /// When the token stops being accepted, if ever.
+    pub expires_at: Option<Timestamp>,
+}
+
+/// The network tuning params in effect for a DNA,
+/// returned by an [`AdminRequest::GetTuningParams`].
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+pub struct DnaTuningParams {
+    /// The DNA these tuning params are for.
+    pub dna_hash: DnaHash,
+
+    /// The tuning params currently in effect.
+    pub tuning_params: kitsune_p2p::dependencies::kitsune_p2p_types::config::KitsuneP2pTuningParams,
 }
 
 /// Error type that goes over the websocket wire.
@@ -61,6 +61,17 @@ pub enum AppRequest {
     /// and has been disabled.
     DisableCloneCell(Box<DisableCloneCellPayload>),
 
+    /// Provision the cell of a role whose provisioning was deferred at
+    /// installation.
+    ///
+    /// The cell is created from the DNA specified in the app manifest, with
+    /// the provided modifiers applied on top, and started if the app is running.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::DeferredCellProvisioned`]
+    ProvisionDeferredCell(Box<ProvisionDeferredCellPayload>),
+
     /// Enable a clone cell that was previously disabled.
     ///
     /// # Returns
@@ -71,11 +82,35 @@ pub enum AppRequest {
     /// Info about networking processes
     NetworkInfo(Box<NetworkInfoRequestPayload>),
 
-    /// Is currently unimplemented and will return
-    /// an [`AppResponse::Unimplemented`].
+    /// Filter the signals of an app which are sent over this connection.
+    ///
+    /// The filters apply to the cells the app has when the subscription is
+    /// made, and replace any earlier subscription for the same app.
+    /// System signals are always sent.
+    ///
+    /// # Returns
+    ///
+    /// [`AppResponse::SignalSubscribed`]
     SignalSubscription(SignalSubscription),
 }
 
+/// A token which authenticates a connection to an app interface.
+/// See [`AdminRequest::IssueAppAuthenticationToken`].
+///
+/// [`AdminRequest::IssueAppAuthenticationToken`]: crate::AdminRequest::IssueAppAuthenticationToken
+pub type AppAuthenticationToken = Vec<u8>;
+
+/// The first message a client must send after connecting to an app interface,
+/// as a signal rather than a request.
+///
+/// If the token is valid, the connection is bound to the app the token was
+/// issued for. Otherwise the connection is closed.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
+pub struct AppAuthenticationRequest {
+    /// The token issued by the admin interface.
+    pub token: AppAuthenticationToken,
+}
+
 /// Represents the possible responses to an [`AppRequest`].
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
 #[serde(rename_all = "snake_case", tag = "type", content = "data")]
@@ -107,6 +142,12 @@ pub enum AppResponse {
     /// cell's [`CloneId`] and [`CellId`].
     CloneCellCreated(InstalledCell),
 
+    /// The successful response to an [`AppRequest::ProvisionDeferredCell`].
+    ///
+    /// The response contains an [`InstalledCell`] with the role name and
+    /// [`CellId`] of the provisioned cell.
+    DeferredCellProvisioned(InstalledCell),
+
     /// The successful response to an [`AppRequest::DisableCloneCell`].
     ///
     /// An existing clone cell has been disabled.
@@ -119,6 +160,11 @@ pub enum AppResponse {
 
     /// NetworkInfo is returned
     NetworkInfo(Vec<NetworkInfo>),
+
+    /// The successful response to an [`AppRequest::SignalSubscription`].
+    ///
+    /// The filters are now applied to the signals sent over this connection.
+    SignalSubscribed,
 }
 
 /// The data provided over an app interface in order to make a zome call
@@ -215,9 +261,13 @@ pub enum CellInfo {
     // cells created by cloning
     Cloned(Cell),
 
-    // potential cells with deferred installation as defined in the bundle
-    // unimplemented
+    // potential cells which are defined in the bundle but have not been
+    // provisioned yet, e.g. roles with the `disabled` provisioning strategy
     Stem(StemCell),
+
+    // cells with deferred installation as defined in the bundle, which can be
+    // provisioned at runtime with custom modifiers
+    Deferred(StemCell),
 }
 
 impl CellInfo {
@@ -251,6 +301,22 @@ impl CellInfo {
             enabled,
         })
     }
+
+    fn new_stem(dna: DnaHash, dna_modifiers: DnaModifiers, name: String) -> Self {
+        Self::Stem(StemCell {
+            dna,
+            name: Some(name),
+            dna_modifiers,
+        })
+    }
+
+    fn new_deferred(dna: DnaHash, dna_modifiers: DnaModifiers, name: String) -> Self {
+        Self::Deferred(StemCell {
+            dna,
+            name: Some(name),
+            dna_modifiers,
+        })
+    }
 }
 
 #[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize)]
@@ -310,9 +376,23 @@ impl AppInfo {
                     tracing::error!("no DNA definition found for cell id {}", provisioned_cell);
                 }
             } else {
-                // no provisioned cell, thus there must be a deferred cell
-                // this is not implemented as of now
-                unimplemented!()
+                // no provisioned cell, so the role is only a potential cell
+                let base_cell = role_assignment.cell_id();
+                if let Some(dna_def) = dna_definitions.get(base_cell) {
+                    let new_cell_info = if role_assignment.is_deferred() {
+                        CellInfo::new_deferred
+                    } else {
+                        CellInfo::new_stem
+                    };
+                    let cell_info = new_cell_info(
+                        base_cell.dna_hash().clone(),
+                        dna_def.modifiers.to_owned(),
+                        dna_def.name.to_owned(),
+                    );
+                    cell_info_for_role.push(cell_info);
+                } else {
+                    tracing::error!("no DNA definition found for cell id {}", base_cell);
+                }
             };
 
             // push enabled clone cells to the vector of cell infos
@@ -11,6 +11,7 @@ mod dpki_config;
 #[allow(missing_docs)]
 mod error;
 mod keystore_config;
+mod metrics_endpoint_config;
 pub mod paths;
 //mod logger_config;
 //mod signal_config;
@@ -21,6 +22,7 @@ pub use dpki_config::DpkiConfig;
 //pub use logger_config::LoggerConfig;
 pub use error::*;
 pub use keystore_config::KeystoreConfig;
+pub use metrics_endpoint_config::MetricsEndpointConfig;
 //pub use signal_config::SignalConfig;
 use std::path::Path;
 
@@ -36,8 +38,8 @@ pub struct ConductorConfig {
     #[serde(default)]
     pub keystore: KeystoreConfig,
 
-    /// Optional DPKI configuration if conductor is using a DPKI app to initalize and manage
-    /// keys for new instances.
+    /// Optional DPKI configuration if conductor is using a DPKI app to register and manage
+    /// the agent keys it generates.
     pub dpki: Option<DpkiConfig>,
 
     /// Setup admin interfaces to control this conductor through a websocket connection.
@@ -62,6 +64,18 @@ pub struct ConductorConfig {
     /// [sqlite documentation]: https://www.sqlite.org/pragma.html#pragma_synchronous
     #[serde(default)]
     pub db_sync_strategy: DbSyncStrategy,
+
+    /// If true, refuse connections from agents which have had a warrant
+    /// issued against them, and drop their agent info from the peer store.
+    #[serde(default)]
+    pub block_warranted_agents: bool,
+
+    /// Optionally serve metrics about this conductor and its network
+    /// over HTTP, for scraping by Prometheus or a compatible collector.
+    /// The conductor fails to start if holochain was built without the
+    /// `metrics_endpoint` feature.
+    #[serde(default)]
+    pub metrics_endpoint: Option<MetricsEndpointConfig>,
     //
     //
     // Which signals to emit
@@ -134,10 +148,56 @@ pub mod tests {
                 admin_interfaces: None,
                 db_sync_strategy: DbSyncStrategy::default(),
                 chc_namespace: None,
+                block_warranted_agents: false,
+                metrics_endpoint: None,
             }
         );
     }
 
+    #[test]
+    fn test_config_unix_socket_admin_interface() {
+        let yaml = r#"---
+    environment_path: /path/to/env
+
+    keystore:
+      type: danger_test_keystore
+
+    admin_interfaces:
+      - driver:
+          type: unix_socket
+          path: /path/to/admin.sock
+    "#;
+        let result: ConductorConfig = config_from_yaml(yaml).unwrap();
+        assert_eq!(
+            result.admin_interfaces,
+            Some(vec![AdminInterfaceConfig {
+                driver: InterfaceDriver::UnixSocket {
+                    path: PathBuf::from("/path/to/admin.sock"),
+                }
+            }])
+        );
+    }
+
+    #[test]
+    fn test_config_metrics_endpoint() {
+        let yaml = r#"---
+    environment_path: /path/to/env
+
+    keystore:
+      type: danger_test_keystore
+
+    metrics_endpoint:
+      bind_to: 127.0.0.1:9100
+    "#;
+        let result: ConductorConfig = config_from_yaml(yaml).unwrap();
+        assert_eq!(
+            result.metrics_endpoint,
+            Some(MetricsEndpointConfig {
+                bind_to: ([127, 0, 0, 1], 9100).into(),
+            })
+        );
+    }
+
     #[test]
     fn test_config_complete_config() {
         observability::test_run().ok();
@@ -154,6 +214,7 @@ pub mod tests {
     dpki:
       instance_id: some_id
       init_params: some_params
+      network_seed: some_seed
 
     admin_interfaces:
       - driver:
@@ -216,7 +277,9 @@ pub mod tests {
                 environment_path: PathBuf::from("/path/to/env").into(),
                 dpki: Some(DpkiConfig {
                     instance_id: "some_id".into(),
-                    init_params: "some_params".into()
+                    init_params: "some_params".into(),
+                    network_seed: Some("some_seed".into()),
+                    bundle_path: None,
                 }),
                 keystore: KeystoreConfig::LairServerInProc { lair_root: None },
                 admin_interfaces: Some(vec![AdminInterfaceConfig {
@@ -225,6 +288,8 @@ pub mod tests {
                 network: Some(network_config),
                 db_sync_strategy: DbSyncStrategy::Fast,
                 chc_namespace: None,
+                block_warranted_agents: false,
+                metrics_endpoint: None,
             }
         );
     }
@@ -252,6 +317,8 @@ pub mod tests {
                 admin_interfaces: None,
                 db_sync_strategy: DbSyncStrategy::Fast,
                 chc_namespace: None,
+                block_warranted_agents: false,
+                metrics_endpoint: None,
             }
         );
     }
@@ -1,13 +1,28 @@
-// Legacy config that will probably change
-#![allow(missing_docs)]
-
 use serde::Deserialize;
 use serde::Serialize;
+use std::path::PathBuf;
 
-/// Configure which app instance ID to treat as the DPKI application handler
-/// as well as what parameters to pass it on its initialization.
+/// Configure which app to treat as the DPKI service, and how to install it.
+///
+/// Agent keys generated by the conductor are registered with the DPKI
+/// service, and the keys which sign incoming data are checked against it
+/// during sys validation.
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
 pub struct DpkiConfig {
+    /// The installed app id of the DPKI app. Its cell must have a zome
+    /// named [`DPKI_ZOME_NAME`](holochain_zome_types::dpki::DPKI_ZOME_NAME).
     pub instance_id: String,
+    /// Parameters to pass to the DPKI app when the conductor installs it,
+    /// through its [`DPKI_INIT_FN`](holochain_zome_types::dpki::DPKI_INIT_FN)
+    /// zome function.
     pub init_params: String,
+    /// The network seed to install the DPKI app with, so that all conductors
+    /// which share the same seed share the same key registry.
+    /// If not set, the seed from the bundle is used.
+    #[serde(default)]
+    pub network_seed: Option<String>,
+    /// If the DPKI app isn't installed when the conductor starts up, it is
+    /// installed from this bundle, with a newly generated agent key.
+    #[serde(default)]
+    pub bundle_path: Option<PathBuf>,
 }
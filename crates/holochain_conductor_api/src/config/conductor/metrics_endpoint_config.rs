@@ -0,0 +1,15 @@
+use serde::Deserialize;
+use serde::Serialize;
+use std::net::SocketAddr;
+
+/// Serve conductor and network metrics over HTTP, in the OpenMetrics text
+/// format, so they can be scraped by Prometheus or a compatible collector.
+///
+/// The metrics are served at `/metrics` on the given address. There is no
+/// authentication, so this should only be bound to an address which is not
+/// reachable from outside the host, unless it is protected some other way.
+#[derive(Clone, Deserialize, Serialize, Debug, PartialEq, Eq)]
+pub struct MetricsEndpointConfig {
+    /// The address to listen on, e.g. `127.0.0.1:9100`.
+    pub bind_to: SocketAddr,
+}
@@ -1,11 +1,15 @@
 use serde::Deserialize;
 use serde::Serialize;
+use std::path::PathBuf;
+
+pub use holochain_websocket::AllowedOrigins;
 
 /// Information neeeded to spawn an admin interface
 #[derive(Clone, Deserialize, Serialize, Debug, PartialEq)]
 pub struct AdminInterfaceConfig {
     /// By what means the interface will be exposed.
-    /// Currently the only option is a local websocket running on a configurable port.
+    /// Either a local websocket running on a configurable port,
+    /// or a websocket over a unix domain socket.
     pub driver: InterfaceDriver,
     // How long will this interface be accessible between authentications?
     // TODO: implement once we have authentication
@@ -29,13 +33,22 @@ pub enum InterfaceDriver {
         /// The port on which to establish the WebsocketListener
         port: u16,
     },
+    /// An interface implemented via websockets over a unix domain socket.
+    /// Only the user running the conductor can connect to the socket, so
+    /// access to the interface can be controlled with filesystem permissions.
+    /// Only supported on unix platforms.
+    UnixSocket {
+        /// The path at which to create the socket file
+        path: PathBuf,
+    },
 }
 
 impl InterfaceDriver {
-    /// Get the port for this driver.
-    pub fn port(&self) -> u16 {
+    /// Get the port for this driver, if it listens on a port.
+    pub fn port(&self) -> Option<u16> {
         match self {
-            InterfaceDriver::Websocket { port } => *port,
+            InterfaceDriver::Websocket { port } => Some(*port),
+            InterfaceDriver::UnixSocket { .. } => None,
         }
     }
 }
@@ -1,16 +1,19 @@
 use holochain_serialized_bytes::prelude::*;
 use holochain_types::app::InstalledAppId;
 use holochain_zome_types::cell::CellId;
+use holochain_zome_types::signal::AppSignal;
+use holochain_zome_types::zome::ZomeName;
 use std::collections::HashMap;
+use std::collections::HashSet;
 
 /// Declares updated Signal subscription settings for an App.
 /// This message is part of the AppInterfaceApi
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
 pub struct SignalSubscription {
     /// The app for which to manage subscription
-    installed_app_id: InstalledAppId,
+    pub installed_app_id: InstalledAppId,
     /// Fine-grained per-cell filters
-    filters: SignalFilterSet,
+    pub filters: SignalFilterSet,
 }
 
 /// Associate a SignalFilter with each Cell in an App.
@@ -46,11 +49,43 @@ impl SignalFilterSet {
     pub fn block_all() -> Self {
         SignalFilterSet::Include(HashMap::new())
     }
+
+    /// The cells which have a filter in this set
+    pub fn cell_ids(&self) -> impl Iterator<Item = &CellId> {
+        match self {
+            Self::Include(filters) | Self::Exclude(filters) => filters.keys(),
+        }
+    }
+
+    /// Whether a signal emitted by a zome of a cell gets through this set
+    pub fn allows(&self, cell_id: &CellId, zome_name: &ZomeName, signal: &AppSignal) -> bool {
+        match self {
+            Self::Include(filters) => filters
+                .get(cell_id)
+                .map_or(false, |filter| filter.matches(zome_name, signal)),
+            Self::Exclude(filters) => !filters
+                .get(cell_id)
+                .map_or(false, |filter| filter.matches(zome_name, signal)),
+        }
+    }
 }
 
-/// Specifies fine-grained filter controls for the signals
+/// Specifies fine-grained filter controls for the signals.
+///
+/// A signal matches the filter if it matches every non-empty set of the filter.
 #[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
-pub struct SignalFilter;
+pub struct SignalFilter {
+    /// Match signals emitted by these zomes.
+    #[serde(default)]
+    pub zome_names: HashSet<ZomeName>,
+    /// Match signals with these type tags.
+    ///
+    /// The type tag of a signal is the `type` field of the map it was
+    /// serialized as, which is how an enum with `#[serde(tag = "type")]`
+    /// is serialized. Signals without a type tag don't match.
+    #[serde(default)]
+    pub signal_types: HashSet<String>,
+}
 
 impl Default for SignalFilter {
     fn default() -> Self {
@@ -60,8 +95,90 @@ impl Default for SignalFilter {
 }
 
 impl SignalFilter {
-    /// A passthrough filter which filters nothing
+    /// A filter which matches every signal
     pub fn empty() -> Self {
-        SignalFilter
+        SignalFilter {
+            zome_names: HashSet::new(),
+            signal_types: HashSet::new(),
+        }
+    }
+
+    /// Whether a signal emitted by a zome matches this filter
+    pub fn matches(&self, zome_name: &ZomeName, signal: &AppSignal) -> bool {
+        if !self.zome_names.is_empty() && !self.zome_names.contains(zome_name) {
+            return false;
+        }
+        if self.signal_types.is_empty() {
+            return true;
+        }
+        #[derive(Debug, serde::Deserialize)]
+        struct TypeTag {
+            #[serde(rename = "type")]
+            signal_type: String,
+        }
+        signal
+            .as_extern_io()
+            .decode::<TypeTag>()
+            .map_or(false, |tag| self.signal_types.contains(&tag.signal_type))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use holochain_zome_types::ExternIO;
+
+    #[derive(Debug, serde::Serialize)]
+    #[serde(tag = "type")]
+    enum TestSignal {
+        Ping,
+        Pong { count: u32 },
+    }
+
+    fn signal<T: serde::Serialize + std::fmt::Debug>(t: T) -> AppSignal {
+        AppSignal::new(ExternIO::encode(t).unwrap())
+    }
+
+    #[test]
+    fn filters_match_zomes_and_type_tags() {
+        let zome: ZomeName = "chat".into();
+        let other_zome: ZomeName = "profiles".into();
+        let ping = signal(TestSignal::Ping);
+        let pong = signal(TestSignal::Pong { count: 1 });
+        let untagged = signal("ping");
+
+        assert!(SignalFilter::empty().matches(&other_zome, &untagged));
+
+        let filter = SignalFilter {
+            zome_names: [zome.clone()].into(),
+            signal_types: ["Pong".to_string()].into(),
+        };
+        assert!(filter.matches(&zome, &pong));
+        assert!(!filter.matches(&zome, &ping));
+        assert!(!filter.matches(&zome, &untagged));
+        assert!(!filter.matches(&other_zome, &pong));
+
+        let cell_id = CellId::new(
+            holo_hash::DnaHash::from_raw_36(vec![0; 36]),
+            holo_hash::AgentPubKey::from_raw_36(vec![0; 36]),
+        );
+        let other_cell_id = CellId::new(
+            holo_hash::DnaHash::from_raw_36(vec![1; 36]),
+            holo_hash::AgentPubKey::from_raw_36(vec![0; 36]),
+        );
+        let filters: HashMap<_, _> = [(cell_id.clone(), filter)].into();
+
+        let include = SignalFilterSet::Include(filters.clone());
+        assert!(include.allows(&cell_id, &zome, &pong));
+        assert!(!include.allows(&cell_id, &zome, &ping));
+        assert!(!include.allows(&other_cell_id, &zome, &pong));
+
+        let exclude = SignalFilterSet::Exclude(filters);
+        assert!(!exclude.allows(&cell_id, &zome, &pong));
+        assert!(exclude.allows(&cell_id, &zome, &ping));
+        assert!(exclude.allows(&other_cell_id, &zome, &pong));
+
+        assert!(SignalFilterSet::allow_all().allows(&cell_id, &zome, &ping));
+        assert!(!SignalFilterSet::block_all().allows(&cell_id, &zome, &ping));
     }
 }
@@ -613,6 +613,14 @@ impl EntryType {
             EntryType::CapGrant => &EntryVisibility::Private,
         }
     }
+
+    /// The zome which defines this entry type, if it is an app entry type.
+    pub fn zome_index(&self) -> Option<ZomeIndex> {
+        match self {
+            EntryType::App(app_entry_def) => Some(app_entry_def.zome_index()),
+            _ => None,
+        }
+    }
 }
 
 impl std::fmt::Display for EntryType {
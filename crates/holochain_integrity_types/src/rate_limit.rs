@@ -1,5 +1,7 @@
 //! Rate limiting data types
 
+use std::collections::BTreeMap;
+
 use holochain_serialized_bytes::prelude::*;
 
 use crate::{Create, CreateLink, Delete, Entry, Update};
@@ -91,6 +93,16 @@ impl Default for EntryRateWeight {
     }
 }
 
+impl From<RateWeight> for EntryRateWeight {
+    fn from(w: RateWeight) -> Self {
+        Self {
+            bucket_id: w.bucket_id,
+            units: w.units,
+            rate_bytes: 0,
+        }
+    }
+}
+
 impl From<EntryRateWeight> for RateWeight {
     fn from(w: EntryRateWeight) -> Self {
         Self {
@@ -99,3 +111,40 @@ impl From<EntryRateWeight> for RateWeight {
         }
     }
 }
+
+/// The limits of a rate limiting bucket.
+///
+/// Each action weighed into the bucket fills it by the action's units, and
+/// the bucket steadily drains over time. An action which fills the bucket
+/// beyond its capacity is invalid.
+#[derive(
+    Debug, Clone, Copy, serde::Serialize, serde::Deserialize, PartialEq, Eq, SerializedBytes, Hash,
+)]
+#[cfg_attr(feature = "arbitrary", derive(arbitrary::Arbitrary))]
+pub struct RateLimit {
+    /// The most units the bucket can hold.
+    pub capacity: RateBucketCapacity,
+    /// The units which drain from the bucket every `drain_interval_ms`.
+    /// A bucket which never drains limits the total units on a chain.
+    pub drain_amount: RateBucketCapacity,
+    /// How often `drain_amount` units drain from the bucket, in milliseconds.
+    pub drain_interval_ms: u32,
+}
+
+/// The limits of each rate limiting bucket declared by a DNA.
+pub type RateLimits = BTreeMap<RateBucketId, RateLimit>;
+
+/// The result of the `rate_limits` callback of an integrity zome, which
+/// declares the rate limiting buckets of the DNA.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub enum RateLimitsCallbackResult {
+    /// The limits of each bucket declared by the zome.
+    Limits(RateLimits),
+}
+
+/// The result of the `weigh` callback of an integrity zome.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
+pub enum WeighCallbackResult {
+    /// The weight of the action.
+    Weight(RateWeight),
+}
@@ -94,6 +94,9 @@ pub trait HolochainP2pDnaT {
         op: DhtOp,
     ) -> actor::HolochainP2pResult<()>;
 
+    /// Publish a warrant to the authorities of the warrantee's agent activity.
+    async fn publish_warrant(&self, warrant: SignedWarrant) -> actor::HolochainP2pResult<()>;
+
     /// Get an entry from the DHT.
     async fn get(
         &self,
@@ -115,6 +118,13 @@ pub trait HolochainP2pDnaT {
         options: actor::GetLinksOptions,
     ) -> actor::HolochainP2pResult<Vec<WireLinkOps>>;
 
+    /// Count links on the DHT, getting only the hashes of the links.
+    async fn count_links(
+        &self,
+        link_key: WireLinkKey,
+        options: actor::GetLinksOptions,
+    ) -> actor::HolochainP2pResult<Vec<WireLinkHashes>>;
+
     /// Get agent activity from the DHT.
     async fn get_agent_activity(
         &self,
@@ -288,6 +298,14 @@ impl HolochainP2pDnaT for HolochainP2pDna {
             .await
     }
 
+    /// Publish a warrant to the authorities of the warrantee's agent activity.
+    async fn publish_warrant(&self, warrant: SignedWarrant) -> actor::HolochainP2pResult<()> {
+        let basis_hash = warrant.warrant.warrantee().clone().into();
+        self.sender
+            .publish_warrant((*self.dna_hash).clone(), basis_hash, warrant)
+            .await
+    }
+
     /// Get [`DhtOp::StoreRecord`] or [`DhtOp::StoreEntry`] from the DHT.
     async fn get(
         &self,
@@ -322,6 +340,17 @@ impl HolochainP2pDnaT for HolochainP2pDna {
             .await
     }
 
+    /// Count links on the DHT, getting only the hashes of the links.
+    async fn count_links(
+        &self,
+        link_key: WireLinkKey,
+        options: actor::GetLinksOptions,
+    ) -> actor::HolochainP2pResult<Vec<WireLinkHashes>> {
+        self.sender
+            .count_links((*self.dna_hash).clone(), link_key, options)
+            .await
+    }
+
     /// Get agent activity from the DHT.
     async fn get_agent_activity(
         &self,
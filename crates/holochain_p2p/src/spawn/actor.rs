@@ -11,6 +11,7 @@ use kitsune_p2p::gossip::sharded_gossip::KitsuneDiagnostics;
 use kitsune_p2p::KOp;
 use kitsune_p2p::KitsuneOpData;
 use kitsune_p2p_fetch::FetchContext;
+use kitsune_p2p_types::config::{KitsuneP2pTuningParams, TuningParamsPatch};
 
 use crate::types::AgentPubKeyExt;
 
@@ -160,6 +161,17 @@ impl WrapEvtSender {
         }, %op_count, "(hp2p:handle) publish")
     }
 
+    fn publish_warrant(
+        &self,
+        dna_hash: DnaHash,
+        warrant: SignedWarrant,
+    ) -> impl Future<Output = HolochainP2pResult<()>> + 'static + Send {
+        timing_trace!(
+            { self.0.publish_warrant(dna_hash, warrant) },
+            "(hp2p:handle) publish_warrant",
+        )
+    }
+
     fn get(
         &self,
         dna_hash: DnaHash,
@@ -199,6 +211,19 @@ impl WrapEvtSender {
         )
     }
 
+    fn count_links(
+        &self,
+        dna_hash: DnaHash,
+        to_agent: AgentPubKey,
+        link_key: WireLinkKey,
+        options: event::GetLinksOptions,
+    ) -> impl Future<Output = HolochainP2pResult<WireLinkHashes>> + 'static + Send {
+        timing_trace!(
+            { self.0.count_links(dna_hash, to_agent, link_key, options) },
+            "(hp2p:handle) count_links",
+        )
+    }
+
     fn get_agent_activity(
         &self,
         dna_hash: DnaHash,
@@ -316,7 +341,9 @@ impl WrapEvtSender {
 }
 
 pub(crate) struct HolochainP2pActor {
-    tuning_params: kitsune_p2p_types::config::KitsuneP2pTuningParams,
+    /// Only network wide params are kept up to date,
+    /// as they are the only ones used here which can change at runtime.
+    tuning_params: kitsune_p2p_types::config::LiveTuningParams,
     evt_sender: WrapEvtSender,
     kitsune_p2p: ghost_actor::GhostSender<kitsune_p2p::actor::KitsuneP2p>,
     host: kitsune_p2p::HostApi,
@@ -344,7 +371,7 @@ impl HolochainP2pActor {
         evt_sender: futures::channel::mpsc::Sender<HolochainP2pEvent>,
         host: kitsune_p2p::HostApi,
     ) -> HolochainP2pResult<Self> {
-        let tuning_params = config.tuning_params.clone();
+        let tuning_params = config.tuning_params.clone().into();
         let (kitsune_p2p, kitsune_p2p_events) =
             kitsune_p2p::spawn_kitsune_p2p(config, tls_config, host.clone()).await?;
 
@@ -459,6 +486,27 @@ impl HolochainP2pActor {
         .into())
     }
 
+    /// receiving an incoming count_links request from a remote node
+    fn handle_incoming_count_links(
+        &mut self,
+        dna_hash: DnaHash,
+        to_agent: AgentPubKey,
+        link_key: WireLinkKey,
+        options: event::GetLinksOptions,
+    ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<Vec<u8>> {
+        let evt_sender = self.evt_sender.clone();
+        Ok(async move {
+            let res = evt_sender
+                .count_links(dna_hash, to_agent, link_key, options)
+                .await;
+            res.and_then(|r| Ok(SerializedBytes::try_from(r)?))
+                .map_err(kitsune_p2p::KitsuneP2pError::from)
+                .map(|res| UnsafeBytes::from(res).into())
+        }
+        .boxed()
+        .into())
+    }
+
     /// receiving an incoming get_links request from a remote node
     fn handle_incoming_get_agent_activity(
         &mut self,
@@ -526,6 +574,21 @@ impl HolochainP2pActor {
         .into())
     }
 
+    /// receiving an incoming warrant from a remote node
+    fn handle_incoming_publish_warrant(
+        &mut self,
+        dna_hash: DnaHash,
+        warrant: SignedWarrant,
+    ) -> kitsune_p2p::actor::KitsuneP2pHandlerResult<()> {
+        let evt_sender = self.evt_sender.clone();
+        Ok(async move {
+            evt_sender.publish_warrant(dna_hash, warrant).await?;
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
+
     /// receiving an incoming validation receipt from a remote node
     fn handle_incoming_validation_receipt(
         &mut self,
@@ -724,6 +787,9 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
             crate::wire::WireMessage::GetLinks { link_key, options } => {
                 self.handle_incoming_get_links(space, to_agent, link_key, options)
             }
+            crate::wire::WireMessage::CountLinks { link_key, options } => {
+                self.handle_incoming_count_links(space, to_agent, link_key, options)
+            }
             crate::wire::WireMessage::GetAgentActivity {
                 agent,
                 query,
@@ -743,6 +809,12 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
                 )
                 .into())
             }
+            crate::wire::WireMessage::PublishWarrant { .. } => {
+                Err(HolochainP2pError::invalid_p2p_message(
+                    "invalid: warrants are broadcast, not requests".to_string(),
+                )
+                .into())
+            }
         }
     }
 
@@ -765,6 +837,7 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
             crate::wire::WireMessage::Get { .. }
             | crate::wire::WireMessage::GetMeta { .. }
             | crate::wire::WireMessage::GetLinks { .. }
+            | crate::wire::WireMessage::CountLinks { .. }
             | crate::wire::WireMessage::GetAgentActivity { .. }
             | crate::wire::WireMessage::MustGetAgentActivity { .. }
             | crate::wire::WireMessage::ValidationReceipt { .. } => {
@@ -830,6 +903,9 @@ impl kitsune_p2p::event::KitsuneP2pEventHandler for HolochainP2pActor {
             crate::wire::WireMessage::PublishCountersign { flag, op } => {
                 self.handle_incoming_publish(space, false, flag, vec![op])
             }
+            crate::wire::WireMessage::PublishWarrant { warrant } => {
+                self.handle_incoming_publish_warrant(space, warrant)
+            }
         }
     }
 
@@ -1041,7 +1117,7 @@ impl HolochainP2pHandler for HolochainP2pActor {
         )
         .encode()?;
 
-        let timeout = self.tuning_params.implicit_timeout();
+        let timeout = self.tuning_params.get().implicit_timeout();
 
         let kitsune_p2p = self.kitsune_p2p.clone();
         Ok(async move {
@@ -1073,7 +1149,7 @@ impl HolochainP2pHandler for HolochainP2pActor {
         let basis = basis_hash.to_kitsune();
         let timeout = match timeout_ms {
             Some(ms) => KitsuneTimeout::from_millis(ms),
-            None => self.tuning_params.implicit_timeout(),
+            None => self.tuning_params.get().implicit_timeout(),
         };
 
         let fetch_context = FetchContext::default()
@@ -1136,7 +1212,7 @@ impl HolochainP2pHandler for HolochainP2pActor {
     ) -> HolochainP2pHandlerResult<()> {
         let space = dna_hash.into_kitsune();
         let basis = basis_hash.to_kitsune();
-        let timeout = self.tuning_params.implicit_timeout();
+        let timeout = self.tuning_params.get().implicit_timeout();
 
         let kitsune_p2p = self.kitsune_p2p.clone();
         Ok(async move {
@@ -1151,6 +1227,30 @@ impl HolochainP2pHandler for HolochainP2pActor {
         .into())
     }
 
+    #[tracing::instrument(skip(self), level = "trace")]
+    fn handle_publish_warrant(
+        &mut self,
+        dna_hash: DnaHash,
+        basis_hash: holo_hash::OpBasis,
+        warrant: SignedWarrant,
+    ) -> HolochainP2pHandlerResult<()> {
+        let space = dna_hash.into_kitsune();
+        let basis = basis_hash.to_kitsune();
+        let timeout = self.tuning_params.get().implicit_timeout();
+
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(async move {
+            let payload = crate::wire::WireMessage::publish_warrant(warrant).encode()?;
+
+            kitsune_p2p
+                .broadcast(space, basis, timeout, BroadcastData::User(payload))
+                .await?;
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
+
     #[tracing::instrument(skip(self, dna_hash, dht_hash, options), level = "trace")]
     fn handle_get(
         &mut self,
@@ -1165,7 +1265,7 @@ impl HolochainP2pHandler for HolochainP2pActor {
         let payload = crate::wire::WireMessage::get(dht_hash, r_options).encode()?;
 
         let kitsune_p2p = self.kitsune_p2p.clone();
-        let tuning_params = self.tuning_params.clone();
+        let tuning_params = self.tuning_params.get();
         Ok(async move {
             let input = kitsune_p2p::actor::RpcMulti::new(&tuning_params, space, basis, payload);
             let result = kitsune_p2p
@@ -1199,7 +1299,7 @@ impl HolochainP2pHandler for HolochainP2pActor {
         let payload = crate::wire::WireMessage::get_meta(dht_hash, r_options).encode()?;
 
         let kitsune_p2p = self.kitsune_p2p.clone();
-        let tuning_params = self.tuning_params.clone();
+        let tuning_params = self.tuning_params.get();
         Ok(async move {
             let input = kitsune_p2p::actor::RpcMulti::new(&tuning_params, space, basis, payload);
             let result = kitsune_p2p.rpc_multi(input).await?;
@@ -1230,7 +1330,7 @@ impl HolochainP2pHandler for HolochainP2pActor {
         let payload = crate::wire::WireMessage::get_links(link_key, r_options).encode()?;
 
         let kitsune_p2p = self.kitsune_p2p.clone();
-        let tuning_params = self.tuning_params.clone();
+        let tuning_params = self.tuning_params.get();
         Ok(async move {
             let mut input =
                 kitsune_p2p::actor::RpcMulti::new(&tuning_params, space, basis, payload);
@@ -1252,6 +1352,40 @@ impl HolochainP2pHandler for HolochainP2pActor {
         .into())
     }
 
+    #[tracing::instrument(skip(self), level = "trace")]
+    fn handle_count_links(
+        &mut self,
+        dna_hash: DnaHash,
+        link_key: WireLinkKey,
+        options: actor::GetLinksOptions,
+    ) -> HolochainP2pHandlerResult<Vec<WireLinkHashes>> {
+        let space = dna_hash.into_kitsune();
+        let basis = link_key.base.to_kitsune();
+        let r_options: event::GetLinksOptions = (&options).into();
+
+        let payload = crate::wire::WireMessage::count_links(link_key, r_options).encode()?;
+
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        let tuning_params = self.tuning_params.get();
+        Ok(async move {
+            let mut input =
+                kitsune_p2p::actor::RpcMulti::new(&tuning_params, space, basis, payload);
+            // Like get_links, only a single remote node is targeted.
+            input.max_remote_agent_count = 1;
+            let result = kitsune_p2p.rpc_multi(input).await?;
+
+            let mut out = Vec::new();
+            for item in result {
+                let kitsune_p2p::actor::RpcMultiResponse { response, .. } = item;
+                out.push(SerializedBytes::from(UnsafeBytes::from(response)).try_into()?);
+            }
+
+            Ok(out)
+        }
+        .boxed()
+        .into())
+    }
+
     #[tracing::instrument(skip(self), level = "trace")]
     fn handle_get_agent_activity(
         &mut self,
@@ -1271,7 +1405,7 @@ impl HolochainP2pHandler for HolochainP2pActor {
             crate::wire::WireMessage::get_agent_activity(agent, query, r_options).encode()?;
 
         let kitsune_p2p = self.kitsune_p2p.clone();
-        let tuning_params = self.tuning_params.clone();
+        let tuning_params = self.tuning_params.get();
         Ok(async move {
             let mut input =
                 kitsune_p2p::actor::RpcMulti::new(&tuning_params, space, basis, payload);
@@ -1309,7 +1443,7 @@ impl HolochainP2pHandler for HolochainP2pActor {
         let payload = crate::wire::WireMessage::must_get_agent_activity(agent, filter).encode()?;
 
         let kitsune_p2p = self.kitsune_p2p.clone();
-        let tuning_params = self.tuning_params.clone();
+        let tuning_params = self.tuning_params.get();
         Ok(async move {
             let mut input =
                 kitsune_p2p::actor::RpcMulti::new(&tuning_params, space, basis, payload);
@@ -1391,7 +1525,7 @@ impl HolochainP2pHandler for HolochainP2pActor {
         let space = dna_hash.into_kitsune();
         let agents = agents.into_iter().map(|a| a.into_kitsune()).collect();
 
-        let timeout = self.tuning_params.implicit_timeout();
+        let timeout = self.tuning_params.get().implicit_timeout();
 
         let payload =
             crate::wire::WireMessage::countersigning_session_negotiation(message).encode()?;
@@ -1436,4 +1570,63 @@ impl HolochainP2pHandler for HolochainP2pActor {
         .boxed()
         .into())
     }
+
+    fn handle_get_gossip_throttle(
+        &mut self,
+    ) -> HolochainP2pHandlerResult<kitsune_p2p::GossipThrottleStatus> {
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(async move { Ok(kitsune_p2p.get_gossip_throttle().await?) }
+            .boxed()
+            .into())
+    }
+
+    fn handle_set_gossip_throttle(
+        &mut self,
+        config: kitsune_p2p::GossipThrottleConfig,
+    ) -> HolochainP2pHandlerResult<()> {
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(
+            async move { Ok(kitsune_p2p.set_gossip_throttle(config).await?) }
+                .boxed()
+                .into(),
+        )
+    }
+
+    fn handle_get_tuning_params(
+        &mut self,
+        dna_hash: DnaHash,
+    ) -> HolochainP2pHandlerResult<KitsuneP2pTuningParams> {
+        let space = dna_hash.into_kitsune();
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        Ok(
+            async move { Ok(kitsune_p2p.get_tuning_params(space).await?) }
+                .boxed()
+                .into(),
+        )
+    }
+
+    fn handle_update_tuning_params(
+        &mut self,
+        dna_hash: Option<DnaHash>,
+        patch: TuningParamsPatch,
+    ) -> HolochainP2pHandlerResult<()> {
+        let space = dna_hash.map(|h| h.into_kitsune());
+        let kitsune_p2p = self.kitsune_p2p.clone();
+        let tuning_params = self.tuning_params.clone();
+        Ok(async move {
+            let network_wide = space.is_none();
+            kitsune_p2p
+                .update_tuning_params(space, patch.clone())
+                .await?;
+            if network_wide {
+                // Kitsune has accepted the patch, so it applies to our params too.
+                tuning_params
+                    .patch(&patch)
+                    .map_err(HolochainP2pError::other)?;
+            }
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
 }
@@ -85,6 +85,15 @@ impl HolochainP2pHandler for StubNetwork {
         Err("stub".into())
     }
 
+    fn handle_publish_warrant(
+        &mut self,
+        dna_hash: DnaHash,
+        basis_hash: holo_hash::OpBasis,
+        warrant: SignedWarrant,
+    ) -> HolochainP2pHandlerResult<()> {
+        Err("stub".into())
+    }
+
     fn handle_get(
         &mut self,
         dna_hash: DnaHash,
@@ -112,6 +121,15 @@ impl HolochainP2pHandler for StubNetwork {
         Err("stub".into())
     }
 
+    fn handle_count_links(
+        &mut self,
+        dna_hash: DnaHash,
+        link_key: WireLinkKey,
+        options: actor::GetLinksOptions,
+    ) -> HolochainP2pHandlerResult<Vec<WireLinkHashes>> {
+        Err("stub".into())
+    }
+
     fn handle_get_agent_activity(
         &mut self,
         dna_hash: DnaHash,
@@ -173,6 +191,34 @@ impl HolochainP2pHandler for StubNetwork {
     ) -> HolochainP2pHandlerResult<kitsune_p2p::gossip::sharded_gossip::KitsuneDiagnostics> {
         Err("stub".into())
     }
+
+    fn handle_get_gossip_throttle(
+        &mut self,
+    ) -> HolochainP2pHandlerResult<kitsune_p2p::GossipThrottleStatus> {
+        Err("stub".into())
+    }
+
+    fn handle_set_gossip_throttle(
+        &mut self,
+        config: kitsune_p2p::GossipThrottleConfig,
+    ) -> HolochainP2pHandlerResult<()> {
+        Err("stub".into())
+    }
+
+    fn handle_get_tuning_params(
+        &mut self,
+        dna_hash: DnaHash,
+    ) -> HolochainP2pHandlerResult<kitsune_p2p_types::config::KitsuneP2pTuningParams> {
+        Err("stub".into())
+    }
+
+    fn handle_update_tuning_params(
+        &mut self,
+        dna_hash: Option<DnaHash>,
+        patch: kitsune_p2p_types::config::TuningParamsPatch,
+    ) -> HolochainP2pHandlerResult<()> {
+        Err("stub".into())
+    }
 }
 
 /// Spawn a stub network that doesn't respond to any messages.
@@ -639,11 +685,7 @@ mod tests {
             b"eeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeeee".to_vec(),
             holo_hash::hash_type::Entry,
         );
-        let link_key = WireLinkKey {
-            base: hash.into(),
-            type_query: LinkTypeFilter::single_dep(0.into()),
-            tag: None,
-        };
+        let link_key = WireLinkKey::new(hash.into(), LinkTypeFilter::single_dep(0.into()), None);
 
         let res = p2p
             .get_links(dna, link_key, crate::actor::GetLinksOptions::default())
@@ -7,6 +7,7 @@ use holochain_types::activity::AgentActivityResponse;
 use kitsune_p2p::dependencies::kitsune_p2p_fetch::FetchContext;
 use kitsune_p2p::dependencies::kitsune_p2p_fetch::OpHashSized;
 use kitsune_p2p::gossip::sharded_gossip::KitsuneDiagnostics;
+use kitsune_p2p_types::config::{KitsuneP2pTuningParams, TuningParamsPatch};
 
 /// Holochain-specific FetchContext extension trait.
 pub trait FetchContextExt {
@@ -312,6 +313,13 @@ ghost_actor::ghost_chan! {
             op: DhtOp,
         ) -> ();
 
+        /// Publish a warrant to the authorities of the warrantee's agent activity.
+        fn publish_warrant(
+            dna_hash: DnaHash,
+            basis_hash: holo_hash::OpBasis,
+            warrant: SignedWarrant,
+        ) -> ();
+
         /// Get an entry from the DHT.
         fn get(
             dna_hash: DnaHash,
@@ -333,6 +341,13 @@ ghost_actor::ghost_chan! {
             options: GetLinksOptions,
         ) -> Vec<WireLinkOps>;
 
+        /// Count links on the DHT, getting only the hashes of the links.
+        fn count_links(
+            dna_hash: DnaHash,
+            link_key: WireLinkKey,
+            options: GetLinksOptions,
+        ) -> Vec<WireLinkHashes>;
+
         /// Get agent activity from the DHT.
         fn get_agent_activity(
             dna_hash: DnaHash,
@@ -371,6 +386,19 @@ ghost_actor::ghost_chan! {
 
         /// Get struct for diagnostic data
         fn get_diagnostics(dna_hash: DnaHash) -> KitsuneDiagnostics;
+
+        /// Get the current gossip bandwidth throttling.
+        fn get_gossip_throttle() -> kitsune_p2p::GossipThrottleStatus;
+
+        /// Replace the gossip bandwidth throttling configuration.
+        fn set_gossip_throttle(config: kitsune_p2p::GossipThrottleConfig) -> ();
+
+        /// Get the tuning params currently in effect for a DNA.
+        fn get_tuning_params(dna_hash: DnaHash) -> KitsuneP2pTuningParams;
+
+        /// Change some tuning params while the network is running,
+        /// for a single DNA, or for every DNA if none is given.
+        fn update_tuning_params(dna_hash: Option<DnaHash>, patch: TuningParamsPatch) -> ();
     }
 }
 
@@ -195,6 +195,13 @@ ghost_actor::ghost_chan! {
             ops: Vec<holochain_types::dht_op::DhtOp>,
         ) -> ();
 
+        /// A remote node is publishing a warrant against an agent whose
+        /// activity we claim to be holding.
+        fn publish_warrant(
+            dna_hash: DnaHash,
+            warrant: SignedWarrant,
+        ) -> ();
+
         /// A remote node is requesting entry data from us.
         fn get(
             dna_hash: DnaHash,
@@ -219,6 +226,14 @@ ghost_actor::ghost_chan! {
             options: GetLinksOptions,
         ) -> WireLinkOps;
 
+        /// A remote node is requesting the hashes of links from us.
+        fn count_links(
+            dna_hash: DnaHash,
+            to_agent: AgentPubKey,
+            link_key: WireLinkKey,
+            options: GetLinksOptions,
+        ) -> WireLinkHashes;
+
         /// A remote node is requesting agent activity from us.
         fn get_agent_activity(
             dna_hash: DnaHash,
@@ -288,6 +303,7 @@ macro_rules! match_p2p_evt {
             HolochainP2pEvent::Get { $i, .. } => { $($t)* }
             HolochainP2pEvent::GetMeta { $i, .. } => { $($t)* }
             HolochainP2pEvent::GetLinks { $i, .. } => { $($t)* }
+            HolochainP2pEvent::CountLinks { $i, .. } => { $($t)* }
             HolochainP2pEvent::GetAgentActivity { $i, .. } => { $($t)* }
             HolochainP2pEvent::MustGetAgentActivity { $i, .. } => { $($t)* }
             HolochainP2pEvent::ValidationReceiptReceived { $i, .. } => { $($t)* }
@@ -303,6 +319,7 @@ impl HolochainP2pEvent {
     pub fn dna_hash(&self) -> &DnaHash {
         match_p2p_evt!(self => |dna_hash| { dna_hash }, {
             HolochainP2pEvent::Publish { dna_hash, .. } => { dna_hash }
+            HolochainP2pEvent::PublishWarrant { dna_hash, .. } => { dna_hash }
             HolochainP2pEvent::FetchOpData { dna_hash, .. } => { dna_hash }
             HolochainP2pEvent::QueryOpHashes { dna_hash, .. } => { dna_hash }
             HolochainP2pEvent::QueryAgentInfoSigned { dna_hash, .. } => { dna_hash }
@@ -317,6 +334,7 @@ impl HolochainP2pEvent {
     pub fn target_agents(&self) -> &AgentPubKey {
         match_p2p_evt!(self => |to_agent| { to_agent }, {
             HolochainP2pEvent::Publish { .. } => { unimplemented!("There is no single agent target for Publish") }
+            HolochainP2pEvent::PublishWarrant { .. } => { unimplemented!("There is no single agent target for PublishWarrant") }
             HolochainP2pEvent::FetchOpData { .. } => { unimplemented!("There is no single agent target for FetchOpData") }
             HolochainP2pEvent::QueryOpHashes { .. } => { unimplemented!("There is no single agent target for QueryOpHashes") }
             HolochainP2pEvent::QueryAgentInfoSigned { .. } => { unimplemented!("There is no single agent target for QueryAgentInfoSigned") },
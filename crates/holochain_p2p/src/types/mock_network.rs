@@ -344,13 +344,13 @@ impl HolochainP2pMockMsg {
                 | crate::wire::WireMessage::Get { .. }
                 | crate::wire::WireMessage::GetMeta { .. }
                 | crate::wire::WireMessage::GetLinks { .. }
+                | crate::wire::WireMessage::CountLinks { .. }
                 | crate::wire::WireMessage::GetAgentActivity { .. }
                 | crate::wire::WireMessage::PublishCountersign { .. }
                 | crate::wire::WireMessage::MustGetAgentActivity { .. } => next_msg_id().as_req(),
 
-                crate::wire::WireMessage::CountersigningSessionNegotiation { .. } => {
-                    MsgId::new_notify()
-                }
+                crate::wire::WireMessage::CountersigningSessionNegotiation { .. }
+                | crate::wire::WireMessage::PublishWarrant { .. } => MsgId::new_notify(),
             },
             HolochainP2pMockMsg::PeerGet(_) | HolochainP2pMockMsg::PeerQuery(_) => {
                 next_msg_id().as_req()
@@ -373,9 +373,11 @@ impl HolochainP2pMockMsg {
                     | crate::wire::WireMessage::Get { .. }
                     | crate::wire::WireMessage::GetMeta { .. }
                     | crate::wire::WireMessage::GetLinks { .. }
+                    | crate::wire::WireMessage::CountLinks { .. }
                     | crate::wire::WireMessage::GetAgentActivity { .. }
                     | crate::wire::WireMessage::MustGetAgentActivity { .. } => true,
                     crate::wire::WireMessage::PublishCountersign { .. }
+                    | crate::wire::WireMessage::PublishWarrant { .. }
                     | crate::wire::WireMessage::CountersigningSessionNegotiation { .. } => false,
                 };
                 let to_agent = to_agent.to_kitsune();
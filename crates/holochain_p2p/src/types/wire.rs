@@ -64,6 +64,10 @@ pub enum WireMessage {
         link_key: WireLinkKey,
         options: event::GetLinksOptions,
     },
+    CountLinks {
+        link_key: WireLinkKey,
+        options: event::GetLinksOptions,
+    },
     GetAgentActivity {
         agent: AgentPubKey,
         query: ChainQueryFilter,
@@ -80,6 +84,9 @@ pub enum WireMessage {
         flag: bool,
         op: DhtOp,
     },
+    PublishWarrant {
+        warrant: SignedWarrant,
+    },
 }
 
 #[allow(missing_docs)]
@@ -96,6 +103,10 @@ impl WireMessage {
         Self::PublishCountersign { flag, op }
     }
 
+    pub fn publish_warrant(warrant: SignedWarrant) -> WireMessage {
+        Self::PublishWarrant { warrant }
+    }
+
     /// For an outgoing remote call.
     #[allow(clippy::too_many_arguments)]
     pub fn call_remote(
@@ -166,6 +177,10 @@ impl WireMessage {
         Self::GetLinks { link_key, options }
     }
 
+    pub fn count_links(link_key: WireLinkKey, options: event::GetLinksOptions) -> WireMessage {
+        Self::CountLinks { link_key, options }
+    }
+
     pub fn get_agent_activity(
         agent: AgentPubKey,
         query: ChainQueryFilter,
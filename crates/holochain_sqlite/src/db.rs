@@ -25,6 +25,9 @@ pub use p2p_agent_store::*;
 mod p2p_metrics;
 pub use p2p_metrics::*;
 
+mod p2p_fetch_queue;
+pub use p2p_fetch_queue::*;
+
 #[async_trait::async_trait]
 /// A trait for being generic over [`DbWrite`] and [`DbRead`] that
 /// both implement read access.
@@ -108,6 +111,19 @@ pub struct DbRead<Kind: DbKindT> {
     num_readers: Arc<AtomicUsize>,
 }
 
+/// A snapshot of the usage of a database's connection pool.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DbPoolState {
+    /// Connections currently open, whether in use or idle.
+    pub connections: u32,
+    /// Open connections which aren't in use.
+    pub idle_connections: u32,
+    /// The most connections the pool will open.
+    pub max_connections: u32,
+    /// Readers waiting for a connection.
+    pub waiting_readers: usize,
+}
+
 #[derive(Shrinkwrap)]
 #[shrinkwrap(mutable)]
 pub struct PConnGuard(#[shrinkwrap(main_field)] pub PConn, OwnedSemaphorePermit);
@@ -150,6 +166,17 @@ impl<Kind: DbKindT> DbRead<Kind> {
         &self.path
     }
 
+    /// How many connections the pool holds, and how many of them are in use.
+    pub fn pool_state(&self) -> DbPoolState {
+        let state = self.connection_pool.state();
+        DbPoolState {
+            connections: state.connections,
+            idle_connections: state.idle_connections,
+            max_connections: self.connection_pool.max_size(),
+            waiting_readers: self.num_readers.load(std::sync::atomic::Ordering::Relaxed),
+        }
+    }
+
     /// Get a connection from the pool.
     /// TODO: We should eventually swap this for an async solution.
     fn connection_pooled(&self) -> DatabaseResult<PConn> {
@@ -0,0 +1,59 @@
+//! p2p_fetch_queue sql logic
+
+use crate::prelude::*;
+use crate::sql::*;
+use kitsune_p2p::dependencies::kitsune_p2p_fetch::{FetchKey, FetchQueueRecord};
+use rusqlite::*;
+
+#[cfg(test)]
+mod p2p_fetch_queue_test;
+
+/// Extension trait to treat transaction instances
+/// as accessors of the persisted fetch queue.
+pub trait AsP2pFetchQueueTxExt {
+    /// List all persisted fetch queue items, in queue order
+    fn p2p_list_fetch_queue(&self) -> DatabaseResult<Vec<FetchQueueRecord>>;
+
+    /// Persist fetch queue items, replacing any existing record with the same
+    /// key without changing its position in the queue
+    fn p2p_put_fetch_queue_items(&self, records: &[FetchQueueRecord]) -> DatabaseResult<()>;
+
+    /// Remove fetch queue items
+    fn p2p_remove_fetch_queue_items(&self, keys: &[FetchKey]) -> DatabaseResult<()>;
+}
+
+impl AsP2pFetchQueueTxExt for Transaction<'_> {
+    fn p2p_list_fetch_queue(&self) -> DatabaseResult<Vec<FetchQueueRecord>> {
+        let mut stmt = self.prepare(sql_p2p_agent_store::fetch_queue::SELECT_ALL)?;
+        let mut out = Vec::new();
+        for r in stmt.query_map([], |r| r.get::<_, Vec<u8>>(0))? {
+            out.push(rmp_serde::from_slice(&r?)?);
+        }
+        Ok(out)
+    }
+
+    fn p2p_put_fetch_queue_items(&self, records: &[FetchQueueRecord]) -> DatabaseResult<()> {
+        for record in records {
+            let key = rmp_serde::to_vec_named(&record.key)?;
+            let encoded = rmp_serde::to_vec_named(record)?;
+            let params = named_params! {
+                ":key": &key,
+                ":encoded": &encoded,
+            };
+            self.execute(sql_p2p_agent_store::fetch_queue::UPDATE, params)?;
+            self.execute(sql_p2p_agent_store::fetch_queue::INSERT, params)?;
+        }
+        Ok(())
+    }
+
+    fn p2p_remove_fetch_queue_items(&self, keys: &[FetchKey]) -> DatabaseResult<()> {
+        for key in keys {
+            let key = rmp_serde::to_vec_named(key)?;
+            self.execute(
+                sql_p2p_agent_store::fetch_queue::DELETE,
+                named_params! { ":key": &key },
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,74 @@
+use crate::prelude::*;
+use kitsune_p2p::dependencies::kitsune_p2p_fetch::{FetchKey, FetchQueueRecord, FetchSource};
+use kitsune_p2p::dependencies::kitsune_p2p_timestamp::Timestamp;
+use kitsune_p2p::{KitsuneAgent, KitsuneOpHash, KitsuneSpace};
+use std::sync::Arc;
+
+fn record(space: &Arc<KitsuneSpace>, n: u8, last_fetch: Option<Timestamp>) -> FetchQueueRecord {
+    FetchQueueRecord {
+        key: FetchKey::Op(Arc::new(KitsuneOpHash(vec![n; 36]))),
+        space: space.clone(),
+        sources: vec![(
+            FetchSource::Agent(Arc::new(KitsuneAgent(vec![n; 36]))),
+            last_fetch,
+        )],
+        size: Some(100.into()),
+        context: Some(1.into()),
+        last_fetch,
+    }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_p2p_fetch_queue_sanity() {
+    let tmp_dir = tempfile::Builder::new()
+        .prefix("p2p_fetch_queue_sanity")
+        .tempdir()
+        .unwrap();
+
+    let space = Arc::new(KitsuneSpace(vec![0; 36]));
+
+    let db = DbWrite::test(tmp_dir.path(), DbKindP2pAgents(space.clone())).unwrap();
+
+    let items: Vec<_> = (1..=3).map(|n| record(&space, n, None)).collect();
+    let updated = record(&space, 1, Some(Timestamp::now()));
+
+    let list = db
+        .async_commit({
+            let items = items.clone();
+            let updated = updated.clone();
+            move |txn| {
+                txn.p2p_put_fetch_queue_items(&items)?;
+                txn.p2p_put_fetch_queue_items(&[updated])?;
+                txn.p2p_remove_fetch_queue_items(&[items[1].key.clone()])?;
+                txn.p2p_list_fetch_queue()
+            }
+        })
+        .await
+        .unwrap();
+
+    // Updating an item doesn't move it to the back of the queue
+    assert_eq!(list, vec![updated, items[2].clone()]);
+
+    // clean up temp dir
+    tmp_dir.close().unwrap();
+}
+
+#[test]
+fn test_p2p_fetch_queue_in_a_database_from_before_the_queue() {
+    let space = Arc::new(KitsuneSpace(vec![0; 36]));
+    for user_version in [0u16, 1] {
+        let mut conn = rusqlite::Connection::open_in_memory().unwrap();
+        conn.execute_batch(crate::sql::sql_p2p_agent_store::SCHEMA)
+            .unwrap();
+        conn.pragma_update(None, "user_version", user_version)
+            .unwrap();
+        crate::schema::SCHEMA_P2P_STATE
+            .initialize(&mut conn, None)
+            .unwrap();
+
+        let items: Vec<_> = (1..=2).map(|n| record(&space, n, None)).collect();
+        let txn = conn.transaction().unwrap();
+        txn.p2p_put_fetch_queue_items(&items).unwrap();
+        assert_eq!(txn.p2p_list_fetch_queue().unwrap(), items);
+    }
+}
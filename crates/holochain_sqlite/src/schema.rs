@@ -6,10 +6,11 @@ use crate::sql::*;
 
 pub static SCHEMA_CELL: Lazy<Schema> = Lazy::new(|| {
     let migration_0 = Migration::initial(sql_cell::SCHEMA);
+    let migration_1 = Migration::forward(sql_cell::MIGRATION_ADD_WARRANT);
 
     Schema {
-        current_index: 0,
-        migrations: vec![migration_0],
+        current_index: 1,
+        migrations: vec![migration_0, migration_1],
     }
 });
 
@@ -33,10 +34,11 @@ pub static SCHEMA_WASM: Lazy<Schema> = Lazy::new(|| {
 
 pub static SCHEMA_P2P_STATE: Lazy<Schema> = Lazy::new(|| {
     let migration_0 = Migration::initial(sql_p2p_agent_store::SCHEMA);
+    let migration_1 = Migration::forward(sql_p2p_agent_store::MIGRATION_ADD_FETCH_QUEUE);
 
     Schema {
-        current_index: 0,
-        migrations: vec![migration_0],
+        current_index: 1,
+        migrations: vec![migration_0, migration_1],
     }
 });
 
@@ -59,6 +61,10 @@ impl Schema {
     /// The decision is based on the difference between this Schema's
     /// current_index and the user_version pragma value in the database itself.
     /// NB: The current_index is 0-based, and the user_version is 1-based.
+    ///
+    /// Databases created before the user_version was recorded also have a
+    /// user_version of 0, so the initial schema and every forward migration
+    /// must be safe to run again on a database which already has them.
     pub fn initialize(
         &self,
         conn: &mut Connection,
@@ -72,7 +78,13 @@ impl Schema {
 
         if user_version == 0 {
             // database just needs to be created / initialized
-            self.migrations[self.current_index].initialize(conn)?;
+            let txn = conn.transaction()?;
+            self.migrations[0].initialize(&txn)?;
+            for migration in &self.migrations[1..=self.current_index] {
+                migration.run(&txn)?;
+            }
+            txn.pragma_update(None, "user_version", (self.current_index + 1) as u16)?;
+            txn.commit()?;
             tracing::info!("database initialized: {}", db_kind);
             return Ok(());
         } else {
@@ -80,13 +92,15 @@ impl Schema {
             match current_index.cmp(&self.current_index) {
                 std::cmp::Ordering::Less => {
                     // run forward migrations
-                    for v in current_index..self.current_index + 1 {
-                        self.migrations[v].run(conn)?;
+                    let txn = conn.transaction()?;
+                    for migration in &self.migrations[current_index + 1..=self.current_index] {
+                        migration.run(&txn)?;
                     }
                     // set the DB user_version so that next time we don't run
                     // the same migration
                     let new_user_version = (self.current_index + 1) as u16;
-                    conn.pragma_update(None, "user_version", new_user_version)?;
+                    txn.pragma_update(None, "user_version", new_user_version)?;
+                    txn.commit()?;
                     tracing::info!(
                         "database forward migrated: {} from {} to {}",
                         db_kind,
@@ -112,7 +126,7 @@ impl Schema {
 
 pub struct Migration {
     schema: Sql,
-    _forward: Sql,
+    forward: Sql,
     _backward: Option<Sql>,
 }
 
@@ -120,19 +134,76 @@ impl Migration {
     pub fn initial(schema: &str) -> Self {
         Self {
             schema: schema.into(),
-            _forward: "".into(),
+            forward: "".into(),
+            _backward: None,
+        }
+    }
+
+    /// A migration from the previous schema version to this one.
+    pub fn forward(forward: &str) -> Self {
+        Self {
+            schema: "".into(),
+            forward: forward.into(),
             _backward: None,
         }
     }
 
-    pub fn initialize(&self, conn: &mut Connection) -> rusqlite::Result<()> {
+    pub fn initialize(&self, conn: &Connection) -> rusqlite::Result<()> {
         conn.execute_batch(&self.schema)?;
         Ok(())
     }
 
-    pub fn run(&self, _conn: &mut Connection) -> rusqlite::Result<()> {
-        unimplemented!("actual migrations not yet implemented")
+    pub fn run(&self, conn: &Connection) -> rusqlite::Result<()> {
+        conn.execute_batch(&self.forward)?;
+        Ok(())
     }
 }
 
 type Sql = String;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn has_table(conn: &Connection, name: &str) -> bool {
+        conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = ?)",
+            [name],
+            |row| row.get(0),
+        )
+        .unwrap()
+    }
+
+    fn user_version(conn: &Connection) -> u16 {
+        conn.pragma_query_value(None, "user_version", |row| row.get(0))
+            .unwrap()
+    }
+
+    #[test]
+    fn cell_databases_are_migrated() {
+        // A new database
+        let mut conn = Connection::open_in_memory().unwrap();
+        SCHEMA_CELL.initialize(&mut conn, None).unwrap();
+        assert!(has_table(&conn, "Warrant"));
+        assert_eq!(user_version(&conn), 2);
+
+        // A database created before the user_version was recorded
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(sql_cell::SCHEMA).unwrap();
+        SCHEMA_CELL.initialize(&mut conn, None).unwrap();
+        assert!(has_table(&conn, "Warrant"));
+        assert_eq!(user_version(&conn), 2);
+
+        // A database at the initial version
+        let mut conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(sql_cell::SCHEMA).unwrap();
+        conn.pragma_update(None, "user_version", 1u16).unwrap();
+        SCHEMA_CELL.initialize(&mut conn, None).unwrap();
+        assert!(has_table(&conn, "Warrant"));
+        assert_eq!(user_version(&conn), 2);
+
+        // Opening a migrated database again leaves it alone
+        SCHEMA_CELL.initialize(&mut conn, None).unwrap();
+        assert_eq!(user_version(&conn), 2);
+    }
+}
@@ -1,5 +1,7 @@
 pub mod sql_cell {
     pub(crate) const SCHEMA: &str = include_str!("sql/cell/schema.sql");
+    pub(crate) const MIGRATION_ADD_WARRANT: &str =
+        include_str!("sql/cell/migrations/add_warrant.sql");
     pub const UPDATE_INTEGRATE_DEP_ACTIVITY: &str =
         include_str!("sql/cell/update_dep_activity.sql");
     pub const ACTIVITY_INTEGRATED_UPPER_BOUND: &str =
@@ -23,6 +25,8 @@ pub mod sql_cell {
         include_str!("sql/cell/fetch_hashes/fetch_op_hashes_p2.sql");
 
     pub const FETCH_OP_REGION: &str = include_str!("sql/cell/fetch_op_region.sql");
+    pub const FETCH_OP_REGION_DATA_SINCE: &str =
+        include_str!("sql/cell/fetch_op_region_data_since.sql");
     pub const FETCH_OPS_BY_REGION: &str = include_str!("sql/cell/fetch_ops_by_region.sql");
     pub const FETCH_REGION_OP_HASHES: &str = include_str!("sql/cell/fetch_region_op_hashes.sql");
 
@@ -63,6 +67,8 @@ pub mod sql_conductor {
 
 pub(crate) mod sql_p2p_agent_store {
     pub(crate) const SCHEMA: &str = include_str!("sql/p2p_agent_store/schema.sql");
+    pub(crate) const MIGRATION_ADD_FETCH_QUEUE: &str =
+        include_str!("sql/p2p_agent_store/migrations/add_fetch_queue.sql");
     pub(crate) const INSERT: &str = include_str!("sql/p2p_agent_store/insert.sql");
     pub(crate) const SELECT_ALL: &str = include_str!("sql/p2p_agent_store/select_all.sql");
     pub(crate) const SELECT: &str = include_str!("sql/p2p_agent_store/select.sql");
@@ -73,6 +79,14 @@ pub(crate) mod sql_p2p_agent_store {
     pub(crate) const EXTRAPOLATED_COVERAGE: &str =
         include_str!("sql/p2p_agent_store/extrapolated_coverage.sql");
     pub(crate) const PRUNE: &str = include_str!("sql/p2p_agent_store/prune.sql");
+
+    pub(crate) mod fetch_queue {
+        pub(crate) const UPDATE: &str = include_str!("sql/p2p_agent_store/fetch_queue/update.sql");
+        pub(crate) const INSERT: &str = include_str!("sql/p2p_agent_store/fetch_queue/insert.sql");
+        pub(crate) const SELECT_ALL: &str =
+            include_str!("sql/p2p_agent_store/fetch_queue/select_all.sql");
+        pub(crate) const DELETE: &str = include_str!("sql/p2p_agent_store/fetch_queue/delete.sql");
+    }
 }
 
 pub(crate) mod sql_p2p_metrics {
@@ -0,0 +1,18 @@
+-- The data each op added after the given rowid contributes to the region
+-- containing it, sized the same way as in fetch_op_region.sql.
+SELECT
+  DhtOp.rowid AS rowid,
+  DhtOp.hash AS hash,
+  DhtOp.storage_center_loc AS storage_center_loc,
+  DhtOp.authored_timestamp AS authored_timestamp,
+  LENGTH(Action.blob) AS action_size,
+  CASE
+    WHEN DhtOp.type IN ('StoreEntry', 'StoreRecord') THEN IFNULL(LENGTH(Entry.blob), 0)
+    ELSE 0
+  END AS entry_size
+FROM
+  DhtOp
+  JOIN Action ON DhtOp.action_hash = Action.hash
+  LEFT JOIN Entry ON Action.entry_hash = Entry.hash
+WHERE
+  DhtOp.rowid > :last_rowid
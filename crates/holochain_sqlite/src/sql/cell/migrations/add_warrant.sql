@@ -0,0 +1,14 @@
+-- no-sql-format --
+
+-- Warrants issued against agents, either by this conductor or by other
+-- authorities which published them to us.
+CREATE TABLE IF NOT EXISTS Warrant (
+    -- Hash of the warrant author and proof, so that the same author can't
+    -- issue the same warrant twice.
+    hash            BLOB           PRIMARY KEY ON CONFLICT IGNORE,
+    author          BLOB           NOT NULL,
+    warrantee       BLOB           NOT NULL,
+    timestamp       INTEGER        NOT NULL,
+    blob            BLOB           NOT NULL
+);
+CREATE INDEX IF NOT EXISTS Warrant_warrantee_idx ON Warrant ( warrantee );
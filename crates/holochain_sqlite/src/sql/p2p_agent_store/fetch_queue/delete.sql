@@ -0,0 +1,4 @@
+DELETE FROM
+  p2p_fetch_queue
+WHERE
+  key = :key;
@@ -0,0 +1,5 @@
+-- a no-op if the item was already updated in place
+INSERT
+  OR IGNORE INTO p2p_fetch_queue (key, encoded)
+VALUES
+  (:key, :encoded);
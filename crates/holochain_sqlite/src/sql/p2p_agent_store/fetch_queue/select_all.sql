@@ -0,0 +1,7 @@
+-- select the whole queue, in order
+SELECT
+  encoded
+FROM
+  p2p_fetch_queue
+ORDER BY
+  rowid;
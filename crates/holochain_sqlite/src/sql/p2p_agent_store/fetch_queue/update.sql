@@ -0,0 +1,7 @@
+-- update the item in place, so that it keeps its position in the queue
+UPDATE
+  p2p_fetch_queue
+SET
+  encoded = :encoded
+WHERE
+  key = :key;
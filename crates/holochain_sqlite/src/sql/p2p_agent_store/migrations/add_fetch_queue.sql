@@ -0,0 +1,14 @@
+-- no-sql-format --
+
+-- ops and regions we know we are missing, persisted from the kitsune
+-- fetch queue so they survive a restart
+-- items are kept in queue order by their rowid
+CREATE TABLE IF NOT EXISTS p2p_fetch_queue (
+  rowid                   INTEGER   PRIMARY KEY UNIQUE NOT NULL,
+
+  -- encoded fetch key (op hash or region)
+  key                     BLOB      UNIQUE NOT NULL,
+
+  -- encoded item, including its sources and retry timers
+  encoded                 BLOB      NOT NULL
+);
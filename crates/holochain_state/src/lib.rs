@@ -44,6 +44,7 @@ pub mod scratch;
 pub mod source_chain;
 pub mod validation_db;
 pub mod validation_receipts;
+pub mod warrant;
 #[allow(missing_docs)]
 pub mod wasm;
 pub mod workspace;
@@ -212,6 +212,23 @@ pub fn insert_validation_receipt(
     Ok(())
 }
 
+/// Insert a [`SignedWarrant`] into the database.
+/// Returns false if the same author had already issued the same warrant.
+pub fn insert_warrant(txn: &mut Transaction, warrant: SignedWarrant) -> StateMutationResult<bool> {
+    let author = warrant.warrant.author.clone();
+    let warrantee = warrant.warrant.warrantee().clone();
+    let timestamp = warrant.warrant.timestamp;
+    let hash = blake2b_256(&to_blob(&(&author, &warrant.warrant.proof))?);
+    let changed = sql_insert!(txn, Warrant, {
+        "hash": hash,
+        "author": author,
+        "warrantee": warrantee,
+        "timestamp": timestamp,
+        "blob": to_blob(&warrant)?,
+    })?;
+    Ok(changed > 0)
+}
+
 /// Insert a [`DnaWasm`](holochain_types::prelude::DnaWasm) into the database.
 pub fn insert_wasm(txn: &mut Transaction, wasm: DnaWasmHashed) -> StateMutationResult<()> {
     let (wasm, hash) = wasm.into_inner();
@@ -1,6 +1,7 @@
 use holo_hash::*;
 use holochain_sqlite::rusqlite::named_params;
 use holochain_types::dht_op::DhtOpType;
+use holochain_types::link::WireLinkKey;
 use holochain_types::sql::ToSqlStatement;
 use holochain_zome_types::*;
 use std::fmt::Debug;
@@ -17,20 +18,47 @@ pub struct LinksQuery {
     pub base: Arc<AnyLinkableHash>,
     pub type_query: LinkTypeFilter,
     pub tag: Option<String>,
+    pub author: Option<AgentPubKey>,
+    pub after: Option<Timestamp>,
+    pub before: Option<Timestamp>,
+    pub limit: Option<u32>,
+    pub cursor: Option<(Timestamp, ActionHash)>,
     query: String,
 }
 
 impl LinksQuery {
     pub fn new(base: AnyLinkableHash, type_query: LinkTypeFilter, tag: Option<LinkTag>) -> Self {
+        Self::from_key(WireLinkKey::new(base, type_query, tag))
+    }
+
+    /// Create a query for the links matching all the constraints of a key.
+    pub fn from_key(key: WireLinkKey) -> Self {
+        let WireLinkKey {
+            base,
+            type_query,
+            tag,
+            author,
+            after,
+            before,
+            limit,
+            cursor,
+        } = key;
         let tag = tag.map(|tag| Self::tag_to_hex(&tag));
-        let create_string = Self::create_query_string(&type_query, tag.clone());
-        let delete_string = Self::delete_query_string(&type_query, tag.clone());
-        Self {
+        let mut query = Self {
             base: Arc::new(base),
             type_query,
             tag,
-            query: Self::create_query(create_string, delete_string),
-        }
+            author,
+            after,
+            before,
+            limit,
+            cursor: cursor.map(|c| (c.timestamp(), c.create_link_hash().clone())),
+            query: String::new(),
+        };
+        let create_string = query.create_query_string();
+        let delete_string = query.delete_query_string();
+        query.query = Self::create_query(create_string, delete_string);
+        query
     }
 
     pub fn tag_to_hex(tag: &LinkTag) -> String {
@@ -65,7 +93,7 @@ impl LinksQuery {
             AND DhtOp.when_integrated IS NOT NULL
         "
     }
-    fn create_query_string(type_query: &LinkTypeFilter, tag: Option<String>) -> String {
+    fn create_query_string(&self) -> String {
         let mut s = format!(
             "
             SELECT Action.blob AS action_blob FROM DhtOp
@@ -73,8 +101,9 @@ impl LinksQuery {
             ",
             Self::common_query_string()
         );
-        s = Self::add_type_query(s, type_query);
-        Self::add_tag(s, tag)
+        s = Self::add_type_query(s, &self.type_query);
+        s = Self::add_tag(s, self.tag.clone());
+        self.add_constraints(s)
     }
     fn add_tag(q: String, tag: Option<String>) -> String {
         match tag {
@@ -92,7 +121,48 @@ impl LinksQuery {
     fn add_type_query(q: String, type_query: &LinkTypeFilter) -> String {
         format!("{} {} ", q, type_query.to_sql_statement())
     }
-    fn delete_query_string(type_query: &LinkTypeFilter, tag: Option<String>) -> String {
+    /// The limit is not applied here because deleted links
+    /// don't count towards it. See [`LinksQuery::page`].
+    fn add_constraints(&self, mut q: String) -> String {
+        if self.author.is_some() {
+            q.push_str(
+                "
+                AND
+                Action.author = :author
+                ",
+            );
+        }
+        if self.after.is_some() {
+            q.push_str(
+                "
+                AND
+                DhtOp.authored_timestamp >= :after
+                ",
+            );
+        }
+        if self.before.is_some() {
+            q.push_str(
+                "
+                AND
+                DhtOp.authored_timestamp < :before
+                ",
+            );
+        }
+        if self.cursor.is_some() {
+            q.push_str(
+                "
+                AND
+                (
+                    DhtOp.authored_timestamp > :cursor_timestamp
+                    OR
+                    (DhtOp.authored_timestamp = :cursor_timestamp AND Action.hash > :cursor_hash)
+                )
+                ",
+            );
+        }
+        q
+    }
+    fn delete_query_string(&self) -> String {
         let mut sub_create_query = format!(
             "
             SELECT Action.hash FROM DhtOp
@@ -100,8 +170,9 @@ impl LinksQuery {
             ",
             Self::common_query_string()
         );
-        sub_create_query = Self::add_type_query(sub_create_query, type_query);
-        sub_create_query = Self::add_tag(sub_create_query, tag);
+        sub_create_query = Self::add_type_query(sub_create_query, &self.type_query);
+        sub_create_query = Self::add_tag(sub_create_query, self.tag.clone());
+        sub_create_query = self.add_constraints(sub_create_query);
         let delete_query = format!(
             "
             SELECT Action.blob AS action_blob FROM DhtOp
@@ -120,7 +191,7 @@ impl LinksQuery {
     }
 
     pub fn params(&self) -> Vec<Params> {
-        {
+        let mut params = {
             named_params! {
                 ":create": DhtOpType::RegisterAddLink,
                 ":delete": DhtOpType::RegisterRemoveLink,
@@ -128,7 +199,50 @@ impl LinksQuery {
                 ":base_hash": self.base,
             }
         }
-        .to_vec()
+        .to_vec();
+        if let Some(author) = &self.author {
+            params.push((":author", author));
+        }
+        if let Some(after) = &self.after {
+            params.push((":after", after));
+        }
+        if let Some(before) = &self.before {
+            params.push((":before", before));
+        }
+        if let Some((timestamp, hash)) = &self.cursor {
+            params.push((":cursor_timestamp", timestamp));
+            params.push((":cursor_hash", hash));
+        }
+        params
+    }
+
+    /// Does a create link match this query?
+    /// The cursor is not checked here, see [`LinksQuery::page`].
+    pub fn matches_create(&self, create: &CreateLink) -> bool {
+        create.base_address == *self.base
+            && self
+                .type_query
+                .contains(&create.zome_index, &create.link_type)
+            && self.tag.as_ref().map_or(true, |t| {
+                LinksQuery::tag_to_hex(&create.tag).starts_with(&(**t))
+            })
+            && self.author.as_ref().map_or(true, |a| create.author == *a)
+            && self.after.map_or(true, |after| create.timestamp >= after)
+            && self.before.map_or(true, |before| create.timestamp < before)
+    }
+
+    /// Sort links by their cursor then cut them
+    /// down to the page after the cursor.
+    pub fn page<T>(&self, mut links: Vec<T>, cursor: impl Fn(&T) -> LinkCursor) -> Vec<T> {
+        links.sort_by_cached_key(|l| cursor(l));
+        if let Some((timestamp, hash)) = &self.cursor {
+            let after = LinkCursor::new(*timestamp, hash.clone());
+            links.retain(|l| cursor(l) > after);
+        }
+        if let Some(limit) = self.limit {
+            links.truncate(limit as usize);
+        }
+        links
     }
 }
 
@@ -144,6 +258,12 @@ impl GetLinksQuery {
             query: LinksQuery::base(base, dependencies),
         }
     }
+
+    pub fn from_key(key: WireLinkKey) -> Self {
+        Self {
+            query: LinksQuery::from_key(key),
+        }
+    }
 }
 
 impl Query for GetLinksQuery {
@@ -169,25 +289,10 @@ impl Query for GetLinksQuery {
     }
 
     fn as_filter(&self) -> Box<dyn Fn(&QueryData<Self>) -> bool> {
-        let query = &self.query;
-        let base_filter = query.base.clone();
-        let type_query_filter = query.type_query.clone();
-        let tag_filter = query.tag.clone();
+        let query = self.query.clone();
         let f = move |action: &QueryData<Self>| match action.action() {
-            Action::CreateLink(CreateLink {
-                base_address,
-                tag,
-                zome_index,
-                link_type,
-                ..
-            }) => {
-                *base_address == *base_filter
-                    && type_query_filter.contains(zome_index, link_type)
-                    && tag_filter
-                        .as_ref()
-                        .map_or(true, |t| LinksQuery::tag_to_hex(tag).starts_with(&(**t)))
-            }
-            Action::DeleteLink(DeleteLink { base_address, .. }) => *base_address == *base_filter,
+            Action::CreateLink(create_link) => query.matches_create(create_link),
+            Action::DeleteLink(DeleteLink { base_address, .. }) => *base_address == *query.base,
             _ => false,
         };
         Box::new(f)
@@ -218,9 +323,8 @@ impl Query for GetLinksQuery {
     where
         S: Store,
     {
-        let mut links: Self::Output = state.creates.into_values().collect();
-        links.sort_by_key(|l| l.timestamp);
-        Ok(links)
+        let links: Self::Output = state.creates.into_values().collect();
+        Ok(self.query.page(links, LinkCursor::from))
     }
 }
 
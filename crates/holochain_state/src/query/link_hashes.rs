@@ -0,0 +1,89 @@
+use holo_hash::*;
+use holochain_types::link::WireLinkHashes;
+use holochain_types::link::WireLinkKey;
+use holochain_zome_types::*;
+use std::fmt::Debug;
+
+use super::link::LinksQuery;
+use super::*;
+
+/// Get the create links matching a key without their content, and which of
+/// them are deleted. Deleted links are kept so the results can be merged
+/// with the hashes from other sources.
+#[derive(Debug, Clone)]
+pub struct GetLinkHashesQuery {
+    query: LinksQuery,
+}
+
+impl GetLinkHashesQuery {
+    pub fn from_key(key: WireLinkKey) -> Self {
+        Self {
+            query: LinksQuery::from_key(key),
+        }
+    }
+}
+
+impl Query for GetLinkHashesQuery {
+    type Item = Judged<SignedActionHashed>;
+    type State = Maps<LinkCursor>;
+    type Output = WireLinkHashes;
+    fn query(&self) -> String {
+        self.query.query()
+    }
+
+    fn params(&self) -> Vec<Params> {
+        self.query.params()
+    }
+
+    fn init_fold(&self) -> StateQueryResult<Self::State> {
+        Ok(Maps::new())
+    }
+
+    fn as_map(&self) -> Arc<dyn Fn(&Row) -> StateQueryResult<Self::Item>> {
+        let f = row_blob_to_action("action_blob");
+        // Data is valid because it is filtered in the sql query.
+        Arc::new(move |row| Ok(Judged::valid(f(row)?)))
+    }
+
+    fn as_filter(&self) -> Box<dyn Fn(&QueryData<Self>) -> bool> {
+        let query = self.query.clone();
+        let f = move |action: &QueryData<Self>| match action.action() {
+            Action::CreateLink(create_link) => query.matches_create(create_link),
+            Action::DeleteLink(DeleteLink { base_address, .. }) => *base_address == *query.base,
+            _ => false,
+        };
+        Box::new(f)
+    }
+
+    fn fold(&self, mut state: Self::State, data: Self::Item) -> StateQueryResult<Self::State> {
+        let shh = data.data;
+        match shh.action() {
+            Action::CreateLink(create_link) => {
+                let cursor = LinkCursor::new(create_link.timestamp, shh.as_hash().clone());
+                state.creates.insert(shh.as_hash().clone(), cursor);
+            }
+            Action::DeleteLink(delete_link) => {
+                state.deletes.insert(delete_link.link_add_address.clone());
+            }
+            action => return Err(StateQueryError::UnexpectedAction(action.action_type())),
+        }
+        Ok(state)
+    }
+
+    fn render<S>(&self, state: Self::State, _stores: S) -> StateQueryResult<Self::Output>
+    where
+        S: Store,
+    {
+        let Maps { creates, deletes } = state;
+        let creates = self
+            .query
+            .page(creates.into_values().collect(), LinkCursor::clone);
+        let deleted = creates
+            .iter()
+            .map(|c| c.create_link_hash())
+            .filter(|hash| deletes.contains(*hash))
+            .cloned()
+            .collect();
+        Ok(WireLinkHashes { creates, deleted })
+    }
+}
@@ -162,6 +162,9 @@ impl SourceChain {
         maybe_entry: Option<Entry>,
         chain_top_ordering: ChainTopOrdering,
     ) -> SourceChainResult<ActionHash> {
+        if self.is_chain_closed().await? {
+            return Err(SourceChainError::ChainClosed);
+        }
         let action = ActionHashed::from_content_sync(action);
         let hash = action.as_hash().clone();
         let action = SignedActionHashed::sign(&self.keystore, action).await?;
@@ -219,6 +222,27 @@ impl SourceChain {
         chain_top_ordering: ChainTopOrdering,
         weight: W,
     ) -> SourceChainResult<ActionHash> {
+        self.put_weighed_by(action_builder, maybe_entry, chain_top_ordering, |_| {
+            Ok(weight)
+        })
+        .await
+    }
+
+    /// Put a new record at the end of the source chain, using a ActionBuilder
+    /// and a function which weighs the built, unweighed action for rate
+    /// limiting, e.g. by running the `weigh` callback of its integrity zome.
+    pub async fn put_weighed_by<W, U, B, F>(
+        &self,
+        action_builder: B,
+        maybe_entry: Option<Entry>,
+        chain_top_ordering: ChainTopOrdering,
+        weigh: F,
+    ) -> SourceChainResult<ActionHash>
+    where
+        U: ActionUnweighed<Weight = W>,
+        B: ActionBuilder<U>,
+        F: FnOnce(&U) -> SourceChainResult<W>,
+    {
         let (prev_action, chain_head_seq, chain_head_timestamp) = self.chain_head()?;
         let action_seq = chain_head_seq + 1;
 
@@ -239,8 +263,10 @@ impl SourceChain {
             action_seq,
             prev_action,
         };
+        let unweighed = action_builder.build(common);
+        let weight = weigh(&unweighed)?;
         self.put_with_action(
-            action_builder.build(common).weighed(weight).into(),
+            unweighed.weighed(weight).into(),
             maybe_entry,
             chain_top_ordering,
         )
@@ -335,6 +361,12 @@ impl SourceChain {
                     return Ok(Vec::new());
                 }
 
+                // Nothing may follow a CloseChain action, regardless of
+                // chain top ordering.
+                if chain_closed_db(txn, &latest_head)? {
+                    return Err(SourceChainError::ChainClosed);
+                }
+
                 if persisted_head != latest_head {
                     return Err(SourceChainError::HeadMoved(
                         actions,
@@ -904,6 +936,23 @@ where
             .await?)
     }
 
+    /// Whether the chain has been closed, either by a CloseChain action in
+    /// the scratch or by one at the persisted head.
+    pub async fn is_chain_closed(&self) -> SourceChainResult<bool> {
+        let closed_in_scratch = self.scratch.apply(|scratch| {
+            scratch
+                .actions()
+                .any(|shh| matches!(shh.action(), Action::CloseChain(_)))
+        })?;
+        if closed_in_scratch {
+            return Ok(true);
+        }
+        let persisted_head = self.persisted_head.clone();
+        self.vault
+            .async_reader(move |txn| chain_closed_db(&txn, &persisted_head))
+            .await
+    }
+
     /// If there is a countersigning session get the
     /// StoreEntry op to send to the entry authorities.
     pub fn countersigning_op(&self) -> SourceChainResult<Option<DhtOp>> {
@@ -1159,6 +1208,20 @@ pub fn chain_head_db(
     Ok(chain_head.run(Txn::from(txn))?)
 }
 
+/// Check whether the action at the given chain head is a CloseChain.
+pub fn chain_closed_db(txn: &Transaction, head: &ActionHash) -> SourceChainResult<bool> {
+    Ok(txn
+        .query_row(
+            "SELECT EXISTS(SELECT 1 FROM Action WHERE hash = :hash AND type = :type)",
+            named_params! {
+                ":hash": head,
+                ":type": ActionType::CloseChain.as_sql(),
+            },
+            |row| row.get(0),
+        )
+        .map_err(StateQueryError::from)?)
+}
+
 /// Get the current chain head of the database.
 /// Error if the chain is empty.
 pub fn chain_head_db_nonempty(
@@ -1396,9 +1459,7 @@ pub mod tests {
         )
         .await?;
 
-        let action_builder = builder::CloseChain {
-            new_dna_hash: fixt!(DnaHash),
-        };
+        let action_builder = builder::InitZomesComplete {};
         chain_1
             .put(action_builder.clone(), None, ChainTopOrdering::Strict)
             .await?;
@@ -1436,6 +1497,96 @@ pub mod tests {
 
         Ok(())
     }
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_no_writes_after_close_chain() -> SourceChainResult<()> {
+        let test_db = test_authored_db();
+        let dht_db = test_dht_db();
+        let keystore = test_keystore();
+        let db = test_db.to_db();
+        let alice = fixt!(AgentPubKey, Predictable, 0);
+
+        let mut mock = MockHolochainP2pDnaT::new();
+        mock.expect_authority_for_hash().returning(|_| Ok(false));
+        mock.expect_chc().return_const(None);
+        let dht_db_cache = DhtDbQueryCache::new(dht_db.to_db().into());
+
+        source_chain::genesis(
+            db.clone(),
+            dht_db.to_db(),
+            &dht_db_cache,
+            keystore.clone(),
+            fake_dna_hash(1),
+            alice.clone(),
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+        let new_chain = || {
+            SourceChain::new(
+                db.clone().into(),
+                dht_db.to_db(),
+                dht_db_cache.clone(),
+                keystore.clone(),
+                alice.clone(),
+            )
+        };
+
+        // A stale chain which was opened before the close was flushed.
+        let stale_chain = new_chain().await?;
+        stale_chain
+            .put(
+                builder::InitZomesComplete {},
+                None,
+                ChainTopOrdering::Relaxed,
+            )
+            .await?;
+
+        let chain = new_chain().await?;
+        chain
+            .put(
+                builder::CloseChain {
+                    new_dna_hash: fixt!(DnaHash),
+                },
+                None,
+                ChainTopOrdering::Strict,
+            )
+            .await?;
+        // Nothing can follow the close in the same scratch.
+        assert!(matches!(
+            chain
+                .put(
+                    builder::InitZomesComplete {},
+                    None,
+                    ChainTopOrdering::Strict
+                )
+                .await,
+            Err(SourceChainError::ChainClosed)
+        ));
+        chain.flush(&mock).await?;
+
+        // Nothing can follow the close once it is persisted.
+        assert!(matches!(
+            new_chain()
+                .await?
+                .put(
+                    builder::InitZomesComplete {},
+                    None,
+                    ChainTopOrdering::Strict
+                )
+                .await,
+            Err(SourceChainError::ChainClosed)
+        ));
+
+        // A relaxed write can't be rebased onto the close either.
+        assert!(matches!(
+            stale_chain.flush(&mock).await,
+            Err(SourceChainError::ChainClosed)
+        ));
+
+        Ok(())
+    }
+
     #[tokio::test(flavor = "multi_thread")]
     async fn test_relaxed_ordering_with_entry() -> SourceChainResult<()> {
         let test_db = test_authored_db();
@@ -43,6 +43,9 @@ pub enum SourceChainError {
     #[error("Attempted to write anything other than the countersigning session entry while the chain was locked for a countersigning session.")]
     ChainLocked,
 
+    #[error("Attempted to write to a source chain which has been closed by a CloseChain action.")]
+    ChainClosed,
+
     #[error("Attempted to write a countersigning session that has already expired")]
     LockExpired,
 
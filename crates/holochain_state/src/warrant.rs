@@ -0,0 +1,298 @@
+//! Functions for signing, verifying and storing warrants
+
+use holo_hash::AgentPubKey;
+use holo_hash::DnaHash;
+use holochain_keystore::AgentPubKeyExt;
+use holochain_keystore::MetaLairClient;
+use holochain_sqlite::rusqlite::named_params;
+use holochain_sqlite::rusqlite::Transaction;
+use holochain_zome_types::prelude::*;
+
+use crate::mutations;
+use crate::mutations::StateMutationResult;
+use crate::prelude::from_blob;
+use crate::prelude::StateQueryResult;
+
+/// Issue a warrant with the given proof, signed by `author`.
+pub async fn sign_warrant(
+    keystore: &MetaLairClient,
+    author: AgentPubKey,
+    proof: WarrantProof,
+) -> holochain_keystore::LairResult<SignedWarrant> {
+    let warrant = Warrant::new(proof, author);
+    let signature = warrant.author.sign(keystore, &warrant).await?;
+    Ok(SignedWarrant { warrant, signature })
+}
+
+/// Whether two actions fork the chain of their author in `dna_hash`.
+///
+/// An agent uses the same key in every DNA, so two different actions at the
+/// same seq only fork a chain if they follow the same previous action, or
+/// are both the [`Dna`] action of this DNA.
+pub fn is_chain_fork(dna_hash: &DnaHash, a: &Action, b: &Action) -> bool {
+    let same_chain = match (a, b) {
+        (Action::Dna(a), Action::Dna(b)) => a.hash == *dna_hash && b.hash == *dna_hash,
+        _ => a.prev_action().is_some() && a.prev_action() == b.prev_action(),
+    };
+    same_chain && a.author() == b.author() && a.action_seq() == b.action_seq() && a != b
+}
+
+/// Check that a warrant was signed by its author, and that the evidence in
+/// its proof is genuine for the chain of the warrantee in `dna_hash`.
+///
+/// Whether the action in an [`ChainIntegrityWarrant::InvalidChainOp`] is
+/// actually invalid can't be checked without validating it again, so this
+/// only checks that it was authored by the warrantee. Receivers must
+/// validate the action themselves before acting on such a warrant.
+pub async fn verify_warrant(dna_hash: &DnaHash, warrant: &SignedWarrant) -> bool {
+    let SignedWarrant { warrant, signature } = warrant;
+    if !warrant.author.verify_signature(signature, warrant).await {
+        return false;
+    }
+    let warrantee = warrant.warrantee();
+    for action in warrant.proof.actions() {
+        if action.action().author() != warrantee
+            || !warrantee
+                .verify_signature(action.signature(), action.action())
+                .await
+        {
+            return false;
+        }
+    }
+    match &warrant.proof {
+        WarrantProof::ChainIntegrity(ChainIntegrityWarrant::ChainFork {
+            action_pair: (a, b),
+        }) => is_chain_fork(dna_hash, a.action(), b.action()),
+        WarrantProof::ChainIntegrity(ChainIntegrityWarrant::InvalidChainOp { .. }) => true,
+    }
+}
+
+/// Store a warrant unless the same author has already issued it.
+/// Returns true if the warrant was not already stored.
+pub fn add_if_unique(txn: &mut Transaction, warrant: SignedWarrant) -> StateMutationResult<bool> {
+    mutations::insert_warrant(txn, warrant)
+}
+
+/// Remove every warrant which has been issued against an agent.
+pub fn remove_warrants(txn: &mut Transaction, warrantee: &AgentPubKey) -> StateMutationResult<()> {
+    txn.execute("DELETE FROM Warrant WHERE warrantee = ?", [warrantee])?;
+    Ok(())
+}
+
+/// Whether the Warrant table exists. A database created before warrants
+/// has no warrants until the migration which adds the table has run.
+fn has_warrant_table(txn: &Transaction) -> StateQueryResult<bool> {
+    Ok(txn.query_row(
+        "SELECT EXISTS(SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'Warrant')",
+        [],
+        |row| row.get(0),
+    )?)
+}
+
+/// Get all the warrants which have been issued against an agent.
+pub fn list_warrants(
+    txn: &Transaction,
+    warrantee: &AgentPubKey,
+) -> StateQueryResult<Vec<SignedWarrant>> {
+    if !has_warrant_table(txn)? {
+        return Ok(Vec::new());
+    }
+    let mut stmt = txn.prepare(
+        "
+        SELECT blob FROM Warrant WHERE warrantee = :warrantee ORDER BY timestamp
+        ",
+    )?;
+    let iter = stmt.query_and_then(
+        named_params! {
+            ":warrantee": warrantee
+        },
+        |row| from_blob::<SignedWarrant>(row.get("blob")?),
+    )?;
+    iter.collect()
+}
+
+/// Get every agent which has had a warrant issued against it.
+pub fn list_warrantees(txn: &Transaction) -> StateQueryResult<Vec<AgentPubKey>> {
+    if !has_warrant_table(txn)? {
+        return Ok(Vec::new());
+    }
+    let mut stmt = txn.prepare("SELECT DISTINCT warrantee FROM Warrant")?;
+    let iter = stmt.query_and_then([], |row| StateQueryResult::Ok(row.get(0)?))?;
+    iter.collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fixt::prelude::*;
+
+    async fn sign_action(
+        keystore: &MetaLairClient,
+        author: &AgentPubKey,
+        action: Action,
+    ) -> SignedAction {
+        let signature = author.sign(keystore, &action).await.unwrap();
+        SignedAction(action, signature)
+    }
+
+    async fn signed_action(
+        keystore: &MetaLairClient,
+        author: &AgentPubKey,
+        prev_action: &ActionHash,
+    ) -> SignedAction {
+        let mut create = fixt!(Create);
+        create.author = author.clone();
+        create.action_seq = 5;
+        create.prev_action = prev_action.clone();
+        sign_action(keystore, author, Action::Create(create)).await
+    }
+
+    async fn signed_dna_action(
+        keystore: &MetaLairClient,
+        author: &AgentPubKey,
+        dna_hash: &DnaHash,
+    ) -> SignedAction {
+        let mut dna = fixt!(Dna);
+        dna.author = author.clone();
+        dna.hash = dna_hash.clone();
+        sign_action(keystore, author, Action::Dna(dna)).await
+    }
+
+    async fn is_fork(
+        keystore: &MetaLairClient,
+        authority: &AgentPubKey,
+        dna_hash: &DnaHash,
+        a: SignedAction,
+        b: SignedAction,
+    ) -> bool {
+        let proof = WarrantProof::ChainIntegrity(ChainIntegrityWarrant::chain_fork(a, b));
+        let warrant = sign_warrant(keystore, authority.clone(), proof)
+            .await
+            .unwrap();
+        verify_warrant(dna_hash, &warrant).await
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn chain_forks_must_be_from_the_same_chain() {
+        let keystore = crate::test_utils::test_keystore();
+        let authority = keystore.new_sign_keypair_random().await.unwrap();
+        let offender = keystore.new_sign_keypair_random().await.unwrap();
+        let dna_hash = fixt!(DnaHash);
+        let other_dna_hash = fixt!(DnaHash);
+        let prev_action = fixt!(ActionHash);
+
+        let a = signed_action(&keystore, &offender, &prev_action).await;
+        let b = signed_action(&keystore, &offender, &prev_action).await;
+        assert!(is_fork(&keystore, &authority, &dna_hash, a.clone(), b).await);
+
+        // Actions at the same seq of two different chains, such as the
+        // chains of the same agent in two DNAs, are not a fork
+        let other_chain = signed_action(&keystore, &offender, &fixt!(ActionHash)).await;
+        assert!(!is_fork(&keystore, &authority, &dna_hash, a, other_chain).await);
+
+        let a = signed_dna_action(&keystore, &offender, &dna_hash).await;
+        let b = signed_dna_action(&keystore, &offender, &dna_hash).await;
+        assert!(is_fork(&keystore, &authority, &dna_hash, a.clone(), b).await);
+
+        let other_dna = signed_dna_action(&keystore, &offender, &other_dna_hash).await;
+        assert!(!is_fork(&keystore, &authority, &dna_hash, a, other_dna.clone()).await);
+
+        // Two genesis chains of another DNA are not a fork in this one
+        let other_dna_2 = signed_dna_action(&keystore, &offender, &other_dna_hash).await;
+        assert!(!is_fork(&keystore, &authority, &dna_hash, other_dna, other_dna_2).await);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn warrants_are_verified_and_stored_once() {
+        let keystore = crate::test_utils::test_keystore();
+        let authority = keystore.new_sign_keypair_random().await.unwrap();
+        let offender = keystore.new_sign_keypair_random().await.unwrap();
+
+        let dna_hash = fixt!(DnaHash);
+        let prev_action = fixt!(ActionHash);
+        let a = signed_action(&keystore, &offender, &prev_action).await;
+        let b = signed_action(&keystore, &offender, &prev_action).await;
+        let fork =
+            WarrantProof::ChainIntegrity(ChainIntegrityWarrant::chain_fork(a.clone(), b.clone()));
+        assert_eq!(
+            fork,
+            WarrantProof::ChainIntegrity(ChainIntegrityWarrant::chain_fork(b, a.clone()))
+        );
+
+        let warrant = sign_warrant(&keystore, authority.clone(), fork.clone())
+            .await
+            .unwrap();
+        assert!(verify_warrant(&dna_hash, &warrant).await);
+
+        // A fork needs two different actions
+        let not_a_fork = WarrantProof::ChainIntegrity(ChainIntegrityWarrant::ChainFork {
+            action_pair: (a.clone(), a.clone()),
+        });
+        let bad = sign_warrant(&keystore, authority.clone(), not_a_fork)
+            .await
+            .unwrap();
+        assert!(!verify_warrant(&dna_hash, &bad).await);
+
+        // The evidence must be signed by the warrantee
+        let mut forged = signed_action(&keystore, &authority, &prev_action).await;
+        forged.0 = a.action().clone();
+        let invalid_op = WarrantProof::ChainIntegrity(ChainIntegrityWarrant::InvalidChainOp {
+            action: forged,
+            validation_type: ValidationType::App,
+        });
+        let bad = sign_warrant(&keystore, authority.clone(), invalid_op)
+            .await
+            .unwrap();
+        assert!(!verify_warrant(&dna_hash, &bad).await);
+
+        // The warrant must be signed by its author
+        let mut tampered = warrant.clone();
+        tampered.warrant.author = offender.clone();
+        assert!(!verify_warrant(&dna_hash, &tampered).await);
+
+        let db = crate::test_utils::test_dht_db();
+        let reissued = sign_warrant(&keystore, authority, fork).await.unwrap();
+        db.to_db()
+            .async_commit({
+                let warrant = warrant.clone();
+                move |txn| {
+                    assert!(add_if_unique(txn, warrant)?);
+                    assert!(!add_if_unique(txn, reissued)?);
+                    StateMutationResult::Ok(())
+                }
+            })
+            .await
+            .unwrap();
+        let (warrants, warrantees) = db
+            .to_db()
+            .async_reader(move |txn| {
+                StateQueryResult::Ok((list_warrants(&txn, &offender)?, list_warrantees(&txn)?))
+            })
+            .await
+            .unwrap();
+        assert_eq!(warrants, vec![warrant.clone()]);
+        assert_eq!(warrantees, vec![warrant.warrant.warrantee().clone()]);
+
+        let warrantee = warrant.warrant.warrantee().clone();
+        db.to_db()
+            .async_commit(move |txn| remove_warrants(txn, &warrantee))
+            .await
+            .unwrap();
+        let warrantees = db
+            .to_db()
+            .async_reader(|txn| list_warrantees(&txn))
+            .await
+            .unwrap();
+        assert!(warrantees.is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn a_missing_warrant_table_has_no_warrants() {
+        let keystore = crate::test_utils::test_keystore();
+        let agent = keystore.new_sign_keypair_random().await.unwrap();
+        let mut conn = holochain_sqlite::rusqlite::Connection::open_in_memory().unwrap();
+        let txn = conn.transaction().unwrap();
+        assert!(list_warrants(&txn, &agent).unwrap().is_empty());
+        assert!(list_warrantees(&txn).unwrap().is_empty());
+    }
+}
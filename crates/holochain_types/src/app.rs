@@ -97,6 +97,20 @@ pub struct CreateCloneCellPayload {
     pub name: Option<String>,
 }
 
+/// The arguments to provision the cell of a role whose provisioning was
+/// deferred at installation.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct ProvisionDeferredCellPayload {
+    /// The app id that the role belongs to
+    pub app_id: InstalledAppId,
+    /// The deferred role to provision a cell for
+    pub role_name: RoleName,
+    /// Modifiers to apply on top of those given in the app manifest.
+    pub modifiers: DnaModifiersOpt<YamlProperties>,
+    /// Optionally set a proof of membership for the new cell
+    pub membrane_proof: Option<MembraneProof>,
+}
+
 /// Ways of specifying a clone cell.
 #[derive(Clone, Debug, Display, serde::Serialize, serde::Deserialize)]
 #[serde(untagged)]
@@ -122,6 +136,28 @@ pub type EnableCloneCellPayload = DisableCloneCellPayload;
 /// Arguments to delete a disabled clone cell of an app.
 pub type DeleteCloneCellPayload = DisableCloneCellPayload;
 
+/// Arguments to provision the base cell of a role which was installed with the
+/// `disabled` provisioning strategy.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct EnableAppRolePayload {
+    /// The app id that the role belongs to
+    pub app_id: InstalledAppId,
+    /// The role to provision a cell for
+    pub role_name: RoleName,
+    /// Optionally set a proof of membership for the new cell
+    pub membrane_proof: Option<MembraneProof>,
+}
+
+/// Arguments to migrate an agent's source chain to another DNA.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub struct MigrateAgentPayload {
+    /// The cell whose source chain will be closed
+    pub cell_id: CellId,
+    /// The DNA to migrate to. The same agent must already have a cell of
+    /// this DNA, whose source chain will be opened.
+    pub new_dna_hash: DnaHash,
+}
+
 /// An [AppBundle] along with an [AgentPubKey] and optional [InstalledAppId]
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct InstallAppPayload {
@@ -154,8 +190,8 @@ pub enum AppBundleSource {
     Bundle(AppBundle),
     /// A local file path
     Path(PathBuf),
-    // /// A URL
-    // Url(String),
+    /// A URL to download the bundle from
+    Url(String),
 }
 
 impl AppBundleSource {
@@ -164,7 +200,7 @@ impl AppBundleSource {
         Ok(match self {
             Self::Bundle(bundle) => bundle,
             Self::Path(path) => AppBundle::decode(&ffs::read(&path).await?)?,
-            // Self::Url(url) => todo!("reqwest::get"),
+            Self::Url(url) => mr_bundle::Bundle::read_from_url(&url).await?.into(),
         })
     }
 }
@@ -449,6 +485,32 @@ impl InstalledAppCommon {
             .filter_map(|(role_name, role)| role.into_provisioned_cell().map(|c| (role_name, c)))
     }
 
+    /// The provisioned cells which were created by this app, i.e. excluding
+    /// cells which were provisioned by other apps.
+    pub fn owned_provisioned_cells(&self) -> impl Iterator<Item = (&RoleName, &CellId)> {
+        self.role_assignments
+            .iter()
+            .filter(|(_, role)| !role.is_dependency)
+            .filter_map(|(role_name, role)| role.provisioned_cell().map(|c| (role_name, c)))
+    }
+
+    /// The cells provisioned by other apps, which this app depends on.
+    pub fn dependency_cells(&self) -> impl Iterator<Item = (&RoleName, &CellId)> {
+        self.role_assignments
+            .iter()
+            .filter(|(_, role)| role.is_dependency)
+            .filter_map(|(role_name, role)| role.provisioned_cell().map(|c| (role_name, c)))
+    }
+
+    /// The base cells of roles which have not been provisioned yet, e.g. roles
+    /// using the `disabled` provisioning strategy.
+    pub fn unprovisioned_cells(&self) -> impl Iterator<Item = (&RoleName, &CellId)> {
+        self.role_assignments
+            .iter()
+            .filter(|(_, role)| !role.is_provisioned)
+            .map(|(role_name, role)| (role_name, &role.base_cell_id))
+    }
+
     /// Accessor
     pub fn clone_cells(&self) -> impl Iterator<Item = (&CloneId, &CellId)> {
         self.role_assignments
@@ -528,6 +590,67 @@ impl InstalledAppCommon {
         &self.role_assignments
     }
 
+    /// Mark the base cell of a role as provisioned, returning its id.
+    /// The cell itself must be created separately.
+    /// Deferred roles must be provisioned with
+    /// [`InstalledAppCommon::provision_deferred_role`] instead.
+    pub fn provision_role(&mut self, role_name: &RoleName) -> AppResult<CellId> {
+        let app_role_assignment = self.role_mut(role_name)?;
+        if app_role_assignment.is_provisioned {
+            return Err(AppError::RoleAlreadyProvisioned(role_name.clone()));
+        }
+        if app_role_assignment.is_deferred {
+            return Err(AppError::RoleDeferred(role_name.clone()));
+        }
+        app_role_assignment.is_provisioned = true;
+        Ok(app_role_assignment.base_cell_id.clone())
+    }
+
+    /// Undo [`InstalledAppCommon::provision_role`] if the cell couldn't be
+    /// created.
+    pub fn unprovision_role(&mut self, role_name: &RoleName) -> AppResult<()> {
+        self.role_mut(role_name)?.is_provisioned = false;
+        Ok(())
+    }
+
+    /// Provision the cell of a deferred role. The cell may differ from the
+    /// one originally assigned to the role if other modifiers were used.
+    /// The cell itself must be created separately.
+    pub fn provision_deferred_role(
+        &mut self,
+        role_name: &RoleName,
+        cell_id: CellId,
+    ) -> AppResult<()> {
+        let app_role_assignment = self.role_mut(role_name)?;
+        if !app_role_assignment.is_deferred {
+            return Err(AppError::RoleNotDeferred(role_name.clone()));
+        }
+        if cell_id.agent_pubkey() != app_role_assignment.agent_key() {
+            return Err(AppError::DeferredCellAgentMismatch(
+                role_name.clone(),
+                cell_id,
+            ));
+        }
+        app_role_assignment.base_cell_id = cell_id;
+        app_role_assignment.is_provisioned = true;
+        app_role_assignment.is_deferred = false;
+        Ok(())
+    }
+
+    /// Undo [`InstalledAppCommon::provision_deferred_role`] if the cell
+    /// couldn't be created, deferring the role again with its original cell.
+    pub fn unprovision_deferred_role(
+        &mut self,
+        role_name: &RoleName,
+        base_cell_id: CellId,
+    ) -> AppResult<()> {
+        let app_role_assignment = self.role_mut(role_name)?;
+        app_role_assignment.base_cell_id = base_cell_id;
+        app_role_assignment.is_provisioned = false;
+        app_role_assignment.is_deferred = true;
+        Ok(())
+    }
+
     /// Add a clone cell.
     pub fn add_clone(&mut self, role_name: &RoleName, cell_id: &CellId) -> AppResult<CloneId> {
         let app_role_assignment = self.role_mut(role_name)?;
@@ -721,6 +844,8 @@ impl InstalledAppCommon {
                 let role = AppRoleAssignment {
                     base_cell_id: cell_id,
                     is_provisioned: true,
+                    is_dependency: false,
+                    is_deferred: false,
                     clones: HashMap::new(),
                     clone_limit: 256,
                     next_clone_index: 0,
@@ -949,6 +1074,18 @@ pub struct AppRoleAssignment {
     /// If false, then `base_cell_id` is just recording what that cell will be
     /// called in the future.
     is_provisioned: bool,
+    /// Records whether the base cell was provisioned by another app, via the
+    /// `UseExisting` or `CreateIfNotExists` strategies. If true, this app
+    /// depends on that other app, which cannot be uninstalled while this app
+    /// is installed.
+    #[serde(default)]
+    is_dependency: bool,
+    /// Records whether provisioning of the base cell was deferred by the app
+    /// manifest. If true, `base_cell_id` refers to the DNA as specified in the
+    /// manifest, and the actual cell may be created later with different
+    /// modifiers.
+    #[serde(default)]
+    is_deferred: bool,
     /// The number of allowed clone cells.
     clone_limit: u32,
     /// The index of the next clone cell to be created.
@@ -968,6 +1105,8 @@ impl AppRoleAssignment {
         Self {
             base_cell_id,
             is_provisioned,
+            is_dependency: false,
+            is_deferred: false,
             clone_limit,
             clones: HashMap::new(),
             next_clone_index: 0,
@@ -975,6 +1114,23 @@ impl AppRoleAssignment {
         }
     }
 
+    /// Constructor for a role which is filled by a Cell that was provisioned
+    /// by another app.
+    pub fn new_dependency(base_cell_id: CellId, clone_limit: u32) -> Self {
+        Self {
+            is_dependency: true,
+            ..Self::new(base_cell_id, true, clone_limit)
+        }
+    }
+
+    /// Constructor for a role whose cell will be provisioned at runtime.
+    pub fn new_deferred(base_cell_id: CellId, clone_limit: u32) -> Self {
+        Self {
+            is_deferred: true,
+            ..Self::new(base_cell_id, false, clone_limit)
+        }
+    }
+
     /// Accessor
     pub fn cell_id(&self) -> &CellId {
         &self.base_cell_id
@@ -999,6 +1155,16 @@ impl AppRoleAssignment {
         }
     }
 
+    /// Accessor
+    pub fn is_dependency(&self) -> bool {
+        self.is_dependency
+    }
+
+    /// Accessor
+    pub fn is_deferred(&self) -> bool {
+        self.is_deferred
+    }
+
     /// Accessor
     pub fn clone_ids(&self) -> impl Iterator<Item = &CloneId> {
         self.clones.keys()
@@ -1118,4 +1284,38 @@ mod tests {
         // Assert the deleted cell cannot be enabled
         assert!(app.enable_clone_cell(&clone_id_0).is_err());
     }
+
+    #[test]
+    fn deferred_role_must_be_provisioned_for_its_agent() {
+        let base_cell_id = fixt!(CellId);
+        let agent = base_cell_id.agent_pubkey().clone();
+        let role_name: RoleName = "role_name".into();
+        let mut app = InstalledAppCommon::new(
+            "app",
+            agent.clone(),
+            vec![(
+                role_name.clone(),
+                AppRoleAssignment::new_deferred(base_cell_id.clone(), 0),
+            )],
+        )
+        .unwrap();
+
+        let other_agents_cell = CellId::new(fixt!(DnaHash), fixt!(AgentPubKey));
+        matches::assert_matches!(
+            app.provision_deferred_role(&role_name, other_agents_cell),
+            Err(AppError::DeferredCellAgentMismatch(_, _))
+        );
+        assert!(app.role(&role_name).unwrap().is_deferred());
+
+        let cell_id = CellId::new(fixt!(DnaHash), agent);
+        app.provision_deferred_role(&role_name, cell_id.clone())
+            .unwrap();
+        assert_eq!(app.role(&role_name).unwrap().cell_id(), &cell_id);
+
+        app.unprovision_deferred_role(&role_name, base_cell_id.clone())
+            .unwrap();
+        let role = app.role(&role_name).unwrap();
+        assert!(role.is_deferred());
+        assert_eq!(role.cell_id(), &base_cell_id);
+    }
 }
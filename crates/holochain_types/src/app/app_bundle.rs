@@ -2,7 +2,10 @@ use std::{collections::HashMap, path::PathBuf, sync::Arc};
 
 use self::error::AppBundleResult;
 
-use super::{dna_gamut::DnaGamut, AppManifest, AppManifestValidated};
+use super::{
+    dna_gamut::{CellResolution, DnaGamut, DnaResolution},
+    AppManifest, AppManifestValidated,
+};
 use crate::prelude::*;
 
 #[allow(missing_docs)]
@@ -45,19 +48,109 @@ impl AppBundle {
         self.0
     }
 
+    /// Fetch the DNAs of all roles with a URL location and embed them in the
+    /// bundle, so that installing it doesn't require any further downloads.
+    ///
+    /// If a role specifies a DNA version, the fetched DNA must have one of the
+    /// hashes listed there. A DNA is not fetched at all if `cached` returns a
+    /// DNA file for one of those hashes, or for the hash `fetched_from` returns
+    /// for the URL of a role without a DNA version. The DNAs which were fetched
+    /// are returned along with their URLs, so that the caller can cache them.
+    pub async fn embed_remote_dnas(
+        self,
+        cached: impl Fn(&DnaHash) -> Option<DnaFile>,
+        fetched_from: impl Fn(&str) -> Option<DnaHash>,
+    ) -> AppBundleResult<(Self, Vec<(String, DnaFile)>)> {
+        let mut manifest = self.manifest().clone();
+        let mut resources = vec![];
+        let mut fetched = vec![];
+        let roles = match &mut manifest {
+            AppManifest::V1(m) => &mut m.roles,
+        };
+        for role in roles.iter_mut() {
+            let (location, url) = match &role.dna.location {
+                Some(location @ mr_bundle::Location::Url(url)) => (location.clone(), url.clone()),
+                _ => continue,
+            };
+            let version: Option<DnaVersionSpec> = role.dna.version.clone().map(Into::into);
+            let candidates: Vec<DnaHash> = match &version {
+                Some(version) => version
+                    .dna_hashes()
+                    .iter()
+                    .map(|hash| DnaHash::from(hash.clone()))
+                    .collect(),
+                None => fetched_from(&url).into_iter().collect(),
+            };
+            let (bytes, hash) = match self.resolve_cached_dna(candidates, &cached).await {
+                Some(cached) => cached,
+                None => {
+                    let bytes = self.resolve(&location).await?.into_owned();
+                    let (dna_file, hash) = DnaBundle::decode(&bytes)?
+                        .into_dna_file(DnaModifiersOpt::none())
+                        .await?;
+                    if let Some(version) = &version {
+                        if !version.matches(hash.clone()) {
+                            return Err(AppBundleError::DnaHashMismatch(location, hash));
+                        }
+                    }
+                    fetched.push((url, dna_file));
+                    (bytes, hash)
+                }
+            };
+            let path = PathBuf::from(format!("{}.dna", hash));
+            role.dna.location = Some(mr_bundle::Location::Bundled(path.clone()));
+            resources.push((path, bytes));
+        }
+        let bundle = self
+            .into_inner()
+            .update_manifest_with_resources(manifest, resources)?;
+        Ok((bundle.into(), fetched))
+    }
+
+    /// Look up a DNA with one of these hashes via `cached`, and encode it
+    /// as a DNA bundle.
+    async fn resolve_cached_dna(
+        &self,
+        hashes: Vec<DnaHash>,
+        cached: impl Fn(&DnaHash) -> Option<DnaFile>,
+    ) -> Option<(mr_bundle::ResourceBytes, DnaHash)> {
+        for hash in hashes {
+            if let Some(dna_file) = cached(&hash) {
+                let bytes = DnaBundle::bundle_dna_file(dna_file).ok()?.encode().ok()?;
+                // Not everything in a DnaDef makes it into a manifest, e.g. a
+                // custom quantum time, so make sure that this is still the same DNA.
+                let (_, bundled_hash) = DnaBundle::decode(&bytes)
+                    .ok()?
+                    .into_dna_file(DnaModifiersOpt::none())
+                    .await
+                    .ok()?;
+                if bundled_hash == hash {
+                    return Some((bytes, hash));
+                }
+            }
+        }
+        None
+    }
+
     /// Given a DnaGamut, decide which of the available DNAs or Cells should be
     /// used for each cell in this app.
     pub async fn resolve_cells(
         self,
         agent: AgentPubKey,
-        _gamut: DnaGamut,
+        gamut: DnaGamut,
         membrane_proofs: HashMap<RoleName, MembraneProof>,
     ) -> AppBundleResult<AppRoleResolution> {
         let AppManifestValidated { name: _, roles } = self.manifest().clone().validate()?;
         let bundle = Arc::new(self);
-        let tasks = roles.into_iter().map(|(role_name, role)| async {
+        let gamut = Arc::new(gamut);
+        let tasks = roles.into_iter().map(|(role_name, role)| {
             let bundle = bundle.clone();
-            Ok((role_name, bundle.resolve_cell(role).await?))
+            let gamut = gamut.clone();
+            let agent = agent.clone();
+            async move {
+                let op = bundle.resolve_cell(role, &gamut, &agent).await?;
+                Ok((role_name, op))
+            }
         });
         let resolution = futures::future::join_all(tasks)
             .await
@@ -79,8 +172,16 @@ impl AppBundle {
                                 resolution.dnas_to_register.push((dna, proof));
                                 resolution.role_assignments.push((role_name, role));
                             }
+                            CellProvisioningOp::Defer(dna, clone_limit) => {
+                                let agent = resolution.agent.clone();
+                                let dna_hash = dna.dna_hash().clone();
+                                let cell_id = CellId::new(dna_hash, agent);
+                                let role = AppRoleAssignment::new_deferred(cell_id, clone_limit);
+                                resolution.dnas_to_register.push((dna, None));
+                                resolution.role_assignments.push((role_name, role));
+                            }
                             CellProvisioningOp::Existing(cell_id, clone_limit) => {
-                                let role = AppRoleAssignment::new(cell_id, true, clone_limit);
+                                let role = AppRoleAssignment::new_dependency(cell_id, clone_limit);
                                 resolution.role_assignments.push((role_name, role));
                             }
                             CellProvisioningOp::Noop(cell_id, clone_limit) => {
@@ -89,12 +190,18 @@ impl AppBundle {
                                     AppRoleAssignment::new(cell_id, false, clone_limit),
                                 ));
                             }
-                            other => {
-                                tracing::error!(
-                                    "Encountered unexpected CellProvisioningOp: {:?}",
-                                    other
-                                );
-                                unimplemented!()
+                            CellProvisioningOp::NoMatch => {
+                                return Err(AppBundleError::CellResolutionFailure(
+                                    role_name,
+                                    "no DNA or cell on this conductor matches the DNA version spec"
+                                        .into(),
+                                ));
+                            }
+                            CellProvisioningOp::Conflict(_) => {
+                                return Err(AppBundleError::CellResolutionFailure(
+                                    role_name,
+                                    "multiple cells match the DNA version spec".into(),
+                                ));
                             }
                         }
                         Ok(resolution)
@@ -111,6 +218,8 @@ impl AppBundle {
     async fn resolve_cell(
         &self,
         role: AppRoleManifestValidated,
+        gamut: &DnaGamut,
+        agent: &AgentPubKey,
     ) -> AppBundleResult<CellProvisioningOp> {
         Ok(match role {
             AppRoleManifestValidated::Create {
@@ -118,49 +227,64 @@ impl AppBundle {
                 version,
                 clone_limit,
                 modifiers,
-                deferred: _,
+                deferred,
+            } => self
+                .resolve_cell_create(&location, version.as_ref(), clone_limit, modifiers)
+                .await?
+                .deferred_if(deferred),
+
+            AppRoleManifestValidated::CreateClone {
+                location,
+                version,
+                clone_limit,
+                mut modifiers,
+                deferred,
             } => {
+                // Every installation gets its own network, even when the
+                // same DNA is used by another role or another app.
+                modifiers.network_seed = Some(nanoid::nanoid!());
                 self.resolve_cell_create(&location, version.as_ref(), clone_limit, modifiers)
                     .await?
-            }
-
-            AppRoleManifestValidated::CreateClone { .. } => {
-                unimplemented!("`create_clone` provisioning strategy is currently unimplemented")
+                    .deferred_if(deferred)
             }
             AppRoleManifestValidated::UseExisting {
                 version,
                 clone_limit,
                 deferred: _,
-            } => self.resolve_cell_existing(&version, clone_limit),
+            } => self.resolve_cell_existing(version, clone_limit, gamut, agent),
             AppRoleManifestValidated::CreateIfNotExists {
                 location,
                 version,
                 clone_limit,
                 modifiers,
-                deferred: _,
-            } => match self.resolve_cell_existing(&version, clone_limit) {
+                deferred,
+            } => match self.resolve_cell_existing(version.clone(), clone_limit, gamut, agent) {
                 op @ CellProvisioningOp::Existing(_, _) => op,
-                CellProvisioningOp::NoMatch => {
-                    self.resolve_cell_create(&location, Some(&version), clone_limit, modifiers)
-                        .await?
-                }
-                CellProvisioningOp::Conflict(_) => {
-                    unimplemented!("conflicts are not handled, or even possible yet")
-                }
+                CellProvisioningOp::NoMatch => self
+                    .resolve_cell_create(&location, Some(&version), clone_limit, modifiers)
+                    .await?
+                    .deferred_if(deferred),
+                op @ CellProvisioningOp::Conflict(_) => op,
                 CellProvisioningOp::Create(_, _) => {
                     unreachable!("resolve_cell_existing will never return a Create op")
                 }
                 CellProvisioningOp::Noop(_, _) => {
                     unreachable!("resolve_cell_existing will never return a Noop")
                 }
+                CellProvisioningOp::Defer(_, _) => {
+                    unreachable!("resolve_cell_existing will never return a Defer op")
+                }
             },
             AppRoleManifestValidated::Disabled {
-                version: _,
-                clone_limit: _,
-            } => {
-                unimplemented!("`disabled` provisioning strategy is currently unimplemented")
-                // CellProvisioningOp::Noop(clone_limit)
-            }
+                version,
+                clone_limit,
+            } => match gamut.resolve_dna(version) {
+                DnaResolution::Match(dna_hash, _) => CellProvisioningOp::Noop(
+                    CellId::new(dna_hash.into(), agent.clone()),
+                    clone_limit,
+                ),
+                DnaResolution::NoMatch | DnaResolution::Conflict => CellProvisioningOp::NoMatch,
+            },
         })
     }
 
@@ -184,19 +308,19 @@ impl AppBundle {
 
     fn resolve_cell_existing(
         &self,
-        _version: &DnaVersionSpec,
-        _clone_limit: u32,
+        version: DnaVersionSpec,
+        clone_limit: u32,
+        gamut: &DnaGamut,
+        agent: &AgentPubKey,
     ) -> CellProvisioningOp {
-        unimplemented!("Reusing existing cells is not yet implemented")
+        match gamut.resolve_cell(version, agent) {
+            CellResolution::Match(cell_id, _) => CellProvisioningOp::Existing(cell_id, clone_limit),
+            // TODO: surface conflicts once the gamut is able to produce them
+            CellResolution::NoMatch | CellResolution::Conflict => CellProvisioningOp::NoMatch,
+        }
     }
 }
 
-/// This function is called in places where it will be necessary to rework that
-/// area after use_existing has been implemented
-pub fn we_must_remember_to_rework_cell_panic_handling_after_implementing_use_existing_cell_resolution(
-) {
-}
-
 /// The answer to the question:
 /// "how do we concretely assign DNAs to the open roles of this App?"
 /// Includes the DNAs selected to fill the roles and the details of the role assignments.
@@ -220,7 +344,8 @@ impl AppRoleResolution {
     }
 
     /// Return the IDs of new cells to be created as part of the resolution.
-    /// Does not return existing cells to be reused.
+    /// Does not return existing cells to be reused, nor deferred cells whose
+    /// DNA is registered but which are not created yet.
     pub fn cells_to_create(&self) -> Vec<(CellId, Option<MembraneProof>)> {
         self.dnas_to_register
             .iter()
@@ -230,6 +355,11 @@ impl AppRoleResolution {
                     proof.clone(),
                 )
             })
+            .filter(|(cell_id, _)| {
+                self.role_assignments
+                    .iter()
+                    .any(|(_, role)| role.provisioned_cell() == Some(cell_id))
+            })
             .collect()
     }
 }
@@ -240,6 +370,9 @@ impl AppRoleResolution {
 pub enum CellProvisioningOp {
     /// Create a new Cell
     Create(DnaFile, u32),
+    /// Register the DNA, but defer creating the Cell until it is
+    /// provisioned at runtime
+    Defer(DnaFile, u32),
     /// Use an existing Cell
     Existing(CellId, u32),
     /// No provisioning needed, but there might be a clone_limit, and so we need
@@ -251,6 +384,16 @@ pub enum CellProvisioningOp {
     Conflict(CellProvisioningConflict),
 }
 
+impl CellProvisioningOp {
+    /// Turn a Create op into a Defer op, if the manifest says so.
+    fn deferred_if(self, deferred: bool) -> Self {
+        match self {
+            Self::Create(dna, clone_limit) if deferred => Self::Defer(dna, clone_limit),
+            op => op,
+        }
+    }
+}
+
 /// Uninhabitable placeholder
 #[derive(Debug)]
 pub enum CellProvisioningConflict {}
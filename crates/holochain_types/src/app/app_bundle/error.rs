@@ -1,13 +1,16 @@
 use holochain_util::ffs;
 use mr_bundle::error::MrBundleError;
 
-use crate::prelude::{AppManifestError, DnaError, RoleName};
+use crate::prelude::{AppManifestError, DnaError, DnaHash, RoleName};
 
 /// Errors occurring while installing an AppBundle
 #[derive(thiserror::Error, Debug)]
 pub enum AppBundleError {
-    #[error("Could not resolve the app role '{0}'")]
-    CellResolutionFailure(RoleName),
+    #[error("Could not resolve the app role '{0}': {1}")]
+    CellResolutionFailure(RoleName, String),
+
+    #[error("The DNA at {0:?} has hash {1}, which does not match the DNA version in the manifest")]
+    DnaHashMismatch(mr_bundle::Location, DnaHash),
 
     #[error(transparent)]
     AppManifestError(#[from] AppManifestError),
@@ -1,12 +1,18 @@
 use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
 
 use crate::prelude::*;
 use ::fixt::prelude::*;
 use app_manifest_v1::tests::{app_manifest_fixture, app_manifest_properties_fixture};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
 
 use super::AppBundle;
 
-async fn app_bundle_fixture(modifiers: DnaModifiersOpt<YamlProperties>) -> (AppBundle, DnaFile) {
+async fn app_bundle_fixture(
+    modifiers: DnaModifiersOpt<YamlProperties>,
+    provisioning: CellProvisioning,
+) -> (AppBundle, DnaFile) {
     let dna_wasm = DnaWasmHashed::from_content(DnaWasm::new_invalid()).await;
     let fake_wasms = vec![dna_wasm.clone().into_content()];
     let fake_zomes = vec![IntegrityZome::new(
@@ -21,12 +27,13 @@ async fn app_bundle_fixture(modifiers: DnaModifiersOpt<YamlProperties>) -> (AppB
 
     let path1 = PathBuf::from(format!("{}", dna1.dna_hash()));
 
-    let (manifest, _dna_hashes) = app_manifest_fixture(
+    let (mut manifest, _dna_hashes) = app_manifest_fixture(
         Some(DnaLocation::Bundled(path1.clone())),
         vec![dna1.dna_def().clone(), dna2.dna_def().clone()],
         modifiers,
     )
     .await;
+    manifest.roles[0].provisioning = Some(provisioning);
 
     let resources = vec![(path1, DnaBundle::from_dna_file(dna1.clone()).await.unwrap())];
 
@@ -47,7 +54,8 @@ async fn provisioning_1_create() {
         origin_time: None,
         quantum_time: None,
     };
-    let (bundle, dna) = app_bundle_fixture(modifiers).await;
+    let (bundle, dna) =
+        app_bundle_fixture(modifiers, CellProvisioning::Create { deferred: false }).await;
 
     // Apply the modifier overrides specified in the manifest fixture
     let dna = dna
@@ -74,3 +82,314 @@ async fn provisioning_1_create() {
     };
     assert_eq!(resolution, expected);
 }
+
+/// Test that an app with a single deferred Created cell registers its DNA
+/// without creating the cell
+#[tokio::test]
+async fn provisioning_1_create_deferred() {
+    let agent = fixt!(AgentPubKey);
+    let (bundle, dna) = app_bundle_fixture(
+        DnaModifiersOpt::none(),
+        CellProvisioning::Create { deferred: true },
+    )
+    .await;
+    let cell_id = CellId::new(dna.dna_hash().to_owned(), agent.clone());
+
+    let resolution = bundle
+        .resolve_cells(agent.clone(), DnaGamut::placeholder(), Default::default())
+        .await
+        .unwrap();
+
+    let expected = AppRoleResolution {
+        agent,
+        dnas_to_register: vec![(dna, None)],
+        role_assignments: vec![("name".into(), AppRoleAssignment::new_deferred(cell_id, 50))],
+    };
+    assert_eq!(resolution, expected);
+    assert!(resolution.cells_to_create().is_empty());
+}
+
+/// Test that an app with a single UseExisting cell binds to the matching cell
+/// in the gamut, and fails to install if there is no such cell
+#[tokio::test]
+async fn provisioning_1_use_existing() {
+    let agent = fixt!(AgentPubKey);
+    let (bundle, dna) = app_bundle_fixture(
+        DnaModifiersOpt::none(),
+        CellProvisioning::UseExisting { deferred: false },
+    )
+    .await;
+    let cell_id = CellId::new(dna.dna_hash().to_owned(), agent.clone());
+
+    let resolution = bundle
+        .resolve_cells(
+            agent.clone(),
+            DnaGamut::new([cell_id.clone()]),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+
+    let expected = AppRoleResolution {
+        agent: agent.clone(),
+        dnas_to_register: vec![],
+        role_assignments: vec![(
+            "name".into(),
+            AppRoleAssignment::new_dependency(cell_id, 50),
+        )],
+    };
+    assert_eq!(resolution, expected);
+
+    let (bundle, _) = app_bundle_fixture(
+        DnaModifiersOpt::none(),
+        CellProvisioning::UseExisting { deferred: false },
+    )
+    .await;
+    matches::assert_matches!(
+        bundle
+            .resolve_cells(agent, DnaGamut::placeholder(), Default::default())
+            .await,
+        Err(AppBundleError::CellResolutionFailure(role_name, _)) if role_name == "name"
+    );
+}
+
+/// Test that an app with a single CreateIfNotExists cell prefers a matching
+/// cell in the gamut, and falls back to creating a new cell
+#[tokio::test]
+async fn provisioning_1_create_if_not_exists() {
+    let agent = fixt!(AgentPubKey);
+    let provisioning = CellProvisioning::CreateIfNotExists { deferred: false };
+    let (bundle, dna) = app_bundle_fixture(DnaModifiersOpt::none(), provisioning.clone()).await;
+    let cell_id = CellId::new(dna.dna_hash().to_owned(), agent.clone());
+
+    let resolution = bundle
+        .resolve_cells(
+            agent.clone(),
+            DnaGamut::new([cell_id.clone()]),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    assert!(resolution.dnas_to_register.is_empty());
+    assert_eq!(
+        resolution.role_assignments,
+        vec![(
+            "name".into(),
+            AppRoleAssignment::new_dependency(cell_id.clone(), 50)
+        )]
+    );
+
+    let (bundle, dna) = app_bundle_fixture(DnaModifiersOpt::none(), provisioning).await;
+    let cell_id = CellId::new(dna.dna_hash().to_owned(), agent.clone());
+    let resolution = bundle
+        .resolve_cells(agent, DnaGamut::placeholder(), Default::default())
+        .await
+        .unwrap();
+    assert_eq!(resolution.dnas_to_register, vec![(dna, None)]);
+    assert_eq!(
+        resolution.role_assignments,
+        vec![("name".into(), AppRoleAssignment::new(cell_id, true, 50))]
+    );
+}
+
+/// Test that an app with a single CreateClone cell gets a distinct DNA with
+/// a unique network seed every time it is installed
+#[tokio::test]
+async fn provisioning_1_create_clone() {
+    let agent = fixt!(AgentPubKey);
+    let (bundle, dna) = app_bundle_fixture(
+        DnaModifiersOpt::none(),
+        CellProvisioning::CreateClone { deferred: false },
+    )
+    .await;
+    let bytes = bundle.encode().unwrap();
+    let resolve = || async {
+        AppBundle::decode(&bytes)
+            .unwrap()
+            .resolve_cells(agent.clone(), DnaGamut::placeholder(), Default::default())
+            .await
+            .unwrap()
+    };
+    let resolution_1 = resolve().await;
+    let resolution_2 = resolve().await;
+
+    let (dna_1, _) = &resolution_1.dnas_to_register[0];
+    let (dna_2, _) = &resolution_2.dnas_to_register[0];
+    assert_ne!(dna_1.dna_hash(), dna.dna_hash());
+    assert_ne!(dna_1.dna_hash(), dna_2.dna_hash());
+    assert_ne!(
+        dna_1.dna_def().modifiers.network_seed,
+        dna_2.dna_def().modifiers.network_seed
+    );
+    assert_eq!(
+        resolution_1.role_assignments,
+        vec![(
+            "name".into(),
+            AppRoleAssignment::new(
+                CellId::new(dna_1.dna_hash().clone(), agent.clone()),
+                true,
+                50
+            )
+        )]
+    );
+}
+
+/// Test that an app with a single Disabled cell refers to a registered DNA
+/// without provisioning a cell, and fails to install if the DNA is unknown
+#[tokio::test]
+async fn provisioning_1_disabled() {
+    let agent = fixt!(AgentPubKey);
+    let (bundle, dna) =
+        app_bundle_fixture(DnaModifiersOpt::none(), CellProvisioning::Disabled).await;
+    let cell_id = CellId::new(dna.dna_hash().to_owned(), agent.clone());
+
+    let resolution = bundle
+        .resolve_cells(
+            agent.clone(),
+            DnaGamut::placeholder().with_dnas([dna.dna_hash().clone()]),
+            Default::default(),
+        )
+        .await
+        .unwrap();
+
+    let expected = AppRoleResolution {
+        agent: agent.clone(),
+        dnas_to_register: vec![],
+        role_assignments: vec![("name".into(), AppRoleAssignment::new(cell_id, false, 50))],
+    };
+    assert_eq!(resolution, expected);
+
+    let (bundle, _) = app_bundle_fixture(DnaModifiersOpt::none(), CellProvisioning::Disabled).await;
+    matches::assert_matches!(
+        bundle
+            .resolve_cells(agent, DnaGamut::placeholder(), Default::default())
+            .await,
+        Err(AppBundleError::CellResolutionFailure(role_name, _)) if role_name == "name"
+    );
+}
+
+/// Serve the given bytes in response to any HTTP request on a local port.
+/// Returns the URL to request, and a count of the requests served.
+async fn serve_bytes(bytes: Vec<u8>) -> (String, Arc<AtomicUsize>) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/test.dna", listener.local_addr().unwrap());
+    let requests = Arc::new(AtomicUsize::new(0));
+    tokio::spawn({
+        let requests = requests.clone();
+        async move {
+            loop {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                requests.fetch_add(1, Ordering::SeqCst);
+                // The request itself is irrelevant, as long as it was sent
+                let mut buf = [0; 4096];
+                let _ = socket.read(&mut buf).await.unwrap();
+                let header = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+                    bytes.len()
+                );
+                socket.write_all(header.as_bytes()).await.unwrap();
+                socket.write_all(&bytes).await.unwrap();
+            }
+        }
+    });
+    (url, requests)
+}
+
+/// Test that DNAs at URL locations are fetched, checked against the DNA
+/// version, and embedded in the bundle, unless they are already cached
+#[tokio::test]
+async fn embed_remote_dnas() {
+    let agent = fixt!(AgentPubKey);
+    let dna_wasm = DnaWasmHashed::from_content(DnaWasm::new_invalid()).await;
+    let zomes = vec![IntegrityZome::new(
+        "hi".into(),
+        ZomeDef::Wasm(WasmZome::new(dna_wasm.as_hash().clone())).into(),
+    )];
+    let dna = DnaFile::new(
+        DnaDef::unique_from_zomes(zomes.clone(), vec![]),
+        vec![dna_wasm.clone().into_content()],
+    )
+    .await;
+    let other_dna_def = DnaDef::unique_from_zomes(zomes, vec![]);
+    let dna_bytes = DnaBundle::from_dna_file(dna.clone())
+        .await
+        .unwrap()
+        .encode()
+        .unwrap();
+    let (url, requests) = serve_bytes(dna_bytes).await;
+
+    // A bundle whose role has the DNA version of these DNAs, or none at all
+    let bundle_for = |dna_defs: Option<Vec<DnaDef>>| {
+        let url = url.clone();
+        async move {
+            let (mut manifest, _) = app_manifest_fixture(
+                Some(DnaLocation::Url(url)),
+                dna_defs.clone().unwrap_or_default(),
+                DnaModifiersOpt::none(),
+            )
+            .await;
+            if dna_defs.is_none() {
+                manifest.roles[0].dna.version = None;
+            }
+            AppBundle::new(manifest.into(), vec![], PathBuf::from("."))
+                .await
+                .unwrap()
+        }
+    };
+
+    let (bundle, fetched) = bundle_for(Some(vec![dna.dna_def().clone()]))
+        .await
+        .embed_remote_dnas(|_| None, |_| None)
+        .await
+        .unwrap();
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+    assert_eq!(fetched, vec![(url.clone(), dna.clone())]);
+    matches::assert_matches!(
+        &bundle.manifest().app_roles()[0].dna.location,
+        Some(DnaLocation::Bundled(_))
+    );
+    let resolution = bundle
+        .resolve_cells(agent, DnaGamut::placeholder(), Default::default())
+        .await
+        .unwrap();
+    assert_eq!(resolution.dnas_to_register, vec![(dna.clone(), None)]);
+
+    let cached = |hash: &DnaHash| (hash == dna.dna_hash()).then(|| dna.clone());
+
+    // A cached DNA is not fetched again
+    let (bundle, fetched) = bundle_for(Some(vec![dna.dna_def().clone()]))
+        .await
+        .embed_remote_dnas(cached, |_| None)
+        .await
+        .unwrap();
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+    assert!(fetched.is_empty());
+    assert_eq!(bundle.bundled_resources().len(), 1);
+
+    // A DNA without a version is fetched and returned for caching,
+    // and isn't fetched again once it's known to come from the URL
+    let (_, fetched) = bundle_for(None)
+        .await
+        .embed_remote_dnas(cached, |_| None)
+        .await
+        .unwrap();
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+    assert_eq!(fetched, vec![(url.clone(), dna.clone())]);
+    let (bundle, fetched) = bundle_for(None)
+        .await
+        .embed_remote_dnas(cached, |u| (u == url).then(|| dna.dna_hash().clone()))
+        .await
+        .unwrap();
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+    assert!(fetched.is_empty());
+    assert_eq!(bundle.bundled_resources().len(), 1);
+
+    // A DNA which doesn't match the version is rejected
+    matches::assert_matches!(
+        bundle_for(Some(vec![other_dna_def]))
+            .await
+            .embed_remote_dnas(|_| None, |_| None)
+            .await,
+        Err(AppBundleError::DnaHashMismatch(_, hash)) if &hash == dna.dna_hash()
+    );
+}
@@ -46,6 +46,14 @@ impl DnaGamut {
         Self(map)
     }
 
+    /// Include DNAs which are registered but not necessarily used by any Cell.
+    pub fn with_dnas<I: IntoIterator<Item = DnaHash>>(mut self, dnas: I) -> Self {
+        for dna in dnas {
+            self.0.entry(dna).or_default();
+        }
+        self
+    }
+
     #[allow(missing_docs)]
     pub fn placeholder() -> Self {
         Self::new(std::iter::empty())
@@ -61,23 +69,19 @@ impl DnaGamut {
         DnaResolution::NoMatch
     }
 
-    /// Given a version spec, return the best-matching CellId
-    // TODO: use DPKI to filter Cells which belong to Agents that are not
+    /// Given a version spec, return the CellId of the given agent for the
+    /// first matching DNA. Cells belonging to other agents never match.
+    // TODO: use DPKI to also match Cells which belong to Agents that are
     //       associated with the provided agent
-    pub fn resolve_cell(&self, spec: DnaVersionSpec, _agent: &AgentPubKey) -> CellResolution {
+    pub fn resolve_cell(&self, spec: DnaVersionSpec, agent: &AgentPubKey) -> CellResolution {
         for hash in spec.dna_hashes() {
-            if let Some(agent) = self
-                .0
-                .get(hash.as_ref())
-                // TODO: this is where an agent check could go, but for now we
-                //       just return the first one available
-                .map(|agents| agents.iter().next())
-                .unwrap_or(None)
-            {
-                return CellResolution::Match(
-                    CellId::new(hash.clone().into(), agent.clone()),
-                    DnaVersion,
-                );
+            if let Some(agents) = self.0.get(hash.as_ref()) {
+                if agents.contains(agent) {
+                    return CellResolution::Match(
+                        CellId::new(hash.clone().into(), agent.clone()),
+                        DnaVersion,
+                    );
+                }
             }
         }
         CellResolution::NoMatch
@@ -105,3 +109,29 @@ pub enum CellResolution {
     /// intervention for resolution (TODO, placeholder)
     Conflict,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ::fixt::prelude::*;
+
+    #[test]
+    fn resolve_cell_only_matches_cells_of_the_agent() {
+        let dna_hash = fixt!(DnaHash);
+        let alice = fixt!(AgentPubKey);
+        let bob = fixt!(AgentPubKey);
+        let gamut = DnaGamut::new([CellId::new(dna_hash.clone(), alice.clone())]);
+        let spec = DnaVersionSpec::from(vec![dna_hash.clone().into()]);
+
+        match gamut.resolve_cell(spec.clone(), &alice) {
+            CellResolution::Match(cell_id, _) => {
+                assert_eq!(cell_id, CellId::new(dna_hash.clone(), alice))
+            }
+            _ => panic!("expected alice's cell to match"),
+        }
+        assert!(matches!(
+            gamut.resolve_cell(spec, &bob),
+            CellResolution::NoMatch
+        ));
+    }
+}
@@ -20,6 +20,18 @@ pub enum AppError {
     #[error("Tried to access missing role name: '{0}'")]
     RoleNameMissing(RoleName),
 
+    #[error("The cell for role '{0}' has already been provisioned")]
+    RoleAlreadyProvisioned(RoleName),
+
+    #[error("The provisioning of role '{0}' was not deferred")]
+    RoleNotDeferred(RoleName),
+
+    #[error("The provisioning of role '{0}' was deferred, so its cell must be provisioned with ProvisionDeferredCell")]
+    RoleDeferred(RoleName),
+
+    #[error("Tried to provision cell {1} for role '{0}', but the cell must use the agent key of the role")]
+    DeferredCellAgentMismatch(RoleName, CellId),
+
     #[error("Tried to install app '{0}' which contains duplicate role names. The following role names have duplicates: {1:?}")]
     DuplicateRoleNames(InstalledAppId, Vec<RoleName>),
 }
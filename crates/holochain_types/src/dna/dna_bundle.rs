@@ -138,8 +138,14 @@ impl DnaBundle {
     }
 
     /// Build a bundle from a DnaFile. Useful for tests.
+    /// Fails if the DNA has any inline zomes, since those can't be bundled.
     #[cfg(feature = "test_utils")]
     pub async fn from_dna_file(dna_file: DnaFile) -> DnaResult<Self> {
+        Self::bundle_dna_file(dna_file)
+    }
+
+    /// Build a bundle from a DnaFile, failing if it has any inline zomes.
+    pub(crate) fn bundle_dna_file(dna_file: DnaFile) -> DnaResult<Self> {
         let DnaFile { dna, code, .. } = dna_file;
         let manifest = Self::manifest_from_dna_def(dna.into_content())?;
         let resources = code
@@ -149,12 +155,11 @@ impl DnaBundle {
         DnaBundle::new(manifest.try_into()?, resources, PathBuf::from("."))
     }
 
-    #[cfg(feature = "test_utils")]
     fn manifest_from_dna_def(dna_def: DnaDef) -> DnaResult<DnaManifest> {
         let integrity = dna_def
             .integrity_zomes
             .into_iter()
-            .filter_map(|(name, zome)| {
+            .map(|(name, zome)| {
                 let dependencies = zome
                     .as_any_zome_def()
                     .dependencies()
@@ -162,22 +167,20 @@ impl DnaBundle {
                     .cloned()
                     .map(|name| ZomeDependency { name })
                     .collect();
-                zome.wasm_hash(&name).ok().map(|hash| {
-                    let hash = WasmHashB64::from(hash);
-                    let filename = format!("{}", hash);
-                    ZomeManifest {
-                        name,
-                        hash: Some(hash),
-                        location: Location::Bundled(PathBuf::from(filename)),
-                        dependencies: Some(dependencies),
-                    }
+                let hash = WasmHashB64::from(zome.wasm_hash(&name)?);
+                let filename = format!("{}", hash);
+                Ok(ZomeManifest {
+                    name,
+                    hash: Some(hash),
+                    location: Location::Bundled(PathBuf::from(filename)),
+                    dependencies: Some(dependencies),
                 })
             })
-            .collect();
+            .collect::<DnaResult<_>>()?;
         let coordinator = dna_def
             .coordinator_zomes
             .into_iter()
-            .filter_map(|(name, zome)| {
+            .map(|(name, zome)| {
                 let dependencies = zome
                     .as_any_zome_def()
                     .dependencies()
@@ -185,18 +188,16 @@ impl DnaBundle {
                     .cloned()
                     .map(|name| ZomeDependency { name })
                     .collect();
-                zome.wasm_hash(&name).ok().map(|hash| {
-                    let hash = WasmHashB64::from(hash);
-                    let filename = format!("{}", hash);
-                    ZomeManifest {
-                        name,
-                        hash: Some(hash),
-                        location: Location::Bundled(PathBuf::from(filename)),
-                        dependencies: Some(dependencies),
-                    }
+                let hash = WasmHashB64::from(zome.wasm_hash(&name)?);
+                let filename = format!("{}", hash);
+                Ok(ZomeManifest {
+                    name,
+                    hash: Some(hash),
+                    location: Location::Bundled(PathBuf::from(filename)),
+                    dependencies: Some(dependencies),
                 })
             })
-            .collect();
+            .collect::<DnaResult<_>>()?;
         Ok(DnaManifestCurrent {
             name: dna_def.name,
             integrity: IntegrityManifest {
@@ -343,4 +344,33 @@ mod tests {
             SerializedBytes::try_from(properties).unwrap()
         );
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn dna_file_with_inline_zomes_cant_be_bundled() {
+        let wasm = DnaWasm::from(vec![1, 2, 3]);
+        let mut dna_modifiers = DnaModifiersBuilder::default();
+        dna_modifiers.network_seed("network seed".into());
+        let mut dna_def = DnaDefBuilder::default();
+        dna_def
+            .integrity_zomes(vec![
+                (
+                    "wasm".into(),
+                    IntegrityZomeDef::from(ZomeDef::Wasm(WasmZome {
+                        wasm_hash: wasm.to_hash().await,
+                        dependencies: vec![],
+                    })),
+                ),
+                (
+                    "inline".into(),
+                    InlineIntegrityZome::new_unique(vec![], 0).into(),
+                ),
+            ])
+            .modifiers(dna_modifiers.build().unwrap());
+        let dna = DnaFile::new(dna_def.build().unwrap(), vec![wasm]).await;
+
+        matches::assert_matches!(
+            DnaBundle::from_dna_file(dna).await,
+            Err(DnaError::ZomeError(ZomeError::NonWasmZome(name))) if name == ZomeName::from("inline")
+        );
+    }
 }
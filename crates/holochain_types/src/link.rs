@@ -31,6 +31,63 @@ pub struct WireLinkKey {
     pub type_query: LinkTypeFilter,
     /// Optionally specify a tag for more specific queries.
     pub tag: Option<LinkTag>,
+    /// Only include links created by this agent.
+    #[serde(default)]
+    pub author: Option<AgentPubKey>,
+    /// Only include links created at or after this time.
+    #[serde(default)]
+    pub after: Option<Timestamp>,
+    /// Only include links created before this time.
+    #[serde(default)]
+    pub before: Option<Timestamp>,
+    /// Return at most this many links.
+    #[serde(default)]
+    pub limit: Option<u32>,
+    /// Only include links which come after this cursor.
+    #[serde(default)]
+    pub cursor: Option<LinkCursor>,
+}
+
+impl WireLinkKey {
+    /// Create a key for all links on a base of the given types,
+    /// optionally with a tag prefix.
+    pub fn new(base: AnyLinkableHash, type_query: LinkTypeFilter, tag: Option<LinkTag>) -> Self {
+        Self {
+            base,
+            type_query,
+            tag,
+            author: None,
+            after: None,
+            before: None,
+            limit: None,
+            cursor: None,
+        }
+    }
+}
+
+impl From<GetLinksInput> for WireLinkKey {
+    fn from(input: GetLinksInput) -> Self {
+        let GetLinksInput {
+            base_address,
+            link_type,
+            tag_prefix,
+            author,
+            after,
+            before,
+            limit,
+            cursor,
+        } = input;
+        Self {
+            base: base_address,
+            type_query: link_type,
+            tag: tag_prefix,
+            author,
+            after,
+            before,
+            limit,
+            cursor,
+        }
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes, Default)]
@@ -61,6 +118,24 @@ impl WireLinkOps {
     }
 }
 
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes, Default)]
+/// The create links matching a link key without their content, for sending
+/// across the wire in response to count links.
+pub struct WireLinkHashes {
+    /// The positions of the create links that match this query,
+    /// in the order they were created.
+    pub creates: Vec<LinkCursor>,
+    /// The hashes of the create links which have been deleted.
+    pub deleted: Vec<ActionHash>,
+}
+
+impl WireLinkHashes {
+    /// Create an empty wire response.
+    pub fn new() -> Self {
+        Default::default()
+    }
+}
+
 #[allow(missing_docs)]
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize, SerializedBytes)]
 /// Condensed version of a [`CreateLink`]
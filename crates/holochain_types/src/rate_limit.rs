@@ -1,3 +1,145 @@
 //! Types for rate limiting
 
 pub use holochain_zome_types::rate_limit::*;
+use holochain_zome_types::Timestamp;
+
+/// A rate limiting bucket which actions are weighed into in chain order.
+#[derive(Debug, Clone)]
+pub struct RateBucket {
+    limit: RateLimit,
+    level: u64,
+    last_change: Option<Timestamp>,
+}
+
+impl RateBucket {
+    /// Create an empty bucket with the given limits.
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            level: 0,
+            last_change: None,
+        }
+    }
+
+    /// The units currently in the bucket.
+    pub fn level(&self) -> u64 {
+        self.level
+    }
+
+    /// Drain the bucket up to the given time, then fill it with the units
+    /// of an action authored at that time.
+    /// Returns the new level, or None if the bucket overflowed.
+    pub fn fill(&mut self, units: RateUnits, timestamp: Timestamp) -> Option<u64> {
+        match self.last_change {
+            Some(last_change) if self.level > 0 => {
+                let elapsed_us = (timestamp.as_micros() - last_change.as_micros()).max(0) as u64;
+                let interval_us = self.limit.drain_interval_ms as u64 * 1000;
+                let drain_amount = self.limit.drain_amount as u64;
+                let drained = match interval_us {
+                    0 => self.level,
+                    _ => elapsed_us.saturating_mul(drain_amount) / interval_us,
+                };
+                if drained >= self.level {
+                    self.level = 0;
+                    self.last_change = Some(timestamp);
+                } else if drained > 0 {
+                    // Only the time it took to drain whole units is used up,
+                    // so the remainder still counts towards the next unit.
+                    self.level -= drained;
+                    let used_us = (drained * interval_us + drain_amount - 1) / drain_amount;
+                    self.last_change = Some(Timestamp::from_micros(
+                        last_change.as_micros() + used_us as i64,
+                    ));
+                }
+            }
+            // An empty bucket has nothing to drain, so time spent empty
+            // doesn't count.
+            _ => self.last_change = Some(timestamp),
+        }
+        self.level += units as u64;
+        (self.level <= self.limit.capacity as u64).then_some(self.level)
+    }
+
+    /// How far back in a chain's history actions can still affect the level
+    /// of the bucket, or None if it never fully drains.
+    pub fn history_window(&self) -> Option<std::time::Duration> {
+        let RateLimit {
+            capacity,
+            drain_amount,
+            drain_interval_ms,
+        } = self.limit;
+        if drain_amount == 0 {
+            return None;
+        }
+        // A bucket at capacity takes this long to drain completely.
+        let intervals = (capacity as u64 + drain_amount as u64 - 1) / drain_amount as u64;
+        Some(std::time::Duration::from_millis(
+            intervals * drain_interval_ms as u64,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(ms: i64) -> Timestamp {
+        Timestamp::from_micros(ms * 1000)
+    }
+
+    #[test]
+    fn bucket_fills_and_drains() {
+        let mut bucket = RateBucket::new(RateLimit {
+            capacity: 3,
+            drain_amount: 1,
+            drain_interval_ms: 1000,
+        });
+        assert_eq!(
+            bucket.history_window(),
+            Some(std::time::Duration::from_secs(3))
+        );
+        assert_eq!(bucket.fill(2, at(0)), Some(2));
+        assert_eq!(bucket.fill(1, at(500)), Some(3));
+        assert_eq!(bucket.fill(1, at(900)), None);
+
+        let mut bucket = RateBucket::new(RateLimit {
+            capacity: 3,
+            drain_amount: 1,
+            drain_interval_ms: 1000,
+        });
+        assert_eq!(bucket.fill(3, at(0)), Some(3));
+        assert_eq!(bucket.fill(1, at(1000)), Some(3));
+        // Draining never goes below empty
+        assert_eq!(bucket.fill(0, at(100_000)), Some(0));
+        assert_eq!(bucket.level(), 0);
+
+        let mut never_drains = RateBucket::new(RateLimit {
+            capacity: 2,
+            drain_amount: 0,
+            drain_interval_ms: 1000,
+        });
+        assert_eq!(never_drains.history_window(), None);
+        assert_eq!(never_drains.fill(1, at(0)), Some(1));
+        assert_eq!(never_drains.fill(1, at(1_000_000)), Some(2));
+        assert_eq!(never_drains.fill(1, at(2_000_000)), None);
+    }
+
+    #[test]
+    fn bucket_accepts_writes_at_the_drain_rate() {
+        let mut bucket = RateBucket::new(RateLimit {
+            capacity: 3,
+            drain_amount: 3,
+            drain_interval_ms: 1000,
+        });
+        // One unit every third of a second, which doesn't divide into whole
+        // milliseconds, so no single write drains a whole unit on the dot.
+        for i in 0..100 {
+            assert!(
+                bucket.fill(1, at(i * 1000 / 3)).is_some(),
+                "write {} was rejected",
+                i
+            );
+        }
+        assert!(bucket.level() <= 2);
+    }
+}
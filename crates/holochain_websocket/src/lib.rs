@@ -12,6 +12,9 @@
 //! [`WebsocketReceiver`]
 //! ).
 //!
+//! On unix, [`WebsocketListener::bind_unix`] and [`connect_unix`] do the same
+//! over a unix domain socket, so access can be controlled with file permissions.
+//!
 //! If you want to be able to shutdown the stream use [`WebsocketListener::bind_with_handle`]
 //! which will give you a tuple ([`ListenerHandle`], [`ListenerStream`]).
 //! You can use [`ListenerHandle::close`] to close immediately or
@@ -119,20 +122,42 @@ pub async fn connect(
     // socket.set_keepalive(Some(std::time::Duration::from_secs(
     //     config.tcp_keepalive_s as u64,
     // )))?;
-    let (socket, _) = tokio_tungstenite::client_async_with_config(
-        url.as_str(),
-        socket,
-        Some(config.to_tungstenite()),
-    )
-    .await
-    .map_err(|e| Error::new(ErrorKind::Other, e))?;
+    let remote_addr = util::addr_to_url(socket.peer_addr()?, config.scheme);
+    client_handshake(url.as_str(), Box::new(socket), remote_addr, config).await
+}
+
+#[cfg(unix)]
+#[instrument(skip(config))]
+/// Create a new websocket connection to a listener bound to
+/// a unix domain socket at this path.
+pub async fn connect_unix(
+    path: impl AsRef<std::path::Path> + std::fmt::Debug,
+    config: Arc<WebsocketConfig>,
+) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
+    let path = path.as_ref();
+    let socket = tokio::net::UnixStream::connect(path).await?;
+    // The handshake needs a request uri but there is no host to put in it.
+    let url = format!("{}://localhost/", config.scheme);
+    client_handshake(&url, Box::new(socket), util::path_to_url(path), config).await
+}
+
+async fn client_handshake(
+    url: &str,
+    socket: Box<dyn util::RawSocket>,
+    remote_addr: Url2,
+    config: Arc<WebsocketConfig>,
+) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
+    let (socket, _) =
+        tokio_tungstenite::client_async_with_config(url, socket, Some(config.to_tungstenite()))
+            .await
+            .map_err(|e| Error::new(ErrorKind::Other, e))?;
     tracing::debug!("Client connected");
 
     // Noop valve because we don't have a listener to shutdown the
     // ends when creating a client
     let (exit, valve) = Valve::new();
     exit.disable();
-    Websocket::create_ends(config, socket, valve)
+    Websocket::create_ends(config, socket, remote_addr, valve)
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize, SerializedBytes)]
@@ -15,8 +15,8 @@ use tracing::Instrument;
 use ghost_actor::*;
 use tungstenite::protocol::frame::coding::CloseCode;
 use tungstenite::protocol::CloseFrame;
+use url2::Url2;
 
-use crate::util::addr_to_url;
 use crate::util::ToFromSocket;
 use crate::util::CLOSE_TIMEOUT;
 use crate::CancelResponse;
@@ -125,13 +125,10 @@ impl Websocket {
     pub fn create_ends(
         config: Arc<WebsocketConfig>,
         socket: ToFromSocket,
+        remote_addr: Url2,
         listener_shutdown: Valve,
     ) -> WebsocketResult<(WebsocketSender, WebsocketReceiver)> {
-        let remote_addr = url2::url2!(
-            "{}#{}",
-            addr_to_url(socket.get_ref().peer_addr()?, config.scheme),
-            nanoid::nanoid!(),
-        );
+        let remote_addr = url2::url2!("{}#{}", remote_addr, nanoid::nanoid!());
 
         // Channel to the websocket from the application
         let (tx_to_websocket, rx_to_websocket) = tokio::sync::mpsc::channel(config.max_send_queue);
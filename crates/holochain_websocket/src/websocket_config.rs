@@ -1,5 +1,7 @@
 //! defines a builder-style config struct for setting up websockets
 
+use std::collections::HashSet;
+
 /// A builder-style config struct for setting up websockets.
 #[derive(Debug)]
 pub struct WebsocketConfig {
@@ -26,6 +28,10 @@ pub struct WebsocketConfig {
 
     /// Maximum number of pending new incoming connections. [default = 255]
     pub max_pending_connections: usize,
+
+    /// Which origins a listener accepts connections from.
+    /// [default = AllowedOrigins::Any]
+    pub allowed_origins: AllowedOrigins,
 }
 
 impl Default for WebsocketConfig {
@@ -38,6 +44,7 @@ impl Default for WebsocketConfig {
             max_message_size: 64 << 20,
             max_frame_size: 16 << 20,
             max_pending_connections: 255,
+            allowed_origins: AllowedOrigins::Any,
         }
     }
 }
@@ -78,6 +85,38 @@ impl WebsocketConfig {
         self.max_frame_size = max;
         self
     }
+
+    /// Builder-style setter.
+    pub fn allowed_origins(mut self, allowed_origins: AllowedOrigins) -> Self {
+        self.allowed_origins = allowed_origins;
+        self
+    }
+}
+
+/// The origins a listener accepts connections from, checked against the
+/// `Origin` header of the websocket handshake.
+#[derive(Clone, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AllowedOrigins {
+    /// Accept connections from anywhere, including from clients which send
+    /// no `Origin` header.
+    #[default]
+    Any,
+
+    /// Only accept connections which send one of these origins, e.g.
+    /// `http://localhost:8888`. Clients which send no `Origin` header are
+    /// refused, so non-browser clients must set it too.
+    Origins(HashSet<String>),
+}
+
+impl AllowedOrigins {
+    /// Whether a connection with this `Origin` header is accepted.
+    pub fn is_allowed(&self, origin: Option<&str>) -> bool {
+        match self {
+            AllowedOrigins::Any => true,
+            AllowedOrigins::Origins(origins) => origin.map_or(false, |o| origins.contains(o)),
+        }
+    }
 }
 
 /// internal helper to convert our configs into tungstenite configs
@@ -8,11 +8,19 @@ use std::sync::Arc;
 use stream_cancel::Trigger;
 use stream_cancel::Valve;
 use tracing::instrument;
+use tungstenite::handshake::server::ErrorResponse;
+use tungstenite::handshake::server::Request;
+use tungstenite::handshake::server::Response;
+use tungstenite::http::header;
+use tungstenite::http::StatusCode;
 
 use url2::Url2;
 
 use crate::util::addr_to_url;
+#[cfg(unix)]
+use crate::util::path_to_url;
 use crate::util::url_to_addr;
+use crate::util::RawSocket;
 use crate::websocket::Websocket;
 use crate::WebsocketConfig;
 use crate::WebsocketError;
@@ -106,6 +114,36 @@ impl WebsocketListener {
     )> {
         websocket_bind(addr, config).await
     }
+
+    #[cfg(unix)]
+    /// Bind to a unix domain socket at this path to accept incoming connections.
+    /// The socket file is only accessible to the user running the listener.
+    /// A stale socket file left behind by a previous listener is replaced,
+    /// but binding fails if another listener is still using the path.
+    pub async fn bind_unix(
+        path: &std::path::Path,
+        config: Arc<WebsocketConfig>,
+    ) -> WebsocketResult<Self> {
+        let (handle, stream) = Self::bind_unix_with_handle(path, config).await?;
+        Ok(Self {
+            handle,
+            stream: stream.boxed(),
+        })
+    }
+
+    #[cfg(unix)]
+    /// Same as [`WebsocketListener::bind_unix`] but gives you a [`ListenerHandle`] to shutdown
+    /// the listener and any open connections.
+    pub async fn bind_unix_with_handle(
+        path: &std::path::Path,
+        config: Arc<WebsocketConfig>,
+    ) -> WebsocketResult<(
+        ListenerHandle,
+        impl futures::stream::Stream<Item = ListenerItem>,
+    )> {
+        websocket_bind_unix(path, config).await
+    }
+
     /// Shutdown the listener stream.
     pub fn close(self) {
         self.handle.close()
@@ -203,7 +241,92 @@ async fn websocket_bind(
     let local_addr = addr_to_url(socket.local_addr()?, config.scheme);
     let listener = tokio::net::TcpListener::from_std(socket)?;
     let listener_stream = tokio_stream::wrappers::TcpListenerStream::new(listener);
+    let scheme = config.scheme;
+    let listener_stream = listener_stream.map(move |socket| {
+        let socket = socket?;
+        let remote_addr = addr_to_url(socket.peer_addr()?, scheme);
+        Ok((Box::new(socket) as Box<dyn RawSocket>, remote_addr))
+    });
+    Ok(listen(listener_stream, local_addr, config))
+}
 
+#[cfg(unix)]
+async fn websocket_bind_unix(
+    path: &std::path::Path,
+    config: Arc<WebsocketConfig>,
+) -> WebsocketResult<(
+    ListenerHandle,
+    impl futures::stream::Stream<Item = ListenerItem>,
+)> {
+    use std::os::unix::fs::DirBuilderExt;
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::fs::PermissionsExt;
+
+    match std::fs::symlink_metadata(path) {
+        Ok(metadata) if !metadata.file_type().is_socket() => {
+            return Err(Error::new(
+                ErrorKind::AlreadyExists,
+                format!("{} exists and is not a unix socket", path.display()),
+            )
+            .into());
+        }
+        Ok(_) => {
+            // Only remove the socket if nothing is listening on it.
+            if tokio::net::UnixStream::connect(path).await.is_ok() {
+                return Err(Error::new(
+                    ErrorKind::AddrInUse,
+                    format!("unix socket {} is already in use", path.display()),
+                )
+                .into());
+            }
+            std::fs::remove_file(path)?;
+        }
+        Err(e) if e.kind() == ErrorKind::NotFound => (),
+        Err(e) => return Err(e.into()),
+    }
+
+    // Bind inside a directory only we can enter, so the socket can't be
+    // connected to before its permissions are restricted, then move it
+    // into place.
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| Error::new(ErrorKind::InvalidInput, "unix socket path has no file name"))?;
+    let private_dir = path.with_file_name(format!(
+        ".{}.{}",
+        file_name.to_string_lossy(),
+        nanoid::nanoid!()
+    ));
+    std::fs::DirBuilder::new()
+        .mode(0o700)
+        .create(&private_dir)?;
+    let private_path = private_dir.join(file_name);
+    let bound = tokio::net::UnixListener::bind(&private_path).and_then(|listener| {
+        std::fs::set_permissions(&private_path, std::fs::Permissions::from_mode(0o600))?;
+        std::fs::rename(&private_path, path)?;
+        Ok(listener)
+    });
+    let _ = std::fs::remove_file(&private_path);
+    std::fs::remove_dir(&private_dir)?;
+    let listener = bound?;
+    let local_addr = path_to_url(&std::fs::canonicalize(path)?);
+    let listener_stream = tokio_stream::wrappers::UnixListenerStream::new(listener);
+    let remote_addr = local_addr.clone();
+    let listener_stream = listener_stream
+        .map_ok(move |socket| (Box::new(socket) as Box<dyn RawSocket>, remote_addr.clone()));
+    Ok(listen(listener_stream, local_addr, config))
+}
+
+/// Accept websocket connections on a stream of raw sockets.
+fn listen(
+    listener_stream: impl futures::stream::Stream<Item = std::io::Result<(Box<dyn RawSocket>, Url2)>>
+        + Send
+        + 'static,
+    local_addr: Url2,
+    config: Arc<WebsocketConfig>,
+) -> (
+    ListenerHandle,
+    impl futures::stream::Stream<Item = ListenerItem>,
+) {
     // Setup proper shutdown
     let (shutdown, valve) = Valve::new();
 
@@ -212,7 +335,7 @@ async fn websocket_bind(
         .map_ok({
             let config = config.clone();
             let valve = valve.clone();
-            move |socket_result| connect(config.clone(), socket_result, valve.clone())
+            move |(socket, remote_addr)| connect(config.clone(), socket, remote_addr, valve.clone())
         })
         .try_buffer_unordered(config.max_pending_connections);
     tracing::debug!(sever_listening_on = ?local_addr);
@@ -224,13 +347,14 @@ async fn websocket_bind(
         config,
         local_addr,
     };
-    Ok((listener_handle, stream))
+    (listener_handle, stream)
 }
 
 #[instrument(skip(config, socket, valve))]
 async fn connect(
     config: Arc<WebsocketConfig>,
-    socket: tokio::net::TcpStream,
+    socket: Box<dyn RawSocket>,
+    remote_addr: Url2,
     valve: Valve,
 ) -> WebsocketResult<Pair> {
     // TODO: find alternative to set the keepalive
@@ -239,10 +363,26 @@ async fn connect(
     // )))?;
     tracing::debug!(
         message = "accepted incoming raw socket",
-        remote_addr = %socket.peer_addr()?,
+        remote_addr = %remote_addr,
     );
-    let socket = tokio_tungstenite::accept_async_with_config(
+    let allowed_origins = config.allowed_origins.clone();
+    let check_origin = move |request: &Request, response: Response| {
+        let origin = request
+            .headers()
+            .get(header::ORIGIN)
+            .and_then(|o| o.to_str().ok());
+        if allowed_origins.is_allowed(origin) {
+            Ok(response)
+        } else {
+            tracing::warn!(?origin, "refused websocket connection from origin");
+            let mut refusal = ErrorResponse::new(Some("Origin not allowed".into()));
+            *refusal.status_mut() = StatusCode::FORBIDDEN;
+            Err(refusal)
+        }
+    };
+    let socket = tokio_tungstenite::accept_hdr_async_with_config(
         socket,
+        check_origin,
         Some(tungstenite::protocol::WebSocketConfig {
             max_send_queue: Some(config.max_send_queue),
             max_message_size: Some(config.max_message_size),
@@ -253,5 +393,5 @@ async fn connect(
     .await
     .map_err(|e| Error::new(ErrorKind::Other, e))?;
 
-    Websocket::create_ends(config, socket, valve)
+    Websocket::create_ends(config, socket, remote_addr, valve)
 }
@@ -3,6 +3,7 @@ use std::sync::Arc;
 use futures::StreamExt;
 use holochain_serialized_bytes::prelude::*;
 use holochain_websocket::connect;
+use holochain_websocket::AllowedOrigins;
 use holochain_websocket::ListenerHandle;
 use holochain_websocket::ListenerItem;
 use holochain_websocket::WebsocketConfig;
@@ -452,3 +453,107 @@ async fn cancel_response() {
     c_jh.await.unwrap();
     s_jh.await.unwrap();
 }
+
+#[tokio::test(flavor = "multi_thread")]
+async fn refuses_disallowed_origins() {
+    observability::test_run().ok();
+    let allowed = AllowedOrigins::Origins(["http://localhost:8888".to_string()].into());
+    let (handle, mut listener) = WebsocketListener::bind_with_handle(
+        url2!("ws://127.0.0.1:0"),
+        Arc::new(WebsocketConfig::default().allowed_origins(allowed)),
+    )
+    .await
+    .unwrap();
+    tokio::task::spawn(async move { while listener.next().await.is_some() {} });
+    let addr = handle.local_addr().clone();
+
+    let connect_from = |origin: Option<&'static str>| {
+        let addr = addr.clone();
+        async move {
+            let mut request = tungstenite::http::Request::builder().uri(addr.as_str());
+            if let Some(origin) = origin {
+                request = request.header("Origin", origin);
+            }
+            let socket = tokio::net::TcpStream::connect(addr.socket_addrs(|| None).unwrap()[0])
+                .await
+                .unwrap();
+            tokio_tungstenite::client_async(request.body(()).unwrap(), socket).await
+        }
+    };
+
+    assert!(connect_from(Some("http://localhost:8888")).await.is_ok());
+    assert!(connect_from(Some("http://localhost:9999")).await.is_err());
+    assert!(connect_from(None).await.is_err());
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn can_send_request_over_unix_socket() {
+    use holochain_websocket::connect_unix;
+    use std::os::unix::fs::PermissionsExt;
+
+    observability::test_run().ok();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("ws.sock");
+    // A stale socket file from a previous run is replaced.
+    drop(std::os::unix::net::UnixListener::bind(&path).unwrap());
+
+    let (handle, mut listener) =
+        WebsocketListener::bind_unix_with_handle(&path, Arc::new(WebsocketConfig::default()))
+            .await
+            .unwrap();
+    assert_eq!(handle.local_addr().scheme(), "unix");
+    let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o600);
+
+    // The path can't be bound again while the listener is using it.
+    assert!(
+        WebsocketListener::bind_unix(&path, Arc::new(WebsocketConfig::default()))
+            .await
+            .is_err()
+    );
+
+    let jh = tokio::task::spawn(async move {
+        // Skip the connection made when checking the path was in use.
+        let (_sender, mut receiver) = loop {
+            if let Ok(pair) = listener.next().await.unwrap() {
+                break pair;
+            }
+        };
+        let (msg, resp) = receiver.next().await.unwrap();
+        let msg: TestString = msg.try_into().unwrap();
+        assert_eq!(msg.0, "Hey from client");
+        resp.respond(TestString("Bye from server".into()).try_into().unwrap())
+            .await
+            .unwrap();
+    });
+
+    let (mut sender, _receiver) = connect_unix(&path, Arc::new(WebsocketConfig::default()))
+        .await
+        .unwrap();
+    let msg: TestString = sender
+        .request(TestString("Hey from client".into()))
+        .await
+        .unwrap();
+    assert_eq!(msg.0, "Bye from server");
+
+    jh.await.unwrap();
+}
+
+#[cfg(unix)]
+#[tokio::test(flavor = "multi_thread")]
+async fn unix_socket_doesnt_replace_other_files() {
+    observability::test_run().ok();
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("ws.sock");
+    std::fs::write(&path, "not a socket").unwrap();
+
+    assert!(
+        WebsocketListener::bind_unix(&path, Arc::new(WebsocketConfig::default()))
+            .await
+            .is_err()
+    );
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "not a socket");
+    // Nothing is left behind next to the path.
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 1);
+}
@@ -0,0 +1,69 @@
+//! Types for the interface between the conductor and a DPKI service.
+//!
+//! A DPKI service is a DNA, in the style of DeepKey, which keeps a
+//! distributed registry of agent keys. The conductor calls the functions of
+//! its [`DPKI_ZOME_NAME`] zome to register, revoke and rotate the keys it
+//! generates, and to look up the state of the keys which sign the data it
+//! validates.
+use crate::Timestamp;
+use holo_hash::AgentPubKey;
+use holochain_serialized_bytes::prelude::*;
+
+/// The name of the zome which implements DPKI in the DPKI service DNA.
+pub const DPKI_ZOME_NAME: &str = "dpki";
+
+/// The zome function called once, after the conductor installs the DPKI app.
+/// Takes the `init_params` of the conductor's DPKI config as a [`String`]
+/// and returns nothing.
+pub const DPKI_INIT_FN: &str = "init_dpki";
+
+/// The zome function called to register a newly generated key.
+/// Takes an [`AgentPubKey`] and returns nothing.
+pub const DPKI_REGISTER_KEY_FN: &str = "register_key";
+
+/// The zome function called to revoke a key.
+/// Takes an [`AgentPubKey`] and returns its new [`KeyState`].
+pub const DPKI_REVOKE_KEY_FN: &str = "revoke_key";
+
+/// The zome function called to replace a key with a new one.
+/// Takes a [`KeyRotation`] and returns nothing.
+pub const DPKI_ROTATE_KEY_FN: &str = "rotate_key";
+
+/// The zome function called to look up the state of a key.
+/// Takes an [`AgentPubKey`] and returns its [`KeyState`].
+pub const DPKI_KEY_STATE_FN: &str = "key_state";
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+/// The state of an agent key in the DPKI registry.
+pub enum KeyState {
+    /// The key has never been registered.
+    NotFound,
+    /// The key is registered and has not been revoked.
+    Valid,
+    /// The key was revoked. Anything it signed after it was revoked is invalid.
+    Revoked {
+        /// When the key was revoked.
+        revoked_at: Timestamp,
+        /// The key which replaced this one, if it was revoked by a rotation.
+        replaced_by: Option<AgentPubKey>,
+    },
+}
+
+impl KeyState {
+    /// Whether something signed by this key at the given time is valid.
+    pub fn is_valid_at(&self, timestamp: Timestamp) -> bool {
+        match self {
+            Self::NotFound | Self::Valid => true,
+            Self::Revoked { revoked_at, .. } => timestamp < *revoked_at,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+/// A request to revoke a key and register another one in its place.
+pub struct KeyRotation {
+    /// The key to revoke.
+    pub old_key: AgentPubKey,
+    /// The key to register in its place.
+    pub new_key: AgentPubKey,
+}
@@ -1,6 +1,7 @@
 use crate::record::SignedActionHashed;
 use crate::ChainTopOrdering;
 use holo_hash::ActionHash;
+use holo_hash::AgentPubKey;
 use holochain_integrity_types::ZomeIndex;
 use holochain_serialized_bytes::prelude::*;
 
@@ -87,6 +88,22 @@ pub struct GetLinksInput {
     /// The link types to include in this get.
     pub link_type: LinkTypeFilter,
     pub tag_prefix: Option<crate::link::LinkTag>,
+    /// Only include links created by this agent.
+    #[serde(default)]
+    pub author: Option<AgentPubKey>,
+    /// Only include links created at or after this time.
+    #[serde(default)]
+    pub after: Option<crate::Timestamp>,
+    /// Only include links created before this time.
+    #[serde(default)]
+    pub before: Option<crate::Timestamp>,
+    /// Return at most this many links.
+    #[serde(default)]
+    pub limit: Option<u32>,
+    /// Only include links which come after this cursor,
+    /// as returned for the last link of the previous page.
+    #[serde(default)]
+    pub cursor: Option<LinkCursor>,
 }
 
 impl GetLinksInput {
@@ -99,8 +116,119 @@ impl GetLinksInput {
             base_address,
             link_type,
             tag_prefix,
+            author: None,
+            after: None,
+            before: None,
+            limit: None,
+            cursor: None,
         }
     }
+
+    /// Only include links created by this agent.
+    pub fn author(mut self, author: AgentPubKey) -> Self {
+        self.author = Some(author);
+        self
+    }
+
+    /// Only include links created at or after this time.
+    pub fn after(mut self, after: crate::Timestamp) -> Self {
+        self.after = Some(after);
+        self
+    }
+
+    /// Only include links created before this time.
+    pub fn before(mut self, before: crate::Timestamp) -> Self {
+        self.before = Some(before);
+        self
+    }
+
+    /// Return at most this many links.
+    pub fn limit(mut self, limit: u32) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Start after this cursor, to get the next page of links.
+    pub fn cursor(mut self, cursor: LinkCursor) -> Self {
+        self.cursor = Some(cursor);
+        self
+    }
+}
+
+/// Zome IO inner type for counting the links which match a [`GetLinksInput`]
+/// without getting the links themselves.
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize)]
+pub struct CountLinksInput {
+    /// The links to count.
+    pub query: GetLinksInput,
+    /// Also return the hashes of the create actions of the counted links.
+    pub include_hashes: bool,
+}
+
+impl CountLinksInput {
+    pub fn new(query: GetLinksInput, include_hashes: bool) -> Self {
+        Self {
+            query,
+            include_hashes,
+        }
+    }
+}
+
+/// The number of links which match a [`CountLinksInput`].
+#[derive(PartialEq, Clone, Debug, Serialize, Deserialize, SerializedBytes)]
+pub struct LinkCount {
+    /// The number of links which match the query.
+    pub count: u32,
+    /// The hashes of the create actions of the counted links,
+    /// in the order the links were created, if they were asked for.
+    pub create_link_hashes: Option<Vec<ActionHash>>,
+}
+
+/// An opaque position in the list of links on a base.
+///
+/// Links are ordered by the time they were created and then by
+/// the hash of their create action. Pass the cursor of the last link
+/// of a page to [`GetLinksInput::cursor`] to get the next page.
+#[derive(
+    Debug, PartialOrd, Ord, Clone, Hash, PartialEq, Eq, Serialize, Deserialize, SerializedBytes,
+)]
+pub struct LinkCursor {
+    timestamp: crate::Timestamp,
+    create_link_hash: ActionHash,
+}
+
+impl LinkCursor {
+    /// Create a cursor positioned at a create link action.
+    pub fn new(timestamp: crate::Timestamp, create_link_hash: ActionHash) -> Self {
+        Self {
+            timestamp,
+            create_link_hash,
+        }
+    }
+
+    /// The time the link at this cursor was created.
+    pub fn timestamp(&self) -> crate::Timestamp {
+        self.timestamp
+    }
+
+    /// The hash of the create action of the link at this cursor.
+    pub fn create_link_hash(&self) -> &ActionHash {
+        &self.create_link_hash
+    }
+}
+
+impl From<&Link> for LinkCursor {
+    fn from(link: &Link) -> Self {
+        Self::new(link.timestamp, link.create_link_hash.clone())
+    }
+}
+
+impl Link {
+    /// The cursor to pass to [`GetLinksInput::cursor`] to get
+    /// the links which come after this one.
+    pub fn cursor(&self) -> LinkCursor {
+        self.into()
+    }
 }
 
 type CreateLinkWithDeleteLinks = Vec<(SignedActionHashed, Vec<SignedActionHashed>)>;
@@ -125,4 +253,12 @@ impl LinkDetails {
     pub fn into_inner(self) -> CreateLinkWithDeleteLinks {
         self.into()
     }
+
+    /// The cursor to pass to [`GetLinksInput::cursor`] to get
+    /// the link details which come after these.
+    pub fn next_cursor(&self) -> Option<LinkCursor> {
+        self.0.last().map(|(create, _)| {
+            LinkCursor::new(create.action().timestamp(), create.as_hash().clone())
+        })
+    }
 }
@@ -1,6 +1,132 @@
 //! Types for warrants
+//!
+//! A warrant is a signed statement by an authority that some agent has
+//! broken the rules of the DHT, along with the evidence for it.
+use crate::record::SignedAction;
+use crate::signature::Signature;
+use crate::Timestamp;
+use holo_hash::AgentPubKey;
 pub use holochain_serialized_bytes::prelude::*;
 
-#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, SerializedBytes)]
-/// Placeholder for warrant type
-pub struct Warrant;
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+/// A statement by an authority that an agent has authored invalid data.
+pub struct Warrant {
+    /// The evidence against the offending agent.
+    pub proof: WarrantProof,
+    /// The authority which issued this warrant.
+    pub author: AgentPubKey,
+    /// When this warrant was issued.
+    pub timestamp: Timestamp,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+/// The evidence that a warrant is based on.
+pub enum WarrantProof {
+    /// The offending agent broke the integrity of their source chain.
+    ChainIntegrity(ChainIntegrityWarrant),
+}
+
+#[allow(clippy::large_enum_variant)]
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+/// Ways in which an agent can break the integrity of their source chain.
+pub enum ChainIntegrityWarrant {
+    /// An action on the chain was found to be invalid.
+    InvalidChainOp {
+        /// The invalid action, signed by its author.
+        action: SignedAction,
+        /// The kind of validation which rejected the action.
+        validation_type: ValidationType,
+    },
+    /// Two different actions were authored at the same chain position.
+    ChainFork {
+        /// The two actions at the fork, ordered by signature so that the same
+        /// fork always results in the same proof.
+        action_pair: (SignedAction, SignedAction),
+    },
+}
+
+#[derive(
+    Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, SerializedBytes,
+)]
+/// The kind of validation which found an action to be invalid.
+pub enum ValidationType {
+    /// System validation, which is the same for every DNA.
+    Sys,
+    /// App validation, as defined by the DNA's integrity zomes.
+    App,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, SerializedBytes)]
+/// A warrant along with the signature of its author.
+pub struct SignedWarrant {
+    /// The warrant.
+    pub warrant: Warrant,
+    /// The signature of the warrant's author.
+    pub signature: Signature,
+}
+
+impl Warrant {
+    /// Create a new warrant, issued now.
+    pub fn new(proof: WarrantProof, author: AgentPubKey) -> Self {
+        Self {
+            proof,
+            author,
+            timestamp: Timestamp::now(),
+        }
+    }
+
+    /// The agent which this warrant is against.
+    pub fn warrantee(&self) -> &AgentPubKey {
+        self.proof.warrantee()
+    }
+}
+
+impl WarrantProof {
+    /// The agent which this proof is against.
+    pub fn warrantee(&self) -> &AgentPubKey {
+        match self {
+            Self::ChainIntegrity(ChainIntegrityWarrant::InvalidChainOp { action, .. }) => {
+                action.action().author()
+            }
+            Self::ChainIntegrity(ChainIntegrityWarrant::ChainFork { action_pair, .. }) => {
+                action_pair.0.action().author()
+            }
+        }
+    }
+
+    /// The actions which this proof refers to.
+    pub fn actions(&self) -> Vec<&SignedAction> {
+        match self {
+            Self::ChainIntegrity(ChainIntegrityWarrant::InvalidChainOp { action, .. }) => {
+                vec![action]
+            }
+            Self::ChainIntegrity(ChainIntegrityWarrant::ChainFork { action_pair, .. }) => {
+                vec![&action_pair.0, &action_pair.1]
+            }
+        }
+    }
+}
+
+impl ChainIntegrityWarrant {
+    /// Create a proof of a chain fork from the two actions at the fork.
+    pub fn chain_fork(a: SignedAction, b: SignedAction) -> Self {
+        let action_pair = if a.signature() <= b.signature() {
+            (a, b)
+        } else {
+            (b, a)
+        };
+        Self::ChainFork { action_pair }
+    }
+}
+
+impl SignedWarrant {
+    /// Accessor for the warrant.
+    pub fn warrant(&self) -> &Warrant {
+        &self.warrant
+    }
+
+    /// Accessor for the signature.
+    pub fn signature(&self) -> &Signature {
+        &self.signature
+    }
+}
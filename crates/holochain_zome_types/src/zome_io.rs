@@ -66,6 +66,9 @@ wasm_io_types! {
     // Create a link between two entries.
     fn create_link (zt::link::CreateLinkInput) -> holo_hash::ActionHash;
 
+    // Count links by entry hash from the cascade, without getting them.
+    fn count_links (zt::link::CountLinksInput) -> zt::link::LinkCount;
+
     fn create_x25519_keypair(()) -> zt::x_salsa20_poly1305::x25519::X25519PubKey;
 
     // The debug host import takes a TraceMsg to output wherever the host wants to display it.
@@ -1,3 +1,4 @@
+use crate::gate::{clear_auth, Gate};
 use crate::store::Store;
 
 use super::*;
@@ -5,15 +6,18 @@ use warp::Filter;
 
 pub(crate) fn clear(
     store: Store,
+    gate: Gate,
 ) -> impl Filter<Extract = impl warp::Reply + Sized, Error = warp::Rejection> + Clone {
     warp::post()
         .and(warp::header::exact("X-Op", "clear"))
+        .and(clear_auth(gate))
         .and(with_store(store))
         .and_then(clear_info)
 }
 
 async fn clear_info(store: Store) -> Result<impl warp::Reply, warp::Rejection> {
-    store.clear();
+    store.clear().await.map_err(|_| warp::reject())?;
+    CLEAR.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
     Ok(warp::reply())
 }
 
@@ -29,7 +33,7 @@ mod tests {
     async fn test_clear() {
         let store = Store::new(vec![]);
 
-        let filter = super::clear(store.clone());
+        let filter = super::clear(store.clone(), Gate::open());
         let space: Arc<KitsuneSpace> = Arc::new(fixt!(KitsuneSpace));
 
         for _ in 0..20 {
@@ -44,7 +48,7 @@ mod tests {
             )
             .await
             .unwrap();
-            store.put(info);
+            store.put(info).await.unwrap();
         }
 
         let res = warp::test::request()
@@ -0,0 +1,284 @@
+//! Decides which requests the bootstrap server is willing to handle.
+
+use std::{
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    net::{IpAddr, SocketAddr},
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+use kitsune_p2p_types::bin_types::KitsuneSpace;
+use parking_lot::Mutex;
+use warp::{http::StatusCode, Filter};
+
+use super::*;
+
+/// Limits on the number of requests accepted within each `window`.
+/// A limit of `None` means unlimited.
+#[derive(Clone, Debug)]
+pub struct RateLimitConfig {
+    /// How many requests a single IP address can make.
+    pub per_ip: Option<u32>,
+
+    /// How many `put` and `random` requests can be made for a single space,
+    /// from all IP addresses together.
+    pub per_space: Option<u32>,
+
+    /// The length of the window over which requests are counted.
+    pub window: Duration,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            per_ip: None,
+            per_space: None,
+            window: Duration::from_secs(60),
+        }
+    }
+}
+
+/// Who may call the `clear` op, which deletes every stored agent info.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ClearPolicy {
+    /// Anyone. Only for tests.
+    Open,
+
+    /// Only requests with an `Authorization: Bearer <token>` header.
+    Token(String),
+
+    /// No one.
+    Disabled,
+}
+
+/// A reason a request was refused.
+#[derive(Debug)]
+pub(crate) enum Refused {
+    RateLimited,
+    SpaceNotAllowed,
+    Unauthorized,
+    ClearDisabled,
+}
+
+impl warp::reject::Reject for Refused {}
+
+/// Counts requests per key in fixed windows.
+struct RateLimiter<K> {
+    limit: u32,
+    window: Duration,
+    counts: Mutex<HashMap<K, (Instant, u32)>>,
+}
+
+impl<K: Hash + Eq> RateLimiter<K> {
+    fn new(limit: u32, window: Duration) -> Self {
+        Self {
+            limit,
+            window,
+            counts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn check(&self, key: K) -> bool {
+        let now = Instant::now();
+        let mut counts = self.counts.lock();
+        let (start, count) = counts.entry(key).or_insert((now, 0));
+        if now.duration_since(*start) >= self.window {
+            *start = now;
+            *count = 0;
+        }
+        if *count >= self.limit {
+            return false;
+        }
+        *count += 1;
+        true
+    }
+
+    fn prune(&self) {
+        let now = Instant::now();
+        self.counts
+            .lock()
+            .retain(|_, (start, _)| now.duration_since(*start) < self.window);
+    }
+}
+
+/// Shared by all routes to check requests against the configured limits,
+/// allowed spaces and clear policy.
+#[derive(Clone)]
+pub(crate) struct Gate(Arc<GateInner>);
+
+struct GateInner {
+    per_ip: Option<RateLimiter<IpAddr>>,
+    per_space: Option<RateLimiter<Arc<KitsuneSpace>>>,
+    allowed_spaces: Option<HashSet<Arc<KitsuneSpace>>>,
+    clear: ClearPolicy,
+}
+
+impl Gate {
+    pub fn new(config: &BootstrapConfig) -> Self {
+        let limits = &config.rate_limits;
+        Self(Arc::new(GateInner {
+            per_ip: limits.per_ip.map(|l| RateLimiter::new(l, limits.window)),
+            per_space: limits.per_space.map(|l| RateLimiter::new(l, limits.window)),
+            allowed_spaces: config.allowed_spaces.clone(),
+            clear: config.clear.clone(),
+        }))
+    }
+
+    /// No limits, all spaces allowed and anyone can clear.
+    #[cfg(test)]
+    pub fn open() -> Self {
+        Self::new(&BootstrapConfig {
+            clear: ClearPolicy::Open,
+            ..Default::default()
+        })
+    }
+
+    /// Refuse requests for spaces which aren't allowed, or which have
+    /// made too many requests.
+    pub fn check_space(&self, space: &Arc<KitsuneSpace>) -> Result<(), warp::Rejection> {
+        if let Some(allowed) = &self.0.allowed_spaces {
+            if !allowed.contains(space) {
+                return Err(refuse(Refused::SpaceNotAllowed));
+            }
+        }
+        if let Some(limiter) = &self.0.per_space {
+            if !limiter.check(space.clone()) {
+                return Err(refuse(Refused::RateLimited));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_ip(&self, addr: Option<SocketAddr>) -> Result<(), warp::Rejection> {
+        if let (Some(limiter), Some(addr)) = (&self.0.per_ip, addr) {
+            if !limiter.check(addr.ip()) {
+                return Err(refuse(Refused::RateLimited));
+            }
+        }
+        Ok(())
+    }
+
+    fn check_clear(&self, authorization: Option<String>) -> Result<(), warp::Rejection> {
+        match &self.0.clear {
+            ClearPolicy::Open => Ok(()),
+            ClearPolicy::Token(token) => {
+                let expected = format!("Bearer {}", token);
+                let given = authorization.unwrap_or_default();
+                if constant_time_eq(given.as_bytes(), expected.as_bytes()) {
+                    Ok(())
+                } else {
+                    Err(refuse(Refused::Unauthorized))
+                }
+            }
+            ClearPolicy::Disabled => Err(refuse(Refused::ClearDisabled)),
+        }
+    }
+
+    /// Forget about windows which have passed.
+    pub fn prune(&self) {
+        if let Some(limiter) = &self.0.per_ip {
+            limiter.prune();
+        }
+        if let Some(limiter) = &self.0.per_space {
+            limiter.prune();
+        }
+    }
+}
+
+/// Compare without stopping at the first difference, so that the clear
+/// token can't be guessed a byte at a time from response times.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0
+}
+
+fn refuse(reason: Refused) -> warp::Rejection {
+    REFUSED.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
+    warp::reject::custom(reason)
+}
+
+/// Refuse requests from IP addresses which have made too many requests.
+pub(crate) fn ip_limit(gate: Gate) -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    warp::addr::remote()
+        .and_then(move |addr| {
+            let gate = gate.clone();
+            async move { gate.check_ip(addr) }
+        })
+        .untuple_one()
+}
+
+/// Refuse `clear` requests unless the clear policy allows them.
+pub(crate) fn clear_auth(gate: Gate) -> impl Filter<Extract = (), Error = warp::Rejection> + Clone {
+    warp::header::optional::<String>("Authorization")
+        .and_then(move |authorization| {
+            let gate = gate.clone();
+            async move { gate.check_clear(authorization) }
+        })
+        .untuple_one()
+}
+
+/// Turn refusals into status codes. Any other rejection is passed on.
+pub(crate) async fn handle_refused(
+    rejection: warp::Rejection,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let status = match rejection.find::<Refused>() {
+        Some(Refused::RateLimited) => StatusCode::TOO_MANY_REQUESTS,
+        Some(Refused::SpaceNotAllowed) | Some(Refused::ClearDisabled) => StatusCode::FORBIDDEN,
+        Some(Refused::Unauthorized) => StatusCode::UNAUTHORIZED,
+        None => return Err(rejection),
+    };
+    Ok(warp::reply::with_status(warp::reply(), status))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_ip_limit() {
+        let gate = Gate::new(&BootstrapConfig {
+            rate_limits: RateLimitConfig {
+                per_ip: Some(2),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let filter = ip_limit(gate).map(warp::reply).recover(handle_refused);
+        let request = |ip: [u8; 4]| {
+            warp::test::request()
+                .method("POST")
+                .remote_addr(SocketAddr::from((ip, 1234)))
+                .reply(&filter)
+        };
+
+        assert_eq!(request([1, 1, 1, 1]).await.status(), 200);
+        assert_eq!(request([1, 1, 1, 1]).await.status(), 200);
+        assert_eq!(request([1, 1, 1, 1]).await.status(), 429);
+        // Other addresses have their own limit
+        assert_eq!(request([2, 2, 2, 2]).await.status(), 200);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_clear_auth() {
+        let request = |gate: Gate, authorization: Option<&'static str>| async move {
+            let filter = clear_auth(gate).map(warp::reply).recover(handle_refused);
+            let mut request = warp::test::request().method("POST");
+            if let Some(authorization) = authorization {
+                request = request.header("Authorization", authorization);
+            }
+            request.reply(&filter).await.status()
+        };
+        let token = Gate::new(&BootstrapConfig {
+            clear: ClearPolicy::Token("secret".into()),
+            ..Default::default()
+        });
+        let disabled = Gate::new(&BootstrapConfig::default());
+
+        assert_eq!(request(token.clone(), Some("Bearer secret")).await, 200);
+        assert_eq!(request(token.clone(), Some("Bearer guess")).await, 401);
+        assert_eq!(request(token.clone(), Some("Bearer secret2")).await, 401);
+        assert_eq!(request(token, None).await, 401);
+        assert_eq!(request(disabled, Some("Bearer secret")).await, 403);
+        assert_eq!(request(Gate::open(), None).await, 200);
+    }
+}
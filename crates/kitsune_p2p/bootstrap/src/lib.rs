@@ -1,19 +1,30 @@
 // Fixes some warnings introduced by `warp`
 #![allow(opaque_hidden_inferred_bound)]
 
+use std::collections::HashSet;
 use std::net::SocketAddr;
 use std::sync::atomic::AtomicUsize;
+use std::sync::Arc;
 
+use gate::Gate;
+pub use gate::{ClearPolicy, RateLimitConfig};
+use kitsune_p2p_types::bin_types::KitsuneSpace;
 use kitsune_p2p_types::codec::rmp_decode;
 use kitsune_p2p_types::codec::rmp_encode;
 use store::Store;
+pub use store::{BootstrapStore, BootstrapStoreError, BootstrapStoreResult, MemStore, SqliteStore};
 use warp::{hyper::body::Bytes, Filter};
 
 static NOW: AtomicUsize = AtomicUsize::new(0);
 static RANDOM: AtomicUsize = AtomicUsize::new(0);
 static PUT: AtomicUsize = AtomicUsize::new(0);
+static CLEAR: AtomicUsize = AtomicUsize::new(0);
+static PROXY_LIST: AtomicUsize = AtomicUsize::new(0);
+static REFUSED: AtomicUsize = AtomicUsize::new(0);
 
 mod clear;
+mod gate;
+mod metrics;
 mod now;
 mod proxy_list;
 mod put;
@@ -31,6 +42,39 @@ pub type BootstrapDriver = futures::future::BoxFuture<'static, ()>;
 
 pub type BootstrapShutdown = Box<dyn FnOnce() + 'static + Send>;
 
+/// How a bootstrap server should run.
+///
+/// The default has no rate limits, allows every space and disables `clear`.
+#[derive(Clone, Debug)]
+pub struct BootstrapConfig {
+    /// Proxy server addresses returned by the `proxy_list` op.
+    pub proxy_list: Vec<String>,
+
+    /// How often expired agent infos are pruned.
+    pub prune_frequency: std::time::Duration,
+
+    /// Limits on the number of requests per IP address and per space.
+    pub rate_limits: RateLimitConfig,
+
+    /// If set, `put` and `random` are refused for any other space.
+    pub allowed_spaces: Option<HashSet<Arc<KitsuneSpace>>>,
+
+    /// Who may call `clear`.
+    pub clear: ClearPolicy,
+}
+
+impl Default for BootstrapConfig {
+    fn default() -> Self {
+        Self {
+            proxy_list: Vec::new(),
+            prune_frequency: PRUNE_EXPIRED_FREQ,
+            rate_limits: RateLimitConfig::default(),
+            allowed_spaces: None,
+            clear: ClearPolicy::Disabled,
+        }
+    }
+}
+
 /// Run a bootstrap with the default prune frequency [`PRUNE_EXPIRED_FREQ`].
 pub async fn run(
     addr: impl Into<SocketAddr> + 'static,
@@ -40,28 +84,61 @@ pub async fn run(
 }
 
 /// Run a bootstrap server with a set prune frequency.
+///
+/// This server has no limits and anyone can `clear` it, so it is only
+/// suitable for tests. Use [`run_with_config`] otherwise.
 pub async fn run_with_prune_freq(
     addr: impl Into<SocketAddr> + 'static,
     proxy_list: Vec<String>,
     prune_frequency: std::time::Duration,
 ) -> Result<(BootstrapDriver, SocketAddr, BootstrapShutdown), String> {
-    let store = Store::new(proxy_list);
+    run_with_config(
+        addr,
+        Arc::new(MemStore::default()),
+        BootstrapConfig {
+            proxy_list,
+            prune_frequency,
+            clear: ClearPolicy::Open,
+            ..Default::default()
+        },
+    )
+    .await
+}
+
+/// Run a bootstrap server which keeps agent infos in the given storage
+/// backend.
+pub async fn run_with_config(
+    addr: impl Into<SocketAddr> + 'static,
+    backend: Arc<dyn BootstrapStore>,
+    config: BootstrapConfig,
+) -> Result<(BootstrapDriver, SocketAddr, BootstrapShutdown), String> {
+    let store = Store::with_backend(backend, config.proxy_list.clone());
+    let gate = Gate::new(&config);
 
     {
         let store = store.clone();
+        let gate = gate.clone();
+        let prune_frequency = config.prune_frequency;
         tokio::task::spawn(async move {
+            // A persistent store may hold infos which expired while the
+            // server was down, so prune right away before waiting.
             loop {
+                if let Err(err) = store.prune().await {
+                    eprintln!("Failed to prune expired agent infos: {}", err);
+                }
+                gate.prune();
                 tokio::time::sleep(prune_frequency).await;
-                store.prune();
             }
         });
     }
 
-    let boot = now::now()
-        .or(put::put(store.clone()))
-        .or(random::random(store.clone()))
+    let api = now::now()
+        .or(put::put(store.clone(), gate.clone()))
+        .or(random::random(store.clone(), gate.clone()))
         .or(proxy_list::proxy_list(store.clone()))
-        .or(clear::clear(store));
+        .or(clear::clear(store.clone(), gate.clone()))
+        .or(metrics::metrics(store, config.allowed_spaces.clone()));
+    let boot = gate::ip_limit(gate).and(api).recover(gate::handle_refused);
 
     let (s, r) = tokio::sync::oneshot::channel();
     let shutdown = Box::new(move || {
@@ -84,3 +161,9 @@ fn with_store(
 ) -> impl Filter<Extract = (Store,), Error = std::convert::Infallible> + Clone {
     warp::any().map(move || store.clone())
 }
+
+fn with_gate(
+    gate: Gate,
+) -> impl Filter<Extract = (Gate,), Error = std::convert::Infallible> + Clone {
+    warp::any().map(move || gate.clone())
+}
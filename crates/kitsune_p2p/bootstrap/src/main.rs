@@ -1,4 +1,16 @@
-use clap::Parser;
+use clap::{ArgEnum, Parser};
+use kitsune_p2p_bootstrap::{BootstrapConfig, ClearPolicy, RateLimitConfig};
+use kitsune_p2p_types::bin_types::KitsuneSpace;
+use std::{path::PathBuf, sync::Arc};
+
+/// Where the agent infos registered with the server are kept
+#[derive(ArgEnum, Clone, Copy, Debug)]
+enum StoreKind {
+    /// in memory, lost when the server stops
+    Memory,
+    /// in a SQLite database, see `--database`
+    Sqlite,
+}
 
 #[derive(Parser, Debug)]
 #[clap(version, about, long_about = None)]
@@ -12,6 +24,52 @@ struct Args {
     /// multiple times
     #[clap(short, long, verbatim_doc_comment)]
     proxy: Vec<String>,
+
+    /// where to keep registered agent infos
+    #[clap(long, arg_enum, default_value = "memory")]
+    store: StoreKind,
+
+    /// path to the database file, when `--store sqlite`
+    /// is used, created if it doesn't exist
+    #[clap(
+        long,
+        default_value = "kitsune-bootstrap.sqlite3",
+        verbatim_doc_comment
+    )]
+    database: PathBuf,
+
+    /// how many requests a single IP address can
+    /// make per minute, unlimited if not set
+    #[clap(long, verbatim_doc_comment)]
+    ip_rate_limit: Option<u32>,
+
+    /// how many `put` and `random` requests can be
+    /// made for a single space per minute, unlimited
+    /// if not set
+    #[clap(long, verbatim_doc_comment)]
+    space_rate_limit: Option<u32>,
+
+    /// only accept agent infos for this space, given
+    /// in url-safe base64, can be specified multiple
+    /// times, all spaces are accepted if not set
+    #[clap(long, parse(try_from_str = parse_space), verbatim_doc_comment)]
+    allowed_space: Vec<Arc<KitsuneSpace>>,
+
+    /// allow the `clear` op for requests with an
+    /// `Authorization: Bearer <TOKEN>` header
+    #[clap(long, verbatim_doc_comment)]
+    clear_token: Option<String>,
+
+    /// allow anyone to use the `clear` op,
+    /// never use this outside of tests
+    #[clap(long, conflicts_with = "clear-token", verbatim_doc_comment)]
+    test_mode: bool,
+}
+
+fn parse_space(s: &str) -> Result<Arc<KitsuneSpace>, String> {
+    base64::decode_config(s, base64::URL_SAFE_NO_PAD)
+        .map(|bytes| Arc::new(KitsuneSpace(bytes)))
+        .map_err(|e| e.to_string())
 }
 
 #[tokio::main(flavor = "multi_thread")]
@@ -27,7 +85,38 @@ async fn main() {
         .next()
         .unwrap();
 
-    match kitsune_p2p_bootstrap::run(addr, args.proxy).await {
+    let store: Arc<dyn kitsune_p2p_bootstrap::BootstrapStore> = match args.store {
+        StoreKind::Memory => Arc::new(kitsune_p2p_bootstrap::MemStore::default()),
+        StoreKind::Sqlite => match kitsune_p2p_bootstrap::SqliteStore::open(&args.database) {
+            Ok(store) => Arc::new(store),
+            Err(err) => {
+                eprintln!("Failed to open {}: {}", args.database.display(), err);
+                return;
+            }
+        },
+    };
+
+    let config = BootstrapConfig {
+        proxy_list: args.proxy,
+        rate_limits: RateLimitConfig {
+            per_ip: args.ip_rate_limit,
+            per_space: args.space_rate_limit,
+            ..Default::default()
+        },
+        allowed_spaces: if args.allowed_space.is_empty() {
+            None
+        } else {
+            Some(args.allowed_space.into_iter().collect())
+        },
+        clear: match (args.clear_token, args.test_mode) {
+            (Some(token), _) => ClearPolicy::Token(token),
+            (None, true) => ClearPolicy::Open,
+            (None, false) => ClearPolicy::Disabled,
+        },
+        ..Default::default()
+    };
+
+    match kitsune_p2p_bootstrap::run_with_config(addr, store, config).await {
         Ok((driver, addr, _shutdown)) => {
             println!("http://{}", addr);
             driver.await;
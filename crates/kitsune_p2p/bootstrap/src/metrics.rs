@@ -0,0 +1,187 @@
+use crate::store::Store;
+
+use super::*;
+use std::fmt::Write;
+use std::sync::atomic::Ordering;
+use warp::Filter;
+
+/// Metrics in the Prometheus text format.
+///
+/// Agents are reported per space only for the spaces in `allowed_spaces`.
+/// Without an allow-list anyone can create a space, and labelling each one
+/// would give unbounded series and reveal which networks use this bootstrap
+/// service, so only the totals are reported.
+pub(crate) fn metrics(
+    store: Store,
+    allowed_spaces: Option<HashSet<Arc<KitsuneSpace>>>,
+) -> impl Filter<Extract = impl warp::Reply + Sized, Error = warp::Rejection> + Clone {
+    let allowed_spaces = Arc::new(allowed_spaces);
+    warp::get()
+        .and(warp::path("metrics"))
+        .and(warp::path::end())
+        .and(with_store(store))
+        .and(warp::any().map(move || allowed_spaces.clone()))
+        .and_then(get_metrics)
+}
+
+async fn get_metrics(
+    store: Store,
+    allowed_spaces: Arc<Option<HashSet<Arc<KitsuneSpace>>>>,
+) -> Result<impl warp::Reply, warp::Rejection> {
+    let agent_counts = store.agent_counts().await.map_err(|_| warp::reject())?;
+    let mut out = String::new();
+
+    let _ = writeln!(
+        out,
+        "# HELP kitsune_bootstrap_agents Number of agent infos stored across all spaces."
+    );
+    let _ = writeln!(out, "# TYPE kitsune_bootstrap_agents gauge");
+    let _ = writeln!(
+        out,
+        "kitsune_bootstrap_agents {}",
+        agent_counts.iter().map(|(_, count)| count).sum::<usize>()
+    );
+
+    let _ = writeln!(
+        out,
+        "# HELP kitsune_bootstrap_spaces Number of spaces with agent infos stored."
+    );
+    let _ = writeln!(out, "# TYPE kitsune_bootstrap_spaces gauge");
+    let _ = writeln!(out, "kitsune_bootstrap_spaces {}", agent_counts.len());
+
+    if let Some(allowed_spaces) = allowed_spaces.as_ref() {
+        let _ = writeln!(
+            out,
+            "# HELP kitsune_bootstrap_space_agents Number of agent infos stored for each allowed space."
+        );
+        let _ = writeln!(out, "# TYPE kitsune_bootstrap_space_agents gauge");
+        for space in allowed_spaces {
+            let count = agent_counts
+                .iter()
+                .find(|(s, _)| s == space)
+                .map(|(_, count)| *count)
+                .unwrap_or(0);
+            let _ = writeln!(
+                out,
+                "kitsune_bootstrap_space_agents{{space=\"{}\"}} {}",
+                space, count
+            );
+        }
+    }
+
+    let _ = writeln!(
+        out,
+        "# HELP kitsune_bootstrap_requests_total Number of requests handled for each op."
+    );
+    let _ = writeln!(out, "# TYPE kitsune_bootstrap_requests_total counter");
+    for (op, count) in [
+        ("now", &NOW),
+        ("put", &PUT),
+        ("random", &RANDOM),
+        ("proxy_list", &PROXY_LIST),
+        ("clear", &CLEAR),
+    ] {
+        let _ = writeln!(
+            out,
+            "kitsune_bootstrap_requests_total{{op=\"{}\"}} {}",
+            op,
+            count.load(Ordering::Relaxed)
+        );
+    }
+
+    let _ = writeln!(
+        out,
+        "# HELP kitsune_bootstrap_refused_total Number of requests refused by rate limits, the space allow-list or the clear policy."
+    );
+    let _ = writeln!(out, "# TYPE kitsune_bootstrap_refused_total counter");
+    let _ = writeln!(
+        out,
+        "kitsune_bootstrap_refused_total {}",
+        REFUSED.load(Ordering::Relaxed)
+    );
+
+    Ok(warp::reply::with_header(
+        out,
+        "Content-Type",
+        "text/plain; version=0.0.4",
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use fixt::prelude::*;
+    use kitsune_p2p::{agent_store::AgentInfoSigned, fixt::*, KitsuneSpace};
+
+    async fn put_agents(store: &Store, space: &Arc<KitsuneSpace>, n: usize) {
+        for _ in 0..n {
+            let info = AgentInfoSigned::sign(
+                space.clone(),
+                Arc::new(fixt!(KitsuneAgent, Unpredictable)),
+                u32::MAX / 4,
+                fixt!(UrlList, Empty),
+                0,
+                std::time::UNIX_EPOCH.elapsed().unwrap().as_millis() as u64 + 60_000_000,
+                |_| async move { Ok(Arc::new(fixt!(KitsuneSignature, Unpredictable))) },
+            )
+            .await
+            .unwrap();
+            store.put(info).await.unwrap();
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_metrics() {
+        let store = Store::new(vec![]);
+        let filter = super::metrics(store.clone(), None);
+        let space: Arc<KitsuneSpace> = Arc::new(fixt!(KitsuneSpace));
+        put_agents(&store, &space, 3).await;
+
+        let res = warp::test::request()
+            .method("GET")
+            .path("/metrics")
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), 200);
+        let body = String::from_utf8(res.body().to_vec()).unwrap();
+        assert!(body.contains("kitsune_bootstrap_agents 3\n"));
+        assert!(body.contains("kitsune_bootstrap_spaces 1\n"));
+        assert!(!body.contains(&space.to_string()));
+        assert!(body.contains("kitsune_bootstrap_requests_total{op=\"put\"}"));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_metrics_per_allowed_space() {
+        let store = Store::new(vec![]);
+        let space: Arc<KitsuneSpace> = Arc::new(fixt!(KitsuneSpace));
+        let empty_space: Arc<KitsuneSpace> = Arc::new(fixt!(KitsuneSpace));
+        let other_space: Arc<KitsuneSpace> = Arc::new(fixt!(KitsuneSpace));
+        let filter = super::metrics(
+            store.clone(),
+            Some([space.clone(), empty_space.clone()].into_iter().collect()),
+        );
+        put_agents(&store, &space, 3).await;
+        // Stored before the allow-list was set.
+        put_agents(&store, &other_space, 2).await;
+
+        let res = warp::test::request()
+            .method("GET")
+            .path("/metrics")
+            .reply(&filter)
+            .await;
+        assert_eq!(res.status(), 200);
+        let body = String::from_utf8(res.body().to_vec()).unwrap();
+        assert!(body.contains("kitsune_bootstrap_agents 5\n"));
+        assert!(body.contains(&format!(
+            "kitsune_bootstrap_space_agents{{space=\"{}\"}} 3\n",
+            space
+        )));
+        assert!(body.contains(&format!(
+            "kitsune_bootstrap_space_agents{{space=\"{}\"}} 0\n",
+            empty_space
+        )));
+        assert!(!body.contains(&other_space.to_string()));
+    }
+}
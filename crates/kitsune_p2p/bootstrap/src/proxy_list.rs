@@ -16,6 +16,7 @@ async fn get_proxy_list(store: Store) -> Result<impl warp::Reply, warp::Rejectio
     let proxy_list = store.proxy_list();
     let mut buf = Vec::new();
     rmp_encode(&mut buf, proxy_list).map_err(|_| warp::reject())?;
+    PROXY_LIST.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
     Ok(buf)
 }
 
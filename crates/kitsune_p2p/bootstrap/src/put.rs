@@ -1,3 +1,4 @@
+use crate::gate::Gate;
 use crate::store::Store;
 
 use super::*;
@@ -6,21 +7,28 @@ use warp::Filter;
 
 pub(crate) fn put(
     store: Store,
+    gate: Gate,
 ) -> impl Filter<Extract = impl warp::Reply + Sized, Error = warp::Rejection> + Clone {
     warp::post()
         .and(warp::header::exact("X-Op", "put"))
         .and(warp::body::content_length_limit(SIZE_LIMIT))
         .and(warp::body::bytes())
         .and(with_store(store))
+        .and(with_gate(gate))
         .and_then(put_info)
 }
 
-async fn put_info(peer: Bytes, store: Store) -> Result<impl warp::Reply, warp::Rejection> {
+async fn put_info(
+    peer: Bytes,
+    store: Store,
+    gate: Gate,
+) -> Result<impl warp::Reply, warp::Rejection> {
     let peer: AgentInfoSigned =
         rmp_decode(&mut AsRef::<[u8]>::as_ref(&peer)).map_err(|_| warp::reject())?;
+    gate.check_space(&peer.space)?;
     // TODO: Return rejection if agent info was invalid?
     if valid(&peer) {
-        store.put(peer);
+        store.put(peer).await.map_err(|_| warp::reject())?;
     }
     PUT.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
     let mut buf = Vec::with_capacity(1);
@@ -49,7 +57,7 @@ mod tests {
     #[tokio::test(flavor = "multi_thread")]
     async fn test_put() {
         let store = Store::new(vec![]);
-        let filter = put(store.clone());
+        let filter = put(store.clone(), Gate::open());
 
         let info = AgentInfoSigned::sign(
             Arc::new(fixt!(KitsuneSpace, Unpredictable)),
@@ -83,4 +91,51 @@ mod tests {
             info
         );
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_put_refused() {
+        let store = Store::new(vec![]);
+        let allowed: Arc<KitsuneSpace> = Arc::new(fixt!(KitsuneSpace, Unpredictable));
+        let gate = Gate::new(&BootstrapConfig {
+            allowed_spaces: Some([allowed.clone()].into_iter().collect()),
+            rate_limits: crate::RateLimitConfig {
+                per_space: Some(1),
+                ..Default::default()
+            },
+            ..Default::default()
+        });
+        let filter = put(store.clone(), gate).recover(crate::gate::handle_refused);
+
+        let put_into = |space: Arc<KitsuneSpace>| {
+            let filter = filter.clone();
+            async move {
+                let info = AgentInfoSigned::sign(
+                    space,
+                    Arc::new(fixt!(KitsuneAgent, Unpredictable)),
+                    u32::MAX / 4,
+                    fixt!(UrlList, Empty),
+                    0,
+                    std::time::UNIX_EPOCH.elapsed().unwrap().as_millis() as u64 + 60_000_000,
+                    |_| async move { Ok(Arc::new(fixt!(KitsuneSignature, Unpredictable))) },
+                )
+                .await
+                .unwrap();
+                let mut buf = Vec::new();
+                rmp_encode(&mut buf, info).unwrap();
+                warp::test::request()
+                    .method("POST")
+                    .header("X-Op", "put")
+                    .body(buf)
+                    .reply(&filter)
+                    .await
+                    .status()
+            }
+        };
+
+        let other = Arc::new(fixt!(KitsuneSpace, Unpredictable));
+        assert_eq!(put_into(other).await, 403);
+        assert_eq!(put_into(allowed.clone()).await, 200);
+        assert_eq!(put_into(allowed).await, 429);
+        assert_eq!(store.all().values().map(|m| m.len()).sum::<usize>(), 1);
+    }
 }
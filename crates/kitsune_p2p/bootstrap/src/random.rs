@@ -1,3 +1,4 @@
+use crate::gate::Gate;
 use crate::store::Store;
 
 use super::*;
@@ -6,19 +7,26 @@ use warp::Filter;
 
 pub(crate) fn random(
     store: Store,
+    gate: Gate,
 ) -> impl Filter<Extract = impl warp::Reply + Sized, Error = warp::Rejection> + Clone {
     warp::post()
         .and(warp::header::exact("X-Op", "random"))
         .and(warp::body::content_length_limit(SIZE_LIMIT))
         .and(warp::body::bytes())
         .and(with_store(store))
+        .and(with_gate(gate))
         .and_then(random_info)
 }
 
-async fn random_info(query: Bytes, store: Store) -> Result<impl warp::Reply, warp::Rejection> {
+async fn random_info(
+    query: Bytes,
+    store: Store,
+    gate: Gate,
+) -> Result<impl warp::Reply, warp::Rejection> {
     let query: RandomQuery =
         rmp_decode(&mut AsRef::<[u8]>::as_ref(&query)).map_err(|_| warp::reject())?;
-    let result = store.random(query);
+    gate.check_space(&query.space)?;
+    let result = store.random(query).await.map_err(|_| warp::reject())?;
     let mut buf = Vec::with_capacity(result.len());
     rmp_encode(&mut buf, result).map_err(|_| warp::reject())?;
     RANDOM.fetch_add(1, std::sync::atomic::Ordering::Relaxed);
@@ -35,7 +43,7 @@ mod tests {
     use kitsune_p2p_types::bootstrap::RandomLimit;
 
     async fn put(store: Store, peers: Vec<AgentInfoSigned>) {
-        let filter = crate::put::put(store);
+        let filter = crate::put::put(store, Gate::open());
 
         for peer in peers {
             let mut buf = Vec::new();
@@ -55,7 +63,7 @@ mod tests {
     #[tokio::test(flavor = "multi_thread")]
     async fn test_random() {
         let store = Store::new(vec![]);
-        let filter = super::random(store.clone());
+        let filter = super::random(store.clone(), Gate::open());
         let space: Arc<KitsuneSpace> = Arc::new(fixt!(KitsuneSpace));
         let mut peers = Vec::new();
         for _ in 0..20 {
@@ -1,94 +1,122 @@
-use std::{collections::HashMap, sync::Arc};
+//! Storage for the agent infos registered with the bootstrap server.
+
+use std::sync::Arc;
 
 use kitsune_p2p_types::{
-    agent_info::AgentInfoSigned,
-    bin_types::{KitsuneAgent, KitsuneSpace},
-    bootstrap::RandomQuery,
-    codec::rmp_encode,
+    agent_info::AgentInfoSigned, bin_types::KitsuneSpace, bootstrap::RandomQuery,
 };
-use parking_lot::RwLock;
-use rand::seq::IteratorRandom;
 
-type AgentMap = HashMap<Arc<KitsuneAgent>, AgentInfoSigned>;
-type SpaceMap = HashMap<Arc<KitsuneSpace>, AgentMap>;
+mod mem;
+mod sqlite;
+
+pub use mem::MemStore;
+pub use sqlite::SqliteStore;
+
+/// Any error from a storage backend
+pub type BootstrapStoreError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Result type for storage backends
+pub type BootstrapStoreResult<T> = Result<T, BootstrapStoreError>;
+
+/// A storage backend for the bootstrap server.
+///
+/// Methods may block, since the server only calls them on the blocking
+/// thread pool.
+pub trait BootstrapStore: 'static + Send + Sync + std::fmt::Debug {
+    /// Remove every agent info which expired before `now_ms`.
+    fn prune(&self, now_ms: u64) -> BootstrapStoreResult<()>;
+
+    /// Store an agent info, unless a more recently signed one is already
+    /// stored for the same agent in the same space.
+    fn put(&self, info: AgentInfoSigned) -> BootstrapStoreResult<()>;
+
+    /// Get up to `query.limit` randomly chosen agent infos from `query.space`,
+    /// encoded. Only agents which haven't expired by `now_ms` and which have
+    /// at least one url are returned.
+    fn random(&self, query: RandomQuery, now_ms: u64) -> BootstrapStoreResult<Vec<Vec<u8>>>;
+
+    /// Remove every agent info.
+    fn clear(&self) -> BootstrapStoreResult<()>;
+
+    /// Get every stored agent info.
+    fn all(&self) -> BootstrapStoreResult<Vec<AgentInfoSigned>>;
+
+    /// Count the stored agent infos of each space.
+    fn agent_counts(&self) -> BootstrapStoreResult<Vec<(Arc<KitsuneSpace>, usize)>>;
+}
 
 #[derive(Clone, Debug)]
-pub(crate) struct Store(Arc<RwLock<SpaceMap>>, Arc<Vec<String>>);
+pub(crate) struct Store(Arc<dyn BootstrapStore>, Arc<Vec<String>>);
 
 impl Store {
+    /// An in-memory store
+    #[cfg(test)]
     pub fn new(proxy_list: Vec<String>) -> Self {
-        Self(Arc::new(RwLock::new(HashMap::new())), Arc::new(proxy_list))
+        Self::with_backend(Arc::new(MemStore::default()), proxy_list)
+    }
+
+    pub fn with_backend(backend: Arc<dyn BootstrapStore>, proxy_list: Vec<String>) -> Self {
+        Self(backend, Arc::new(proxy_list))
     }
 
     pub fn proxy_list(&self) -> Arc<Vec<String>> {
         self.1.clone()
     }
 
-    pub fn prune(&self) {
-        let now = std::time::UNIX_EPOCH
-            .elapsed()
-            .expect("Bootstrap server time set before epoch")
-            .as_millis() as u64;
+    pub async fn prune(&self) -> BootstrapStoreResult<()> {
+        let now_ms = now_ms();
+        self.blocking(move |backend| backend.prune(now_ms)).await
+    }
 
-        self.0.write().retain(|_, map| {
-            map.retain(|_, info| info.expires_at_ms >= now);
-            !map.is_empty()
-        });
+    pub async fn put(&self, info: AgentInfoSigned) -> BootstrapStoreResult<()> {
+        self.blocking(move |backend| backend.put(info)).await
     }
 
-    pub fn put(&self, info: AgentInfoSigned) {
-        let mut lock = self.0.write();
-        let space_map = lock.entry(info.space.clone()).or_insert_with(HashMap::new);
-        match space_map.entry(info.agent.clone()) {
-            std::collections::hash_map::Entry::Occupied(mut e) => {
-                if info.signed_at_ms > e.get().signed_at_ms {
-                    e.insert(info);
-                }
-            }
-            std::collections::hash_map::Entry::Vacant(e) => {
-                e.insert(info);
-            }
-        }
+    pub async fn random(&self, query: RandomQuery) -> BootstrapStoreResult<Vec<Vec<u8>>> {
+        let now_ms = now_ms();
+        self.blocking(move |backend| backend.random(query, now_ms))
+            .await
     }
 
-    pub fn random(&self, query: RandomQuery) -> Vec<Vec<u8>> {
-        // TODO: Max this limit
-        let limit = query.limit.0 as usize;
-        let mut rng = rand::thread_rng();
-        let now = std::time::UNIX_EPOCH
-            .elapsed()
-            .expect("Bootstrap server time set before epoch")
-            .as_millis() as u64;
-        self.0
-            .read()
-            .get(query.space.as_ref())
-            .map(|space| {
-                space
-                    .values()
-                    .filter_map(|i| {
-                        if i.expires_at_ms <= now {
-                            return None;
-                        }
-                        if i.url_list.is_empty() {
-                            return None;
-                        }
-                        let mut buf = Vec::new();
-                        match rmp_encode(&mut buf, i) {
-                            Ok(_) => Some(buf),
-                            Err(_) => None,
-                        }
-                    })
-                    .choose_multiple(&mut rng, limit)
-            })
-            .unwrap_or_default()
+    pub async fn clear(&self) -> BootstrapStoreResult<()> {
+        self.blocking(|backend| backend.clear()).await
     }
 
-    pub fn clear(&self) {
-        self.0.write().clear()
+    pub async fn agent_counts(&self) -> BootstrapStoreResult<Vec<(Arc<KitsuneSpace>, usize)>> {
+        self.blocking(|backend| backend.agent_counts()).await
+    }
+
+    /// Backends like [`SqliteStore`] block on IO, so they are called on the
+    /// blocking thread pool rather than on the async handlers' threads.
+    async fn blocking<T, F>(&self, f: F) -> BootstrapStoreResult<T>
+    where
+        T: 'static + Send,
+        F: 'static + Send + FnOnce(&dyn BootstrapStore) -> BootstrapStoreResult<T>,
+    {
+        let backend = self.0.clone();
+        tokio::task::spawn_blocking(move || f(backend.as_ref())).await?
     }
 
     #[cfg(test)]
-    pub fn all(&self) -> HashMap<Arc<KitsuneSpace>, HashMap<Arc<KitsuneAgent>, AgentInfoSigned>> {
-        self.0.read().clone()
+    pub fn all(
+        &self,
+    ) -> std::collections::HashMap<
+        Arc<kitsune_p2p_types::bin_types::KitsuneSpace>,
+        std::collections::HashMap<Arc<kitsune_p2p_types::bin_types::KitsuneAgent>, AgentInfoSigned>,
+    > {
+        let mut out = std::collections::HashMap::new();
+        for info in self.0.all().unwrap() {
+            out.entry(info.space.clone())
+                .or_insert_with(std::collections::HashMap::new)
+                .insert(info.agent.clone(), info);
+        }
+        out
     }
 }
+
+fn now_ms() -> u64 {
+    std::time::UNIX_EPOCH
+        .elapsed()
+        .expect("Bootstrap server time set before epoch")
+        .as_millis() as u64
+}
@@ -0,0 +1,97 @@
+use std::{collections::HashMap, sync::Arc};
+
+use kitsune_p2p_types::{
+    agent_info::AgentInfoSigned,
+    bin_types::{KitsuneAgent, KitsuneSpace},
+    bootstrap::RandomQuery,
+    codec::rmp_encode,
+};
+use parking_lot::RwLock;
+use rand::seq::IteratorRandom;
+
+use super::{BootstrapStore, BootstrapStoreResult};
+
+type AgentMap = HashMap<Arc<KitsuneAgent>, AgentInfoSigned>;
+type SpaceMap = HashMap<Arc<KitsuneSpace>, AgentMap>;
+
+/// Keeps all agent infos in memory. Everything is lost when the server stops.
+#[derive(Debug, Default)]
+pub struct MemStore(RwLock<SpaceMap>);
+
+impl BootstrapStore for MemStore {
+    fn prune(&self, now_ms: u64) -> BootstrapStoreResult<()> {
+        self.0.write().retain(|_, map| {
+            map.retain(|_, info| info.expires_at_ms >= now_ms);
+            !map.is_empty()
+        });
+        Ok(())
+    }
+
+    fn put(&self, info: AgentInfoSigned) -> BootstrapStoreResult<()> {
+        let mut lock = self.0.write();
+        let space_map = lock.entry(info.space.clone()).or_insert_with(HashMap::new);
+        match space_map.entry(info.agent.clone()) {
+            std::collections::hash_map::Entry::Occupied(mut e) => {
+                if info.signed_at_ms > e.get().signed_at_ms {
+                    e.insert(info);
+                }
+            }
+            std::collections::hash_map::Entry::Vacant(e) => {
+                e.insert(info);
+            }
+        }
+        Ok(())
+    }
+
+    fn random(&self, query: RandomQuery, now_ms: u64) -> BootstrapStoreResult<Vec<Vec<u8>>> {
+        // TODO: Max this limit
+        let limit = query.limit.0 as usize;
+        let mut rng = rand::thread_rng();
+        Ok(self
+            .0
+            .read()
+            .get(query.space.as_ref())
+            .map(|space| {
+                space
+                    .values()
+                    .filter_map(|i| {
+                        if i.expires_at_ms <= now_ms {
+                            return None;
+                        }
+                        if i.url_list.is_empty() {
+                            return None;
+                        }
+                        let mut buf = Vec::new();
+                        match rmp_encode(&mut buf, i) {
+                            Ok(_) => Some(buf),
+                            Err(_) => None,
+                        }
+                    })
+                    .choose_multiple(&mut rng, limit)
+            })
+            .unwrap_or_default())
+    }
+
+    fn clear(&self) -> BootstrapStoreResult<()> {
+        self.0.write().clear();
+        Ok(())
+    }
+
+    fn all(&self) -> BootstrapStoreResult<Vec<AgentInfoSigned>> {
+        Ok(self
+            .0
+            .read()
+            .values()
+            .flat_map(|map| map.values().cloned())
+            .collect())
+    }
+
+    fn agent_counts(&self) -> BootstrapStoreResult<Vec<(Arc<KitsuneSpace>, usize)>> {
+        Ok(self
+            .0
+            .read()
+            .iter()
+            .map(|(space, map)| (space.clone(), map.len()))
+            .collect())
+    }
+}
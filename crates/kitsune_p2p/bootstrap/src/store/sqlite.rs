@@ -0,0 +1,221 @@
+use std::{path::Path, sync::Arc};
+
+use kitsune_p2p_types::{
+    agent_info::AgentInfoSigned,
+    bin_types::KitsuneSpace,
+    bootstrap::RandomQuery,
+    codec::{rmp_decode, rmp_encode},
+};
+use parking_lot::Mutex;
+use rusqlite::{named_params, Connection};
+
+use super::{BootstrapStore, BootstrapStoreResult};
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS agents (
+    space         BLOB    NOT NULL,
+    agent         BLOB    NOT NULL,
+    signed_at_ms  INTEGER NOT NULL,
+    expires_at_ms INTEGER NOT NULL,
+    -- 1 if the agent info has at least one url, otherwise 0
+    has_urls      INTEGER NOT NULL,
+    -- the agent info, encoded the way it is sent to and from the server
+    encoded       BLOB    NOT NULL,
+    PRIMARY KEY (space, agent)
+);
+
+CREATE INDEX IF NOT EXISTS agents_expires_at_idx ON agents (expires_at_ms);
+";
+
+// Only replace an agent info with a more recently signed one.
+const PUT: &str = "
+INSERT INTO agents (space, agent, signed_at_ms, expires_at_ms, has_urls, encoded)
+VALUES (:space, :agent, :signed_at_ms, :expires_at_ms, :has_urls, :encoded)
+ON CONFLICT (space, agent) DO UPDATE SET
+    signed_at_ms = excluded.signed_at_ms,
+    expires_at_ms = excluded.expires_at_ms,
+    has_urls = excluded.has_urls,
+    encoded = excluded.encoded
+WHERE excluded.signed_at_ms > agents.signed_at_ms
+";
+
+const RANDOM: &str = "
+SELECT encoded FROM agents
+WHERE space = :space AND expires_at_ms > :now_ms AND has_urls = 1
+ORDER BY RANDOM()
+LIMIT :limit
+";
+
+const PRUNE: &str = "DELETE FROM agents WHERE expires_at_ms < :now_ms";
+
+const CLEAR: &str = "DELETE FROM agents";
+
+const ALL: &str = "SELECT encoded FROM agents";
+
+const AGENT_COUNTS: &str = "SELECT space, COUNT(*) FROM agents GROUP BY space";
+
+/// Keeps all agent infos in a SQLite database, so that they survive a restart
+/// of the server.
+#[derive(Debug)]
+pub struct SqliteStore(Mutex<Connection>);
+
+impl SqliteStore {
+    /// Open the database at this path, creating it if it doesn't exist yet.
+    pub fn open(path: impl AsRef<Path>) -> BootstrapStoreResult<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    /// Open a database which only lives in memory.
+    pub fn open_in_memory() -> BootstrapStoreResult<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> BootstrapStoreResult<Self> {
+        conn.pragma_update(None, "journal_mode", "WAL")?;
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self(Mutex::new(conn)))
+    }
+}
+
+/// SQLite only has signed integers.
+fn sql_ms(ms: u64) -> i64 {
+    i64::try_from(ms).unwrap_or(i64::MAX)
+}
+
+impl BootstrapStore for SqliteStore {
+    fn prune(&self, now_ms: u64) -> BootstrapStoreResult<()> {
+        self.0
+            .lock()
+            .execute(PRUNE, named_params! { ":now_ms": sql_ms(now_ms) })?;
+        Ok(())
+    }
+
+    fn put(&self, info: AgentInfoSigned) -> BootstrapStoreResult<()> {
+        let mut encoded = Vec::new();
+        rmp_encode(&mut encoded, &info)?;
+        self.0.lock().execute(
+            PUT,
+            named_params! {
+                ":space": &info.space.0,
+                ":agent": &info.agent.0,
+                ":signed_at_ms": sql_ms(info.signed_at_ms),
+                ":expires_at_ms": sql_ms(info.expires_at_ms),
+                ":has_urls": !info.url_list.is_empty(),
+                ":encoded": &encoded,
+            },
+        )?;
+        Ok(())
+    }
+
+    fn random(&self, query: RandomQuery, now_ms: u64) -> BootstrapStoreResult<Vec<Vec<u8>>> {
+        let conn = self.0.lock();
+        let mut stmt = conn.prepare_cached(RANDOM)?;
+        let rows = stmt.query_map(
+            named_params! {
+                ":space": &query.space.0,
+                ":now_ms": sql_ms(now_ms),
+                ":limit": query.limit.0,
+            },
+            |row| row.get(0),
+        )?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+
+    fn clear(&self) -> BootstrapStoreResult<()> {
+        self.0.lock().execute(CLEAR, [])?;
+        Ok(())
+    }
+
+    fn all(&self) -> BootstrapStoreResult<Vec<AgentInfoSigned>> {
+        let conn = self.0.lock();
+        let mut stmt = conn.prepare_cached(ALL)?;
+        let rows = stmt.query_map([], |row| row.get::<_, Vec<u8>>(0))?;
+        let mut out = Vec::new();
+        for encoded in rows {
+            out.push(rmp_decode(&mut encoded?.as_slice())?);
+        }
+        Ok(out)
+    }
+
+    fn agent_counts(&self) -> BootstrapStoreResult<Vec<(Arc<KitsuneSpace>, usize)>> {
+        let conn = self.0.lock();
+        let mut stmt = conn.prepare_cached(AGENT_COUNTS)?;
+        let rows = stmt.query_map([], |row| {
+            Ok((Arc::new(KitsuneSpace(row.get(0)?)), row.get(1)?))
+        })?;
+        Ok(rows.collect::<Result<_, _>>()?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use super::*;
+    use fixt::prelude::*;
+    use kitsune_p2p::{fixt::*, KitsuneSpace};
+    use kitsune_p2p_types::bootstrap::RandomLimit;
+
+    async fn info(
+        space: Arc<KitsuneSpace>,
+        agent: Arc<kitsune_p2p::KitsuneAgent>,
+        signed_at_ms: u64,
+        expires_at_ms: u64,
+    ) -> AgentInfoSigned {
+        AgentInfoSigned::sign(
+            space,
+            agent,
+            u32::MAX / 4,
+            vec!["fake:".into()],
+            signed_at_ms,
+            expires_at_ms,
+            |_| async move { Ok(Arc::new(fixt!(KitsuneSignature, Unpredictable))) },
+        )
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_sqlite_store_survives_reopen() {
+        let tmp_dir = tempfile::tempdir().unwrap();
+        let path = tmp_dir.path().join("bootstrap.sqlite3");
+        let space: Arc<KitsuneSpace> = Arc::new(fixt!(KitsuneSpace));
+        let agent = Arc::new(fixt!(KitsuneAgent, Unpredictable));
+
+        let newer = info(space.clone(), agent.clone(), 2, 10_000).await;
+        let older = info(space.clone(), agent.clone(), 1, 10_000).await;
+        let expiring = info(
+            space.clone(),
+            Arc::new(fixt!(KitsuneAgent, Unpredictable)),
+            1,
+            5_000,
+        )
+        .await;
+        {
+            let store = SqliteStore::open(&path).unwrap();
+            store.put(newer.clone()).unwrap();
+            // An older info never replaces a newer one
+            store.put(older).unwrap();
+            store.put(expiring.clone()).unwrap();
+        }
+
+        let store = SqliteStore::open(&path).unwrap();
+        let mut all = store.all().unwrap();
+        all.sort_by_key(|i| i.expires_at_ms);
+        assert_eq!(all, vec![expiring, newer.clone()]);
+
+        // Expired infos are pruned after a restart too
+        store.prune(6_000).unwrap();
+        assert_eq!(store.all().unwrap(), vec![newer.clone()]);
+
+        let query = RandomQuery {
+            space: space.clone(),
+            limit: RandomLimit(10),
+        };
+        let mut expected = Vec::new();
+        rmp_encode(&mut expected, &newer).unwrap();
+        assert_eq!(store.random(query.clone(), 6_000).unwrap(), vec![expected]);
+        assert!(store.random(query, 10_000).unwrap().is_empty());
+        assert_eq!(store.agent_counts().unwrap(), vec![(space, 1)]);
+    }
+}
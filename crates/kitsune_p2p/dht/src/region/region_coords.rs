@@ -87,6 +87,27 @@ impl RegionBounds {
         self.arc_interval().contains(x) && self.time_range().contains(t)
     }
 
+    /// Does this region entirely contain the other region?
+    pub fn contains_bounds(&self, other: &RegionBounds) -> bool {
+        // Measure locations clockwise from the start of this region, so that
+        // regions which wrap around the end of the location space compare
+        // correctly.
+        let start = self.x.0.as_u32();
+        let len = self.x.1.as_u32().wrapping_sub(start);
+        let (a, b) = (
+            other.x.0.as_u32().wrapping_sub(start),
+            other.x.1.as_u32().wrapping_sub(start),
+        );
+        a <= b && b <= len && self.t.0 <= other.t.0 && other.t.1 <= self.t.1
+    }
+
+    /// The number of (location, microsecond) points in this region.
+    pub fn volume(&self) -> u128 {
+        let x = self.x.1.as_u32().wrapping_sub(self.x.0.as_u32()) as u128 + 1;
+        let t = (self.t.1.as_micros() - self.t.0.as_micros()) as u128 + 1;
+        x * t
+    }
+
     /// Just the primitive underlying numbers. For diagnostics.
     pub fn to_primitive(&self) -> ((u32, u32), (i64, i64)) {
         (
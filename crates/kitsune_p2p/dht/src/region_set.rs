@@ -20,8 +20,7 @@ use crate::region::{Region, RegionBounds, RegionCoords, RegionData, RegionDataCo
 /// The current representation is very specific to our current algorithm,
 /// but this is an enum to make room for a more generic representation, e.g.
 /// a simple `Vec<Region>`, if we want a more intricate algorithm later.
-#[derive(Debug, derive_more::From)]
-#[cfg_attr(feature = "test_utils", derive(Clone))]
+#[derive(Clone, Debug, derive_more::From)]
 pub enum RegionSet<T: RegionDataConstraints = RegionData> {
     /// Logarithmic Time, Constant Space.
     Ltcs(RegionSetLtcs<T>),
@@ -49,15 +48,22 @@ impl<D: RegionDataConstraints> RegionSet<D> {
     ///
     /// This allows agents with differently computed RegionSets to still engage
     /// in gossip without needing to recompute regions.
-    pub fn query(&self, _bounds: &RegionBounds) -> ! {
-        unimplemented!("only implement after trying naive database-only approach")
+    ///
+    /// Returns None if the larger region can't be constructed from the
+    /// regions in this set.
+    pub fn query(&self, topo: &Topology, bounds: &RegionBounds) -> Option<D> {
+        match self {
+            Self::Ltcs(set) => set.query(topo, bounds),
+        }
     }
 
     /// In order for this RegionSet to be queryable, new data needs to be
     /// integrated into it to avoid needing to recompute it from the database
     /// on each query.
-    pub fn update(&self, _c: SpacetimeQuantumCoords, _d: D) -> ! {
-        unimplemented!("only implement after trying naive database-only approach")
+    pub fn update(&mut self, topo: &Topology, c: SpacetimeQuantumCoords, d: D) {
+        match self {
+            Self::Ltcs(set) => set.update(topo, c, d),
+        }
     }
 
     /// Find a set of Regions which represents the intersection of the two
@@ -192,6 +198,49 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_query_and_update() {
+        use num_traits::Zero;
+        let topo = Topology::unit(Timestamp::from_micros(1000));
+        let arq = Arq::new(8, 0u32.into(), 4.into());
+        let ops = op_grid(
+            &topo,
+            &ArqLocated::new(8, 0u32.into(), 8.into()),
+            (1000..11000i64).step_by(1000),
+        );
+        let mut store = OpStore::new(topo.clone(), GossipParams::zero());
+        store.integrate_ops(ops.clone().into_iter());
+
+        let times = TelescopingTimes::new(TimeQuantum::from(11000));
+        let coords = RegionCoordSetLtcs::new(times, ArqBoundsSet::single(arq.to_bounds(&topo)));
+        let rset: RegionSet = RegionSetLtcs::from_store(&store, coords.clone()).into();
+
+        // A region twice as wide as the arq's segments is the union of two regions
+        let big = RegionCoords::new(SpaceSegment::new(9, 0), times.segments()[0]);
+        assert!(store.query_region_data(&big).count > 0);
+        assert_eq!(
+            rset.query(&topo, &big.to_bounds(&topo)),
+            Some(store.query_region_data(&big))
+        );
+
+        // A region which only partially covers a segment can't be constructed
+        let partial = RegionBounds::new((0u32, 100u32), big.to_bounds(&topo).t);
+        assert_eq!(rset.query(&topo, &partial), None);
+
+        // Updating an empty set with each op gives the same set as computing
+        // it from the store
+        let mut updated: RegionSet = coords
+            .into_region_set_infallible(|_| RegionData::zero())
+            .into();
+        for op in ops {
+            updated.update(&topo, op.coords(&topo), op.region_data());
+        }
+        assert_eq!(
+            updated.regions().collect::<Vec<_>>(),
+            rset.regions().collect::<Vec<_>>()
+        );
+    }
+
     #[test]
     fn test_rectify() {
         let topo = Topology::unit_zero();
@@ -8,15 +8,16 @@ use crate::{
 };
 use derivative::Derivative;
 
-use super::{Region, RegionCoords, RegionData, RegionDataConstraints};
+use super::{Region, RegionBounds, RegionCoords, RegionData, RegionDataConstraints};
 
 /// A compact representation of a set of [`RegionCoords`].
 /// The [`TelescopingTimes`] generates all relevant [`TimeSegment`]s, and the
 /// [`SpaceSegment`]s are implied by the [`ArqBoundsSet`].
 ///
 /// LTCS stands for Logarithmic Time, Constant Space.
-#[derive(Debug, PartialEq, Eq, derive_more::Constructor, serde::Serialize, serde::Deserialize)]
-#[cfg_attr(feature = "test_utils", derive(Clone))]
+#[derive(
+    Clone, Debug, PartialEq, Eq, derive_more::Constructor, serde::Serialize, serde::Deserialize,
+)]
 pub struct RegionCoordSetLtcs {
     pub(super) times: TelescopingTimes,
     pub(super) arq_set: ArqBoundsSet,
@@ -107,9 +108,8 @@ impl RegionCoordSetLtcs {
 /// The coordinates for the regions are specified by a few values.
 /// The data to match the coordinates are specified in a 2D vector which must
 /// correspond to the generated coordinates.
-#[derive(serde::Serialize, serde::Deserialize, Derivative)]
+#[derive(Clone, serde::Serialize, serde::Deserialize, Derivative)]
 #[derivative(PartialEq, Eq)]
-#[cfg_attr(feature = "test_utils", derive(Clone))]
 pub struct RegionSetLtcs<D: RegionDataConstraints = RegionData> {
     /// The generator for the coordinates
     pub coords: RegionCoordSetLtcs,
@@ -209,14 +209,62 @@ impl<D: RegionDataConstraints> RegionSetLtcs<D> {
 
         let regions = self
             .regions()
-            .into_iter()
-            .zip(other.regions().into_iter())
+            .zip(other.regions())
             .filter_map(|(a, b)| (a.data != b.data).then_some(a))
             .collect();
 
         Ok(regions)
     }
 
+    /// Compute the data of an arbitrary region by combining the regions of
+    /// this set which lie within it.
+    ///
+    /// The regions of each arq are disjoint, so if the regions of some arq
+    /// which lie within the bounds also cover the bounds completely, their
+    /// data sums to the data of the whole region. If no arq's regions cover
+    /// the bounds, None is returned, and the data must be computed from the
+    /// op store instead.
+    pub fn query(&self, topo: &Topology, bounds: &RegionBounds) -> Option<D> {
+        let volume = bounds.volume();
+        self.coords
+            .region_coords_nested()
+            .zip(self.data.iter())
+            .find_map(|(arq_coords, arq_data)| {
+                let mut covered = 0;
+                let mut sum = D::zero();
+                for ((_, ix, it), coords) in arq_coords.flatten() {
+                    let region_bounds = coords.to_bounds(topo);
+                    if bounds.contains_bounds(&region_bounds) {
+                        covered += region_bounds.volume();
+                        sum += arq_data[ix][it].clone();
+                    }
+                }
+                (covered == volume).then_some(sum)
+            })
+    }
+
+    /// Add the data of a newly integrated op to every region in this set
+    /// which contains it, so that the set stays up to date without being
+    /// recomputed from the op store.
+    ///
+    /// Ops which lie outside of every region, including ops newer than the
+    /// most recent time segment, are not represented in the set.
+    pub fn update(&mut self, topo: &Topology, coords: SpacetimeQuantumCoords, d: D) {
+        let times = self.coords.times.segments();
+        for (ia, arq) in self.coords.arq_set.arqs().iter().enumerate() {
+            for (ix, space) in arq.segments().enumerate() {
+                if !space.contains_quantum(topo, coords.space) {
+                    continue;
+                }
+                for (it, time) in times.iter().enumerate() {
+                    if time.contains_quantum(topo, coords.time) {
+                        self.data[ia][ix][it] += d.clone();
+                    }
+                }
+            }
+        }
+    }
+
     /// Return only the regions which have ops in them. Useful for testing
     /// sparse scenarios.
     pub fn nonzero_regions(
@@ -8,6 +8,9 @@ use crate::FetchKey;
 pub enum FetchError {
     #[error("Key not present in the queue: {0:?}")]
     KeyMissing(FetchKey),
+
+    #[error("Fetch queue store error: {0}")]
+    Store(Box<dyn Send + Sync + std::error::Error>),
 }
 
 /// Kitsune Fetch Result
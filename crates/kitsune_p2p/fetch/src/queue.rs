@@ -9,6 +9,9 @@
 //! from the current moment. The items thus returned are not guaranteed to be returned in
 //! order of last_fetch time, but they are guaranteed to be at least as old as the specified
 //! interval.
+//!
+//! The queue lives in memory, but can optionally write through to a [`FetchQueueStore`],
+//! from which a space's items can be restored after a restart.
 
 use std::sync::Arc;
 use tokio::time::{Duration, Instant};
@@ -16,11 +19,14 @@ use tokio::time::{Duration, Instant};
 use kitsune_p2p_types::{tx2::tx2_utils::ShareOpen, KAgent, KSpace /*, Tx2Cert*/};
 use linked_hash_map::{Entry, LinkedHashMap};
 
-use crate::{FetchContext, FetchKey, FetchQueuePush, RoughInt};
+use crate::{FetchContext, FetchError, FetchKey, FetchQueuePush, FetchResult, RoughInt};
 
 mod queue_reader;
 pub use queue_reader::*;
 
+mod queue_store;
+pub use queue_store::*;
+
 /// Max number of queue items to check on each `next()` poll
 const NUM_ITEMS_PER_POLL: usize = 100;
 
@@ -40,6 +46,7 @@ const NUM_ITEMS_PER_POLL: usize = 100;
 pub struct FetchQueue {
     config: FetchConfig,
     state: ShareOpen<State>,
+    store: Option<FetchStore>,
 }
 
 impl std::fmt::Debug for FetchQueue {
@@ -128,7 +135,7 @@ struct SourceRecord {
 }
 
 /// A source to fetch from: either a node, or an agent on a node
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
 pub enum FetchSource {
     /// An agent on a node
     Agent(KAgent),
@@ -150,6 +157,7 @@ impl FetchQueue {
         Self {
             config,
             state: ShareOpen::new(State::default()),
+            store: None,
         }
     }
 
@@ -158,9 +166,45 @@ impl FetchQueue {
         Self {
             config: Arc::new(FetchQueueConfigBitwiseOr),
             state: ShareOpen::new(State::default()),
+            store: None,
         }
     }
 
+    /// Write every change to the queue through to this store.
+    pub fn with_store(mut self, store: FetchStore) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    /// Restore the items persisted for this space, appending them to the queue.
+    /// Items which have been added again since startup are left as they are.
+    /// Returns the number of items restored.
+    pub async fn restore(&self, space: KSpace) -> FetchResult<usize> {
+        let store = match &self.store {
+            Some(store) => store,
+            None => return Ok(0),
+        };
+        let records = store.load(space).await.map_err(FetchError::Store)?;
+        let clocks = Clocks::now();
+        let restored = self.state.share_mut(|s| {
+            let mut restored = 0;
+            for record in records {
+                let (key, item) = FetchQueueItem::from_record(record, clocks);
+                if let Entry::Vacant(e) = s.queue.entry(key) {
+                    e.insert(item);
+                    restored += 1;
+                }
+            }
+            tracing::debug!(
+                "FetchQueue (size = {}) restored {} items",
+                s.queue.len(),
+                restored
+            );
+            restored
+        });
+        Ok(restored)
+    }
+
     /// Add an item to the queue.
     /// If the FetchKey does not already exist, add it to the end of the queue.
     /// If the FetchKey exists, add the new source and merge the context in, without
@@ -172,7 +216,13 @@ impl FetchQueue {
                 s.queue.len() + 1,
                 args
             );
+            let key = args.key.clone();
             s.push(&*self.config, args);
+            if let Some(store) = &self.store {
+                if let Some(item) = s.queue.get(&key) {
+                    store.put(vec![item.to_record(key, Clocks::now())]);
+                }
+            }
         });
     }
 
@@ -186,6 +236,9 @@ impl FetchQueue {
                 key,
                 removed
             );
+            if let (Some(store), Some(item)) = (&self.store, &removed) {
+                store.remove(item.space.clone(), vec![key.clone()]);
+            }
             removed
         })
     }
@@ -199,6 +252,20 @@ impl FetchQueue {
                 out.push((key, space, source, context));
             }
 
+            // Persist the retry timers which were just updated
+            if let Some(store) = &self.store {
+                if !out.is_empty() {
+                    let clocks = Clocks::now();
+                    store.put(
+                        out.iter()
+                            .filter_map(|(key, ..)| {
+                                s.queue.get(key).map(|i| i.to_record(key.clone(), clocks))
+                            })
+                            .collect(),
+                    );
+                }
+            }
+
             out
         })
     }
@@ -507,4 +574,93 @@ mod tests {
 
         assert_eq!(q.iter_mut(&cfg).count(), 3);
     }
+
+    #[derive(Default)]
+    struct MemStore(std::sync::Mutex<LinkedHashMap<FetchKey, FetchQueueRecord>>);
+
+    impl FetchQueueStore for MemStore {
+        fn load(&self, space: KSpace) -> FetchQueueStoreResult<Vec<FetchQueueRecord>> {
+            let records = self
+                .0
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|r| r.space == space)
+                .cloned()
+                .collect();
+            futures::FutureExt::boxed(futures::future::ready(Ok(records))).into()
+        }
+
+        fn put(&self, records: Vec<FetchQueueRecord>) {
+            let mut map = self.0.lock().unwrap();
+            for record in records {
+                match map.entry(record.key.clone()) {
+                    Entry::Occupied(mut e) => {
+                        e.insert(record);
+                    }
+                    Entry::Vacant(e) => {
+                        e.insert(record);
+                    }
+                }
+            }
+        }
+
+        fn remove(&self, _space: KSpace, keys: Vec<FetchKey>) {
+            let mut map = self.0.lock().unwrap();
+            for key in keys {
+                map.remove(&key);
+            }
+        }
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn queue_restore() {
+        let store = Arc::new(MemStore::default());
+        let q = FetchQueue::new(Arc::new(Config(1, 10))).with_store(store.clone());
+
+        q.push(req(1, ctx(1), source(1)));
+        q.push(req(2, ctx(1), source(2)));
+        q.push(req(3, ctx(1), source(3)));
+        q.push(req(3, ctx(0), source(4)));
+        assert_eq!(q.get_items_to_fetch().len(), 3);
+
+        // The op arrived, so it's pruned from the store as well
+        q.remove(&key_op(2));
+        assert_eq!(
+            store.0.lock().unwrap().keys().cloned().collect::<Vec<_>>(),
+            vec![key_op(1), key_op(3)]
+        );
+
+        // Nothing is restored for an unrelated space
+        let restarted = FetchQueue::new(Arc::new(Config(1, 10))).with_store(store.clone());
+        assert_eq!(restarted.restore(space(1)).await.unwrap(), 0);
+        assert_eq!(restarted.restore(space(0)).await.unwrap(), 2);
+
+        let sources_of = |q: &FetchQueue, key| {
+            q.state.share_ref(|s| {
+                s.queue[&key]
+                    .sources
+                    .0
+                    .iter()
+                    .map(|s| s.source.clone())
+                    .collect::<Vec<_>>()
+            })
+        };
+        assert_eq!(sources_of(&restarted, key_op(3)), sources_of(&q, key_op(3)));
+
+        // The retry timers were restored, so nothing is ready to be fetched again
+        // until a source's delay has elapsed.
+        assert!(restarted.get_items_to_fetch().is_empty());
+
+        tokio::time::advance(Duration::from_secs(10)).await;
+
+        assert_eq!(
+            restarted
+                .get_items_to_fetch()
+                .into_iter()
+                .map(|(key, ..)| key)
+                .collect::<Vec<_>>(),
+            vec![key_op(1), key_op(3)]
+        );
+    }
 }
@@ -62,6 +62,7 @@ mod tests {
             FetchQueueReader(FetchQueue {
                 config: Arc::new(cfg),
                 state: ShareOpen::new(State { queue }),
+                store: None,
             })
         };
         let info = q.info([space(0)].into_iter().collect());
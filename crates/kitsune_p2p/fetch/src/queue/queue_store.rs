@@ -0,0 +1,137 @@
+use std::sync::Arc;
+
+use kitsune_p2p_timestamp::Timestamp;
+use kitsune_p2p_types::KSpace;
+use must_future::MustBoxFuture;
+use tokio::time::{Duration, Instant};
+
+use super::{SourceRecord, Sources};
+use crate::{FetchContext, FetchKey, FetchQueueItem, FetchSource, RoughInt};
+
+/// A boxed future result with dynamic error type
+pub type FetchQueueStoreResult<T> =
+    MustBoxFuture<'static, Result<T, Box<dyn Send + Sync + std::error::Error>>>;
+
+/// Alias
+pub type FetchStore = Arc<dyn FetchQueueStore>;
+
+/// An optional persistent backing store for a [`FetchQueue`](crate::FetchQueue).
+///
+/// The queue writes through to the store every time an item is added, attempted
+/// or removed, so that the items, their sources and their retry timers can be
+/// restored after a restart with [`FetchQueue::restore`](crate::FetchQueue::restore).
+///
+/// Writes are fire-and-forget, since they happen while the queue state is locked:
+/// implementations must not block, and must apply writes in the order they were made.
+pub trait FetchQueueStore: 'static + Send + Sync {
+    /// Load all items persisted for this space, in queue order.
+    fn load(&self, space: KSpace) -> FetchQueueStoreResult<Vec<FetchQueueRecord>>;
+
+    /// Persist these items, replacing any previous record with the same key.
+    fn put(&self, records: Vec<FetchQueueRecord>);
+
+    /// Forget about these items.
+    fn remove(&self, space: KSpace, keys: Vec<FetchKey>);
+}
+
+/// A snapshot of a single queue item, as written to a [`FetchQueueStore`].
+///
+/// Retry timers are stored as wall-clock times, since an [`Instant`] has no
+/// meaning beyond the lifetime of the process.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct FetchQueueRecord {
+    /// The key of the item
+    pub key: FetchKey,
+    /// The space to retrieve this item from
+    pub space: KSpace,
+    /// Known sources, in the order they will be tried, along with the last
+    /// time each was asked for this item
+    pub sources: Vec<(FetchSource, Option<Timestamp>)>,
+    /// Approximate size of the item
+    pub size: Option<RoughInt>,
+    /// Opaque user data specified by the host
+    pub context: Option<FetchContext>,
+    /// The last time we tried fetching this item from any source
+    pub last_fetch: Option<Timestamp>,
+}
+
+/// Pairs up the monotonic and the wall clock, so that timers can be converted
+/// between the two.
+#[derive(Clone, Copy)]
+pub(crate) struct Clocks {
+    instant: Instant,
+    timestamp: Timestamp,
+}
+
+impl Clocks {
+    pub(crate) fn now() -> Self {
+        Self {
+            instant: Instant::now(),
+            timestamp: Timestamp::now(),
+        }
+    }
+
+    fn to_timestamp(self, t: Instant) -> Timestamp {
+        self.timestamp
+            .saturating_sub(&self.instant.saturating_duration_since(t))
+    }
+
+    /// A time too far in the past to be represented as an [`Instant`] is
+    /// dropped, which makes it immediately eligible for a retry, just as it
+    /// would have been anyway.
+    fn to_instant(self, t: Timestamp) -> Option<Instant> {
+        let ago = (self.timestamp.as_micros() - t.as_micros()).max(0) as u64;
+        self.instant.checked_sub(Duration::from_micros(ago))
+    }
+}
+
+impl FetchQueueItem {
+    pub(crate) fn to_record(&self, key: FetchKey, clocks: Clocks) -> FetchQueueRecord {
+        FetchQueueRecord {
+            key,
+            space: self.space.clone(),
+            sources: self
+                .sources
+                .0
+                .iter()
+                .map(|s| {
+                    (
+                        s.source.clone(),
+                        s.last_request.map(|t| clocks.to_timestamp(t)),
+                    )
+                })
+                .collect(),
+            size: self.size,
+            context: self.context,
+            last_fetch: self.last_fetch.map(|t| clocks.to_timestamp(t)),
+        }
+    }
+
+    pub(crate) fn from_record(record: FetchQueueRecord, clocks: Clocks) -> (FetchKey, Self) {
+        let FetchQueueRecord {
+            key,
+            space,
+            sources,
+            size,
+            context,
+            last_fetch,
+        } = record;
+        let sources = Sources(
+            sources
+                .into_iter()
+                .map(|(source, last_request)| SourceRecord {
+                    source,
+                    last_request: last_request.and_then(|t| clocks.to_instant(t)),
+                })
+                .collect(),
+        );
+        let item = Self {
+            sources,
+            space,
+            size,
+            context,
+            last_fetch: last_fetch.and_then(|t| clocks.to_instant(t)),
+        };
+        (key, item)
+    }
+}
@@ -49,6 +49,10 @@ pub struct KitsuneP2pConfig {
     pub tuning_params: KitsuneP2pTuningParams,
     /// The network used for connecting to other peers
     pub network_type: NetworkType,
+    /// Gossip bandwidth overrides and schedule. This can also be
+    /// changed at runtime.
+    #[serde(default)]
+    pub gossip_throttle: GossipThrottleConfig,
 }
 
 impl Default for KitsuneP2pConfig {
@@ -58,6 +62,7 @@ impl Default for KitsuneP2pConfig {
             bootstrap_service: None,
             tuning_params: KitsuneP2pTuningParams::default(),
             network_type: NetworkType::QuicBootstrap,
+            gossip_throttle: GossipThrottleConfig::default(),
         }
     }
 }
@@ -253,3 +258,191 @@ pub enum NetworkType {
     /// Via MDNS to the LAN
     QuicMdns,
 }
+
+/// Gossip bandwidth targets in megabits per second.
+/// A target of zero leaves that direction unlimited.
+#[derive(Clone, Copy, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct GossipBandwidth {
+    /// Inbound target for the recent gossip loop.
+    pub recent_inbound_mbps: f64,
+    /// Outbound target for the recent gossip loop.
+    pub recent_outbound_mbps: f64,
+    /// Inbound target for the historical gossip loop.
+    pub historic_inbound_mbps: f64,
+    /// Outbound target for the historical gossip loop.
+    pub historic_outbound_mbps: f64,
+}
+
+impl GossipBandwidth {
+    /// The bandwidth targets set in the tuning params.
+    pub fn from_tuning_params(tuning_params: &KitsuneP2pTuningParams) -> Self {
+        Self {
+            recent_inbound_mbps: tuning_params.gossip_inbound_target_mbps,
+            recent_outbound_mbps: tuning_params.gossip_outbound_target_mbps,
+            historic_inbound_mbps: tuning_params.gossip_historic_inbound_target_mbps,
+            historic_outbound_mbps: tuning_params.gossip_historic_outbound_target_mbps,
+        }
+    }
+
+    /// Multiply every target by this factor.
+    pub fn scale(&self, factor: f64) -> Self {
+        Self {
+            recent_inbound_mbps: self.recent_inbound_mbps * factor,
+            recent_outbound_mbps: self.recent_outbound_mbps * factor,
+            historic_inbound_mbps: self.historic_inbound_mbps * factor,
+            historic_outbound_mbps: self.historic_outbound_mbps * factor,
+        }
+    }
+
+    fn check(&self) -> Result<(), String> {
+        let all = [
+            self.recent_inbound_mbps,
+            self.recent_outbound_mbps,
+            self.historic_inbound_mbps,
+            self.historic_outbound_mbps,
+        ];
+        if all.iter().all(|mbps| mbps.is_finite() && *mbps >= 0.0) {
+            Ok(())
+        } else {
+            Err(format!("invalid gossip bandwidth {:?}", self))
+        }
+    }
+}
+
+/// Gossip bandwidth which applies during the same hours of every day.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct GossipScheduleWindow {
+    /// The UTC hour, from 0 to 23, at which this window starts.
+    pub start_hour_utc: u8,
+    /// The UTC hour, from 1 to 24, at which this window ends.
+    /// A window which ends before it starts runs over midnight.
+    pub end_hour_utc: u8,
+    /// The bandwidth targets during this window.
+    pub bandwidth: GossipBandwidth,
+}
+
+impl GossipScheduleWindow {
+    /// Does this window cover the given UTC hour of the day?
+    pub fn contains(&self, hour_utc: u8) -> bool {
+        if self.start_hour_utc <= self.end_hour_utc {
+            (self.start_hour_utc..self.end_hour_utc).contains(&hour_utc)
+        } else {
+            hour_utc >= self.start_hour_utc || hour_utc < self.end_hour_utc
+        }
+    }
+}
+
+/// Adjustments to the gossip bandwidth targets from the tuning params.
+#[derive(Clone, Debug, Default, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct GossipThrottleConfig {
+    /// Replaces the bandwidth targets from the tuning params.
+    #[serde(default)]
+    pub bandwidth: Option<GossipBandwidth>,
+    /// The first window covering the current time replaces `bandwidth`.
+    #[serde(default)]
+    pub schedule: Vec<GossipScheduleWindow>,
+    /// Don't reduce gossip bandwidth when the host reports pressure.
+    #[serde(default)]
+    pub ignore_host_pressure: bool,
+}
+
+impl GossipThrottleConfig {
+    /// Check that all the targets and hours make sense.
+    pub fn check(&self) -> Result<(), String> {
+        if let Some(bandwidth) = &self.bandwidth {
+            bandwidth.check()?;
+        }
+        for window in &self.schedule {
+            if window.start_hour_utc > 23
+                || window.end_hour_utc > 24
+                || window.start_hour_utc == window.end_hour_utc
+            {
+                return Err(format!("invalid gossip schedule window {:?}", window));
+            }
+            window.bandwidth.check()?;
+        }
+        Ok(())
+    }
+
+    /// The bandwidth targets which apply at the given UTC hour of the day,
+    /// before backing off for host pressure.
+    pub fn bandwidth_at(&self, default: GossipBandwidth, hour_utc: u8) -> GossipBandwidth {
+        self.schedule
+            .iter()
+            .find(|window| window.contains(hour_utc))
+            .map(|window| window.bandwidth)
+            .or(self.bandwidth)
+            .unwrap_or(default)
+    }
+}
+
+/// The current state of gossip throttling.
+#[derive(Clone, Debug, serde::Serialize, serde::Deserialize, PartialEq)]
+pub struct GossipThrottleStatus {
+    /// The configured adjustments.
+    pub config: GossipThrottleConfig,
+    /// The pressure last reported by the host, from 0.0 to 1.0.
+    pub host_pressure: f64,
+    /// The bandwidth targets currently applied to gossip.
+    pub bandwidth: GossipBandwidth,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bandwidth(mbps: f64) -> GossipBandwidth {
+        GossipBandwidth {
+            recent_inbound_mbps: mbps,
+            recent_outbound_mbps: mbps,
+            historic_inbound_mbps: mbps,
+            historic_outbound_mbps: mbps,
+        }
+    }
+
+    #[test]
+    fn gossip_schedule_picks_first_matching_window() {
+        let config = GossipThrottleConfig {
+            bandwidth: Some(bandwidth(10.0)),
+            schedule: vec![
+                GossipScheduleWindow {
+                    start_hour_utc: 22,
+                    end_hour_utc: 6,
+                    bandwidth: bandwidth(100.0),
+                },
+                GossipScheduleWindow {
+                    start_hour_utc: 0,
+                    end_hour_utc: 12,
+                    bandwidth: bandwidth(50.0),
+                },
+            ],
+            ignore_host_pressure: false,
+        };
+        config.check().unwrap();
+        let default = bandwidth(1.0);
+        assert_eq!(config.bandwidth_at(default, 23), bandwidth(100.0));
+        assert_eq!(config.bandwidth_at(default, 3), bandwidth(100.0));
+        assert_eq!(config.bandwidth_at(default, 6), bandwidth(50.0));
+        assert_eq!(config.bandwidth_at(default, 12), bandwidth(10.0));
+        assert_eq!(
+            GossipThrottleConfig::default().bandwidth_at(default, 12),
+            default
+        );
+    }
+
+    #[test]
+    fn gossip_throttle_config_rejects_bad_values() {
+        let mut config = GossipThrottleConfig {
+            bandwidth: Some(bandwidth(-1.0)),
+            ..Default::default()
+        };
+        assert!(config.check().is_err());
+        config.bandwidth = None;
+        config.schedule.push(GossipScheduleWindow {
+            start_hour_utc: 24,
+            end_hour_utc: 2,
+            bandwidth: bandwidth(1.0),
+        });
+        assert!(config.check().is_err());
+    }
+}
@@ -166,7 +166,7 @@ impl ShardedGossip {
     /// Constructor
     #[allow(clippy::too_many_arguments)]
     pub fn new(
-        tuning_params: KitsuneP2pTuningParams,
+        tuning_params: LiveTuningParams,
         space: Arc<KitsuneSpace>,
         ep_hnd: Tx2EpHnd<wire::Wire>,
         evt_sender: EventSender,
@@ -256,7 +256,7 @@ impl ShardedGossip {
             self.gossip.gossip_type.into(),
         );
 
-        let timeout = self.gossip.tuning_params.implicit_timeout();
+        let timeout = self.gossip.tuning_params.get().implicit_timeout();
 
         self.bandwidth.outgoing_bytes(bytes).await;
 
@@ -425,7 +425,7 @@ impl ShardedGossip {
 ///     get sent by the enclosing `ShardedGossip`
 pub struct ShardedGossipLocal {
     gossip_type: GossipType,
-    tuning_params: KitsuneP2pTuningParams,
+    tuning_params: LiveTuningParams,
     space: Arc<KitsuneSpace>,
     evt_sender: EventSender,
     host_api: HostApi,
@@ -708,7 +708,7 @@ impl ShardedGossipLocal {
     /// Calculate the time range for a gossip round.
     fn calculate_time_range(&self) -> TimeWindow {
         const NOW: Duration = Duration::from_secs(0);
-        let threshold = Duration::from_secs(self.tuning_params.danger_gossip_recent_threshold_secs);
+        let threshold = self.tuning_params.get().danger_gossip_recent_threshold();
         match self.gossip_type {
             GossipType::Recent => time_range(threshold, NOW),
             GossipType::Historical => {
@@ -1341,7 +1341,7 @@ impl ShardedRecentGossipFactory {
 impl AsGossipModuleFactory for ShardedRecentGossipFactory {
     fn spawn_gossip_task(
         &self,
-        tuning_params: KitsuneP2pTuningParams,
+        tuning_params: LiveTuningParams,
         space: Arc<KitsuneSpace>,
         ep_hnd: Tx2EpHnd<wire::Wire>,
         evt_sender: futures::channel::mpsc::Sender<event::KitsuneP2pEvent>,
@@ -1376,7 +1376,7 @@ impl ShardedHistoricalGossipFactory {
 impl AsGossipModuleFactory for ShardedHistoricalGossipFactory {
     fn spawn_gossip_task(
         &self,
-        tuning_params: KitsuneP2pTuningParams,
+        tuning_params: LiveTuningParams,
         space: Arc<KitsuneSpace>,
         ep_hnd: Tx2EpHnd<wire::Wire>,
         evt_sender: futures::channel::mpsc::Sender<event::KitsuneP2pEvent>,
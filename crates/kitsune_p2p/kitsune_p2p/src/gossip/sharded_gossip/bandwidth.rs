@@ -6,30 +6,119 @@ use std::{
 use governor::{clock::Clock, Quota};
 
 use super::*;
+use crate::{GossipBandwidth, GossipThrottleConfig, GossipThrottleStatus};
+
+/// Never reduce gossip bandwidth to less than this fraction of its
+/// target because of host pressure.
+const MIN_PRESSURE_FACTOR: f64 = 0.1;
+
+/// Host pressure is rounded to a multiple of this, so small changes
+/// in pressure don't rebuild the limiters.
+const PRESSURE_STEP: f64 = 0.1;
 
 #[derive(Clone)]
 /// Set of bandwidth throttles for all gossip loops.
 pub struct BandwidthThrottles {
     recent: Arc<BandwidthThrottle>,
     historic: Arc<BandwidthThrottle>,
+    state: Arc<parking_lot::Mutex<ThrottleState>>,
+}
+
+struct ThrottleState {
+    default: GossipBandwidth,
+    config: GossipThrottleConfig,
+    host_pressure: f64,
 }
 
 impl BandwidthThrottles {
     /// Create a new set of throttles from the configuration.
     pub fn new(tuning_params: &KitsuneP2pTuningParams) -> Self {
+        let default = GossipBandwidth::from_tuning_params(tuning_params);
         let recent = BandwidthThrottle::new(
-            tuning_params.gossip_inbound_target_mbps,
-            tuning_params.gossip_outbound_target_mbps,
+            default.recent_inbound_mbps,
+            default.recent_outbound_mbps,
             tuning_params.gossip_burst_ratio,
         );
         let historic = BandwidthThrottle::new(
-            tuning_params.gossip_historic_inbound_target_mbps,
-            tuning_params.gossip_historic_outbound_target_mbps,
+            default.historic_inbound_mbps,
+            default.historic_outbound_mbps,
             tuning_params.gossip_burst_ratio,
         );
         Self {
             recent: Arc::new(recent),
             historic: Arc::new(historic),
+            state: Arc::new(parking_lot::Mutex::new(ThrottleState {
+                default,
+                config: GossipThrottleConfig::default(),
+                host_pressure: 0.0,
+            })),
+        }
+    }
+
+    /// Replace the throttle configuration and apply it straight away.
+    pub fn set_config(&self, config: GossipThrottleConfig) {
+        self.state.lock().config = config;
+        self.refresh();
+    }
+
+    /// Replace the bandwidth targets from the tuning params,
+    /// which apply when the configuration doesn't override them.
+    pub fn set_default(&self, tuning_params: &KitsuneP2pTuningParams) {
+        self.state.lock().default = GossipBandwidth::from_tuning_params(tuning_params);
+        self.refresh();
+    }
+
+    /// Record the pressure reported by the host, from 0.0 to 1.0,
+    /// and apply it straight away.
+    /// The pressure is rounded to a multiple of `PRESSURE_STEP`.
+    pub fn set_host_pressure(&self, host_pressure: f64) {
+        let host_pressure = if host_pressure.is_nan() {
+            0.0
+        } else {
+            (host_pressure.clamp(0.0, 1.0) / PRESSURE_STEP).round() * PRESSURE_STEP
+        };
+        self.state.lock().host_pressure = host_pressure;
+        self.refresh();
+    }
+
+    /// Recalculate the bandwidth targets for the current time of day
+    /// and apply them to the throttles.
+    pub fn refresh(&self) {
+        let hour_utc = (SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs()
+            / 3600
+            % 24) as u8;
+        self.refresh_at(hour_utc);
+    }
+
+    fn refresh_at(&self, hour_utc: u8) {
+        let bandwidth = self.state.lock().bandwidth_at(hour_utc);
+        self.recent.set_target_mbps(
+            bandwidth.recent_inbound_mbps,
+            bandwidth.recent_outbound_mbps,
+        );
+        self.historic.set_target_mbps(
+            bandwidth.historic_inbound_mbps,
+            bandwidth.historic_outbound_mbps,
+        );
+    }
+
+    /// The current configuration and the targets being applied.
+    pub fn status(&self) -> GossipThrottleStatus {
+        let state = self.state.lock();
+        let (recent_inbound_mbps, recent_outbound_mbps) = self.recent.target_mbps();
+        let (historic_inbound_mbps, historic_outbound_mbps) = self.historic.target_mbps();
+        GossipThrottleStatus {
+            config: state.config.clone(),
+            host_pressure: state.host_pressure,
+            bandwidth: GossipBandwidth {
+                recent_inbound_mbps,
+                recent_outbound_mbps,
+                historic_inbound_mbps,
+                historic_outbound_mbps,
+            },
         }
     }
 
@@ -44,6 +133,29 @@ impl BandwidthThrottles {
     }
 }
 
+impl ThrottleState {
+    fn bandwidth_at(&self, hour_utc: u8) -> GossipBandwidth {
+        let bandwidth = self.config.bandwidth_at(self.default, hour_utc);
+        if self.config.ignore_host_pressure {
+            bandwidth
+        } else {
+            bandwidth.scale((1.0 - self.host_pressure).max(MIN_PRESSURE_FACTOR))
+        }
+    }
+}
+
+type Limiter<C> = Arc<RateLimiter<NotKeyed, InMemoryState, C>>;
+
+struct Limits<C>
+where
+    C: Clock,
+{
+    inbound_mbps: f64,
+    outbound_mbps: f64,
+    inbound: Option<Limiter<C>>,
+    outbound: Option<Limiter<C>>,
+}
+
 /// Manages incoming and outgoing bandwidth by providing methods which
 /// asynchronously wait for enough bandwidth to become available before
 /// processing a chunk of bytes
@@ -52,8 +164,8 @@ where
     C: Clock,
 {
     clock: C,
-    inbound: Option<RateLimiter<NotKeyed, InMemoryState, C>>,
-    outbound: Option<RateLimiter<NotKeyed, InMemoryState, C>>,
+    burst_ratio: f64,
+    limits: parking_lot::RwLock<Limits<C>>,
     start_time: Instant,
     bits_inbound: AtomicUsize,
     peak_inbound: AtomicUsize,
@@ -92,25 +204,16 @@ where
     C: Clock,
 {
     fn new_inner(inbound_mbps: f64, outbound_mbps: f64, burst_ratio: f64, clock: C) -> Self {
-        // Convert to bits per second.
-        let inbound_bps = inbound_mbps * 1000.0 * 1000.0;
-        let outbound_bps = outbound_mbps * 1000.0 * 1000.0;
-
-        let inbound = NonZeroU32::new(inbound_bps as u32).map(|bps| {
-            let burst = NonZeroU32::new((inbound_bps * burst_ratio) as u32)
-                .expect("burst_ratio cannot be 0");
-            RateLimiter::direct_with_clock(Quota::per_second(bps).allow_burst(burst), &clock)
-        });
-
-        let outbound = NonZeroU32::new(outbound_bps as u32).map(|bps| {
-            let burst = NonZeroU32::new((outbound_bps * burst_ratio) as u32)
-                .expect("burst_ratio cannot be 0");
-            RateLimiter::direct_with_clock(Quota::per_second(bps).allow_burst(burst), &clock)
-        });
+        let limits = Limits {
+            inbound_mbps,
+            outbound_mbps,
+            inbound: Self::limiter(inbound_mbps, burst_ratio, &clock, false),
+            outbound: Self::limiter(outbound_mbps, burst_ratio, &clock, false),
+        };
         Self {
             clock,
-            inbound,
-            outbound,
+            burst_ratio,
+            limits: parking_lot::RwLock::new(limits),
             start_time: Instant::now(),
             bits_inbound: AtomicUsize::new(0),
             peak_inbound: AtomicUsize::new(0),
@@ -121,6 +224,46 @@ where
         }
     }
 
+    /// Build a limiter for this many megabits per second.
+    /// A drained limiter starts with no burst capacity.
+    fn limiter(mbps: f64, burst_ratio: f64, clock: &C, drained: bool) -> Option<Limiter<C>> {
+        // Convert to bits per second.
+        let bps = mbps * 1000.0 * 1000.0;
+        NonZeroU32::new(bps as u32).map(|rate| {
+            let burst =
+                NonZeroU32::new((bps * burst_ratio) as u32).expect("burst_ratio cannot be 0");
+            let limiter =
+                RateLimiter::direct_with_clock(Quota::per_second(rate).allow_burst(burst), clock);
+            if drained {
+                limiter.check_n(burst).ok();
+            }
+            Arc::new(limiter)
+        })
+    }
+
+    /// Change the inbound and outbound bandwidth limits in megabits per second.
+    /// A limit of zero is unlimited.
+    pub fn set_target_mbps(&self, inbound_mbps: f64, outbound_mbps: f64) {
+        let mut limits = self.limits.write();
+        // Only rebuild a limiter when its target changes, and start the
+        // new one drained. Otherwise targets that keep changing, like
+        // those following host pressure, would refill the burst each time.
+        if limits.inbound_mbps != inbound_mbps {
+            limits.inbound_mbps = inbound_mbps;
+            limits.inbound = Self::limiter(inbound_mbps, self.burst_ratio, &self.clock, true);
+        }
+        if limits.outbound_mbps != outbound_mbps {
+            limits.outbound_mbps = outbound_mbps;
+            limits.outbound = Self::limiter(outbound_mbps, self.burst_ratio, &self.clock, true);
+        }
+    }
+
+    /// The inbound and outbound bandwidth limits in megabits per second.
+    pub fn target_mbps(&self) -> (f64, f64) {
+        let limits = self.limits.read();
+        (limits.inbound_mbps, limits.outbound_mbps)
+    }
+
     async fn try_throttle(
         &self,
         verb: &str,
@@ -169,8 +312,9 @@ where
     /// Wait until there's enough bandwidth to send this many bytes.
     pub async fn outgoing_bytes(&self, bytes: usize) {
         if let Some(bits) = NonZeroU32::new(bytes as u32 * 8) {
-            if let Some(outbound) = &self.outbound {
-                self.try_throttle("send", outbound, bytes, bits).await;
+            let outbound = self.limits.read().outbound.clone();
+            if let Some(outbound) = outbound {
+                self.try_throttle("send", &outbound, bytes, bits).await;
             }
             let el = self.start_time.elapsed();
             let last_s = self
@@ -206,8 +350,9 @@ where
     /// Wait until there's enough bandwidth to receive this many bytes.
     pub async fn incoming_bytes(&self, bytes: usize) {
         if let Some(bits) = NonZeroU32::new(bytes as u32 * 8) {
-            if let Some(inbound) = &self.inbound {
-                self.try_throttle("receive", inbound, bytes, bits).await;
+            let inbound = self.limits.read().inbound.clone();
+            if let Some(inbound) = inbound {
+                self.try_throttle("receive", &inbound, bytes, bits).await;
             }
             let el = self.start_time.elapsed();
             let last_s = self
@@ -291,4 +436,110 @@ mod tests {
         // Allow for small rounding error.
         assert!(mbps < 0.11);
     }
+
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn test_limiter_target_can_change() {
+        let clock = governor::clock::FakeRelativeClock::default();
+        let bandwidth = BandwidthThrottle::test(0.1, 0.1, 1.0, clock.clone());
+        let bytes = 10_000;
+        // Use up the burst, then the next send has to wait.
+        bandwidth.outgoing_bytes(bytes).await;
+        let r =
+            tokio::time::timeout(Duration::from_secs(10), bandwidth.outgoing_bytes(bytes)).await;
+        assert!(r.is_err());
+
+        // Lifting the limit lets it through straight away.
+        bandwidth.set_target_mbps(0.1, 0.0);
+        assert_eq!(bandwidth.target_mbps(), (0.1, 0.0));
+        let n = tokio::time::Instant::now();
+        bandwidth.outgoing_bytes(bytes).await;
+        assert!(n.elapsed().is_zero());
+    }
+
+    #[tokio::test(flavor = "current_thread", start_paused = true)]
+    async fn test_changing_target_does_not_refill_burst() {
+        let clock = governor::clock::FakeRelativeClock::default();
+        let burst_ratio = 100.0;
+        let bandwidth = BandwidthThrottle::test(0.1, 0.1, burst_ratio, clock.clone());
+        let bytes = 100_000;
+        let mut bits_sent = 0;
+        let mut seconds = 0;
+        // The target follows host pressure, which changes every 10 seconds.
+        for pressure in [0.0, 0.2, 0.4].iter().cycle().take(30) {
+            bandwidth.set_target_mbps(0.1, 0.1 * (1.0 - pressure));
+            clock.advance(Duration::from_secs(10));
+            seconds += 10;
+            // Send as much as the throttle allows without waiting.
+            while tokio::time::timeout(Duration::from_secs(1), bandwidth.outgoing_bytes(bytes))
+                .await
+                .is_ok()
+            {
+                bits_sent += bytes * 8;
+            }
+        }
+        assert!(bits_sent > 0);
+        // No more than the initial burst plus the highest target rate.
+        let max_bits = 0.1 * 1_000_000.0 * (burst_ratio + seconds as f64);
+        assert!(
+            (bits_sent as f64) <= max_bits,
+            "sent {bits_sent} bits, more than {max_bits}"
+        );
+    }
+
+    #[test]
+    fn test_throttles_follow_config_and_pressure() {
+        let mut tuning_params = tuning_params_struct::KitsuneP2pTuningParams::default();
+        tuning_params.gossip_inbound_target_mbps = 10.0;
+        tuning_params.gossip_outbound_target_mbps = 10.0;
+        tuning_params.gossip_historic_inbound_target_mbps = 20.0;
+        tuning_params.gossip_historic_outbound_target_mbps = 20.0;
+        let throttles = BandwidthThrottles::new(&Arc::new(tuning_params));
+        let night = GossipBandwidth {
+            recent_inbound_mbps: 10.0,
+            recent_outbound_mbps: 10.0,
+            historic_inbound_mbps: 200.0,
+            historic_outbound_mbps: 200.0,
+        };
+        throttles.state.lock().config = GossipThrottleConfig {
+            schedule: vec![crate::GossipScheduleWindow {
+                start_hour_utc: 22,
+                end_hour_utc: 4,
+                bandwidth: night,
+            }],
+            ..Default::default()
+        };
+
+        throttles.refresh_at(12);
+        assert_eq!(throttles.historical().target_mbps(), (20.0, 20.0));
+        throttles.refresh_at(1);
+        assert_eq!(throttles.historical().target_mbps(), (200.0, 200.0));
+
+        throttles.state.lock().host_pressure = 0.5;
+        throttles.refresh_at(1);
+        assert_eq!(throttles.recent().target_mbps(), (5.0, 5.0));
+        assert_eq!(throttles.historical().target_mbps(), (100.0, 100.0));
+
+        // Gossip never stops entirely.
+        throttles.state.lock().host_pressure = 1.0;
+        throttles.refresh_at(12);
+        assert_eq!(throttles.recent().target_mbps(), (1.0, 1.0));
+
+        throttles.state.lock().config.ignore_host_pressure = true;
+        throttles.refresh_at(12);
+        assert_eq!(throttles.status().bandwidth.recent_inbound_mbps, 10.0);
+
+        // Updated tuning params apply outside of the schedule.
+        let mut tuning_params = tuning_params_struct::KitsuneP2pTuningParams::default();
+        tuning_params.gossip_inbound_target_mbps = 30.0;
+        tuning_params.gossip_outbound_target_mbps = 30.0;
+        throttles.set_default(&Arc::new(tuning_params));
+        throttles.refresh_at(12);
+        assert_eq!(throttles.recent().target_mbps(), (30.0, 30.0));
+
+        // Pressure is rounded, so small changes don't rebuild the limiters.
+        throttles.set_host_pressure(0.52);
+        assert_eq!(throttles.status().host_pressure, 0.5);
+        throttles.set_host_pressure(0.48);
+        assert_eq!(throttles.status().host_pressure, 0.5);
+    }
 }
@@ -85,7 +85,7 @@ impl ShardedGossipLocal {
         }
 
         let remote_nodes = remote_nodes.into_values().collect();
-        let tuning_params = self.tuning_params.clone();
+        let tuning_params = self.tuning_params.get();
         // We could clone the metrics store out of the lock here but I don't think
         // the next_remote_node will be that slow so we can just choose the next node inline.
         self.inner.share_mut(|i, _| {
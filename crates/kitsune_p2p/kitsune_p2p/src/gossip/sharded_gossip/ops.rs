@@ -156,7 +156,7 @@ impl ShardedGossipLocal {
         let (to_fetch, finished) = state.ops_batch_queue.share_mut(|queues, _| {
             let items = get_region_queue_batch(
                 &mut queues.region_queue,
-                self.tuning_params.gossip_max_batch_size,
+                self.tuning_params.get().gossip_max_batch_size,
             );
             Ok((items, queues.region_queue.is_empty()))
         })?;
@@ -10,7 +10,7 @@ use kitsune_p2p_types::{
         spacetime::Topology,
     },
     dht_arc::DhtArcSet,
-    KOpData, KOpHash,
+    KOpData, KOpHash, Tx2Cert,
 };
 
 use crate::event::{GetAgentInfoSignedEvt, MetricRecord};
@@ -88,6 +88,53 @@ pub trait KitsuneHost: 'static + Send + Sync {
         )
         .into()
     }
+
+    /// Load the fetch queue items which were persisted for this space,
+    /// in queue order. Hosts which don't persist the fetch queue return nothing.
+    fn load_fetch_queue(
+        &self,
+        space: Arc<KitsuneSpace>,
+    ) -> KitsuneHostResult<Vec<kitsune_p2p_fetch::FetchQueueRecord>> {
+        let _space = space;
+        futures::FutureExt::boxed(async move { Ok(vec![]) }).into()
+    }
+
+    /// Persist fetch queue items, replacing any existing record with the same key.
+    fn put_fetch_queue_items(
+        &self,
+        space: Arc<KitsuneSpace>,
+        records: Vec<kitsune_p2p_fetch::FetchQueueRecord>,
+    ) -> KitsuneHostResult<()> {
+        let _space = space;
+        let _records = records;
+        futures::FutureExt::boxed(async move { Ok(()) }).into()
+    }
+
+    /// Remove persisted fetch queue items which are no longer needed.
+    fn remove_fetch_queue_items(
+        &self,
+        space: Arc<KitsuneSpace>,
+        keys: Vec<kitsune_p2p_fetch::FetchKey>,
+    ) -> KitsuneHostResult<()> {
+        let _space = space;
+        let _keys = keys;
+        futures::FutureExt::boxed(async move { Ok(()) }).into()
+    }
+
+    /// How heavily loaded the host is, from 0.0 (idle) to 1.0 (overloaded).
+    /// Gossip bandwidth is reduced as the pressure rises.
+    fn gossip_pressure(&self) -> KitsuneHostResult<f64> {
+        futures::FutureExt::boxed(async move { Ok(0.0) }).into()
+    }
+
+    /// Whether every message in this space from the node with this
+    /// certificate should be dropped, e.g. because the host has blocked
+    /// an agent running on that node.
+    fn is_blocked(&self, space: Arc<KitsuneSpace>, cert: Tx2Cert) -> KitsuneHostResult<bool> {
+        let _space = space;
+        let _cert = cert;
+        futures::FutureExt::boxed(async move { Ok(false) }).into()
+    }
 }
 
 /// Trait object for the host interface
@@ -268,6 +268,31 @@ impl Ord for CurrentRound {
     }
 }
 
+/// Running totals of the completed gossip rounds of one type.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RoundTotals {
+    /// Rounds which completed successfully
+    pub successes: u64,
+    /// Rounds which ended in an error
+    pub errors: u64,
+    /// Total time spent in rounds which completed successfully
+    pub success_duration: Duration,
+    /// Total time spent in rounds which ended in an error
+    pub error_duration: Duration,
+}
+
+impl RoundTotals {
+    fn record(&mut self, round: &CompletedRound) {
+        if round.error {
+            self.errors += 1;
+            self.error_duration += round.duration();
+        } else {
+            self.successes += 1;
+            self.success_duration += round.duration();
+        }
+    }
+}
+
 #[derive(Debug, Default)]
 /// Metrics tracking for remote nodes to help
 /// choose which remote node to initiate the next round with.
@@ -278,6 +303,10 @@ pub struct Metrics {
     /// Map of remote nodes and gossip history with each
     node_history: HashMap<NodeId, PeerNodeHistory>,
 
+    /// Totals over every gossip round completed in this space,
+    /// which unlike the node history are never truncated.
+    round_totals: HashMap<GossipModuleType, RoundTotals>,
+
     /// Aggregate Extrapolated Dht Coverage
     agg_extrap_cov: RunAvg,
 
@@ -573,7 +602,12 @@ impl Metrics {
         let history = self.node_history.entry(node.clone()).or_default();
         let r = history.current_round.take();
         if let Some(r) = r {
-            history.completed_rounds.push_back(r.completed(error))
+            let round = r.completed(error);
+            self.round_totals
+                .entry(round.gossip_type)
+                .or_default()
+                .record(&round);
+            history.completed_rounds.push_back(round)
         }
     }
 
@@ -682,6 +716,11 @@ impl Metrics {
     pub fn peer_node_histories(&self) -> &HashMap<NodeId, PeerNodeHistory> {
         &self.node_history
     }
+
+    /// Getter
+    pub fn round_totals(&self) -> &HashMap<GossipModuleType, RoundTotals> {
+        &self.round_totals
+    }
 }
 
 impl PeerAgentHistory {
@@ -877,4 +916,30 @@ mod tests {
         a5.push_n(1, 255);
         assert_eq!(1.0, *a5);
     }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_round_totals() {
+        let mut metrics = Metrics::default();
+        let node = NodeId::from(vec![1; 32]);
+        let round = RoundState::new(
+            Vec::new(),
+            Arc::new(kitsune_p2p_types::dht_arc::DhtArcSet::new_empty()),
+            None,
+            Duration::from_secs(60),
+        );
+
+        for error in [false, false, true] {
+            metrics.update_current_round(&node, GossipModuleType::ShardedRecent, &round);
+            metrics.complete_current_round(&node, error);
+        }
+        // Completing a round which was never started isn't counted.
+        metrics.complete_current_round(&node, false);
+
+        let totals = metrics.round_totals()[&GossipModuleType::ShardedRecent];
+        assert_eq!(totals.successes, 2);
+        assert_eq!(totals.errors, 1);
+        assert!(!metrics
+            .round_totals()
+            .contains_key(&GossipModuleType::ShardedHistorical));
+    }
 }
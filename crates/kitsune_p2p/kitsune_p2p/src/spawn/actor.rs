@@ -15,6 +15,7 @@ use kitsune_p2p_fetch::*;
 use kitsune_p2p_proxy::tx2::*;
 use kitsune_p2p_transport_quic::tx2::*;
 use kitsune_p2p_types::async_lazy::AsyncLazy;
+use kitsune_p2p_types::config::{KitsuneP2pTuningParams, LiveTuningParams, TuningParamsPatch};
 use kitsune_p2p_types::tx2::tx2_api::*;
 use kitsune_p2p_types::tx2::tx2_pool_promote::*;
 use kitsune_p2p_types::tx2::tx2_restart_adapter::*;
@@ -29,6 +30,7 @@ use std::sync::Arc;
 /// See <https://github.com/holochain/bootstrap>
 mod bootstrap;
 mod discover;
+mod fetch_store;
 mod space;
 use ghost_actor::dependencies::tracing;
 use space::*;
@@ -97,6 +99,10 @@ ghost_actor::ghost_chan! {
     }
 }
 
+/// How often the gossip throttles are refreshed from the schedule
+/// and the host's reported pressure.
+const GOSSIP_THROTTLE_REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(10);
+
 pub(crate) struct KitsuneP2pActor {
     channel_factory: ghost_actor::actor_builder::GhostActorChannelFactory<Self>,
     internal_sender: ghost_actor::GhostSender<Internal>,
@@ -112,9 +118,11 @@ pub(crate) struct KitsuneP2pActor {
         )>,
     >,
     config: Arc<KitsuneP2pConfig>,
+    tuning_params: LiveTuningParams,
     bandwidth_throttles: BandwidthThrottles,
     parallel_notify_permit: Arc<tokio::sync::Semaphore>,
     fetch_queue: FetchQueue,
+    mdns: Option<kitsune_p2p_mdns::MdnsDiscovery>,
 }
 
 impl KitsuneP2pActor {
@@ -130,6 +138,10 @@ impl KitsuneP2pActor {
 
         let tx2_conf = config.to_tx2().map_err(KitsuneP2pError::other)?;
 
+        // The tuning params shared by every space, which can be updated
+        // while we're running.
+        let tuning_params = LiveTuningParams::new(config.tuning_params.clone());
+
         let mut is_mock = false;
 
         // set up our backend based on config
@@ -166,7 +178,7 @@ impl KitsuneP2pActor {
         let f = tx2_restart_adapter(f);
 
         // convert to frontend
-        let f = tx2_pool_promote(f, config.tuning_params.clone());
+        let f = tx2_pool_promote_live(f, tuning_params.clone());
 
         // wrap in proxy
         let f = if !is_mock {
@@ -242,7 +254,7 @@ impl KitsuneP2pActor {
         // capture endpoint handle
         let ep_hnd = ep.handle().clone();
 
-        struct FetchResponseConfig(kitsune_p2p_types::config::KitsuneP2pTuningParams);
+        struct FetchResponseConfig(LiveTuningParams);
 
         impl kitsune_p2p_fetch::FetchResponseConfig for FetchResponseConfig {
             type User = (
@@ -258,7 +270,7 @@ impl KitsuneP2pActor {
                 completion_guard: kitsune_p2p_fetch::FetchResponseGuard,
                 op: KOpData,
             ) {
-                let timeout = self.0.implicit_timeout();
+                let timeout = self.0.get().implicit_timeout();
                 tokio::task::spawn(async move {
                     let _completion_guard = completion_guard;
 
@@ -279,12 +291,35 @@ impl KitsuneP2pActor {
             }
         }
 
-        let fetch_response_queue = kitsune_p2p_fetch::FetchResponseQueue::new(FetchResponseConfig(
-            config.tuning_params.clone(),
-        ));
+        let fetch_response_queue =
+            kitsune_p2p_fetch::FetchResponseQueue::new(FetchResponseConfig(tuning_params.clone()));
+
+        // One mdns socket is shared by every space this node joins.
+        let mdns = match config.network_type {
+            NetworkType::QuicMdns => {
+                Some(kitsune_p2p_mdns::MdnsDiscovery::new().map_err(KitsuneP2pError::other)?)
+            }
+            NetworkType::QuicBootstrap => None,
+        };
+
+        struct FetchConfig(LiveTuningParams);
+
+        impl FetchQueueConfig for FetchConfig {
+            fn item_retry_delay(&self) -> std::time::Duration {
+                self.0.get().fetch_item_retry_delay()
+            }
+
+            fn source_retry_delay(&self) -> std::time::Duration {
+                self.0.get().fetch_source_retry_delay()
+            }
+
+            fn merge_fetch_contexts(&self, a: u32, b: u32) -> u32 {
+                a | b
+            }
+        }
 
-        // TODO - use a real config
-        let fetch_queue = FetchQueue::new_bitwise_or();
+        let fetch_queue = FetchQueue::new(Arc::new(FetchConfig(tuning_params.clone())))
+            .with_store(fetch_store::HostFetchQueueStore::new(host.clone()));
 
         // Start a loop to handle our fetch queue fetch items.
         {
@@ -322,12 +357,13 @@ impl KitsuneP2pActor {
         tokio::task::spawn({
             let evt_sender = evt_sender.clone();
             let host = host.clone();
-            let tuning_params = config.tuning_params.clone();
+            let tuning_params = tuning_params.clone();
             let fetch_queue = fetch_queue.clone();
             async move {
                 let fetch_response_queue = &fetch_response_queue;
                 let fetch_queue = &fetch_queue;
-                ep.for_each_concurrent(tuning_params.concurrent_limit_per_thread, move |event| {
+                let concurrent_limit = tuning_params.get().concurrent_limit_per_thread;
+                ep.for_each_concurrent(concurrent_limit, move |event| {
                     let evt_sender = evt_sender.clone();
                     let host = host.clone();
                     let tuning_params = tuning_params.clone();
@@ -337,7 +373,8 @@ impl KitsuneP2pActor {
                             ($r:expr, $e:expr) => {
                                 // this can only error as channel closed
                                 // it would be noise to output tracing errors
-                                let _ = $r.respond($e, tuning_params.implicit_timeout()).await;
+                                let _ =
+                                    $r.respond($e, tuning_params.get().implicit_timeout()).await;
                             };
                         }
 
@@ -354,7 +391,14 @@ impl KitsuneP2pActor {
                             ConnectionClosed(Tx2EpConnectionClosed { url, .. }) => {
                                 let _ = i_s.del_con(url).await;
                             }
-                            IncomingRequest(Tx2EpIncomingRequest { data, respond, .. }) => {
+                            IncomingRequest(Tx2EpIncomingRequest {
+                                con, data, respond, ..
+                            }) => {
+                                if is_blocked(&host, con.peer_cert(), &data).await {
+                                    let fail = wire::Wire::failure("blocked".into());
+                                    resp!(respond, fail);
+                                    return;
+                                }
                                 match data {
                                     wire::Wire::Call(wire::Call {
                                         space,
@@ -417,6 +461,9 @@ impl KitsuneP2pActor {
                                 }
                             }
                             IncomingNotify(Tx2EpIncomingNotify { con, data, url, .. }) => {
+                                if is_blocked(&host, con.peer_cert(), &data).await {
+                                    return;
+                                }
                                 match data {
                                     wire::Wire::DelegateBroadcast(wire::DelegateBroadcast {
                                         space,
@@ -682,6 +729,31 @@ impl KitsuneP2pActor {
         });
 
         let bandwidth_throttles = BandwidthThrottles::new(&config.tuning_params);
+        config
+            .gossip_throttle
+            .check()
+            .map_err(KitsuneP2pError::other)?;
+        bandwidth_throttles.set_config(config.gossip_throttle.clone());
+
+        // Keep the throttles in step with the schedule and the host's load.
+        {
+            let bandwidth_throttles = bandwidth_throttles.clone();
+            let i_s = internal_sender.clone();
+            let host = host.clone();
+            tokio::task::spawn(async move {
+                use ghost_actor::GhostControlSender;
+                while i_s.ghost_actor_is_active() {
+                    match host.gossip_pressure().await {
+                        Ok(pressure) => bandwidth_throttles.set_host_pressure(pressure),
+                        Err(err) => {
+                            tracing::warn!(?err, "failed to get gossip pressure from host");
+                            bandwidth_throttles.refresh();
+                        }
+                    }
+                    tokio::time::sleep(GOSSIP_THROTTLE_REFRESH_INTERVAL).await;
+                }
+            });
+        }
         let parallel_notify_permit = Arc::new(tokio::sync::Semaphore::new(
             config.tuning_params.concurrent_limit_per_thread,
         ));
@@ -694,13 +766,26 @@ impl KitsuneP2pActor {
             host,
             spaces: HashMap::new(),
             config: Arc::new(config),
+            tuning_params,
             bandwidth_throttles,
             parallel_notify_permit,
             fetch_queue,
+            mdns,
         })
     }
 }
 
+/// Whether the host has blocked the node with this certificate in any of
+/// the spaces this message is about.
+async fn is_blocked(host: &HostApi, cert: Tx2Cert, data: &wire::Wire) -> bool {
+    for space in data.spaces() {
+        if let Ok(true) = host.is_blocked(space, cert.clone()).await {
+            return true;
+        }
+    }
+    false
+}
+
 use ghost_actor::dependencies::must_future::MustBoxFuture;
 impl ghost_actor::GhostControlHandler for KitsuneP2pActor {
     fn handle_ghost_actor_shutdown(mut self) -> MustBoxFuture<'static, ()> {
@@ -1016,21 +1101,33 @@ impl KitsuneP2pHandler for KitsuneP2pActor {
         let ep_hnd = self.ep_hnd.clone();
         let host = self.host.clone();
         let config = Arc::clone(&self.config);
+        // Each space starts from the network's current tuning params,
+        // and can then be tuned on its own.
+        let tuning_params = LiveTuningParams::new(self.tuning_params.get());
         let bandwidth_throttles = self.bandwidth_throttles.clone();
         let parallel_notify_permit = self.parallel_notify_permit.clone();
         let fetch_queue = self.fetch_queue.clone();
+        let mdns = self.mdns.clone();
 
         let space_sender = match self.spaces.entry(space.clone()) {
             Entry::Occupied(entry) => entry.into_mut(),
             Entry::Vacant(entry) => entry.insert(AsyncLazy::new(async move {
+                // Pick up where we left off with any ops we were still missing
+                // for this space. Ops which arrived in the meantime are pruned
+                // by the fetch loop before they are requested again.
+                if let Err(err) = fetch_queue.restore(space2.clone()).await {
+                    tracing::warn!(?err, "failed to restore the fetch queue");
+                }
                 let (send, send_inner, evt_recv) = spawn_space(
                     space2,
                     ep_hnd,
                     host,
                     config,
+                    tuning_params,
                     bandwidth_throttles,
                     parallel_notify_permit,
                     fetch_queue,
+                    mdns,
                 )
                 .await
                 .expect("cannot fail to create space");
@@ -1214,6 +1311,20 @@ impl KitsuneP2pHandler for KitsuneP2pActor {
         Ok(results)
     }
 
+    fn handle_get_gossip_throttle(&mut self) -> KitsuneP2pHandlerResult<GossipThrottleStatus> {
+        let status = self.bandwidth_throttles.status();
+        Ok(async move { Ok(status) }.boxed().into())
+    }
+
+    fn handle_set_gossip_throttle(
+        &mut self,
+        config: GossipThrottleConfig,
+    ) -> KitsuneP2pHandlerResult<()> {
+        config.check().map_err(KitsuneP2pError::other)?;
+        self.bandwidth_throttles.set_config(config);
+        unit_ok_fut()
+    }
+
     fn handle_get_diagnostics(
         &mut self,
         space: KSpace,
@@ -1230,6 +1341,62 @@ impl KitsuneP2pHandler for KitsuneP2pActor {
         .boxed()
         .into())
     }
+
+    fn handle_get_tuning_params(
+        &mut self,
+        space: KSpace,
+    ) -> KitsuneP2pHandlerResult<KitsuneP2pTuningParams> {
+        let space_sender = match self.spaces.get_mut(&space) {
+            None => return Err(KitsuneP2pError::RoutingSpaceError(space)),
+            Some(space) => space.get(),
+        };
+        Ok(async move {
+            let (space_sender, _) = space_sender.await;
+            space_sender.get_tuning_params(space).await
+        }
+        .boxed()
+        .into())
+    }
+
+    fn handle_update_tuning_params(
+        &mut self,
+        space: Option<KSpace>,
+        patch: TuningParamsPatch,
+    ) -> KitsuneP2pHandlerResult<()> {
+        // A patch for a single space is checked by that space,
+        // while a patch for every space applies network wide params too.
+        let single_space = space.is_some();
+        let space_senders = match space {
+            Some(space) => match self.spaces.get_mut(&space) {
+                None => return Err(KitsuneP2pError::RoutingSpaceError(space)),
+                Some(space_sender) => vec![(space, space_sender.get())],
+            },
+            None => {
+                // Patch our own params first, so the patch is validated
+                // before any space sees it.
+                self.tuning_params
+                    .patch(&patch)
+                    .map_err(KitsuneP2pError::other)?;
+                self.bandwidth_throttles
+                    .set_default(&self.tuning_params.get());
+                self.spaces
+                    .iter()
+                    .map(|(space, space_sender)| (space.clone(), space_sender.get()))
+                    .collect()
+            }
+        };
+        Ok(async move {
+            for (space, space_sender) in space_senders {
+                let (space_sender, _) = space_sender.await;
+                space_sender
+                    .update_tuning_params(single_space.then_some(space), patch.clone())
+                    .await?;
+            }
+            Ok(())
+        }
+        .boxed()
+        .into())
+    }
 }
 
 #[cfg(any(test, feature = "test_utils"))]
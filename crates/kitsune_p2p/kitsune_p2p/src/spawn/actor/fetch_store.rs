@@ -0,0 +1,104 @@
+use super::*;
+use crate::HostApi;
+
+enum Write {
+    Put(FetchQueueRecord),
+    Remove(KSpace, FetchKey),
+}
+
+/// Backs the fetch queue with the host's storage.
+///
+/// Writes are handed to a single task which applies them in order. Any writes
+/// which pile up while the host is busy are coalesced, so that only the latest
+/// state of each item is written, in one batch per space.
+pub(crate) struct HostFetchQueueStore {
+    host: HostApi,
+    writes: tokio::sync::mpsc::UnboundedSender<Write>,
+}
+
+impl HostFetchQueueStore {
+    pub(crate) fn new(host: HostApi) -> Arc<Self> {
+        let (writes, mut recv) = tokio::sync::mpsc::unbounded_channel();
+        tokio::task::spawn({
+            let host = host.clone();
+            async move {
+                while let Some(write) = recv.recv().await {
+                    let mut pending = PendingWrites::default();
+                    pending.add(write);
+                    while let Ok(write) = recv.try_recv() {
+                        pending.add(write);
+                    }
+                    pending.flush(&host).await;
+                }
+            }
+        });
+        Arc::new(Self { host, writes })
+    }
+}
+
+impl FetchQueueStore for HostFetchQueueStore {
+    fn load(&self, space: KSpace) -> FetchQueueStoreResult<Vec<FetchQueueRecord>> {
+        let host = self.host.clone();
+        async move { host.load_fetch_queue(space).await }
+            .boxed()
+            .into()
+    }
+
+    fn put(&self, records: Vec<FetchQueueRecord>) {
+        for record in records {
+            // can only fail if the writer task has stopped, i.e. at shutdown
+            let _ = self.writes.send(Write::Put(record));
+        }
+    }
+
+    fn remove(&self, space: KSpace, keys: Vec<FetchKey>) {
+        for key in keys {
+            let _ = self.writes.send(Write::Remove(space.clone(), key));
+        }
+    }
+}
+
+/// The latest state of every item written to since the last flush,
+/// where `None` means the item was removed.
+#[derive(Default)]
+struct PendingWrites {
+    order: Vec<(KSpace, FetchKey)>,
+    latest: HashMap<(KSpace, FetchKey), Option<FetchQueueRecord>>,
+}
+
+impl PendingWrites {
+    fn add(&mut self, write: Write) {
+        let (id, record) = match write {
+            Write::Put(record) => ((record.space.clone(), record.key.clone()), Some(record)),
+            Write::Remove(space, key) => ((space, key), None),
+        };
+        if self.latest.insert(id.clone(), record).is_none() {
+            self.order.push(id);
+        }
+    }
+
+    async fn flush(mut self, host: &HostApi) {
+        let mut by_space: HashMap<KSpace, (Vec<FetchQueueRecord>, Vec<FetchKey>)> = HashMap::new();
+        for id in self.order {
+            let latest = self.latest.remove(&id).flatten();
+            let (space, key) = id;
+            let (puts, removes) = by_space.entry(space).or_default();
+            match latest {
+                Some(record) => puts.push(record),
+                None => removes.push(key),
+            }
+        }
+        for (space, (puts, removes)) in by_space {
+            if !removes.is_empty() {
+                if let Err(err) = host.remove_fetch_queue_items(space.clone(), removes).await {
+                    tracing::warn!(?err, "failed to remove persisted fetch queue items");
+                }
+            }
+            if !puts.is_empty() {
+                if let Err(err) = host.put_fetch_queue_items(space, puts).await {
+                    tracing::warn!(?err, "failed to persist fetch queue items");
+                }
+            }
+        }
+    }
+}
@@ -9,7 +9,6 @@ use kitsune_p2p_types::codec::{rmp_decode, rmp_encode};
 use kitsune_p2p_types::dht_arc::{DhtArc, DhtArcRange, DhtArcSet};
 use kitsune_p2p_types::tx2::tx2_utils::TxUrl;
 use std::collections::{HashMap, HashSet};
-use std::sync::atomic::AtomicBool;
 use url2::Url2;
 
 /// How often to record historical metrics
@@ -107,9 +106,11 @@ pub(crate) async fn spawn_space(
     ep_hnd: Tx2EpHnd<wire::Wire>,
     host: HostApi,
     config: Arc<KitsuneP2pConfig>,
+    tuning_params: LiveTuningParams,
     bandwidth_throttles: BandwidthThrottles,
     parallel_notify_permit: Arc<tokio::sync::Semaphore>,
     fetch_queue: FetchQueue,
+    mdns: Option<MdnsDiscovery>,
 ) -> KitsuneP2pResult<(
     ghost_actor::GhostSender<KitsuneP2p>,
     ghost_actor::GhostSender<SpaceInternal>,
@@ -136,9 +137,11 @@ pub(crate) async fn spawn_space(
         host,
         ep_hnd,
         config,
+        tuning_params,
         bandwidth_throttles,
         parallel_notify_permit,
         fetch_queue,
+        mdns,
     )));
 
     Ok((sender, i_s, evt_recv))
@@ -172,7 +175,7 @@ impl SpaceInternalHandler for Space {
 
     fn handle_update_agent_info(&mut self) -> SpaceInternalHandlerResult<()> {
         let space = self.space.clone();
-        let mut mdns_handles = self.mdns_handles.clone();
+        let mdns = self.mdns.clone();
         let network_type = self.config.network_type.clone();
         let mut agent_list = Vec::with_capacity(self.local_joined_agents.len());
         for agent in self.local_joined_agents.iter().cloned() {
@@ -182,12 +185,10 @@ impl SpaceInternalHandler for Space {
         let ep_hnd = self.ro_inner.ep_hnd.clone();
         let evt_sender = self.evt_sender.clone();
         let bootstrap_service = self.config.bootstrap_service.clone();
-        let expires_after = self.config.tuning_params.agent_info_expires_after_ms as u64;
-        let dynamic_arcs = self.config.tuning_params.gossip_dynamic_arcs;
-        let single_storage_arc_per_space = self
-            .config
-            .tuning_params
-            .gossip_single_storage_arc_per_space;
+        let tuning_params = self.tuning_params.get();
+        let expires_after = tuning_params.agent_info_expires_after_ms as u64;
+        let dynamic_arcs = tuning_params.gossip_dynamic_arcs;
+        let single_storage_arc_per_space = tuning_params.gossip_single_storage_arc_per_space;
         let internal_sender = self.i_s.clone();
         Ok(async move {
             let urls = vec![ep_hnd.local_addr()?];
@@ -202,7 +203,7 @@ impl SpaceInternalHandler for Space {
                     evt_sender: &evt_sender,
                     internal_sender: &internal_sender,
                     network_type: network_type.clone(),
-                    mdns_handles: &mut mdns_handles,
+                    mdns: &mdns,
                     bootstrap_service: &bootstrap_service,
                     dynamic_arcs,
                     single_storage_arc_per_space,
@@ -226,18 +227,16 @@ impl SpaceInternalHandler for Space {
         agent: Arc<KitsuneAgent>,
     ) -> SpaceInternalHandlerResult<()> {
         let space = self.space.clone();
-        let mut mdns_handles = self.mdns_handles.clone();
+        let mdns = self.mdns.clone();
         let network_type = self.config.network_type.clone();
         let ep_hnd = self.ro_inner.ep_hnd.clone();
         let evt_sender = self.evt_sender.clone();
         let internal_sender = self.i_s.clone();
         let bootstrap_service = self.config.bootstrap_service.clone();
-        let expires_after = self.config.tuning_params.agent_info_expires_after_ms as u64;
-        let dynamic_arcs = self.config.tuning_params.gossip_dynamic_arcs;
-        let single_storage_arc_per_space = self
-            .config
-            .tuning_params
-            .gossip_single_storage_arc_per_space;
+        let tuning_params = self.tuning_params.get();
+        let expires_after = tuning_params.agent_info_expires_after_ms as u64;
+        let dynamic_arcs = tuning_params.gossip_dynamic_arcs;
+        let single_storage_arc_per_space = tuning_params.gossip_single_storage_arc_per_space;
         let arc = self.get_agent_arc(&agent);
 
         Ok(async move {
@@ -251,7 +250,7 @@ impl SpaceInternalHandler for Space {
                 evt_sender: &evt_sender,
                 internal_sender: &internal_sender,
                 network_type: network_type.clone(),
-                mdns_handles: &mut mdns_handles,
+                mdns: &mdns,
                 bootstrap_service: &bootstrap_service,
                 dynamic_arcs,
                 single_storage_arc_per_space,
@@ -273,7 +272,7 @@ impl SpaceInternalHandler for Space {
         &mut self,
         input: PutAgentInfoSignedEvt,
     ) -> SpaceInternalHandlerResult<()> {
-        let timeout = self.config.tuning_params.implicit_timeout();
+        let timeout = self.tuning_params.get().implicit_timeout();
         let tasks: Vec<_> = input
             .peer_data
             .into_iter()
@@ -373,7 +372,7 @@ impl SpaceInternalHandler for Space {
         // next, gather a list of agents covering this data to be
         // published to.
         let ro_inner = self.ro_inner.clone();
-        let timeout = ro_inner.config.tuning_params.implicit_timeout();
+        let timeout = ro_inner.tuning_params.get().implicit_timeout();
         let fut =
             discover::get_cached_remotes_near_basis(ro_inner.clone(), basis.get_loc(), timeout);
 
@@ -514,7 +513,7 @@ impl SpaceInternalHandler for Space {
 
     fn handle_notify(&mut self, to_agent: KAgent, data: wire::Wire) -> InternalHandlerResult<()> {
         let ro_inner = self.ro_inner.clone();
-        let timeout = ro_inner.config.tuning_params.implicit_timeout();
+        let timeout = ro_inner.tuning_params.get().implicit_timeout();
 
         Ok(async move {
             match discover::search_and_discover_peer_connect(
@@ -604,7 +603,7 @@ struct UpdateAgentInfoInput<'borrow> {
     evt_sender: &'borrow futures::channel::mpsc::Sender<KitsuneP2pEvent>,
     internal_sender: &'borrow ghost_actor::GhostSender<SpaceInternal>,
     network_type: NetworkType,
-    mdns_handles: &'borrow mut HashMap<Vec<u8>, Arc<AtomicBool>>,
+    mdns: &'borrow Option<MdnsDiscovery>,
     bootstrap_service: &'borrow Option<Url2>,
     dynamic_arcs: bool,
     single_storage_arc_per_space: bool,
@@ -642,7 +641,7 @@ async fn update_single_agent_info(
         evt_sender,
         internal_sender,
         network_type,
-        mdns_handles,
+        mdns,
         bootstrap_service,
         dynamic_arcs,
         single_storage_arc_per_space,
@@ -692,24 +691,11 @@ async fn update_single_agent_info(
     match network_type {
         NetworkType::QuicMdns => {
             // Broadcast only valid AgentInfo
-            if !urls.is_empty() {
-                // Kill previous broadcast for this space + agent
-                let key = [space.get_bytes(), agent.get_bytes()].concat();
-                if let Some(current_handle) = mdns_handles.get(&key) {
-                    mdns_kill_thread(current_handle.to_owned());
-                }
-                // Broadcast by using Space as service type and Agent as service name
-                let space_b64 = base64::encode_config(&space[..], base64::URL_SAFE_NO_PAD);
-                let agent_b64 = base64::encode_config(&agent[..], base64::URL_SAFE_NO_PAD);
-                //println!("(MDNS) - Broadcasting of Agent {:?} ({}) in space {:?} ({} ; {})",
-                // agent, agent.get_bytes().len(), space, space.get_bytes().len(), space_b64.len());
-                // Broadcast rmp encoded agent_info_signed
-                let mut buffer = Vec::new();
-                rmp_encode(&mut buffer, &agent_info_signed)?;
-                tracing::trace!(?space_b64, ?agent_b64);
-                let handle = mdns_create_broadcast_thread(space_b64, agent_b64, &buffer);
-                // store handle in self
-                mdns_handles.insert(key, handle);
+            if let (Some(mdns), false) = (mdns, urls.is_empty()) {
+                // Publishing again replaces our previous record for this
+                // space + agent, which is how the info is kept fresh
+                // before it expires.
+                mdns_publish(mdns, &space, &agent, &agent_info_signed).await?;
             }
         }
         NetworkType::QuicBootstrap => {
@@ -723,6 +709,34 @@ async fn update_single_agent_info(
     Ok(agent_info_signed)
 }
 
+/// The mdns service type for a space.
+fn mdns_service_type(space: &KitsuneSpace) -> String {
+    base64::encode_config(&space[..], base64::URL_SAFE_NO_PAD)
+}
+
+/// The mdns service name for an agent.
+fn mdns_service_name(agent: &KitsuneAgent) -> String {
+    base64::encode_config(&agent[..], base64::URL_SAFE_NO_PAD)
+}
+
+/// Broadcast rmp encoded agent info, using the space as the service type
+/// and the agent as the service name.
+async fn mdns_publish(
+    mdns: &MdnsDiscovery,
+    space: &KitsuneSpace,
+    agent: &KitsuneAgent,
+    agent_info_signed: &AgentInfoSigned,
+) -> KitsuneP2pResult<()> {
+    let space_b64 = mdns_service_type(space);
+    let agent_b64 = mdns_service_name(agent);
+    let mut buffer = Vec::new();
+    rmp_encode(&mut buffer, agent_info_signed)?;
+    tracing::trace!(?space_b64, ?agent_b64);
+    mdns.publish(&space_b64, &agent_b64, &buffer)
+        .await
+        .map_err(KitsuneP2pError::other)
+}
+
 use ghost_actor::dependencies::must_future::MustBoxFuture;
 impl ghost_actor::GhostControlHandler for Space {
     fn handle_ghost_actor_shutdown(mut self) -> MustBoxFuture<'static, ()> {
@@ -738,6 +752,17 @@ impl ghost_actor::GhostControlHandler for Space {
             for module in self.gossip_mod.values_mut() {
                 module.close();
             }
+            if let Some(mdns) = &self.mdns {
+                for space_b64 in &self.mdns_listened_spaces {
+                    mdns.stop_listening(space_b64);
+                }
+                // The discovery is shared by every space, so what this space
+                // published stays up unless it is withdrawn here.
+                let space_b64 = mdns_service_type(&self.space);
+                for agent in &self.local_joined_agents {
+                    mdns.withdraw(&space_b64, &mdns_service_name(agent)).await;
+                }
+            }
         }
         .boxed()
         .into()
@@ -772,42 +797,64 @@ impl KitsuneP2pHandler for Space {
         match self.config.network_type {
             NetworkType::QuicMdns => {
                 // Listen to MDNS service that has that space as service type
-                let space_b64 = base64::encode_config(&space[..], base64::URL_SAFE_NO_PAD);
-                if !self.mdns_listened_spaces.contains(&space_b64) {
-                    self.mdns_listened_spaces.insert(space_b64.clone());
-                    tokio::task::spawn(async move {
-                        let stream = mdns_listen(space_b64);
-                        tokio::pin!(stream);
-                        while let Some(maybe_response) = stream.next().await {
-                            match maybe_response {
-                                Ok(response) => {
-                                    tracing::trace!(msg = "Peer found via MDNS", ?response);
-                                    // Decode response
-                                    let maybe_agent_info_signed =
-                                        rmp_decode(&mut &*response.buffer);
-                                    if let Err(e) = maybe_agent_info_signed {
-                                        tracing::error!(msg = "Failed to decode MDNS peer", ?e);
+                let space_b64 = mdns_service_type(&space);
+                if let Some(mdns) = self.mdns.clone() {
+                    if self.mdns_listened_spaces.insert(space_b64.clone()) {
+                        let i_s = self.i_s.clone();
+                        tokio::task::spawn(async move {
+                            let stream = mdns.listen(&space_b64).await;
+                            tokio::pin!(stream);
+                            while let Some(event) = stream.next().await {
+                                let response = match event {
+                                    MdnsEvent::Discovered(response) => response,
+                                    MdnsEvent::Withdrawn { service_name, .. } => {
+                                        // The peer's info is kept until it
+                                        // expires or is replaced by its signed
+                                        // leave info, since a withdrawal isn't
+                                        // signed by the agent.
+                                        tracing::trace!(
+                                            msg = "Peer withdrawn via MDNS",
+                                            ?service_name
+                                        );
                                         continue;
                                     }
-                                    if let Ok(remote_agent_info_signed) = maybe_agent_info_signed {
-                                        // Add to local storage
-                                        if let Err(e) = evt_sender
-                                            .put_agent_info_signed(PutAgentInfoSignedEvt {
-                                                space: space.clone(),
-                                                peer_data: vec![remote_agent_info_signed],
-                                            })
-                                            .await
-                                        {
-                                            tracing::error!(msg = "Failed to store MDNS peer", ?e);
+                                };
+                                tracing::trace!(msg = "Peer found via MDNS", ?response);
+                                let remote_agent_info_signed: AgentInfoSigned =
+                                    match rmp_decode(&mut &*response.buffer) {
+                                        Ok(info) => info,
+                                        Err(e) => {
+                                            tracing::error!(msg = "Failed to decode MDNS peer", ?e);
+                                            continue;
                                         }
-                                    }
+                                    };
+                                if remote_agent_info_signed.space != space {
+                                    continue;
                                 }
-                                Err(e) => {
-                                    tracing::error!(msg = "Failed to get peers from MDNS", ?e);
+                                // Our own agents show up too when the
+                                // multicast is looped back to us.
+                                match i_s
+                                    .is_agent_local(remote_agent_info_signed.agent.clone())
+                                    .await
+                                {
+                                    Ok(false) => (),
+                                    Ok(true) => continue,
+                                    // The space has shut down.
+                                    Err(_) => break,
+                                }
+                                // Add to local storage
+                                if let Err(e) = evt_sender
+                                    .put_agent_info_signed(PutAgentInfoSignedEvt {
+                                        space: space.clone(),
+                                        peer_data: vec![remote_agent_info_signed],
+                                    })
+                                    .await
+                                {
+                                    tracing::error!(msg = "Failed to store MDNS peer", ?e);
                                 }
                             }
-                        }
-                    });
+                        });
+                    }
                 }
             }
             NetworkType::QuicBootstrap => {
@@ -843,7 +890,7 @@ impl KitsuneP2pHandler for Space {
         let evt_sender = self.evt_sender.clone();
 
         let timeout_ms = match timeout_ms {
-            None | Some(0) => self.config.tuning_params.default_rpc_single_timeout_ms as u64,
+            None | Some(0) => self.tuning_params.get().default_rpc_single_timeout_ms as u64,
             _ => timeout_ms.unwrap(),
         };
         let timeout = KitsuneTimeout::from_millis(timeout_ms);
@@ -1213,6 +1260,21 @@ impl KitsuneP2pHandler for Space {
         .into())
     }
 
+    fn handle_get_gossip_throttle(&mut self) -> KitsuneP2pHandlerResult<GossipThrottleStatus> {
+        unreachable!(
+            "These requests are handled at the to actor level and are never propagated down to the space."
+        )
+    }
+
+    fn handle_set_gossip_throttle(
+        &mut self,
+        _config: GossipThrottleConfig,
+    ) -> KitsuneP2pHandlerResult<()> {
+        unreachable!(
+            "These requests are handled at the to actor level and are never propagated down to the space."
+        )
+    }
+
     fn handle_get_diagnostics(
         &mut self,
         _space: KSpace,
@@ -1223,6 +1285,38 @@ impl KitsuneP2pHandler for Space {
         };
         Ok(async move { Ok(diagnostics) }.boxed().into())
     }
+
+    fn handle_get_tuning_params(
+        &mut self,
+        _space: KSpace,
+    ) -> KitsuneP2pHandlerResult<KitsuneP2pTuningParams> {
+        let tuning_params = self.tuning_params.get();
+        Ok(async move { Ok(tuning_params) }.boxed().into())
+    }
+
+    fn handle_update_tuning_params(
+        &mut self,
+        space: Option<KSpace>,
+        patch: TuningParamsPatch,
+    ) -> KitsuneP2pHandlerResult<()> {
+        if space.is_some() {
+            use kitsune_p2p_types::config::tuning_params_struct::KitsuneP2pTuningParams;
+            if let Some(key) = patch
+                .keys()
+                .find(|key| KitsuneP2pTuningParams::NETWORK_WIDE.contains(&key.as_str()))
+            {
+                return Err(format!(
+                    "tuning param '{}' is shared by every space, so it can't be changed for a single space",
+                    key
+                )
+                .into());
+            }
+        }
+        self.tuning_params
+            .patch(&patch)
+            .map_err(KitsuneP2pError::other)?;
+        unit_ok_fut()
+    }
 }
 
 pub(crate) struct PendingDelegate {
@@ -1243,6 +1337,7 @@ pub(crate) struct SpaceReadOnlyInner {
     pub(crate) ep_hnd: Tx2EpHnd<wire::Wire>,
     #[allow(dead_code)]
     pub(crate) config: Arc<KitsuneP2pConfig>,
+    pub(crate) tuning_params: LiveTuningParams,
     pub(crate) parallel_notify_permit: Arc<tokio::sync::Semaphore>,
     pub(crate) metrics: MetricsSync,
     pub(crate) metric_exchange: MetricExchangeSync,
@@ -1261,13 +1356,8 @@ impl SpaceReadOnlyInner {
             let this = self.clone();
             let op_hash = op_hash.clone();
             tokio::task::spawn(async move {
-                tokio::time::sleep(
-                    this.config
-                        .tuning_params
-                        .implicit_timeout()
-                        .time_remaining(),
-                )
-                .await;
+                tokio::time::sleep(this.tuning_params.get().implicit_timeout().time_remaining())
+                    .await;
 
                 this.publish_pending_delegates.lock().remove(&op_hash);
             });
@@ -1309,7 +1399,8 @@ pub(crate) struct Space {
     pub(crate) local_joined_agents: HashSet<Arc<KitsuneAgent>>,
     pub(crate) agent_arcs: HashMap<Arc<KitsuneAgent>, DhtArc>,
     pub(crate) config: Arc<KitsuneP2pConfig>,
-    mdns_handles: HashMap<Vec<u8>, Arc<AtomicBool>>,
+    pub(crate) tuning_params: LiveTuningParams,
+    mdns: Option<MdnsDiscovery>,
     mdns_listened_spaces: HashSet<String>,
     gossip_mod: HashMap<GossipModuleType, GossipModule>,
 }
@@ -1324,9 +1415,11 @@ impl Space {
         host_api: HostApi,
         ep_hnd: Tx2EpHnd<wire::Wire>,
         config: Arc<KitsuneP2pConfig>,
+        tuning_params: LiveTuningParams,
         bandwidth_throttles: BandwidthThrottles,
         parallel_notify_permit: Arc<tokio::sync::Semaphore>,
         fetch_queue: FetchQueue,
+        mdns: Option<MdnsDiscovery>,
     ) -> Self {
         let metrics = MetricsSync::default();
 
@@ -1350,7 +1443,7 @@ impl Space {
 
         let metric_exchange = MetricExchangeSync::spawn(
             space.clone(),
-            config.tuning_params.clone(),
+            tuning_params.clone(),
             host_api.clone(),
             metrics.clone(),
         );
@@ -1389,7 +1482,7 @@ impl Space {
                 (
                     module,
                     factory.spawn_gossip_task(
-                        config.tuning_params.clone(),
+                        tuning_params.clone(),
                         space.clone(),
                         ep_hnd.clone(),
                         evt_sender.clone(),
@@ -1402,12 +1495,11 @@ impl Space {
             .collect();
 
         let i_s_c = i_s.clone();
-        let agent_info_update_interval_ms =
-            config.tuning_params.gossip_agent_info_update_interval_ms as u64;
+        let tuning_params_c = tuning_params.clone();
         tokio::task::spawn(async move {
             loop {
                 tokio::time::sleep(std::time::Duration::from_millis(
-                    agent_info_update_interval_ms,
+                    tuning_params_c.get().gossip_agent_info_update_interval_ms as u64,
                 ))
                 .await;
                 if let Err(e) = i_s_c.update_agent_info().await {
@@ -1491,6 +1583,7 @@ impl Space {
             host_api: host_api.clone(),
             ep_hnd,
             config: config.clone(),
+            tuning_params: tuning_params.clone(),
             parallel_notify_permit,
             metrics,
             metric_exchange,
@@ -1507,7 +1600,8 @@ impl Space {
             local_joined_agents: HashSet::new(),
             agent_arcs: HashMap::new(),
             config,
-            mdns_handles: HashMap::new(),
+            tuning_params,
+            mdns,
             mdns_listened_spaces: HashSet::new(),
             gossip_mod,
         }
@@ -1530,8 +1624,9 @@ impl Space {
         let network_type = self.config.network_type.clone();
         let evt_sender = self.evt_sender.clone();
         let bootstrap_service = self.config.bootstrap_service.clone();
-        let expires_after = self.config.tuning_params.agent_info_expires_after_ms as u64;
+        let expires_after = self.tuning_params.get().agent_info_expires_after_ms as u64;
         let host = self.host_api.clone();
+        let mdns = self.mdns.clone();
 
         Ok(async move {
             let signed_at_ms = crate::spawn::actor::bootstrap::now_once(None).await?;
@@ -1566,13 +1661,24 @@ impl Space {
             // TODO: at some point, we should not remove agents who have left, but rather
             // there should be a flag indicating they have left. The removed agent may just
             // get re-gossiped to another local agent in the same space, defeating the purpose.
-            host.remove_agent_info_signed(GetAgentInfoSignedEvt { space, agent })
-                .await
-                .map_err(KitsuneP2pError::other)?;
+            host.remove_agent_info_signed(GetAgentInfoSignedEvt {
+                space: space.clone(),
+                agent: agent.clone(),
+            })
+            .await
+            .map_err(KitsuneP2pError::other)?;
 
             // Push to the network as well
             match network_type {
-                NetworkType::QuicMdns => tracing::warn!("NOT publishing leaves to mdns"),
+                NetworkType::QuicMdns => {
+                    if let Some(mdns) = mdns {
+                        // Peers still listening pick up the leave info, then
+                        // the record is withdrawn so nobody caches it.
+                        mdns_publish(&mdns, &space, &agent, &agent_info_signed).await?;
+                        mdns.withdraw(&mdns_service_type(&space), &mdns_service_name(&agent))
+                            .await;
+                    }
+                }
                 NetworkType::QuicBootstrap => {
                     crate::spawn::actor::bootstrap::put(
                         bootstrap_service.clone(),
@@ -1590,11 +1696,7 @@ impl Space {
 
     /// Get the existing agent storage arc or create a new one.
     fn get_agent_arc(&self, agent: &Arc<KitsuneAgent>) -> DhtArc {
-        if self
-            .config
-            .tuning_params
-            .gossip_single_storage_arc_per_space
-        {
+        if self.tuning_params.get().gossip_single_storage_arc_per_space {
             let arc = self.agent_arcs.get(agent).cloned();
             match arc {
                 Some(arc) => arc,
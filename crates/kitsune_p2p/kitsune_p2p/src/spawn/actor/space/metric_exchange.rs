@@ -1,6 +1,6 @@
 use super::*;
 use crate::wire::MetricExchangeMsg;
-use kitsune_p2p_types::config::KitsuneP2pTuningParams;
+use kitsune_p2p_types::config::LiveTuningParams;
 use kitsune_p2p_types::dht_arc::DhtArcSet;
 use tokio::time::{Duration, Instant};
 
@@ -45,7 +45,7 @@ struct RemoteRef {
 
 pub(crate) struct MetricExchange {
     space: Arc<KitsuneSpace>,
-    tuning_params: KitsuneP2pTuningParams,
+    tuning_params: LiveTuningParams,
     shutdown: bool,
     extrap_cov: f32,
     #[allow(dead_code)]
@@ -57,7 +57,7 @@ pub(crate) struct MetricExchange {
 impl MetricExchange {
     pub fn spawn(
         space: Arc<KitsuneSpace>,
-        tuning_params: KitsuneP2pTuningParams,
+        tuning_params: LiveTuningParams,
         metrics: MetricsSync,
     ) -> Self {
         Self {
@@ -79,7 +79,7 @@ impl MetricExchange {
         for (_, r) in self.remote_refs.iter_mut() {
             if r.last_sync.should_trigger() {
                 let space = self.space.clone();
-                let timeout = self.tuning_params.implicit_timeout();
+                let timeout = self.tuning_params.get().implicit_timeout();
                 let con = r.con.clone();
                 let extrap_cov = self.extrap_cov;
                 tokio::task::spawn(async move {
@@ -154,7 +154,7 @@ impl std::ops::Deref for MetricExchangeSync {
 impl MetricExchangeSync {
     pub fn spawn(
         space: Arc<KitsuneSpace>,
-        tuning_params: KitsuneP2pTuningParams,
+        tuning_params: LiveTuningParams,
         host: HostApi,
         metrics: MetricsSync,
     ) -> Self {
@@ -1,6 +1,6 @@
 //! Definitions related to the KitsuneP2p peer-to-peer / dht communications actor.
 
-use kitsune_p2p_types::config::KitsuneP2pTuningParams;
+use kitsune_p2p_types::config::{KitsuneP2pTuningParams, TuningParamsPatch};
 use kitsune_p2p_types::KitsuneTimeout;
 use std::sync::Arc;
 use url2::Url2;
@@ -162,5 +162,20 @@ ghost_actor::ghost_chan! {
 
         /// Get data for diagnostics
         fn get_diagnostics(space: KSpace) -> KitsuneDiagnostics;
+
+        /// Get the current gossip bandwidth throttling.
+        fn get_gossip_throttle() -> crate::GossipThrottleStatus;
+
+        /// Replace the gossip bandwidth throttling configuration.
+        fn set_gossip_throttle(config: crate::GossipThrottleConfig) -> ();
+
+        /// Get the tuning params currently in effect for a space.
+        fn get_tuning_params(space: KSpace) -> KitsuneP2pTuningParams;
+
+        /// Change some tuning params while the network is running.
+        /// If no space is given, the change applies to every space, to spaces
+        /// joined later, and to params shared by every space such as those of
+        /// the connection pool and the fetch queue.
+        fn update_tuning_params(space: KSpaceOpt, patch: TuningParamsPatch) -> ();
     }
 }
@@ -73,7 +73,7 @@ pub trait AsGossipModuleFactory: 'static + Send + Sync {
     #[allow(clippy::too_many_arguments)]
     fn spawn_gossip_task(
         &self,
-        tuning_params: KitsuneP2pTuningParams,
+        tuning_params: LiveTuningParams,
         space: Arc<KitsuneSpace>,
         ep_hnd: Tx2EpHnd<wire::Wire>,
         evt_sender: futures::channel::mpsc::Sender<event::KitsuneP2pEvent>,
@@ -89,7 +89,7 @@ impl GossipModuleFactory {
     #[allow(clippy::too_many_arguments)]
     pub fn spawn_gossip_task(
         &self,
-        tuning_params: KitsuneP2pTuningParams,
+        tuning_params: LiveTuningParams,
         space: Arc<KitsuneSpace>,
         ep_hnd: Tx2EpHnd<wire::Wire>,
         evt_sender: futures::channel::mpsc::Sender<event::KitsuneP2pEvent>,
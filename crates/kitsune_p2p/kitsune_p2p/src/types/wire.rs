@@ -157,3 +157,29 @@ kitsune_p2p_types::write_codec_enum! {
         },
     }
 }
+
+impl Wire {
+    /// The spaces this message is about.
+    pub fn spaces(&self) -> Vec<Arc<KitsuneSpace>> {
+        match self {
+            Wire::Call(Call { space, .. })
+            | Wire::DelegateBroadcast(DelegateBroadcast { space, .. })
+            | Wire::Broadcast(Broadcast { space, .. })
+            | Wire::Gossip(Gossip { space, .. })
+            | Wire::PeerGet(PeerGet { space, .. })
+            | Wire::PeerQuery(PeerQuery { space, .. })
+            | Wire::MetricExchange(MetricExchange { space, .. }) => vec![space.clone()],
+            Wire::FetchOp(FetchOp { fetch_list }) => {
+                fetch_list.iter().map(|(space, _)| space.clone()).collect()
+            }
+            Wire::PushOpData(PushOpData { op_data_list }) => op_data_list
+                .iter()
+                .map(|(space, _)| space.clone())
+                .collect(),
+            Wire::Failure(_)
+            | Wire::CallResp(_)
+            | Wire::PeerGetResp(_)
+            | Wire::PeerQueryResp(_) => vec![],
+        }
+    }
+}
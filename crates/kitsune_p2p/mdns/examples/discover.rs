@@ -1,21 +1,15 @@
-use futures_util::{self, pin_mut, stream::StreamExt};
 use kitsune_p2p_mdns::*;
+use tokio_stream::StreamExt;
 
 #[tokio::main]
 async fn main() {
     println!("Starting discovery");
-    let service_name = "bobby".to_owned();
+    let service_type = "bobby".to_owned();
+    let mdns = MdnsDiscovery::new().unwrap();
     // Start Stream
-    let stream = mdns_listen(service_name);
-    pin_mut!(stream);
-    while let Some(maybe_response) = stream.next().await {
-        match maybe_response {
-            Ok(response) => {
-                println!("Discovered: {:?}", response);
-            }
-            Err(e) => {
-                println!("!!! Discovery Error: {:?}", e);
-            }
-        }
+    let stream = mdns.listen(&service_type).await;
+    tokio::pin!(stream);
+    while let Some(event) = stream.next().await {
+        println!("Discovered: {:?}", event);
     }
 }
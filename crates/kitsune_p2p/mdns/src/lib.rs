@@ -1,94 +1,71 @@
-///! Crate for discovering Holochain peers over MDNS
-///! Works by broadcasting a service named `HC_SERVICE_NAME`
-///! and adding base64 encoded data in a TXT record
-///!
-///! Uses libmdns crate for broadcasting
-///! Uses mdns crate for discovery
+//! Crate for discovering Holochain peers over MDNS
+//!
+//! An [`MdnsDiscovery`] publishes payloads as services on the local network
+//! and listens for the services published by others. Each service type gets
+//! its own service instance per published name, e.g. one instance per agent
+//! in a space.
+//!
+//! Payloads are split into chunks which each fit in a single small packet,
+//! so a payload isn't limited by the size of a TXT record or of a packet.
+//! Every chunk is sent as a PTR record pointing at a TXT record which holds
+//! the chunk's index, the number of chunks and the payload's version.
+//! Listeners reassemble the chunks and emit the payload once they have all of
+//! them. Withdrawing a payload sends the records again with a TTL of 0, which
+//! is the mDNS way to say goodbye.
+//!
+//! Multicast loopback is enabled, so several instances on the same host
+//! discover each other.
 use err_derive::Error;
-use mdns::RecordKind;
-use std::time::Duration;
-use tokio_stream::{Stream, StreamExt};
-
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::HashMap;
+use std::net::{Ipv4Addr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tokio::net::UdpSocket;
+use tokio::sync::mpsc;
+use tokio_stream::Stream;
 
 const HC_SERVICE_PROTOCOL: &str = "._udp";
-const BROADCAST_INTERVAL_SEC: u64 = 8;
-const QUERY_INTERVAL_SEC: u64 = 5;
-const MAX_TXT_SIZE: usize = 192;
+const MULTICAST_ADDR: Ipv4Addr = Ipv4Addr::new(224, 0, 0, 251);
+const MULTICAST_PORT: u16 = 5353;
+/// How often published payloads are announced without being queried.
+const BROADCAST_INTERVAL_SEC: u64 = 30;
+/// How often the service types being listened for are queried.
+const QUERY_INTERVAL_SEC: u64 = 30;
+/// How long the chunks of an incomplete payload are kept.
+const ASSEMBLY_TIMEOUT_SEC: u64 = 60;
+/// Maximum number of incomplete payloads kept at once.
+/// The oldest is dropped to make room for a new one.
+const MAX_ASSEMBLIES: usize = 256;
+/// TTL of published records.
+const RECORD_TTL_SEC: u32 = 120;
+/// Bytes of payload per chunk. Keeps each packet well under a typical MTU.
+const CHUNK_SIZE: usize = 720;
+/// Maximum length of a single string in a TXT record.
+const MAX_TXT_SIZE: usize = 250;
+/// Maximum number of chunks in a payload.
+const MAX_CHUNKS: usize = 64;
+/// Maximum length of a DNS label.
+const MAX_LABEL_SIZE: usize = 63;
+
+const TYPE_PTR: u16 = 12;
+const TYPE_TXT: u16 = 16;
+const CLASS_IN: u16 = 1;
+/// Set on records which are the only ones with their name.
+const CLASS_CACHE_FLUSH: u16 = 0x8000;
 
 #[derive(Debug, Error)]
 pub enum MdnsError {
-    #[error(display = "Regular Mdns error {}", _0)]
-    Mdns(#[error(source)] mdns::Error),
+    #[error(display = "Io error {}", _0)]
+    Io(#[error(source)] std::io::Error),
     #[error(display = "Base64 decoding error {}", _0)]
     Base64(#[error(source)] base64::DecodeError),
+    #[error(display = "Service type or name is too long: {}", _0)]
+    NameTooLong(String),
+    #[error(display = "Payload of {} bytes is too large to publish", _0)]
+    PayloadTooLarge(usize),
 }
 
-/// Stop thread created by `mdns_create_broadcast_thread()`
-pub fn mdns_kill_thread(can_run: ::std::sync::Arc<AtomicBool>) {
-    can_run.store(false, Ordering::Relaxed);
-}
-
-/// Create a thread that will broadcast a holochain service over mdns
-/// Returns Sender for sending thread termination command
-pub fn mdns_create_broadcast_thread(
-    service_type: String,
-    service_name: String,
-    buffer: &[u8],
-) -> ::std::sync::Arc<AtomicBool> {
-    let svc_type = format!("_{}{}", service_type, HC_SERVICE_PROTOCOL);
-    // Constraints in libmdns
-    assert!(
-        svc_type.len() < 63,
-        "len = {} ({}) ; {}",
-        svc_type.len(),
-        service_type.len(),
-        service_type
-    );
-    assert!(service_name.len() < 63);
-    // Create Termination command variable
-    let can_run = ::std::sync::Arc::new(AtomicBool::new(true));
-    let can_run_clone = can_run.clone();
-    // Change buffer to base64 string
-    let mut b64 = format!(
-        "u{}",
-        base64::encode_config(buffer, base64::URL_SAFE_NO_PAD)
-    );
-    //println!(
-    //    "Broadcasting service type '{}', named '{}' over mdns ({})",
-    //    svc_type,
-    //    service_name,
-    //    b64.len()
-    //);
-    // Create thread
-    let _handle = tokio::task::spawn(async move {
-        // Split buffer to fix TXT max size
-        let mut substrs = Vec::new();
-        while b64.len() > MAX_TXT_SIZE {
-            let start: String = b64.drain(..MAX_TXT_SIZE).collect();
-            substrs.push(start);
-        }
-        substrs.push(b64);
-        let txts: Vec<_> = substrs.iter().map(AsRef::as_ref).collect();
-        //println!("Entering mdns broadcasting thread...");
-        // Create mdns responder
-
-        let responder = libmdns::Responder::new().unwrap();
-        let _svc = responder.register(svc_type, service_name, 0, &txts);
-        // Loop forever unless termination command received
-        loop {
-            tokio::time::sleep(::std::time::Duration::from_secs(BROADCAST_INTERVAL_SEC)).await;
-            if !can_run_clone.load(Ordering::Relaxed) {
-                //println!("Terminating.");
-                break;
-            }
-        }
-    });
-    // Done
-    can_run
-}
-
-///
+/// A payload discovered on the network.
 #[derive(Debug, Clone)]
 pub struct MdnsResponse {
     /// Service type used
@@ -97,79 +74,629 @@ pub struct MdnsResponse {
     pub service_name: String,
     /// IP address that responded to the mdns query
     pub addr: std::net::IpAddr,
-    /// Data contained in the TXT record
+    /// The published payload
     pub buffer: Vec<u8>,
 }
 
-/// Queries the network for the holochain service.
-/// Returns an iterator over all responses received.
-#[allow(clippy::let_and_return)]
-pub fn mdns_listen(service_type: String) -> impl Stream<Item = Result<MdnsResponse, MdnsError>> {
-    //let service_name = format!("{}.local", HC_SERVICE_TYPE);
-    let svc_type = format!("_{}{}.local", service_type, HC_SERVICE_PROTOCOL);
-    //println!("MDNS query for service type '{}'", svc_type);
-    let query = mdns::discover::all(svc_type, Duration::from_secs(QUERY_INTERVAL_SEC))
-        .expect("mdns Discover failed");
-    // Get Mdns Response stream
-    let response_stream = query.listen();
-    // Change it into a MdnsResponse stream
-    let mdns_stream = response_stream
-        // Filtering out Empty responses
-        .filter(move |res| {
-            match res {
-                Ok(response) => !response.is_empty() && response.ip_addr().is_some(),
-                Err(_) => true, // Keep errors
+/// Something which happened to a service being listened for.
+#[derive(Debug, Clone)]
+pub enum MdnsEvent {
+    /// A complete payload was received.
+    Discovered(MdnsResponse),
+    /// A payload was withdrawn by its publisher.
+    Withdrawn {
+        /// Service type used
+        service_type: String,
+        /// Service name used
+        service_name: String,
+    },
+}
+
+/// Publishes payloads and listens for the payloads of others over mDNS.
+///
+/// All the services of a process can share one instance. Everything published
+/// is withdrawn when the last clone is dropped.
+#[derive(Clone)]
+pub struct MdnsDiscovery {
+    shared: Arc<Shared>,
+    _tasks: Arc<Tasks>,
+}
+
+struct Shared {
+    socket: UdpSocket,
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    /// Published chunk packets by service type then service name.
+    published: HashMap<String, HashMap<String, Vec<Vec<u8>>>>,
+    /// Listeners by service type.
+    listeners: HashMap<String, mpsc::UnboundedSender<MdnsEvent>>,
+    /// Payloads being reassembled.
+    assembling: HashMap<AssemblyKey, Assembly>,
+    /// The last version emitted for each service, so repeated
+    /// announcements of a payload are only emitted once.
+    emitted: HashMap<(String, String), u32>,
+}
+
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct AssemblyKey {
+    service_type: String,
+    service_name: String,
+    version: u32,
+}
+
+struct Assembly {
+    chunks: Vec<Option<Vec<u8>>>,
+    started: Instant,
+}
+
+/// Aborts the background tasks and withdraws everything when dropped.
+struct Tasks {
+    shared: Arc<Shared>,
+    handles: Vec<tokio::task::JoinHandle<()>>,
+}
+
+impl Drop for Tasks {
+    fn drop(&mut self) {
+        for handle in &self.handles {
+            handle.abort();
+        }
+        let published = std::mem::take(&mut self.shared.state.lock().unwrap().published);
+        for packets in published.into_values().flat_map(HashMap::into_values) {
+            for packet in packets {
+                // Best effort, peers forget the records when their TTL runs out anyway.
+                let _ = self
+                    .shared
+                    .socket
+                    .try_send_to(&goodbye(&packet), multicast_addr());
             }
+        }
+    }
+}
+
+impl MdnsDiscovery {
+    /// Bind to the mDNS port and start answering queries and
+    /// listening for responses. Must be called within a tokio runtime.
+    pub fn new() -> Result<Self, MdnsError> {
+        let shared = Arc::new(Shared {
+            socket: create_socket()?,
+            state: Mutex::new(State::default()),
+        });
+        let recv_task = tokio::task::spawn(recv_loop(shared.clone()));
+        let broadcast_task = tokio::task::spawn(broadcast_loop(shared.clone()));
+        Ok(Self {
+            shared: shared.clone(),
+            _tasks: Arc::new(Tasks {
+                shared,
+                handles: vec![recv_task, broadcast_task],
+            }),
         })
-        .map(|maybe_response| {
-            if let Err(e) = maybe_response {
-                return Err(MdnsError::Mdns(e));
+    }
+
+    /// Publish a payload under this service type and name, replacing
+    /// anything already published under them.
+    pub async fn publish(
+        &self,
+        service_type: &str,
+        service_name: &str,
+        buffer: &[u8],
+    ) -> Result<(), MdnsError> {
+        let packets = encode_chunks(service_type, service_name, buffer, rand::random())?;
+        self.shared
+            .state
+            .lock()
+            .unwrap()
+            .published
+            .entry(service_type.to_string())
+            .or_default()
+            .insert(service_name.to_string(), packets.clone());
+        send_all(&self.shared.socket, &packets).await;
+        Ok(())
+    }
+
+    /// Withdraw the payload published under this service type and name.
+    pub async fn withdraw(&self, service_type: &str, service_name: &str) {
+        let packets = {
+            let mut state = self.shared.state.lock().unwrap();
+            let packets = state
+                .published
+                .get_mut(service_type)
+                .and_then(|names| names.remove(service_name));
+            if state
+                .published
+                .get(service_type)
+                .map_or(false, HashMap::is_empty)
+            {
+                state.published.remove(service_type);
+            }
+            packets
+        };
+        if let Some(packets) = packets {
+            let goodbyes: Vec<_> = packets.iter().map(|p| goodbye(p)).collect();
+            send_all(&self.shared.socket, &goodbyes).await;
+        }
+    }
+
+    /// Listen for the payloads published under this service type.
+    /// Queries the network straight away, and again periodically.
+    /// A service type only has one listener, so listening again
+    /// replaces the previous stream.
+    pub async fn listen(&self, service_type: &str) -> impl Stream<Item = MdnsEvent> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        {
+            let mut state = self.shared.state.lock().unwrap();
+            state.listeners.insert(service_type.to_string(), tx);
+            state.emitted.retain(|(t, _), _| t != service_type);
+        }
+        send_all(&self.shared.socket, &[encode_query(service_type)]).await;
+        tokio_stream::wrappers::UnboundedReceiverStream::new(rx)
+    }
+
+    /// Stop listening for the payloads published under this service type.
+    pub fn stop_listening(&self, service_type: &str) {
+        let mut state = self.shared.state.lock().unwrap();
+        state.listeners.remove(service_type);
+        state.emitted.retain(|(t, _), _| t != service_type);
+        state
+            .assembling
+            .retain(|k, _| k.service_type != service_type);
+    }
+}
+
+fn create_socket() -> Result<UdpSocket, MdnsError> {
+    let builder = net2::UdpBuilder::new_v4()?;
+    builder.reuse_address(true)?;
+    #[cfg(unix)]
+    net2::unix::UnixUdpBuilderExt::reuse_port(&builder, true)?;
+    let socket = builder.bind((Ipv4Addr::UNSPECIFIED, MULTICAST_PORT))?;
+    socket.join_multicast_v4(&MULTICAST_ADDR, &Ipv4Addr::UNSPECIFIED)?;
+    socket.set_multicast_loop_v4(true)?;
+    socket.set_multicast_ttl_v4(255)?;
+    socket.set_nonblocking(true)?;
+    Ok(UdpSocket::from_std(socket)?)
+}
+
+fn multicast_addr() -> SocketAddr {
+    SocketAddr::new(MULTICAST_ADDR.into(), MULTICAST_PORT)
+}
+
+async fn send_all(socket: &UdpSocket, packets: &[Vec<u8>]) {
+    for packet in packets {
+        if let Err(e) = socket.send_to(packet, multicast_addr()).await {
+            tracing::warn!(?e, "Failed to send mdns packet");
+        }
+    }
+}
+
+async fn broadcast_loop(shared: Arc<Shared>) {
+    let mut broadcast = tokio::time::interval(Duration::from_secs(BROADCAST_INTERVAL_SEC));
+    let mut query = tokio::time::interval(Duration::from_secs(QUERY_INTERVAL_SEC));
+    loop {
+        let packets: Vec<Vec<u8>> = tokio::select! {
+            _ = broadcast.tick() => shared
+                .state
+                .lock()
+                .unwrap()
+                .published
+                .values()
+                .flat_map(HashMap::values)
+                .flatten()
+                .cloned()
+                .collect(),
+            _ = query.tick() => shared
+                .state
+                .lock()
+                .unwrap()
+                .listeners
+                .keys()
+                .map(|t| encode_query(t))
+                .collect(),
+        };
+        send_all(&shared.socket, &packets).await;
+    }
+}
+
+async fn recv_loop(shared: Arc<Shared>) {
+    // Bigger than any packet we send, so other mDNS traffic
+    // is the only thing which could be truncated.
+    let mut buf = vec![0; 9000];
+    loop {
+        let (len, from) = match shared.socket.recv_from(&mut buf).await {
+            Ok(r) => r,
+            Err(e) => {
+                tracing::warn!(?e, "Failed to receive mdns packet");
+                continue;
+            }
+        };
+        let packet = match dns_parser::Packet::parse(&buf[..len]) {
+            Ok(p) => p,
+            // Not every packet on the mDNS port is well formed
+            Err(_) => continue,
+        };
+        if packet.header.query {
+            let answers = answer_queries(&shared.state.lock().unwrap(), &packet);
+            send_all(&shared.socket, &answers).await;
+        } else {
+            handle_response(&mut shared.state.lock().unwrap(), &packet, from);
+        }
+    }
+}
+
+/// Get the chunk packets which answer the PTR questions in a query.
+fn answer_queries(state: &State, packet: &dns_parser::Packet) -> Vec<Vec<u8>> {
+    packet
+        .questions
+        .iter()
+        .filter(|q| q.qtype == dns_parser::QueryType::PTR)
+        .filter_map(|q| parse_service_type(&q.qname.to_string()))
+        .filter_map(|service_type| state.published.get(&service_type))
+        .flat_map(HashMap::values)
+        .flatten()
+        .cloned()
+        .collect()
+}
+
+fn handle_response(state: &mut State, packet: &dns_parser::Packet, from: SocketAddr) {
+    state
+        .assembling
+        .retain(|_, a| a.started.elapsed() < Duration::from_secs(ASSEMBLY_TIMEOUT_SEC));
+    for record in packet.answers.iter().chain(packet.additional.iter()) {
+        let txt = match &record.data {
+            dns_parser::RData::TXT(txt) => txt,
+            _ => continue,
+        };
+        let chunk = match parse_chunk(&record.name.to_string(), txt) {
+            Some(chunk) => chunk,
+            None => continue,
+        };
+        let listener = match state.listeners.get(&chunk.service_type) {
+            Some(listener) => listener.clone(),
+            None => continue,
+        };
+        let service = (chunk.service_type.clone(), chunk.service_name.clone());
+        if record.ttl == 0 {
+            state.assembling.retain(|k, _| {
+                k.service_type != chunk.service_type || k.service_name != chunk.service_name
+            });
+            if state.emitted.remove(&service).is_some() {
+                let _ = listener.send(MdnsEvent::Withdrawn {
+                    service_type: chunk.service_type,
+                    service_name: chunk.service_name,
+                });
             }
-            let response = maybe_response.unwrap();
-            // NOTE: if response.ip_addr() is not te right address,
-            // we should give all A/AAA records found in the answers instead
-            let addr = response.ip_addr().unwrap(); // should have already been filtered out
-            let mut buffer = Vec::new();
-            let mut service_name = String::new();
-            let mut service_type = String::new();
-            //println!("Response Answer count = {}", response.answers.len());
-            for answer in response.answers {
-                match answer.kind {
-                    RecordKind::TXT(txts) => {
-                        //println!("TXT count = {}", txts.len());
-                        let mut b64 = String::new();
-                        for txt in txts {
-                            //println!("Response TXT = {:?}", txt);
-                            b64.push_str(&txt);
-                        }
-                        buffer = match base64::decode_config(&b64[1..], base64::URL_SAFE_NO_PAD) {
-                            Err(e) => return Err(MdnsError::Base64(e)),
-                            Ok(s) => s,
-                        };
-                    }
-                    // Retrieve service name stored in PTR record
-                    RecordKind::PTR(ptr) => {
-                        //println!("PTR = {}", ptr);
-                        service_name = ptr
-                            .split('.')
-                            .into_iter()
-                            .next()
-                            .expect("Found service without a name")
-                            .to_string();
-                        let names: Vec<&str> = answer.name.split("._").collect();
-                        //println!("answer.name = {}", answer.name);
-                        service_type = names[0][1..].to_string();
-                    }
-                    _ => {}
+            continue;
+        }
+        if state.emitted.get(&service) == Some(&chunk.version) {
+            continue;
+        }
+        let key = AssemblyKey {
+            service_type: chunk.service_type,
+            service_name: chunk.service_name,
+            version: chunk.version,
+        };
+        if !state.assembling.contains_key(&key) {
+            // Only the latest version of a payload is worth assembling.
+            state.assembling.retain(|k, _| {
+                k.service_type != key.service_type || k.service_name != key.service_name
+            });
+            if state.assembling.len() >= MAX_ASSEMBLIES {
+                let oldest = state
+                    .assembling
+                    .iter()
+                    .min_by_key(|(_, a)| a.started)
+                    .map(|(k, _)| k.clone());
+                if let Some(oldest) = oldest {
+                    state.assembling.remove(&oldest);
                 }
             }
-            Ok(MdnsResponse {
-                service_type,
-                service_name,
-                addr,
-                buffer,
-            })
-        });
-    // Done
-    mdns_stream
+        }
+        let assembly = state
+            .assembling
+            .entry(key.clone())
+            .or_insert_with(|| Assembly {
+                chunks: vec![None; chunk.total],
+                started: Instant::now(),
+            });
+        if assembly.chunks.len() != chunk.total {
+            continue;
+        }
+        assembly.chunks[chunk.index] = Some(chunk.data);
+        if assembly.chunks.iter().any(Option::is_none) {
+            continue;
+        }
+        let buffer = state
+            .assembling
+            .remove(&key)
+            .into_iter()
+            .flat_map(|a| a.chunks)
+            .flatten()
+            .flatten()
+            .collect();
+        state.emitted.insert(service, key.version);
+        let _ = listener.send(MdnsEvent::Discovered(MdnsResponse {
+            service_type: key.service_type,
+            service_name: key.service_name,
+            addr: from.ip(),
+            buffer,
+        }));
+    }
+}
+
+fn service_type_name(service_type: &str) -> String {
+    format!("_{}{}.local", service_type, HC_SERVICE_PROTOCOL)
+}
+
+fn parse_service_type(name: &str) -> Option<String> {
+    name.strip_prefix('_')?
+        .strip_suffix(".local")?
+        .strip_suffix(HC_SERVICE_PROTOCOL)
+        .map(str::to_string)
+}
+
+fn encode_query(service_type: &str) -> Vec<u8> {
+    let mut builder = dns_parser::Builder::new_query(0, false);
+    builder.add_question(
+        &service_type_name(service_type),
+        false,
+        dns_parser::QueryType::PTR,
+        dns_parser::QueryClass::IN,
+    );
+    builder.build().unwrap_or_else(|truncated| truncated)
+}
+
+/// Encode a payload as one response packet per chunk.
+fn encode_chunks(
+    service_type: &str,
+    service_name: &str,
+    buffer: &[u8],
+    version: u32,
+) -> Result<Vec<Vec<u8>>, MdnsError> {
+    let type_label = format!("_{}", service_type);
+    // Leave room for the chunk index in the instance label
+    if type_label.len() > MAX_LABEL_SIZE || service_name.len() + 4 > MAX_LABEL_SIZE {
+        return Err(MdnsError::NameTooLong(format!(
+            "{}.{}",
+            service_name, service_type
+        )));
+    }
+    // An empty payload is still sent as one empty chunk
+    let chunks: Vec<&[u8]> = if buffer.is_empty() {
+        vec![&[]]
+    } else {
+        buffer.chunks(CHUNK_SIZE).collect()
+    };
+    if chunks.len() > MAX_CHUNKS {
+        return Err(MdnsError::PayloadTooLarge(buffer.len()));
+    }
+    let total = chunks.len();
+    let type_name = service_type_name(service_type);
+    Ok(chunks
+        .into_iter()
+        .enumerate()
+        .map(|(index, chunk)| {
+            let instance_name = format!("{}-{}.{}", service_name, index, type_name);
+            let b64 = base64::encode_config(chunk, base64::URL_SAFE_NO_PAD);
+            let mut txt = vec![format!("v={}", version), format!("c={}/{}", index, total)];
+            txt.extend(
+                b64.as_bytes()
+                    .chunks(MAX_TXT_SIZE)
+                    .map(|d| format!("d={}", String::from_utf8_lossy(d))),
+            );
+            let mut packet = Vec::new();
+            // Header: a response with two answers
+            packet.extend_from_slice(&0u16.to_be_bytes());
+            packet.extend_from_slice(&0x8400u16.to_be_bytes());
+            packet.extend_from_slice(&0u16.to_be_bytes());
+            packet.extend_from_slice(&2u16.to_be_bytes());
+            packet.extend_from_slice(&0u16.to_be_bytes());
+            packet.extend_from_slice(&0u16.to_be_bytes());
+            let mut ptr_data = Vec::new();
+            encode_name(&mut ptr_data, &instance_name);
+            encode_record(&mut packet, &type_name, TYPE_PTR, CLASS_IN, &ptr_data);
+            let mut txt_data = Vec::new();
+            for s in txt {
+                txt_data.push(s.len() as u8);
+                txt_data.extend_from_slice(s.as_bytes());
+            }
+            encode_record(
+                &mut packet,
+                &instance_name,
+                TYPE_TXT,
+                CLASS_IN | CLASS_CACHE_FLUSH,
+                &txt_data,
+            );
+            packet
+        })
+        .collect())
+}
+
+/// Offset of the TTL from the end of a record's name.
+const TTL_OFFSET: usize = 4;
+
+fn encode_record(packet: &mut Vec<u8>, name: &str, rtype: u16, class: u16, data: &[u8]) {
+    encode_name(packet, name);
+    packet.extend_from_slice(&rtype.to_be_bytes());
+    packet.extend_from_slice(&class.to_be_bytes());
+    packet.extend_from_slice(&RECORD_TTL_SEC.to_be_bytes());
+    packet.extend_from_slice(&(data.len() as u16).to_be_bytes());
+    packet.extend_from_slice(data);
+}
+
+fn encode_name(out: &mut Vec<u8>, name: &str) {
+    for label in name.split('.') {
+        out.push(label.len() as u8);
+        out.extend_from_slice(label.as_bytes());
+    }
+    out.push(0);
+}
+
+/// The same packet with the TTL of every record set to 0.
+fn goodbye(packet: &[u8]) -> Vec<u8> {
+    let mut packet = packet.to_vec();
+    // Skip the header, then walk the two uncompressed records
+    let mut pos = 12;
+    for _ in 0..2 {
+        while packet[pos] != 0 {
+            pos += packet[pos] as usize + 1;
+        }
+        pos += 1 + TTL_OFFSET;
+        packet[pos..pos + 4].copy_from_slice(&0u32.to_be_bytes());
+        pos += 4;
+        let len = u16::from_be_bytes([packet[pos], packet[pos + 1]]) as usize;
+        pos += 2 + len;
+    }
+    packet
+}
+
+struct Chunk {
+    service_type: String,
+    service_name: String,
+    version: u32,
+    index: usize,
+    total: usize,
+    data: Vec<u8>,
+}
+
+fn parse_chunk(instance_name: &str, txt: &dns_parser::rdata::txt::Record) -> Option<Chunk> {
+    let (instance, type_name) = instance_name.split_once('.')?;
+    let service_type = parse_service_type(type_name)?;
+    // Names are base64 which can contain '-', so split at the last one
+    let (service_name, _) = instance.rsplit_once('-')?;
+    let mut version = None;
+    let mut position = None;
+    let mut b64 = String::new();
+    for s in txt.iter() {
+        let s = std::str::from_utf8(s).ok()?;
+        if let Some(v) = s.strip_prefix("v=") {
+            version = v.parse().ok();
+        } else if let Some(c) = s.strip_prefix("c=") {
+            let (index, total) = c.split_once('/')?;
+            position = Some((index.parse().ok()?, total.parse().ok()?));
+        } else if let Some(d) = s.strip_prefix("d=") {
+            b64.push_str(d);
+        }
+    }
+    let (index, total): (usize, usize) = position?;
+    if index >= total || total > MAX_CHUNKS {
+        return None;
+    }
+    Some(Chunk {
+        service_type,
+        service_name: service_name.to_string(),
+        version: version?,
+        index,
+        total,
+        data: base64::decode_config(&b64, base64::URL_SAFE_NO_PAD).ok()?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio_stream::StreamExt;
+
+    #[test]
+    fn chunks_round_trip() {
+        let buffer: Vec<u8> = (0..2000).map(|i| (i % 251) as u8).collect();
+        let packets = encode_chunks("space", "agent-name", &buffer, 7).unwrap();
+        assert_eq!(packets.len(), 3);
+        let mut state = State::default();
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        state.listeners.insert("space".into(), tx);
+        let from: SocketAddr = "127.0.0.1:5353".parse().unwrap();
+        // Chunks can arrive in any order and more than once
+        for packet in packets.iter().rev().chain(packets.iter()) {
+            handle_response(
+                &mut state,
+                &dns_parser::Packet::parse(packet).unwrap(),
+                from,
+            );
+        }
+        match rx.try_recv().unwrap() {
+            MdnsEvent::Discovered(response) => {
+                assert_eq!(response.service_name, "agent-name");
+                assert_eq!(response.buffer, buffer);
+            }
+            event => panic!("unexpected {:?}", event),
+        }
+        assert!(rx.try_recv().is_err());
+
+        let goodbye = goodbye(&packets[0]);
+        handle_response(
+            &mut state,
+            &dns_parser::Packet::parse(&goodbye).unwrap(),
+            from,
+        );
+        assert!(matches!(
+            rx.try_recv().unwrap(),
+            MdnsEvent::Withdrawn { service_name, .. } if service_name == "agent-name"
+        ));
+    }
+
+    #[test]
+    fn incomplete_payloads_are_bounded() {
+        let mut state = State::default();
+        let (tx, _rx) = mpsc::unbounded_channel();
+        state.listeners.insert("space".into(), tx);
+        let from: SocketAddr = "127.0.0.1:5353".parse().unwrap();
+        let buffer = vec![0; CHUNK_SIZE * 2];
+        let send_first_chunk = |state: &mut State, name: &str, version: u32| {
+            let packets = encode_chunks("space", name, &buffer, version).unwrap();
+            handle_response(
+                state,
+                &dns_parser::Packet::parse(&packets[0]).unwrap(),
+                from,
+            );
+        };
+
+        // A new version replaces the incomplete old one
+        send_first_chunk(&mut state, "agent", 1);
+        send_first_chunk(&mut state, "agent", 2);
+        assert_eq!(state.assembling.len(), 1);
+        assert!(state.assembling.keys().all(|k| k.version == 2));
+
+        for i in 0..MAX_ASSEMBLIES + 10 {
+            send_first_chunk(&mut state, &format!("agent{}", i), 1);
+        }
+        assert_eq!(state.assembling.len(), MAX_ASSEMBLIES);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn discovers_across_instances_and_spaces() {
+        let a = MdnsDiscovery::new().unwrap();
+        let b = MdnsDiscovery::new().unwrap();
+        let space_1 = format!("test-{}", rand::random::<u32>());
+        let space_2 = format!("test-{}", rand::random::<u32>());
+        let big: Vec<u8> = (0..5000).map(|i| (i % 251) as u8).collect();
+        a.publish(&space_1, "alice", &big).await.unwrap();
+        a.publish(&space_2, "alice", b"small").await.unwrap();
+
+        // Published before listening, so found by querying
+        let mut space_1_events = Box::pin(b.listen(&space_1).await);
+        let mut space_2_events = Box::pin(b.listen(&space_2).await);
+
+        match next(&mut space_1_events).await {
+            MdnsEvent::Discovered(response) => assert_eq!(response.buffer, big),
+            event => panic!("unexpected {:?}", event),
+        }
+        match next(&mut space_2_events).await {
+            MdnsEvent::Discovered(response) => assert_eq!(response.buffer, b"small"),
+            event => panic!("unexpected {:?}", event),
+        }
+
+        a.withdraw(&space_1, "alice").await;
+        assert!(matches!(
+            next(&mut space_1_events).await,
+            MdnsEvent::Withdrawn { .. }
+        ));
+    }
+
+    async fn next(events: &mut (impl Stream<Item = MdnsEvent> + Unpin)) -> MdnsEvent {
+        tokio::time::timeout(Duration::from_secs(10), events.next())
+            .await
+            .expect("timed out waiting for mdns event")
+            .unwrap()
+    }
 }
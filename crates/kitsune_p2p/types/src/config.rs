@@ -55,6 +55,36 @@ pub mod tuning_params_struct {
                 }
             }
 
+            impl KitsuneP2pTuningParams {
+                /// Set a single param from its string representation,
+                /// as it would be written in a config file.
+                pub fn set_param(&mut self, key: &str, value: &str) -> crate::KitsuneResult<()> {
+                    match key {
+                        $(
+                            stringify!($i) => match value.parse::<$t>() {
+                                Ok(v) => self.$i = v,
+                                Err(e) => {
+                                    return Err(format!("failed to parse {}: {}", key, e).into())
+                                }
+                            },
+                        )*
+                        _ => return Err(format!("INVALID TUNING PARAM: '{}'", key).into()),
+                    }
+                    Ok(())
+                }
+
+                /// Get the string representation of a single param,
+                /// or None if there is no param with this name.
+                pub fn get_param(&self, key: &str) -> Option<String> {
+                    match key {
+                        $(
+                            stringify!($i) => Some(format!("{}", &self.$i)),
+                        )*
+                        _ => None,
+                    }
+                }
+            }
+
             impl<'de> serde::Deserialize<'de> for KitsuneP2pTuningParams {
                 fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
                 where
@@ -242,9 +272,74 @@ pub mod tuning_params_struct {
         /// Disable historical gossip. Useful for testing Recent gossip in isolation.
         disable_historical_gossip: bool = false,
 
+        /// How long the fetch queue waits before fetching an item again,
+        /// regardless of source. [Default: 90 seconds]
+        fetch_item_retry_delay_ms: u32 = 1000 * 90,
+
+        /// How long the fetch queue waits before fetching an item again
+        /// from the same source. [Default: 5 minutes]
+        fetch_source_retry_delay_ms: u32 = 1000 * 60 * 5,
+
     }
 
     impl KitsuneP2pTuningParams {
+        /// Params which are only read when the network is started, or
+        /// when the host starts, so changing them requires a restart.
+        pub const RESTART_REQUIRED: &'static [&'static str] = &[
+            "gossip_strategy",
+            "gossip_burst_ratio",
+            "gossip_redundancy_target",
+            "tls_in_mem_session_storage",
+            "proxy_keepalive_ms",
+            "proxy_to_expire_ms",
+            "concurrent_limit_per_thread",
+            "tx2_quic_max_idle_timeout_ms",
+            "tx2_pool_max_connection_count",
+            "danger_tls_keylog",
+            "disable_publish",
+            "disable_recent_gossip",
+            "disable_historical_gossip",
+            "danger_gossip_recent_threshold_secs",
+        ];
+
+        /// Params which are shared by every space on a node,
+        /// so they can't be changed for a single space.
+        pub const NETWORK_WIDE: &'static [&'static str] = &[
+            "gossip_outbound_target_mbps",
+            "gossip_inbound_target_mbps",
+            "gossip_historic_outbound_target_mbps",
+            "gossip_historic_inbound_target_mbps",
+            "tx2_channel_count_per_connection",
+            "tx2_initial_connect_retry_delay_ms",
+            "tx2_implicit_timeout_ms",
+            "default_rpc_multi_remote_agent_count",
+            "default_rpc_multi_remote_request_grace_ms",
+            "fetch_item_retry_delay_ms",
+            "fetch_source_retry_delay_ms",
+        ];
+
+        /// Apply a patch of params, given as strings like in a config file,
+        /// and return the updated params.
+        ///
+        /// Fails if any param can't be parsed, or if the patch changes
+        /// a param which requires a restart.
+        pub fn patch(&self, patch: &super::TuningParamsPatch) -> crate::KitsuneResult<Self> {
+            let mut out = self.clone();
+            for (key, value) in patch.iter() {
+                out.set_param(key, value)?;
+                if Self::RESTART_REQUIRED.contains(&key.as_str())
+                    && out.get_param(key) != self.get_param(key)
+                {
+                    return Err(format!(
+                        "tuning param '{}' can't be changed without a restart",
+                        key
+                    )
+                    .into());
+                }
+            }
+            Ok(out)
+        }
+
         /// Generate a KitsuneTimeout instance
         /// based on the tuning parameter tx2_implicit_timeout_ms
         pub fn implicit_timeout(&self) -> crate::KitsuneTimeout {
@@ -261,9 +356,110 @@ pub mod tuning_params_struct {
         pub fn use_env_tls_keylog(&self) -> bool {
             self.danger_tls_keylog == "env_keylog"
         }
+
+        /// Get the fetch_item_retry_delay_ms param as a proper Duration
+        pub fn fetch_item_retry_delay(&self) -> std::time::Duration {
+            std::time::Duration::from_millis(self.fetch_item_retry_delay_ms as u64)
+        }
+
+        /// Get the fetch_source_retry_delay_ms param as a proper Duration
+        pub fn fetch_source_retry_delay(&self) -> std::time::Duration {
+            std::time::Duration::from_millis(self.fetch_source_retry_delay_ms as u64)
+        }
     }
 }
 
 /// We don't want to clone these tuning params over-and-over.
 /// They should normally be passed around as an Arc.
 pub type KitsuneP2pTuningParams = std::sync::Arc<tuning_params_struct::KitsuneP2pTuningParams>;
+
+/// A set of tuning params to change, keyed by param name, with values
+/// represented as strings just like in the config file.
+pub type TuningParamsPatch = std::collections::HashMap<String, String>;
+
+/// Tuning params which can be replaced while the network is running.
+/// Clones share the same params, so everything holding a clone
+/// sees an update as soon as it's made.
+#[derive(Clone, Debug, Default)]
+pub struct LiveTuningParams(std::sync::Arc<parking_lot::RwLock<KitsuneP2pTuningParams>>);
+
+impl LiveTuningParams {
+    /// Wrap the tuning params so they can be updated.
+    pub fn new(tuning_params: KitsuneP2pTuningParams) -> Self {
+        Self(std::sync::Arc::new(parking_lot::RwLock::new(tuning_params)))
+    }
+
+    /// The tuning params currently in effect.
+    pub fn get(&self) -> KitsuneP2pTuningParams {
+        self.0.read().clone()
+    }
+
+    /// Replace the tuning params.
+    pub fn set(&self, tuning_params: KitsuneP2pTuningParams) {
+        *self.0.write() = tuning_params;
+    }
+
+    /// Apply a patch to the tuning params currently in effect.
+    /// Nothing is changed if the patch can't be applied.
+    pub fn patch(&self, patch: &TuningParamsPatch) -> crate::KitsuneResult<()> {
+        let mut lock = self.0.write();
+        let patched = lock.patch(patch)?;
+        *lock = std::sync::Arc::new(patched);
+        Ok(())
+    }
+}
+
+impl From<KitsuneP2pTuningParams> for LiveTuningParams {
+    fn from(tuning_params: KitsuneP2pTuningParams) -> Self {
+        Self::new(tuning_params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_patch_tuning_params() {
+        let live = LiveTuningParams::default();
+        let shared = live.clone();
+
+        let patch: TuningParamsPatch = [
+            ("gossip_max_batch_size".to_string(), "42".to_string()),
+            ("tx2_implicit_timeout_ms".to_string(), "1000".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        live.patch(&patch).unwrap();
+        assert_eq!(shared.get().gossip_max_batch_size, 42);
+        assert_eq!(shared.get().tx2_implicit_timeout_ms, 1000);
+
+        // Params which need a restart can't be changed,
+        // and nothing is applied from a failed patch.
+        let patch: TuningParamsPatch = [
+            ("gossip_max_batch_size".to_string(), "7".to_string()),
+            ("gossip_strategy".to_string(), "none".to_string()),
+        ]
+        .into_iter()
+        .collect();
+        assert!(live.patch(&patch).is_err());
+        assert_eq!(shared.get().gossip_max_batch_size, 42);
+
+        // But they can be given with their current value.
+        let patch: TuningParamsPatch =
+            [("gossip_strategy".to_string(), "sharded-gossip".to_string())]
+                .into_iter()
+                .collect();
+        live.patch(&patch).unwrap();
+
+        // Unknown params and bad values are errors.
+        let patch: TuningParamsPatch = [("not_a_param".to_string(), "1".to_string())]
+            .into_iter()
+            .collect();
+        assert!(live.patch(&patch).is_err());
+        let patch: TuningParamsPatch = [("gossip_max_batch_size".to_string(), "x".to_string())]
+            .into_iter()
+            .collect();
+        assert!(live.patch(&patch).is_err());
+    }
+}
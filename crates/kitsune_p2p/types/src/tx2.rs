@@ -6,6 +6,9 @@ pub use framed::*;
 mod mem;
 pub use mem::*;
 
+mod mem_network;
+pub use mem_network::*;
+
 pub mod tx2_adapter;
 
 pub mod tx2_api;
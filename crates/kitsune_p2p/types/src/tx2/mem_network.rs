@@ -0,0 +1,509 @@
+//! A controllable network of in-memory tx2 nodes, for simulating
+//! partitions, latency and packet loss in tests.
+
+use crate::tx2::tx2_adapter::*;
+use crate::tx2::tx2_utils::*;
+use crate::tx2::*;
+use crate::*;
+use futures::{
+    future::{BoxFuture, FutureExt},
+    stream::{BoxStream, StreamExt},
+};
+use parking_lot::Mutex;
+use std::collections::HashMap;
+
+/// Identifies a node of a [`MemNetwork`].
+/// Nodes are numbered from zero in the order they are added.
+pub type MemNodeId = usize;
+
+/// A network of memory-based tx2 nodes whose links can be cut,
+/// slowed down or made lossy while the nodes are running.
+///
+/// Each node is an [`AdapterFactory`] wrapping [`tx2_mem_adapter`].
+/// Conditions apply to every frame a node writes to a peer of the same
+/// network, in both directions of a link. Links to nodes which are not
+/// part of the network are never affected.
+///
+/// Packet loss is decided by a seeded generator, so a test sending the
+/// same frames in the same order sees the same frames dropped.
+#[derive(Clone)]
+pub struct MemNetwork(Arc<Mutex<MemNetworkInner>>);
+
+impl Default for MemNetwork {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MemNetwork {
+    /// Construct a new network with no nodes and no conditions.
+    pub fn new() -> Self {
+        Self::with_seed(0x853c_49e6_748f_ea9b)
+    }
+
+    /// Construct a new network, seeding the generator deciding packet loss.
+    pub fn with_seed(seed: u64) -> Self {
+        Self(Arc::new(Mutex::new(MemNetworkInner {
+            nodes: HashMap::new(),
+            groups: None,
+            links: HashMap::new(),
+            bytes_sent: Vec::new(),
+            // xorshift gets stuck on zero
+            rng: seed.max(1),
+        })))
+    }
+
+    /// Add a node to the network, returning its id and the
+    /// adapter factory to bind its endpoint with.
+    pub async fn add_node(&self, config: MemConfig) -> KitsuneResult<(MemNodeId, AdapterFactory)> {
+        let sub = tx2_mem_adapter(config).await?;
+        let node = {
+            let mut inner = self.0.lock();
+            let node = inner.bytes_sent.len();
+            inner.bytes_sent.push(0);
+            inner.nodes.insert(sub.local_cert(), node);
+            node
+        };
+        let out: AdapterFactory = Arc::new(SimBindAdapt {
+            sub,
+            net: self.clone(),
+            node,
+        });
+        Ok((node, out))
+    }
+
+    /// The number of nodes added to the network.
+    pub fn node_count(&self) -> usize {
+        self.0.lock().bytes_sent.len()
+    }
+
+    /// Partition the network into groups of nodes which can only reach
+    /// the nodes of their own group. Nodes not listed in any group form
+    /// one more group together. Replaces any previous partition.
+    ///
+    /// Open connections across groups fail on their next write,
+    /// and new ones are refused until the partition is healed.
+    pub fn partition(&self, groups: &[&[MemNodeId]]) {
+        let mut map = HashMap::new();
+        for (group, nodes) in groups.iter().enumerate() {
+            for node in nodes.iter() {
+                map.insert(*node, group + 1);
+            }
+        }
+        self.0.lock().groups = Some(map);
+    }
+
+    /// Remove the partition, so every node can reach every other again.
+    pub fn heal(&self) {
+        self.0.lock().groups = None;
+    }
+
+    /// Delay every frame sent between nodes `a` and `b` by `latency`.
+    /// A zero duration removes the delay.
+    pub fn set_latency(&self, a: MemNodeId, b: MemNodeId, latency: std::time::Duration) {
+        self.0.lock().links.entry(link(a, b)).or_default().latency = latency;
+    }
+
+    /// Drop each frame sent between nodes `a` and `b` with the
+    /// probability `loss`, between `0.0` (the default) and `1.0`.
+    pub fn set_packet_loss(&self, a: MemNodeId, b: MemNodeId, loss: f64) {
+        self.0.lock().links.entry(link(a, b)).or_default().loss = loss.clamp(0.0, 1.0);
+    }
+
+    /// Remove all latency and packet loss, leaving any partition in place.
+    pub fn clear_link_conditions(&self) {
+        self.0.lock().links.clear();
+    }
+
+    /// Are nodes `a` and `b` currently in different groups of a partition?
+    pub fn is_partitioned(&self, a: MemNodeId, b: MemNodeId) -> bool {
+        self.0.lock().is_partitioned(a, b)
+    }
+
+    /// The number of bytes node `node` has sent to other nodes of the network,
+    /// including those dropped by packet loss.
+    pub fn bytes_sent(&self, node: MemNodeId) -> u64 {
+        self.0
+            .lock()
+            .bytes_sent
+            .get(node)
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// The number of bytes sent by every node, indexed by node id.
+    pub fn all_bytes_sent(&self) -> Vec<u64> {
+        self.0.lock().bytes_sent.clone()
+    }
+
+    fn node_of(&self, cert: &Tx2Cert) -> Option<MemNodeId> {
+        self.0.lock().nodes.get(cert).copied()
+    }
+}
+
+// -- private -- //
+
+#[derive(Default, Clone, Copy)]
+struct LinkConditions {
+    latency: std::time::Duration,
+    loss: f64,
+}
+
+/// What to do with a frame written by a node.
+enum Delivery {
+    Refuse,
+    Drop,
+    Deliver(std::time::Duration),
+}
+
+struct MemNetworkInner {
+    nodes: HashMap<Tx2Cert, MemNodeId>,
+    groups: Option<HashMap<MemNodeId, usize>>,
+    links: HashMap<(MemNodeId, MemNodeId), LinkConditions>,
+    bytes_sent: Vec<u64>,
+    rng: u64,
+}
+
+impl MemNetworkInner {
+    fn is_partitioned(&self, a: MemNodeId, b: MemNodeId) -> bool {
+        match &self.groups {
+            None => false,
+            Some(groups) => {
+                groups.get(&a).copied().unwrap_or_default()
+                    != groups.get(&b).copied().unwrap_or_default()
+            }
+        }
+    }
+
+    fn deliver(&mut self, from: MemNodeId, to: MemNodeId, len: usize) -> Delivery {
+        if self.is_partitioned(from, to) {
+            return Delivery::Refuse;
+        }
+        if let Some(sent) = self.bytes_sent.get_mut(from) {
+            *sent += len as u64;
+        }
+        let conditions = self.links.get(&link(from, to)).copied().unwrap_or_default();
+        if conditions.loss > 0.0 && self.next_f64() < conditions.loss {
+            return Delivery::Drop;
+        }
+        Delivery::Deliver(conditions.latency)
+    }
+
+    /// xorshift64, mapped to `[0, 1)`.
+    fn next_f64(&mut self) -> f64 {
+        self.rng ^= self.rng << 13;
+        self.rng ^= self.rng >> 7;
+        self.rng ^= self.rng << 17;
+        (self.rng >> 11) as f64 / (1u64 << 53) as f64
+    }
+}
+
+fn link(a: MemNodeId, b: MemNodeId) -> (MemNodeId, MemNodeId) {
+    (a.min(b), a.max(b))
+}
+
+/// Wrap a connection of `node`, refusing it if the peer is partitioned off.
+async fn sim_con(net: MemNetwork, node: MemNodeId, con: Con) -> KitsuneResult<Con> {
+    let (con, chan_recv) = con;
+    let peer = net.node_of(&con.peer_cert());
+    if let Some(peer) = peer {
+        if net.is_partitioned(node, peer) {
+            con.close(0, "partitioned").await;
+            return Err(format!("node {} is partitioned from node {}", node, peer).into());
+        }
+    }
+    let con: Arc<dyn ConAdapt> = Arc::new(SimConAdapt {
+        sub: con,
+        net,
+        node,
+        peer,
+    });
+    Ok((con, chan_recv))
+}
+
+struct SimOutChan {
+    sub: OutChan,
+    net: MemNetwork,
+    node: MemNodeId,
+    peer: MemNodeId,
+}
+
+impl AsFramedWriter for SimOutChan {
+    fn write(
+        &mut self,
+        msg_id: MsgId,
+        data: PoolBuf,
+        timeout: KitsuneTimeout,
+    ) -> BoxFuture<'_, KitsuneResult<()>> {
+        async move {
+            let delivery = self.net.0.lock().deliver(self.node, self.peer, data.len());
+            match delivery {
+                Delivery::Refuse => {
+                    Err(format!("node {} is partitioned from node {}", self.node, self.peer).into())
+                }
+                Delivery::Drop => Ok(()),
+                Delivery::Deliver(latency) => {
+                    if !latency.is_zero() {
+                        timeout
+                            .mix("SimOutChan::write", async move {
+                                tokio::time::sleep(latency).await;
+                                KitsuneResult::Ok(())
+                            })
+                            .await?;
+                    }
+                    self.sub.write(msg_id, data, timeout).await
+                }
+            }
+        }
+        .boxed()
+    }
+}
+
+struct SimConAdapt {
+    sub: Arc<dyn ConAdapt>,
+    net: MemNetwork,
+    node: MemNodeId,
+    peer: Option<MemNodeId>,
+}
+
+impl ConAdapt for SimConAdapt {
+    fn uniq(&self) -> Uniq {
+        self.sub.uniq()
+    }
+
+    fn dir(&self) -> Tx2ConDir {
+        self.sub.dir()
+    }
+
+    fn peer_addr(&self) -> KitsuneResult<TxUrl> {
+        self.sub.peer_addr()
+    }
+
+    fn peer_cert(&self) -> Tx2Cert {
+        self.sub.peer_cert()
+    }
+
+    fn out_chan(&self, timeout: KitsuneTimeout) -> OutChanFut {
+        let fut = self.sub.out_chan(timeout);
+        let peer = match self.peer {
+            // peers outside the network are not simulated
+            None => return fut,
+            Some(peer) => peer,
+        };
+        let net = self.net.clone();
+        let node = self.node;
+        async move {
+            let sub = fut.await?;
+            let out: OutChan = Box::new(SimOutChan {
+                sub,
+                net,
+                node,
+                peer,
+            });
+            Ok(out)
+        }
+        .boxed()
+    }
+
+    fn is_closed(&self) -> bool {
+        self.sub.is_closed()
+    }
+
+    fn close(&self, code: u32, reason: &str) -> BoxFuture<'static, ()> {
+        self.sub.close(code, reason)
+    }
+}
+
+struct SimConRecvAdapt(BoxStream<'static, ConFut>);
+
+impl SimConRecvAdapt {
+    pub fn new(sub: Box<dyn ConRecvAdapt>, net: MemNetwork, node: MemNodeId) -> Self {
+        Self(
+            sub.map(move |fut| {
+                let net = net.clone();
+                async move { sim_con(net, node, fut.await?).await }.boxed()
+            })
+            .boxed(),
+        )
+    }
+}
+
+impl futures::stream::Stream for SimConRecvAdapt {
+    type Item = ConFut;
+
+    fn poll_next(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<Option<Self::Item>> {
+        let inner = &mut self.0;
+        tokio::pin!(inner);
+        futures::stream::Stream::poll_next(inner, cx)
+    }
+}
+
+impl ConRecvAdapt for SimConRecvAdapt {}
+
+struct SimEndpointAdapt {
+    sub: Arc<dyn EndpointAdapt>,
+    net: MemNetwork,
+    node: MemNodeId,
+}
+
+impl EndpointAdapt for SimEndpointAdapt {
+    fn debug(&self) -> serde_json::Value {
+        let mut out = self.sub.debug();
+        if let Some(map) = out.as_object_mut() {
+            map.insert("sim_node".into(), self.node.into());
+        }
+        out
+    }
+
+    fn uniq(&self) -> Uniq {
+        self.sub.uniq()
+    }
+
+    fn local_addr(&self) -> KitsuneResult<TxUrl> {
+        self.sub.local_addr()
+    }
+
+    fn local_cert(&self) -> Tx2Cert {
+        self.sub.local_cert()
+    }
+
+    fn connect(&self, url: TxUrl, timeout: KitsuneTimeout) -> ConFut {
+        let fut = self.sub.connect(url, timeout);
+        let net = self.net.clone();
+        let node = self.node;
+        async move { sim_con(net, node, fut.await?).await }.boxed()
+    }
+
+    fn is_closed(&self) -> bool {
+        self.sub.is_closed()
+    }
+
+    fn close(&self, code: u32, reason: &str) -> BoxFuture<'static, ()> {
+        self.sub.close(code, reason)
+    }
+}
+
+struct SimBindAdapt {
+    sub: AdapterFactory,
+    net: MemNetwork,
+    node: MemNodeId,
+}
+
+impl BindAdapt for SimBindAdapt {
+    fn bind(&self, url: TxUrl, timeout: KitsuneTimeout) -> EndpointFut {
+        let fut = self.sub.bind(url, timeout);
+        let net = self.net.clone();
+        let node = self.node;
+        async move {
+            let (ep, con_recv) = fut.await?;
+            let ep: Arc<dyn EndpointAdapt> = Arc::new(SimEndpointAdapt {
+                sub: ep,
+                net: net.clone(),
+                node,
+            });
+            let con_recv: Box<dyn ConRecvAdapt> =
+                Box::new(SimConRecvAdapt::new(con_recv, net, node));
+            Ok((ep, con_recv))
+        }
+        .boxed()
+    }
+
+    fn local_cert(&self) -> Tx2Cert {
+        self.sub.local_cert()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Bind a node which answers every frame it receives with "world".
+    async fn mk_node(f: &AdapterFactory) -> (TxUrl, Arc<dyn EndpointAdapt>) {
+        let t = KitsuneTimeout::from_millis(5000);
+        let (ep, con_recv) = f.bind("none:".into(), t).await.unwrap();
+        metric_task(async move {
+            con_recv
+                .for_each_concurrent(8, |c| async move {
+                    let (con, chan_recv) = match c.await {
+                        Ok(c) => c,
+                        Err(_) => return,
+                    };
+                    let con = &con;
+                    chan_recv
+                        .for_each_concurrent(8, |recv| async move {
+                            let mut recv = recv.await.unwrap();
+                            while let Ok((_, mut buf)) = recv.read(t).await {
+                                buf.clear();
+                                buf.extend_from_slice(b"world");
+                                if let Ok(mut out) = con.out_chan(t).await {
+                                    let _ = out.write(0.into(), buf, t).await;
+                                }
+                            }
+                        })
+                        .await;
+                })
+                .await;
+            KitsuneResult::Ok(())
+        });
+        (ep.local_addr().unwrap(), ep)
+    }
+
+    /// Send "hello" over `con` and wait for the answer.
+    async fn hello(con: &Con, t: KitsuneTimeout) -> KitsuneResult<()> {
+        let mut out = con.0.out_chan(t).await?;
+        let mut buf = PoolBuf::new();
+        buf.extend_from_slice(b"hello");
+        out.write(0.into(), buf, t).await?;
+        Ok(())
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_mem_network_partition_and_heal() {
+        let t = KitsuneTimeout::from_millis(5000);
+        let net = MemNetwork::new();
+        let (a, fa) = net.add_node(MemConfig::default()).await.unwrap();
+        let (b, fb) = net.add_node(MemConfig::default()).await.unwrap();
+        let (_, ep_a) = mk_node(&fa).await;
+        let (addr_b, _ep_b) = mk_node(&fb).await;
+
+        let con = ep_a.connect(addr_b.clone(), t).await.unwrap();
+        hello(&con, t).await.unwrap();
+        assert_eq!(5, net.bytes_sent(a));
+
+        net.partition(&[&[a], &[b]]);
+        assert!(net.is_partitioned(a, b));
+        assert!(hello(&con, t).await.is_err());
+        assert!(ep_a.connect(addr_b.clone(), t).await.is_err());
+        assert_eq!(5, net.bytes_sent(a));
+
+        net.heal();
+        let con = ep_a.connect(addr_b, t).await.unwrap();
+        hello(&con, t).await.unwrap();
+        assert_eq!(10, net.bytes_sent(a));
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_mem_network_latency_and_loss() {
+        let t = KitsuneTimeout::from_millis(5000);
+        let net = MemNetwork::new();
+        let (a, fa) = net.add_node(MemConfig::default()).await.unwrap();
+        let (b, fb) = net.add_node(MemConfig::default()).await.unwrap();
+        let (_, ep_a) = mk_node(&fa).await;
+        let (addr_b, _ep_b) = mk_node(&fb).await;
+        let con = ep_a.connect(addr_b, t).await.unwrap();
+
+        net.set_latency(a, b, std::time::Duration::from_millis(50));
+        let start = tokio::time::Instant::now();
+        hello(&con, t).await.unwrap();
+        assert!(start.elapsed() >= std::time::Duration::from_millis(50));
+
+        // Lost frames still count as sent.
+        net.clear_link_conditions();
+        net.set_packet_loss(b, a, 1.0);
+        hello(&con, t).await.unwrap();
+        assert_eq!(10, net.bytes_sent(a));
+    }
+}
@@ -5,6 +5,7 @@
 
 const INTERNAL_ERR: u32 = 500;
 
+use crate::config::LiveTuningParams;
 use crate::tx2::tx2_adapter::*;
 use crate::tx2::tx2_pool::*;
 use crate::tx2::tx2_utils::*;
@@ -20,6 +21,15 @@ use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 pub fn tx2_pool_promote(
     adapter: AdapterFactory,
     tuning_params: KitsuneP2pTuningParams,
+) -> EpFactory {
+    tx2_pool_promote_live(adapter, LiveTuningParams::new(tuning_params))
+}
+
+/// Promote a tx2 transport adapter to a tx2 transport frontend,
+/// which follows updates to the tuning params while it is running.
+pub fn tx2_pool_promote_live(
+    adapter: AdapterFactory,
+    tuning_params: LiveTuningParams,
 ) -> EpFactory {
     Arc::new(PromoteFactory {
         adapter,
@@ -75,7 +85,7 @@ impl ConItemInner {
 async fn in_chan_recv_logic(
     local_cert: Tx2Cert,
     peer_cert: Tx2Cert,
-    tuning_params: KitsuneP2pTuningParams,
+    tuning_params: LiveTuningParams,
     url: TxUrl,
     con_item: ConItem,
     writer_bucket: ResourceBucket<WriteChan>,
@@ -96,7 +106,7 @@ async fn in_chan_recv_logic(
             // so we'll accept up to 32 incoming channels
             // but we're only establishign 3 outgoing channels, should
             // we set this to tx2_channel_count_per_connection?
-            tuning_params.concurrent_limit_per_thread,
+            tuning_params.get().concurrent_limit_per_thread,
             move |chan| async move {
                 let mut chan = match chan.await {
                     Err(e) => {
@@ -114,7 +124,7 @@ async fn in_chan_recv_logic(
                 };
                 tracing::trace!(?local_cert, ?peer_cert, "accepted incoming channel");
                 loop {
-                    let r = chan.read(tuning_params.implicit_timeout()).await;
+                    let r = chan.read(tuning_params.get().implicit_timeout()).await;
 
                     let (msg_id, data) = match r {
                         Err(e) if *e.kind() == KitsuneErrorKind::Closed => {
@@ -176,7 +186,10 @@ async fn in_chan_recv_logic(
                 Ok(p) => p,
             };
 
-            let writer = match con_item.out_chan(tuning_params.implicit_timeout()).await {
+            let writer = match con_item
+                .out_chan(tuning_params.get().implicit_timeout())
+                .await
+            {
                 Err(e) => {
                     // we were not able to create an outgoing channel
                     // clean up the connection.
@@ -338,7 +351,7 @@ impl ConItem {
     pub async fn reg_con_inner(
         con_init: tokio::time::Instant,
         local_cert: Tx2Cert,
-        tuning_params: KitsuneP2pTuningParams,
+        tuning_params: LiveTuningParams,
         inner: Share<PromoteEpInner>,
         permit: OwnedSemaphorePermit,
         con: Arc<dyn ConAdapt>,
@@ -354,7 +367,7 @@ impl ConItem {
 
         let writer_bucket = ResourceBucket::new();
         let write_chan_limit = Arc::new(Semaphore::new(
-            tuning_params.tx2_channel_count_per_connection,
+            tuning_params.get().tx2_channel_count_per_connection,
         ));
 
         let con_item = Share::new(ConItemInner {
@@ -440,7 +453,7 @@ impl ConItem {
     pub fn inner_con_inner(
         con_init: tokio::time::Instant,
         local_cert: Tx2Cert,
-        tuning_params: KitsuneP2pTuningParams,
+        tuning_params: LiveTuningParams,
         inner: Share<PromoteEpInner>,
         con_limit: Arc<Semaphore>,
         remote: TxUrl,
@@ -474,7 +487,7 @@ impl ConItem {
     // Build the future that goes in pend_cons
     fn inner_con(
         local_cert: Tx2Cert,
-        tuning_params: KitsuneP2pTuningParams,
+        tuning_params: LiveTuningParams,
         inner: Share<PromoteEpInner>,
         con_limit: Arc<Semaphore>,
         remote: TxUrl,
@@ -544,7 +557,7 @@ impl ConItem {
 }
 
 struct PromoteEpInner {
-    tuning_params: KitsuneP2pTuningParams,
+    tuning_params: LiveTuningParams,
     con_limit: Arc<Semaphore>,
     logic_hnd: LogicChanHandle<EpEvent>,
     pend_cons: HashMap<TxUrl, Shared<BoxFuture<'static, KitsuneResult<ConItem>>>>,
@@ -557,7 +570,7 @@ struct PromoteEpHnd(Share<PromoteEpInner>, Uniq, Tx2Cert);
 impl PromoteEpHnd {
     pub fn new(
         local_cert: Tx2Cert,
-        tuning_params: KitsuneP2pTuningParams,
+        tuning_params: LiveTuningParams,
         con_limit: Arc<Semaphore>,
         logic_hnd: LogicChanHandle<EpEvent>,
         sub_ep: Arc<dyn EndpointAdapt>,
@@ -684,7 +697,7 @@ struct PromoteEp {
 
 async fn con_recv_logic(
     local_cert: Tx2Cert,
-    tuning_params: KitsuneP2pTuningParams,
+    tuning_params: LiveTuningParams,
     inner: Share<PromoteEpInner>,
     logic_hnd: LogicChanHandle<EpEvent>,
     _max_cons: usize,
@@ -769,7 +782,7 @@ async fn con_recv_logic(
 
 impl PromoteEp {
     pub async fn new(
-        tuning_params: KitsuneP2pTuningParams,
+        tuning_params: LiveTuningParams,
         max_cons: usize,
         con_limit: Arc<Semaphore>,
         pair: Endpoint,
@@ -825,7 +838,7 @@ impl AsEp for PromoteEp {
 
 struct PromoteFactory {
     adapter: AdapterFactory,
-    tuning_params: KitsuneP2pTuningParams,
+    tuning_params: LiveTuningParams,
 }
 
 impl AsEpFactory for PromoteFactory {
@@ -835,7 +848,7 @@ impl AsEpFactory for PromoteFactory {
         timeout: KitsuneTimeout,
     ) -> BoxFuture<'static, KitsuneResult<Ep>> {
         let tuning_params = self.tuning_params.clone();
-        let max_cons = tuning_params.tx2_pool_max_connection_count;
+        let max_cons = tuning_params.get().tx2_pool_max_connection_count;
         let con_limit = Arc::new(Semaphore::new(max_cons));
         let pair_fut = self.adapter.bind(bind_spec, timeout);
         timeout
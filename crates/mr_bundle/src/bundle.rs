@@ -118,11 +118,30 @@ where
         Self::from_parts(manifest, self.resources, self.root_dir)
     }
 
+    /// Return a new Bundle with an updated manifest and additional resources,
+    /// subject to the same validation constraints as creating a new Bundle
+    /// from scratch. Useful for embedding resources which were previously
+    /// referenced by a non-bundled Location.
+    pub fn update_manifest_with_resources<R: IntoIterator<Item = (PathBuf, ResourceBytes)>>(
+        self,
+        manifest: M,
+        resources: R,
+    ) -> MrBundleResult<Self> {
+        let mut all_resources = self.resources;
+        all_resources.extend(resources);
+        Self::from_parts(manifest, all_resources, self.root_dir)
+    }
+
     /// Load a Bundle into memory from a file
     pub async fn read_from_file(path: &Path) -> MrBundleResult<Self> {
         Self::decode(&ffs::read(path).await?)
     }
 
+    /// Download a Bundle into memory from a URL
+    pub async fn read_from_url(url: &str) -> MrBundleResult<Self> {
+        Self::decode(&crate::location::resolve_remote(url).await?)
+    }
+
     /// Write a Bundle to a file
     pub async fn write_to_file(&self, path: &Path) -> MrBundleResult<()> {
         Ok(ffs::write(path, &self.encode()?).await?)
@@ -25,6 +25,9 @@ pub enum MrBundleError {
     #[error(transparent)]
     HttpError(#[from] reqwest::Error),
 
+    #[error("The resource at {0} is larger than the limit of {limit} bytes", limit = crate::location::MAX_REMOTE_RESOURCE_SIZE)]
+    RemoteResourceTooLarge(String),
+
     #[error(transparent)]
     MsgpackEncodeError(#[from] rmp_serde::encode::Error),
 
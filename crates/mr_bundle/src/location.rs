@@ -1,9 +1,10 @@
 use crate::{
-    error::{BundleError, MrBundleResult},
+    error::{BundleError, MrBundleError, MrBundleResult},
     ResourceBytes,
 };
 use holochain_util::ffs;
 use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 /// Where to find a Resource.
 ///
@@ -48,13 +49,33 @@ pub(crate) async fn resolve_local(path: &Path) -> MrBundleResult<ResourceBytes>
     Ok(ffs::read(path).await?)
 }
 
+/// How long to wait for a connection to the server of a remote resource.
+pub const REMOTE_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long fetching a remote resource may take in total.
+pub const REMOTE_FETCH_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// The largest remote resource which will be fetched, in bytes.
+pub const MAX_REMOTE_RESOURCE_SIZE: usize = 100 * 1024 * 1024;
+
 pub(crate) async fn resolve_remote(url: &str) -> MrBundleResult<ResourceBytes> {
-    Ok(reqwest::get(url)
-        .await?
-        .bytes()
-        .await?
-        .into_iter()
-        .collect())
+    let client = reqwest::Client::builder()
+        .connect_timeout(REMOTE_CONNECT_TIMEOUT)
+        .timeout(REMOTE_FETCH_TIMEOUT)
+        .build()?;
+    let mut response = client.get(url).send().await?.error_for_status()?;
+    let too_large = || MrBundleError::RemoteResourceTooLarge(url.to_string());
+    if response.content_length().unwrap_or(0) > MAX_REMOTE_RESOURCE_SIZE as u64 {
+        return Err(too_large());
+    }
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        if bytes.len() + chunk.len() > MAX_REMOTE_RESOURCE_SIZE {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    Ok(bytes)
 }
 
 #[cfg(test)]
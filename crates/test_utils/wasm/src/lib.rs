@@ -28,6 +28,7 @@ pub enum TestWasm {
     Crd,
     Crud,
     Debug,
+    Dpki,
     EntryDefs,
     EmitSignal,
     HashEntry,
@@ -124,6 +125,7 @@ impl From<TestWasm> for ZomeName {
             TestWasm::Crd => "crd",
             TestWasm::Crud => "crud",
             TestWasm::Debug => "debug",
+            TestWasm::Dpki => "dpki",
             TestWasm::EntryDefs => "entry_defs",
             TestWasm::EmitSignal => "emit_signal",
             TestWasm::HashEntry => "hash_entry",
@@ -187,6 +189,7 @@ impl From<TestWasm> for PathBuf {
             TestWasm::Crd => "wasm32-unknown-unknown/release/test_wasm_crd.wasm",
             TestWasm::Crud => "wasm32-unknown-unknown/release/test_wasm_crud.wasm",
             TestWasm::Debug => "wasm32-unknown-unknown/release/test_wasm_debug.wasm",
+            TestWasm::Dpki => "wasm32-unknown-unknown/release/test_wasm_dpki.wasm",
             TestWasm::EntryDefs => "wasm32-unknown-unknown/release/test_wasm_entry_defs.wasm",
             TestWasm::EmitSignal => "wasm32-unknown-unknown/release/test_wasm_emit_signal.wasm",
             TestWasm::HashEntry => "wasm32-unknown-unknown/release/test_wasm_hash_entry.wasm",
@@ -0,0 +1,6 @@
+use hdk::prelude::hdi::prelude::*;
+
+#[hdk_link_types]
+pub enum LinkTypes {
+    InitParams,
+}
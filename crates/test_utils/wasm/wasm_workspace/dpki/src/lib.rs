@@ -0,0 +1,37 @@
+//! A DPKI service which treats every key as valid, and records the params
+//! it was initialized with.
+use hdk::prelude::*;
+use integrity::*;
+
+mod integrity;
+
+#[hdk_extern]
+fn init_dpki(params: String) -> ExternResult<()> {
+    let agent = agent_info()?.agent_initial_pubkey;
+    create_link(
+        agent.clone(),
+        agent,
+        LinkTypes::InitParams,
+        LinkTag::new(params.into_bytes()),
+    )?;
+    Ok(())
+}
+
+#[hdk_extern]
+fn init_params(_: ()) -> ExternResult<Option<String>> {
+    let agent = agent_info()?.agent_initial_pubkey;
+    Ok(get_links(agent, LinkTypes::InitParams, None)?
+        .into_iter()
+        .next()
+        .map(|link| String::from_utf8_lossy(&link.tag.into_inner()).into_owned()))
+}
+
+#[hdk_extern]
+fn register_key(_: AgentPubKey) -> ExternResult<()> {
+    Ok(())
+}
+
+#[hdk_extern]
+fn key_state(_: AgentPubKey) -> ExternResult<KeyState> {
+    Ok(KeyState::Valid)
+}
@@ -143,6 +143,15 @@ fn get_links_nested(_: ()) -> ExternResult<Vec<Link>> {
     // Include all link types defined in any zome.
 }
 
+#[hdk_extern]
+fn count_links(_: ()) -> ExternResult<(usize, Vec<ActionHash>)> {
+    let input = GetLinksInput::new(base()?, LinkTypes::SomeLinks.try_into()?, None);
+    Ok((
+        hdk::prelude::count_links(input.clone())?,
+        hdk::prelude::get_link_hashes(input)?,
+    ))
+}
+
 #[hdk_extern]
 fn get_baseless_links(_: ()) -> ExternResult<Vec<Link>> {
     hdk::prelude::get_links(baseless()?, LinkTypes::SomeLinks, None)